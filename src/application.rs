@@ -1,5 +1,14 @@
 use bevy::color::palettes;
 use bevy::prelude::*;
+use bevy::render::camera::{ScalingMode, Viewport};
+use bevy::window::{PrimaryWindow, WindowMode};
+
+use crate::input::InputMap;
+use crate::progress::CampaignProgress;
+use crate::rng::GameRng;
+use crate::saves::SavedGames;
+use crate::scores::BestScores;
+use crate::settings::AddSettingsFile;
 
 // Constants
 pub const WINDOW_RESOLUTION: [f32; 2] = [MENU_SIZE.x + GAME_SIZE.x, MENU_SIZE.y];
@@ -29,11 +38,27 @@ pub const GAME_AREA: Rect = Rect {
 };
 
 // Application states
-#[derive(States, Hash, PartialEq, Eq, Clone, Debug)]
+#[derive(States, Hash, PartialEq, Eq, Clone, Copy, Debug)]
 pub enum CurrentGame {
     InMainMenu,
-    Breakout,
-    Tetris,
+
+    // Registered game with that name, see `RetroGame`
+    Playing(&'static str),
+}
+
+/// Exists while any game is played, for what is the same in every game
+#[derive(Hash, PartialEq, Eq, Clone, Copy, Debug)]
+pub struct InGame;
+
+impl ComputedStates for InGame {
+    type SourceStates = CurrentGame;
+
+    fn compute(current_game: CurrentGame) -> Option<Self> {
+        match current_game {
+            CurrentGame::InMainMenu => None,
+            CurrentGame::Playing(_) => Some(InGame),
+        }
+    }
 }
 
 pub struct Application;
@@ -44,17 +69,88 @@ impl Plugin for Application {
             primary_window: Some(Window {
                 resolution: WINDOW_RESOLUTION.into(),
                 title: String::from("Retro Games"),
-                resizable: false,
+                resizable: true,
                 ..Default::default()
             }),
             ..Default::default()
         }))
         .insert_state(CurrentGame::InMainMenu)
+        .add_computed_state::<InGame>()
         .insert_resource(ClearColor(palettes::basic::GRAY.into()))
-        .add_systems(Startup, setup);
+        .init_resource::<GameRng>()
+        .add_settings_file::<InputMap>()
+        .add_settings_file::<BestScores>()
+        .add_settings_file::<SavedGames>()
+        .add_settings_file::<CampaignProgress>()
+        .add_systems(Startup, setup)
+        .add_systems(Update, (toggle_fullscreen, fit_camera).chain());
     }
 }
 
 fn setup(mut commands: Commands) {
-    commands.spawn(Camera2dBundle::default());
+    // Every layout is made for `WINDOW_RESOLUTION`, the camera always shows
+    // that area and `fit_camera` scales it to the window
+    let mut camera = Camera2dBundle::default();
+    camera.projection.scaling_mode = ScalingMode::Fixed {
+        width: WINDOW_RESOLUTION[0],
+        height: WINDOW_RESOLUTION[1],
+    };
+    commands.spawn(camera);
+}
+
+/// Largest area of a window of `window_size` with the aspect ratio of
+/// `WINDOW_RESOLUTION`, centered, leaving bars on the sides or at the top
+/// and bottom. Both are in physical pixels.
+pub fn letterbox(window_size: UVec2) -> URect {
+    let resolution = Vec2::from(WINDOW_RESOLUTION);
+    let scale = (window_size.as_vec2() / resolution).min_element();
+    let size = (resolution * scale).round().as_uvec2().min(window_size);
+    let position = (window_size - size) / 2;
+
+    URect::from_corners(position, position + size)
 }
+
+fn fit_camera(
+    windows: Query<&Window, (With<PrimaryWindow>, Changed<Window>)>,
+    mut cameras: Query<&mut Camera>,
+    mut ui_scale: ResMut<UiScale>,
+) {
+    let Ok(window) = windows.get_single() else {
+        return;
+    };
+    // Minimized
+    if window.physical_width() == 0 || window.physical_height() == 0 {
+        return;
+    }
+
+    let area = letterbox(window.physical_size());
+    for mut camera in &mut cameras {
+        camera.viewport = Some(Viewport {
+            physical_position: area.min,
+            physical_size: area.size(),
+            ..Default::default()
+        });
+    }
+
+    // The UI nodes are placed in pixels of `WINDOW_RESOLUTION` too
+    ui_scale.0 = area.width() as f32 / (WINDOW_RESOLUTION[0] * window.scale_factor());
+}
+
+fn toggle_fullscreen(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut windows: Query<&mut Window, With<PrimaryWindow>>,
+) {
+    if !keyboard.just_pressed(KeyCode::F11) {
+        return;
+    }
+
+    if let Ok(mut window) = windows.get_single_mut() {
+        window.mode = match window.mode {
+            WindowMode::Windowed => WindowMode::BorderlessFullscreen,
+            _ => WindowMode::Windowed,
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,295 @@
+use bevy::prelude::*;
+
+use crate::{
+    breakout::{BreackoutPlugin, GameState, InGameState},
+    input::Action,
+    menu::MenuPlugin,
+    registry::{AddGame, RetroGame},
+    testing::{
+        self, click, headless_app, hover, launcher_app, menu_item, run_frames, snapshot, state,
+        tap, EventCounter,
+    },
+    tetris::{TetrisPlugin, TetrisState},
+};
+
+use super::{letterbox, CurrentGame};
+
+// Selects the launcher item `nb_down` items below the current one. The
+// launcher remembers its selection, so callers go back up when they are done.
+fn select_in_launcher(app: &mut App, nb_down: usize) {
+    for _ in 0..nb_down {
+        tap(app, KeyCode::ArrowDown);
+    }
+    tap(app, KeyCode::Space);
+
+    // Games set their own state when entered, which applies one frame later
+    run_frames(app, 1);
+}
+
+fn enter_breakout(app: &mut App) {
+    select_in_launcher(app, 0);
+
+    assert_eq!(state::<CurrentGame>(app), BreackoutPlugin::STATE);
+    assert_eq!(state::<GameState>(app), GameState::InMenu);
+}
+
+fn enter_tetris(app: &mut App) {
+    select_in_launcher(app, 1);
+
+    assert_eq!(state::<CurrentGame>(app), TetrisPlugin::STATE);
+    assert_eq!(state::<TetrisState>(app), TetrisState::InMenu);
+}
+
+fn play_and_pause_breakout(app: &mut App) {
+    // Play
+    tap(app, KeyCode::Space);
+    assert_eq!(state::<GameState>(app), GameState::InGame);
+    assert_eq!(state::<InGameState>(app), InGameState::Paused);
+
+    // Launch the ball
+    tap(app, KeyCode::Space);
+    assert_eq!(state::<InGameState>(app), InGameState::Playing);
+    run_frames(app, 10);
+
+    // Pause, the level stays frozen behind the menu
+    tap(app, KeyCode::Escape);
+    assert_eq!(state::<GameState>(app), GameState::InMenu);
+    assert_eq!(state::<InGameState>(app), InGameState::Playing);
+}
+
+// "Exit" is the last item of the Breakout menu
+fn exit_breakout(app: &mut App) {
+    tap(app, KeyCode::ArrowUp);
+    tap(app, KeyCode::Space);
+    run_frames(app, 1);
+
+    assert_eq!(state::<CurrentGame>(app), CurrentGame::InMainMenu);
+    assert_eq!(state::<GameState>(app), GameState::Exited);
+}
+
+// "Exit" is the last item of the Tetris main menu
+fn exit_tetris(app: &mut App) {
+    tap(app, KeyCode::ArrowUp);
+    tap(app, KeyCode::Space);
+    run_frames(app, 1);
+
+    assert_eq!(state::<CurrentGame>(app), CurrentGame::InMainMenu);
+    assert_eq!(state::<TetrisState>(app), TetrisState::Exited);
+}
+
+#[test]
+fn breakout_pause_resume_exit_and_reenter() {
+    let mut app = launcher_app();
+    let in_launcher = snapshot(&app);
+
+    enter_breakout(&mut app);
+    play_and_pause_breakout(&mut app);
+
+    // "Resume" counts down before the ball moves again
+    tap(&mut app, KeyCode::Space);
+    assert_eq!(state::<GameState>(&app), GameState::InGame);
+    assert_eq!(state::<InGameState>(&app), InGameState::Resuming);
+
+    tap(&mut app, KeyCode::Escape);
+    exit_breakout(&mut app);
+    assert_eq!(snapshot(&app), in_launcher);
+
+    // The level left paused is continued from the top of the menu
+    enter_breakout(&mut app);
+    menu_item(&mut app, "Continue");
+    tap(&mut app, KeyCode::Space);
+    run_frames(&mut app, 1);
+    assert_eq!(state::<GameState>(&app), GameState::InGame);
+    assert_eq!(state::<InGameState>(&app), InGameState::Resuming);
+
+    tap(&mut app, KeyCode::Escape);
+    exit_breakout(&mut app);
+    assert_eq!(snapshot(&app), in_launcher);
+}
+
+#[test]
+fn breakout_exit_without_playing() {
+    let mut app = launcher_app();
+    let in_launcher = snapshot(&app);
+
+    enter_breakout(&mut app);
+    exit_breakout(&mut app);
+    assert_eq!(snapshot(&app), in_launcher);
+}
+
+#[test]
+fn breakout_level_selection_and_back() {
+    let mut app = launcher_app();
+    let in_launcher = snapshot(&app);
+
+    enter_breakout(&mut app);
+
+    // "Load level", then back with Escape and with the "Back" item
+    tap(&mut app, KeyCode::ArrowDown);
+    tap(&mut app, KeyCode::Space);
+    tap(&mut app, KeyCode::Escape);
+
+    tap(&mut app, KeyCode::ArrowDown);
+    tap(&mut app, KeyCode::Space);
+    tap(&mut app, KeyCode::ArrowUp);
+    tap(&mut app, KeyCode::Space);
+    assert_eq!(state::<GameState>(&app), GameState::InMenu);
+
+    // Level 1, the only one unlocked
+    tap(&mut app, KeyCode::ArrowDown);
+    tap(&mut app, KeyCode::Space);
+    tap(&mut app, KeyCode::Space);
+    assert_eq!(state::<GameState>(&app), GameState::InGame);
+
+    tap(&mut app, KeyCode::Escape);
+    exit_breakout(&mut app);
+    assert_eq!(snapshot(&app), in_launcher);
+}
+
+#[test]
+fn tetris_play_exit_and_reenter() {
+    let mut app = launcher_app();
+    let in_launcher = snapshot(&app);
+
+    enter_tetris(&mut app);
+    exit_tetris(&mut app);
+    assert_eq!(snapshot(&app), in_launcher);
+
+    // The launcher still has Tetris selected
+    select_in_launcher(&mut app, 0);
+    assert_eq!(state::<TetrisState>(&app), TetrisState::InMenu);
+
+    tap(&mut app, KeyCode::Space);
+    assert_eq!(state::<TetrisState>(&app), TetrisState::InGame);
+}
+
+#[test]
+fn switching_games_leaks_nothing() {
+    let mut app = launcher_app();
+    let in_launcher = snapshot(&app);
+
+    enter_breakout(&mut app);
+    play_and_pause_breakout(&mut app);
+    exit_breakout(&mut app);
+
+    enter_tetris(&mut app);
+    exit_tetris(&mut app);
+
+    tap(&mut app, KeyCode::ArrowUp);
+    enter_breakout(&mut app);
+    exit_breakout(&mut app);
+
+    assert_eq!(snapshot(&app), in_launcher);
+}
+
+#[test]
+fn exit_item_quits() {
+    let mut app = launcher_app();
+    let mut app_exit = EventCounter::<AppExit>::new(&app);
+
+    // "Exit" is the last launcher item
+    tap(&mut app, KeyCode::ArrowUp);
+    tap(&mut app, KeyCode::Space);
+
+    assert_eq!(app_exit.update(&app), 1);
+    assert_eq!(testing::state::<CurrentGame>(&app), CurrentGame::InMainMenu);
+}
+
+#[test]
+fn pointer_drives_the_menus() {
+    let mut app = launcher_app();
+
+    // Hovering selects, the keys then confirm the hovered item
+    let tetris = menu_item(&mut app, "Tetris");
+    hover(&mut app, tetris);
+    tap(&mut app, KeyCode::Space);
+    run_frames(&mut app, 1);
+    assert_eq!(state::<TetrisState>(&app), TetrisState::InMenu);
+
+    let play = menu_item(&mut app, "Play");
+    click(&mut app, play);
+    assert_eq!(state::<TetrisState>(&app), TetrisState::InGame);
+
+    testing::set_state(&mut app, CurrentGame::InMainMenu);
+    run_frames(&mut app, 2);
+
+    // Clicking without hovering first, like a touch
+    let breakout = menu_item(&mut app, "Breakout");
+    click(&mut app, breakout);
+    run_frames(&mut app, 1);
+    assert_eq!(state::<GameState>(&app), GameState::InMenu);
+
+    let load_level = menu_item(&mut app, "Load level");
+    click(&mut app, load_level);
+    let level_1 = menu_item(&mut app, "Level 1");
+    click(&mut app, level_1);
+    assert_eq!(state::<GameState>(&app), GameState::InGame);
+    assert_eq!(state::<InGameState>(&app), InGameState::Paused);
+}
+
+struct TestGamePlugin;
+
+impl Plugin for TestGamePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(Self::STATE), |mut commands: Commands| {
+            commands.insert_resource(TestGameRunning)
+        })
+        .add_systems(OnExit(Self::STATE), |mut commands: Commands| {
+            commands.remove_resource::<TestGameRunning>()
+        });
+    }
+}
+
+impl RetroGame for TestGamePlugin {
+    const NAME: &'static str = "Test game";
+    const ICON: &'static str = "menu/menu_background.png";
+    const DESCRIPTION: &'static str = "Registered by the tests.";
+    const CONTROLS: &'static [Action] = &[Action::Confirm];
+}
+
+#[derive(Resource)]
+struct TestGameRunning;
+
+#[test]
+fn registered_games_join_the_launcher() {
+    let mut app = headless_app();
+    app.add_plugins(MenuPlugin)
+        .add_game(BreackoutPlugin)
+        .add_game(TetrisPlugin)
+        .add_game(TestGamePlugin);
+    app.update();
+
+    // Listed after the games registered before it
+    let test_game = menu_item(&mut app, "Test game");
+    click(&mut app, test_game);
+    assert_eq!(state::<CurrentGame>(&app), TestGamePlugin::STATE);
+    assert!(app.world().contains_resource::<TestGameRunning>());
+
+    testing::set_state(&mut app, CurrentGame::InMainMenu);
+    app.update();
+    assert!(!app.world().contains_resource::<TestGameRunning>());
+
+    select_in_launcher(&mut app, 0);
+    assert_eq!(state::<CurrentGame>(&app), TestGamePlugin::STATE);
+}
+
+#[test]
+fn letterbox_keeps_the_layout_aspect_ratio() {
+    // Same aspect ratio, only scaled
+    assert_eq!(
+        letterbox(UVec2::new(2200, 1600)),
+        URect::new(0, 0, 2200, 1600)
+    );
+
+    // Wider, bars on the sides
+    assert_eq!(
+        letterbox(UVec2::new(1920, 1080)),
+        URect::new(217, 0, 1702, 1080)
+    );
+
+    // Taller, bars at the top and bottom
+    assert_eq!(
+        letterbox(UVec2::new(1100, 1000)),
+        URect::new(0, 100, 1100, 900)
+    );
+}
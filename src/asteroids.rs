@@ -0,0 +1,50 @@
+use bevy::prelude::*;
+
+mod menu;
+use menu::MenuPlugin;
+
+mod game;
+use game::GamePlugin;
+
+use crate::game_menu::ignore_launch;
+use crate::input::Action;
+use crate::launch::LaunchGame;
+use crate::registry::RetroGame;
+
+pub struct AsteroidsPlugin;
+
+impl RetroGame for AsteroidsPlugin {
+    const NAME: &'static str = "Asteroids";
+    const ICON: &'static str = "asteroids/icon.png";
+    const DESCRIPTION: &'static str =
+        "Shoot the asteroids to pieces and the UFOs down, jump to hyperspace when cornered.";
+    const CONTROLS: &'static [Action] = &[
+        Action::MoveLeft,
+        Action::MoveRight,
+        Action::MoveUp,
+        Action::Fire,
+        Action::Hyperspace,
+        Action::Pause,
+    ];
+}
+
+impl Plugin for AsteroidsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(MenuPlugin)
+            .add_plugins(GamePlugin)
+            .add_systems(
+                OnEnter(AsteroidsPlugin::STATE),
+                ignore_launch::<AsteroidsPlugin>.run_if(resource_exists::<LaunchGame>),
+            );
+    }
+}
+
+#[derive(States, PartialEq, Eq, Hash, Clone, Debug)]
+pub enum AsteroidsState {
+    Exited,
+    InMenu,
+    InGame,
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,895 @@
+use std::{f32::consts::TAU, time::Duration};
+
+use bevy::prelude::*;
+
+use crate::{application::GAME_AREA, game_menu, input::Action, registry::RetroGame, rng::GameRng};
+
+use super::{AsteroidsPlugin, AsteroidsState};
+
+// Radians per second
+const SHIP_ROTATION_SPEED: f32 = 4.5;
+
+// Pixels per second squared while thrusting, the ship slowly drifts to a
+// stop otherwise, losing `SHIP_DRAG` of its speed per second
+const SHIP_THRUST: f32 = 350.0;
+const SHIP_DRAG: f32 = 0.4;
+const SHIP_MAX_SPEED: f32 = 400.0;
+
+pub const SHIP_RADIUS: f32 = 12.0;
+
+// Of the ship pointing up, around its center
+const SHIP_SHAPE: [Vec2; 4] = [
+    Vec2::new(0.0, 15.0),
+    Vec2::new(-10.0, -11.0),
+    Vec2::new(0.0, -6.0),
+    Vec2::new(10.0, -11.0),
+];
+const FLAME_SHAPE: [Vec2; 3] = [
+    Vec2::new(-5.0, -8.0),
+    Vec2::new(0.0, -18.0),
+    Vec2::new(5.0, -8.0),
+];
+
+// The ship comes back at the center once nothing is this close to it
+const RESPAWN_TIME: f32 = 2.0;
+const SAFE_DISTANCE: f32 = 120.0;
+
+pub const LIVES: u32 = 3;
+const EXTRA_LIFE_POINTS: u32 = 10_000;
+
+// Seconds before the ship can jump again, a jump has `HYPERSPACE_RISK`
+// chances of destroying it
+const HYPERSPACE_COOLDOWN: f32 = 1.0;
+pub const HYPERSPACE_RISK: f32 = 0.1;
+
+// On top of the speed of the ship
+const SHOT_SPEED: f32 = 550.0;
+const SHOT_LIFETIME: f32 = 1.0;
+pub const MAX_SHOTS: usize = 4;
+
+// Large asteroids of the first wave, each wave brings more up to the max
+const WAVE_ASTEROIDS: usize = 4;
+const WAVE_ASTEROIDS_INCREASE: usize = 2;
+const MAX_WAVE_ASTEROIDS: usize = 11;
+
+// Seconds between the last asteroid of a wave and the next wave
+const NEXT_WAVE_TIME: f32 = 2.0;
+
+const ASTEROID_VERTICES: usize = 11;
+
+// Of the radius, the outline of an asteroid is jagged in between
+const ASTEROID_ROUNDNESS: f32 = 0.7;
+
+// Radians per second, either way
+const ASTEROID_MAX_SPIN: f32 = 1.0;
+
+const UFO_INTERVAL: f32 = 15.0;
+const UFO_SHOT_SPEED: f32 = 300.0;
+const UFO_SHOT_LIFETIME: f32 = 1.5;
+const UFO_FIRE_INTERVAL: f32 = 1.2;
+
+// The UFO changes its vertical course this often
+const UFO_COURSE_INTERVAL: f32 = 1.5;
+
+// Chances of a small UFO, more every wave up to the max
+const SMALL_UFO_CHANCE: f32 = 0.15;
+const MAX_SMALL_UFO_CHANCE: f32 = 0.8;
+
+// Small UFOs aim this well, in radians either way
+const SMALL_UFO_AIM: f32 = 0.15;
+
+const LINE_WIDTH: f32 = 2.0;
+
+// Drawn as short lines
+const SHOT_LENGTH: f32 = 3.0;
+
+pub struct GamePlugin;
+
+impl Plugin for GamePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<LoadGameEvent>()
+            .add_event::<CleanupGameEvent>()
+            .init_resource::<LastScore>()
+            .add_systems(OnExit(AsteroidsPlugin::STATE), cleanup_game)
+            .add_systems(
+                Update,
+                (
+                    load_game.run_if(on_event::<LoadGameEvent>()),
+                    (
+                        handle_player_input,
+                        update_game,
+                        game_menu::end_game::<AsteroidsPlugin>
+                            .run_if(game_menu::is_over::<AsteroidsPlugin>),
+                    )
+                        .chain()
+                        .run_if(in_state(AsteroidsState::InGame))
+                        .run_if(resource_exists::<AsteroidsGame>),
+                    cleanup_game.run_if(on_event::<CleanupGameEvent>()),
+                    (draw_lines, update_hud).run_if(resource_exists::<AsteroidsGame>),
+                )
+                    .chain(),
+            );
+    }
+}
+
+#[derive(Event, Default)]
+pub struct LoadGameEvent {
+    pub restart: bool,
+}
+
+#[derive(Event, Default)]
+pub struct CleanupGameEvent;
+
+/// Score of the last finished game
+pub type LastScore = game_menu::LastScore<AsteroidsPlugin>;
+
+// Keeps `position` in the game area, coming back from the other side
+fn wrap(position: Vec2) -> Vec2 {
+    GAME_AREA.min + (position - GAME_AREA.min).rem_euclid(GAME_AREA.size())
+}
+
+// Unit vector at `angle` from up, counterclockwise
+fn heading(angle: f32) -> Vec2 {
+    Vec2::from_angle(angle).rotate(Vec2::Y)
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Ship {
+    pub position: Vec2,
+    pub velocity: Vec2,
+
+    /// In radians from pointing up, counterclockwise
+    pub angle: f32,
+
+    pub thrusting: bool,
+}
+
+impl Ship {
+    fn new() -> Self {
+        Self {
+            position: GAME_AREA.center(),
+            velocity: Vec2::ZERO,
+            angle: 0.0,
+            thrusting: false,
+        }
+    }
+
+    /// Where the shots come out
+    pub fn nose(&self) -> Vec2 {
+        self.position + heading(self.angle) * SHIP_SHAPE[0].y
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum AsteroidSize {
+    Large,
+    Medium,
+    Small,
+}
+
+impl AsteroidSize {
+    pub fn radius(self) -> f32 {
+        match self {
+            AsteroidSize::Large => 40.0,
+            AsteroidSize::Medium => 22.0,
+            AsteroidSize::Small => 12.0,
+        }
+    }
+
+    pub fn points(self) -> u32 {
+        match self {
+            AsteroidSize::Large => 20,
+            AsteroidSize::Medium => 50,
+            AsteroidSize::Small => 100,
+        }
+    }
+
+    // Pixels per second, the smaller the faster
+    fn speed_range(self) -> (f32, f32) {
+        match self {
+            AsteroidSize::Large => (30.0, 70.0),
+            AsteroidSize::Medium => (60.0, 110.0),
+            AsteroidSize::Small => (90.0, 160.0),
+        }
+    }
+
+    /// What it splits into when shot
+    pub fn smaller(self) -> Option<Self> {
+        match self {
+            AsteroidSize::Large => Some(AsteroidSize::Medium),
+            AsteroidSize::Medium => Some(AsteroidSize::Small),
+            AsteroidSize::Small => None,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Asteroid {
+    pub position: Vec2,
+    pub velocity: Vec2,
+    pub size: AsteroidSize,
+
+    angle: f32,
+    spin: f32,
+
+    // Of the radius, for each vertex of the outline
+    shape: [f32; ASTEROID_VERTICES],
+}
+
+impl Asteroid {
+    /// Going any way at a speed fitting its size
+    fn new(position: Vec2, size: AsteroidSize, random: &mut impl FnMut() -> f32) -> Self {
+        let (min_speed, max_speed) = size.speed_range();
+        let speed = min_speed + (max_speed - min_speed) * random();
+
+        Self {
+            position,
+            velocity: heading(random() * TAU) * speed,
+            size,
+            angle: random() * TAU,
+            spin: (random() * 2.0 - 1.0) * ASTEROID_MAX_SPIN,
+            shape: [(); ASTEROID_VERTICES]
+                .map(|()| ASTEROID_ROUNDNESS + (1.0 - ASTEROID_ROUNDNESS) * random()),
+        }
+    }
+
+    pub fn hits(&self, point: Vec2, radius: f32) -> bool {
+        self.position.distance(point) < self.size.radius() + radius
+    }
+
+    /// Vertices of its jagged outline
+    pub fn outline(&self) -> Vec<Vec2> {
+        self.shape
+            .iter()
+            .enumerate()
+            .map(|(index, &factor)| {
+                let angle = self.angle + TAU * index as f32 / ASTEROID_VERTICES as f32;
+                self.position + heading(angle) * self.size.radius() * factor
+            })
+            .collect()
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Shot {
+    pub position: Vec2,
+    pub velocity: Vec2,
+
+    // Seconds before it fades
+    time_left: f32,
+}
+
+impl Shot {
+    // Returns whether it is still there
+    fn advance(&mut self, delta_seconds: f32) -> bool {
+        self.position = wrap(self.position + self.velocity * delta_seconds);
+        self.time_left -= delta_seconds;
+        self.time_left > 0.0
+    }
+}
+
+/// Flying saucer crossing the screen, firing at random when large and at the
+/// ship when small
+#[derive(Clone, Debug)]
+pub struct Ufo {
+    pub position: Vec2,
+    pub small: bool,
+
+    // 1 to the right, -1 to the left
+    direction: f32,
+
+    // Up, down or straight on
+    course: f32,
+
+    fire_timer: Timer,
+    course_timer: Timer,
+}
+
+impl Ufo {
+    pub fn radius(&self) -> f32 {
+        if self.small {
+            10.0
+        } else {
+            18.0
+        }
+    }
+
+    pub fn points(&self) -> u32 {
+        if self.small {
+            1000
+        } else {
+            200
+        }
+    }
+
+    fn speed(&self) -> f32 {
+        if self.small {
+            160.0
+        } else {
+            110.0
+        }
+    }
+
+    /// Hull and dome, as lines
+    pub fn outline(&self) -> Vec<(Vec2, Vec2)> {
+        let radius = self.radius();
+        let hull = [
+            Vec2::new(-radius, 0.0),
+            Vec2::new(-radius / 2.0, -radius / 2.0),
+            Vec2::new(radius / 2.0, -radius / 2.0),
+            Vec2::new(radius, 0.0),
+            Vec2::new(radius / 2.0, radius / 2.0),
+            Vec2::new(-radius / 2.0, radius / 2.0),
+        ];
+        let dome = [
+            Vec2::new(-radius / 2.0, radius / 2.0),
+            Vec2::new(-radius / 4.0, radius),
+            Vec2::new(radius / 4.0, radius),
+            Vec2::new(radius / 2.0, radius / 2.0),
+        ];
+
+        polygon(&hull)
+            .chain(dome.windows(2).map(|line| (line[0], line[1])))
+            .chain([(hull[0], hull[3])])
+            .map(|(from, to)| (self.position + from, self.position + to))
+            .collect()
+    }
+}
+
+// Lines between the points, closing the polygon
+fn polygon(points: &[Vec2]) -> impl Iterator<Item = (Vec2, Vec2)> + '_ {
+    points
+        .iter()
+        .zip(points.iter().cycle().skip(1))
+        .map(|(&from, &to)| (from, to))
+}
+
+/// The game being played
+#[derive(Resource, Clone, Debug)]
+pub struct AsteroidsGame {
+    /// None while waiting to come back after being destroyed
+    pub ship: Option<Ship>,
+
+    pub asteroids: Vec<Asteroid>,
+
+    /// Of the ship, up to `MAX_SHOTS`
+    pub shots: Vec<Shot>,
+
+    pub ufo: Option<Ufo>,
+    pub ufo_shots: Vec<Shot>,
+
+    /// From 1
+    pub wave: u32,
+
+    pub lives: u32,
+    pub score: u32,
+
+    // No life left
+    pub game_over: bool,
+
+    respawn_timer: Timer,
+    hyperspace_timer: Timer,
+    next_wave_timer: Timer,
+    ufo_timer: Timer,
+}
+
+impl game_menu::ScoredGame for AsteroidsGame {
+    fn score(&self) -> u32 {
+        self.score
+    }
+
+    fn is_over(&self) -> bool {
+        self.game_over
+    }
+}
+
+impl AsteroidsGame {
+    pub fn new(random: &mut impl FnMut() -> f32) -> Self {
+        let mut game = Self {
+            ship: Some(Ship::new()),
+            asteroids: Vec::new(),
+            shots: Vec::new(),
+            ufo: None,
+            ufo_shots: Vec::new(),
+            wave: 1,
+            lives: LIVES,
+            score: 0,
+            game_over: false,
+            respawn_timer: Timer::from_seconds(RESPAWN_TIME, TimerMode::Once),
+            hyperspace_timer: Timer::from_seconds(HYPERSPACE_COOLDOWN, TimerMode::Once),
+            next_wave_timer: Timer::from_seconds(NEXT_WAVE_TIME, TimerMode::Once),
+            ufo_timer: Timer::from_seconds(UFO_INTERVAL, TimerMode::Repeating),
+        };
+        // Ready from the start
+        game.hyperspace_timer
+            .tick(Duration::from_secs_f32(HYPERSPACE_COOLDOWN));
+        game.start_wave(random);
+
+        game
+    }
+
+    /// Large asteroids along the sides, more every wave
+    fn start_wave(&mut self, random: &mut impl FnMut() -> f32) {
+        let nb_asteroids = (WAVE_ASTEROIDS + WAVE_ASTEROIDS_INCREASE * (self.wave as usize - 1))
+            .min(MAX_WAVE_ASTEROIDS);
+
+        self.asteroids = (0..nb_asteroids)
+            .map(|index| {
+                let along = random();
+                let position = if index % 2 == 0 {
+                    Vec2::new(GAME_AREA.min.x + GAME_AREA.width() * along, GAME_AREA.min.y)
+                } else {
+                    Vec2::new(
+                        GAME_AREA.min.x,
+                        GAME_AREA.min.y + GAME_AREA.height() * along,
+                    )
+                };
+                Asteroid::new(position, AsteroidSize::Large, random)
+            })
+            .collect();
+        self.ufo_timer.reset();
+    }
+
+    /// Turns the ship, left with 1 and right with -1, and thrusts it
+    /// forward
+    pub fn steer(&mut self, rotation: f32, thrust: bool, delta_seconds: f32) {
+        let Some(ship) = &mut self.ship else {
+            return;
+        };
+
+        ship.angle = (ship.angle + rotation * SHIP_ROTATION_SPEED * delta_seconds).rem_euclid(TAU);
+        ship.thrusting = thrust;
+        if thrust {
+            ship.velocity = (ship.velocity + heading(ship.angle) * SHIP_THRUST * delta_seconds)
+                .clamp_length_max(SHIP_MAX_SPEED);
+        }
+    }
+
+    /// Fires from the nose of the ship unless `MAX_SHOTS` are already on
+    /// screen, returns whether it did
+    pub fn fire(&mut self) -> bool {
+        let Some(ship) = &self.ship else {
+            return false;
+        };
+        if self.shots.len() >= MAX_SHOTS {
+            return false;
+        }
+
+        self.shots.push(Shot {
+            position: ship.nose(),
+            velocity: ship.velocity + heading(ship.angle) * SHOT_SPEED,
+            time_left: SHOT_LIFETIME,
+        });
+        true
+    }
+
+    /// Jumps somewhere at random, stopped, unless the jump goes wrong and
+    /// destroys the ship. Returns whether it jumped.
+    pub fn hyperspace(&mut self, random: &mut impl FnMut() -> f32) -> bool {
+        if self.ship.is_none() || !self.hyperspace_timer.finished() {
+            return false;
+        }
+        self.hyperspace_timer.reset();
+
+        if random() < HYPERSPACE_RISK {
+            self.destroy_ship();
+            return true;
+        }
+
+        if let Some(ship) = &mut self.ship {
+            ship.position = GAME_AREA.min + GAME_AREA.size() * Vec2::new(random(), random());
+            ship.velocity = Vec2::ZERO;
+        }
+        true
+    }
+
+    /// Moves everything by `delta_seconds`. `random` gives numbers from 0
+    /// to 1, for the asteroids and the UFO.
+    pub fn update(&mut self, delta_seconds: f32, mut random: impl FnMut() -> f32) {
+        if self.game_over {
+            return;
+        }
+        let delta = Duration::from_secs_f32(delta_seconds);
+
+        self.hyperspace_timer.tick(delta);
+        if let Some(ship) = &mut self.ship {
+            ship.velocity *= (1.0 - SHIP_DRAG * delta_seconds).max(0.0);
+            ship.position = wrap(ship.position + ship.velocity * delta_seconds);
+        }
+
+        self.shots.retain_mut(|shot| shot.advance(delta_seconds));
+        self.ufo_shots
+            .retain_mut(|shot| shot.advance(delta_seconds));
+
+        for asteroid in &mut self.asteroids {
+            asteroid.position = wrap(asteroid.position + asteroid.velocity * delta_seconds);
+            asteroid.angle += asteroid.spin * delta_seconds;
+        }
+
+        self.move_ufo(delta, &mut random);
+        self.collide(&mut random);
+
+        if self.asteroids.is_empty() && self.next_wave_timer.tick(delta).just_finished() {
+            self.wave += 1;
+            self.start_wave(&mut random);
+            self.next_wave_timer.reset();
+        }
+
+        if self.ship.is_none() && !self.game_over && self.respawn_timer.tick(delta).finished() {
+            self.respawn();
+        }
+    }
+
+    // Sends a UFO now and then, it crosses the screen once
+    fn move_ufo(&mut self, delta: Duration, random: &mut impl FnMut() -> f32) {
+        let delta_seconds = delta.as_secs_f32();
+
+        if self.ufo.is_none() && self.ufo_timer.tick(delta).just_finished() {
+            let small_chance = (SMALL_UFO_CHANCE * self.wave as f32).min(MAX_SMALL_UFO_CHANCE);
+            let from_left = random() < 0.5;
+            self.ufo = Some(Ufo {
+                position: Vec2::new(
+                    if from_left {
+                        GAME_AREA.min.x
+                    } else {
+                        GAME_AREA.max.x
+                    },
+                    GAME_AREA.min.y + GAME_AREA.height() * random(),
+                ),
+                small: random() < small_chance,
+                direction: if from_left { 1.0 } else { -1.0 },
+                course: 0.0,
+                fire_timer: Timer::from_seconds(UFO_FIRE_INTERVAL, TimerMode::Repeating),
+                course_timer: Timer::from_seconds(UFO_COURSE_INTERVAL, TimerMode::Repeating),
+            });
+        }
+
+        let Some(ufo) = &mut self.ufo else {
+            return;
+        };
+
+        if ufo.course_timer.tick(delta).just_finished() {
+            ufo.course = (random() * 3.0).floor() - 1.0;
+        }
+        let velocity = Vec2::new(ufo.direction, ufo.course * 0.5) * ufo.speed();
+        let position = ufo.position + velocity * delta_seconds;
+        if position.x < GAME_AREA.min.x || position.x > GAME_AREA.max.x {
+            self.ufo = None;
+            return;
+        }
+        ufo.position = wrap(position);
+
+        if ufo.fire_timer.tick(delta).just_finished() {
+            let direction = match (&self.ship, ufo.small) {
+                (Some(ship), true) => {
+                    let aim = (ship.position - ufo.position).normalize_or(Vec2::Y);
+                    Vec2::from_angle((random() * 2.0 - 1.0) * SMALL_UFO_AIM).rotate(aim)
+                }
+                _ => heading(random() * TAU),
+            };
+            self.ufo_shots.push(Shot {
+                position: ufo.position,
+                velocity: direction * UFO_SHOT_SPEED,
+                time_left: UFO_SHOT_LIFETIME,
+            });
+        }
+    }
+
+    fn collide(&mut self, random: &mut impl FnMut() -> f32) {
+        // The ship's shots score
+        let mut index = 0;
+        while index < self.shots.len() {
+            let shot = self.shots[index].position;
+            if let Some(hit) = self.asteroids.iter().position(|a| a.hits(shot, 0.0)) {
+                self.shots.remove(index);
+                self.split(hit, true, random);
+            } else if self
+                .ufo
+                .as_ref()
+                .is_some_and(|ufo| ufo.position.distance(shot) < ufo.radius())
+            {
+                self.shots.remove(index);
+                self.destroy_ufo(true);
+            } else {
+                index += 1;
+            }
+        }
+
+        // The UFO's do not
+        let mut index = 0;
+        while index < self.ufo_shots.len() {
+            let shot = self.ufo_shots[index].position;
+            if let Some(hit) = self.asteroids.iter().position(|a| a.hits(shot, 0.0)) {
+                self.ufo_shots.remove(index);
+                self.split(hit, false, random);
+            } else if self
+                .ship
+                .is_some_and(|ship| ship.position.distance(shot) < SHIP_RADIUS)
+            {
+                self.ufo_shots.remove(index);
+                self.destroy_ship();
+            } else {
+                index += 1;
+            }
+        }
+
+        if let Some(ufo) = &self.ufo {
+            let (position, radius) = (ufo.position, ufo.radius());
+            if let Some(hit) = self.asteroids.iter().position(|a| a.hits(position, radius)) {
+                self.split(hit, false, random);
+                self.destroy_ufo(false);
+            }
+        }
+
+        // Running into anything destroys the ship along with it, for the
+        // points
+        let Some(ship) = self.ship else {
+            return;
+        };
+        if let Some(hit) = self
+            .asteroids
+            .iter()
+            .position(|a| a.hits(ship.position, SHIP_RADIUS))
+        {
+            self.split(hit, true, random);
+            self.destroy_ship();
+        } else if self
+            .ufo
+            .as_ref()
+            .is_some_and(|ufo| ufo.position.distance(ship.position) < ufo.radius() + SHIP_RADIUS)
+        {
+            self.destroy_ufo(true);
+            self.destroy_ship();
+        }
+    }
+
+    // Breaks the asteroid at `index` in two smaller ones going their own
+    // ways, the smallest ones are gone
+    fn split(&mut self, index: usize, scores: bool, random: &mut impl FnMut() -> f32) {
+        let asteroid = self.asteroids.swap_remove(index);
+        if scores {
+            self.add_points(asteroid.size.points());
+        }
+
+        if let Some(smaller) = asteroid.size.smaller() {
+            for _ in 0..2 {
+                self.asteroids
+                    .push(Asteroid::new(asteroid.position, smaller, random));
+            }
+        }
+    }
+
+    fn destroy_ufo(&mut self, scores: bool) {
+        if let Some(ufo) = self.ufo.take() {
+            if scores {
+                self.add_points(ufo.points());
+            }
+        }
+        self.ufo_timer.reset();
+    }
+
+    fn destroy_ship(&mut self) {
+        self.ship = None;
+        self.lives = self.lives.saturating_sub(1);
+        self.game_over = self.lives == 0;
+        self.respawn_timer.reset();
+    }
+
+    // At the center, once it is clear
+    fn respawn(&mut self) {
+        let center = GAME_AREA.center();
+        let clear = self
+            .asteroids
+            .iter()
+            .all(|asteroid| !asteroid.hits(center, SAFE_DISTANCE))
+            && self
+                .ufo
+                .as_ref()
+                .is_none_or(|ufo| ufo.position.distance(center) > SAFE_DISTANCE);
+
+        if clear {
+            self.ship = Some(Ship::new());
+        }
+    }
+
+    // An extra life every `EXTRA_LIFE_POINTS`
+    fn add_points(&mut self, points: u32) {
+        if (self.score + points) / EXTRA_LIFE_POINTS > self.score / EXTRA_LIFE_POINTS {
+            self.lives += 1;
+        }
+        self.score += points;
+    }
+
+    /// Every line to draw
+    pub fn lines(&self) -> Vec<(Vec2, Vec2)> {
+        let mut lines = Vec::new();
+
+        if let Some(ship) = &self.ship {
+            let rotation = Vec2::from_angle(ship.angle);
+            let place = |point: &Vec2| ship.position + rotation.rotate(*point);
+            let shape: Vec<Vec2> = SHIP_SHAPE.iter().map(place).collect();
+            lines.extend(polygon(&shape));
+
+            if ship.thrusting {
+                let flame: Vec<Vec2> = FLAME_SHAPE.iter().map(place).collect();
+                lines.extend(flame.windows(2).map(|line| (line[0], line[1])));
+            }
+        }
+
+        for asteroid in &self.asteroids {
+            lines.extend(polygon(&asteroid.outline()));
+        }
+
+        if let Some(ufo) = &self.ufo {
+            lines.extend(ufo.outline());
+        }
+
+        for shot in self.shots.iter().chain(&self.ufo_shots) {
+            let tail = shot.velocity.normalize_or_zero() * SHOT_LENGTH;
+            lines.push((shot.position - tail, shot.position));
+        }
+
+        lines
+    }
+}
+
+#[derive(Component)]
+struct GameEntity;
+
+// Sprite drawing one of `AsteroidsGame::lines`
+#[derive(Component)]
+struct Line(usize);
+
+#[derive(Component)]
+struct Hud;
+
+fn load_game(
+    mut commands: Commands,
+    mut load_events: EventReader<LoadGameEvent>,
+    mut game_rng: ResMut<GameRng>,
+    entities: Query<Entity, With<GameEntity>>,
+) {
+    if let Some(load_event) = load_events.read().last() {
+        if load_event.restart {
+            game_rng.restart_run();
+        } else {
+            game_rng.new_run();
+        }
+    }
+
+    for entity in &entities {
+        commands.entity(entity).despawn();
+    }
+
+    commands.insert_resource(AsteroidsGame::new(&mut || game_rng.random()));
+
+    commands.spawn((
+        SpriteBundle {
+            sprite: Sprite {
+                color: Color::BLACK,
+                custom_size: Some(GAME_AREA.size()),
+                ..Default::default()
+            },
+            transform: Transform::from_translation(Vec3::from((GAME_AREA.center(), -1.0))),
+            ..Default::default()
+        },
+        GameEntity,
+    ));
+
+    commands.spawn((
+        Text2dBundle {
+            text: Text::from_section(
+                "",
+                TextStyle {
+                    font_size: 24.0,
+                    color: Color::WHITE,
+                    ..Default::default()
+                },
+            ),
+            transform: Transform::from_translation(Vec3::from((
+                Vec2::new(GAME_AREA.center().x, GAME_AREA.max.y - 15.0),
+                1.0,
+            ))),
+            ..Default::default()
+        },
+        Hud,
+        GameEntity,
+    ));
+}
+
+fn cleanup_game(mut commands: Commands, entities: Query<Entity, With<GameEntity>>) {
+    for entity in &entities {
+        commands.entity(entity).despawn();
+    }
+
+    commands.remove_resource::<AsteroidsGame>();
+}
+
+fn handle_player_input(
+    time: Res<Time>,
+    input: Res<ButtonInput<Action>>,
+    mut game: ResMut<AsteroidsGame>,
+    mut game_rng: ResMut<GameRng>,
+    mut next_asteroids_state: ResMut<NextState<AsteroidsState>>,
+) {
+    let rotation = input.pressed(Action::MoveLeft) as i32 - input.pressed(Action::MoveRight) as i32;
+    game.steer(
+        rotation as f32,
+        input.pressed(Action::MoveUp),
+        time.delta_seconds(),
+    );
+
+    if input.just_pressed(Action::Fire) {
+        game.fire();
+    }
+    if input.just_pressed(Action::Hyperspace) {
+        game.hyperspace(&mut || game_rng.random());
+    }
+
+    if input.just_pressed(Action::Pause) {
+        next_asteroids_state.set(AsteroidsState::InMenu);
+    }
+}
+
+fn update_game(time: Res<Time>, mut game: ResMut<AsteroidsGame>, mut game_rng: ResMut<GameRng>) {
+    game.update(time.delta_seconds(), || game_rng.random());
+}
+
+// Thin sprites along the lines of the game, more are spawned when there are
+// more lines than ever and the extra ones hidden
+fn draw_lines(
+    mut commands: Commands,
+    game: Res<AsteroidsGame>,
+    mut sprites: Query<(&Line, &mut Transform, &mut Sprite, &mut Visibility)>,
+) {
+    let lines = game.lines();
+    let place = |(from, to): (Vec2, Vec2)| {
+        let along = to - from;
+        let transform = Transform::from_translation(Vec3::from(((from + to) / 2.0, 0.0)))
+            .with_rotation(Quat::from_rotation_z(along.to_angle()));
+        (
+            transform,
+            Vec2::new(along.length() + LINE_WIDTH, LINE_WIDTH),
+        )
+    };
+
+    let mut nb_sprites = 0;
+    for (Line(index), mut transform, mut sprite, mut visibility) in &mut sprites {
+        nb_sprites += 1;
+        match lines.get(*index) {
+            Some(&line) => {
+                let (placed, size) = place(line);
+                *transform = placed;
+                sprite.custom_size = Some(size);
+                *visibility = Visibility::Inherited;
+            }
+            None => *visibility = Visibility::Hidden,
+        }
+    }
+
+    for (index, &line) in lines.iter().enumerate().skip(nb_sprites) {
+        let (transform, size) = place(line);
+        commands.spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    color: Color::WHITE,
+                    custom_size: Some(size),
+                    ..Default::default()
+                },
+                transform,
+                ..Default::default()
+            },
+            Line(index),
+            GameEntity,
+        ));
+    }
+}
+
+fn update_hud(game: Res<AsteroidsGame>, mut hud: Query<&mut Text, With<Hud>>) {
+    if let Ok(mut text) = hud.get_single_mut() {
+        text.sections[0].value = format!(
+            "Score: {}    Wave: {}    Lives: {}",
+            game.score, game.wave, game.lives
+        );
+    }
+}
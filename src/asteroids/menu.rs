@@ -0,0 +1,46 @@
+use bevy::ecs::system::SystemParamItem;
+use bevy::prelude::*;
+
+use crate::game_menu::{GameMenu, GameMenuAction, GameMenuPlugin, Scores};
+use crate::menu_tree::Menu;
+
+use super::{
+    game::{AsteroidsGame, CleanupGameEvent, LoadGameEvent},
+    AsteroidsPlugin, AsteroidsState,
+};
+
+pub struct MenuPlugin;
+
+impl Plugin for MenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(GameMenuPlugin::<AsteroidsPlugin>::default());
+    }
+}
+
+impl GameMenu for AsteroidsPlugin {
+    type State = AsteroidsState;
+    const EXITED: AsteroidsState = AsteroidsState::Exited;
+    const IN_MENU: AsteroidsState = AsteroidsState::InMenu;
+    const IN_GAME: AsteroidsState = AsteroidsState::InGame;
+
+    type Game = AsteroidsGame;
+    type LoadGameEvent = LoadGameEvent;
+    type CleanupGameEvent = CleanupGameEvent;
+
+    type Action = ();
+    type MenuData = Scores<'static, AsteroidsPlugin>;
+
+    const COLOR: Color = Color::WHITE;
+
+    fn main_menu(scores: &SystemParamItem<Self::MenuData>) -> Menu<GameMenuAction<Self>> {
+        scores.main_menu()
+    }
+
+    fn status(scores: &SystemParamItem<Self::MenuData>, game: Option<&AsteroidsGame>) -> String {
+        scores.status(game.map(|game| game.score))
+    }
+
+    fn load_game_event(restart: bool) -> LoadGameEvent {
+        LoadGameEvent { restart }
+    }
+}
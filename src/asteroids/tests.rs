@@ -0,0 +1,252 @@
+use bevy::prelude::*;
+
+use crate::{
+    application::GAME_AREA,
+    testing::{self, game, play_game, press, release, run_frames, state, step_until, tap},
+};
+
+use super::{
+    game::{AsteroidSize, AsteroidsGame, LIVES, MAX_SHOTS},
+    *,
+};
+
+// Always gives `value`, instead of random numbers
+fn fixed(value: f32) -> impl FnMut() -> f32 {
+    move || value
+}
+
+// A game with only the ship, at the center
+fn empty_game() -> AsteroidsGame {
+    let mut game = AsteroidsGame::new(&mut fixed(0.5));
+    game.asteroids.clear();
+
+    game
+}
+
+// Updates until the ship's shots are gone
+fn update_until_shots_are_gone(game: &mut AsteroidsGame) {
+    step_until(
+        game,
+        120,
+        |game| game.update(1.0 / 60.0, fixed(0.5)),
+        |game| game.shots.is_empty(),
+    );
+}
+
+#[test]
+fn the_ship_keeps_drifting_and_wraps_around() {
+    let mut game = empty_game();
+
+    game.steer(0.0, true, 0.5);
+    game.update(0.5, fixed(0.5));
+    let ship = game.ship.unwrap();
+    assert!(ship.position.y > GAME_AREA.center().y);
+    assert_eq!(ship.position.x, GAME_AREA.center().x);
+
+    game.steer(0.0, false, 1.0);
+    game.update(1.0, fixed(0.5));
+    let drifting = game.ship.unwrap();
+    assert!(drifting.velocity.y > 0.0);
+    assert!(drifting.velocity.y < ship.velocity.y);
+    assert!(drifting.position.y > ship.position.y);
+
+    let ship = game.ship.as_mut().unwrap();
+    ship.position.y = GAME_AREA.max.y - 2.0;
+    ship.velocity = Vec2::new(0.0, 100.0);
+    game.update(0.1, fixed(0.5));
+    let position = game.ship.unwrap().position;
+    assert!(position.y > GAME_AREA.min.y && position.y < GAME_AREA.center().y);
+}
+
+#[test]
+fn the_ship_fires_where_it_points_a_few_shots_at_a_time() {
+    let mut game = empty_game();
+
+    // A quarter turn to the left
+    game.steer(1.0, false, 0.35);
+    for _ in 0..MAX_SHOTS {
+        assert!(game.fire());
+    }
+    assert!(!game.fire());
+    assert!(game.shots.iter().all(|shot| shot.velocity.x < 0.0));
+
+    update_until_shots_are_gone(&mut game);
+    assert!(game.fire());
+}
+
+#[test]
+fn shot_asteroids_split_into_smaller_ones() {
+    let mut game = AsteroidsGame::new(&mut fixed(0.5));
+    game.asteroids.truncate(1);
+    game.score = 9_990;
+    let lives = game.lives;
+
+    let above = game.ship.unwrap().position + Vec2::Y * 100.0;
+    for (size, nb_asteroids) in [
+        (AsteroidSize::Large, 2),
+        (AsteroidSize::Medium, 3),
+        (AsteroidSize::Small, 2),
+    ] {
+        // The others out of the way, the next one of `size` right in front
+        // of the ship
+        for asteroid in &mut game.asteroids {
+            asteroid.position = GAME_AREA.min;
+            asteroid.velocity = Vec2::ZERO;
+        }
+        let asteroid = game
+            .asteroids
+            .iter_mut()
+            .find(|asteroid| asteroid.size == size)
+            .unwrap();
+        asteroid.position = above;
+
+        let score = game.score;
+        game.fire();
+        update_until_shots_are_gone(&mut game);
+        assert_eq!(game.asteroids.len(), nb_asteroids);
+        assert_eq!(game.score, score + size.points());
+    }
+
+    // An extra life at 10000 points
+    assert_eq!(game.lives, lives + 1);
+}
+
+#[test]
+fn a_cleared_wave_brings_more_asteroids() {
+    let mut game = empty_game();
+    let nb_asteroids = AsteroidsGame::new(&mut fixed(0.5)).asteroids.len();
+
+    for _ in 0..3 {
+        game.update(1.0, fixed(0.5));
+    }
+    assert_eq!(game.wave, 2);
+    assert!(game.asteroids.len() > nb_asteroids);
+    assert!(game
+        .asteroids
+        .iter()
+        .all(|asteroid| asteroid.size == AsteroidSize::Large));
+}
+
+#[test]
+fn crashing_costs_a_life_and_the_ship_comes_back_once_the_center_is_clear() {
+    let mut game = AsteroidsGame::new(&mut fixed(0.5));
+    game.asteroids.truncate(1);
+    game.asteroids[0].position = GAME_AREA.center();
+    game.asteroids[0].velocity = Vec2::ZERO;
+
+    game.update(0.01, fixed(0.5));
+    assert!(game.ship.is_none());
+    assert_eq!(game.lives, LIVES - 1);
+    assert!(!game.game_over);
+
+    // The pieces are in the way
+    for asteroid in &mut game.asteroids {
+        asteroid.velocity = Vec2::ZERO;
+    }
+    game.update(3.0, fixed(0.5));
+    assert!(game.ship.is_none());
+
+    for asteroid in &mut game.asteroids {
+        asteroid.position = GAME_AREA.min;
+    }
+    game.update(0.01, fixed(0.5));
+    assert_eq!(game.ship.unwrap().position, GAME_AREA.center());
+
+    game.lives = 1;
+    game.asteroids[0].position = GAME_AREA.center();
+    game.update(0.01, fixed(0.5));
+    assert!(game.game_over);
+}
+
+#[test]
+fn hyperspace_jumps_anywhere_but_can_go_wrong() {
+    let mut game = empty_game();
+    game.ship.as_mut().unwrap().velocity = Vec2::new(50.0, 0.0);
+
+    let mut values = [0.5, 0.25, 0.75].into_iter();
+    assert!(game.hyperspace(&mut || values.next().unwrap()));
+    let ship = game.ship.unwrap();
+    assert_eq!(
+        ship.position,
+        GAME_AREA.min + GAME_AREA.size() * Vec2::new(0.25, 0.75)
+    );
+    assert_eq!(ship.velocity, Vec2::ZERO);
+
+    // Not again straight away
+    assert!(!game.hyperspace(&mut fixed(0.5)));
+
+    game.update(1.5, fixed(0.5));
+    assert!(game.hyperspace(&mut fixed(0.05)));
+    assert!(game.ship.is_none());
+    assert_eq!(game.lives, LIVES - 1);
+}
+
+#[test]
+fn ufos_cross_the_screen_and_can_be_shot_down() {
+    let mut game = AsteroidsGame::new(&mut fixed(0.5));
+    for asteroid in &mut game.asteroids {
+        asteroid.position = GAME_AREA.min;
+        asteroid.velocity = Vec2::ZERO;
+    }
+
+    // Coming from the right
+    game.update(14.99, fixed(0.9));
+    assert!(game.ufo.is_none());
+    game.update(0.02, fixed(0.9));
+    let ufo = game.ufo.clone().expect("The UFO should show up");
+    assert!(!ufo.small);
+    assert!(ufo.position.x > GAME_AREA.center().x);
+
+    // Under it, a bit ahead
+    game.ship.as_mut().unwrap().position = ufo.position - Vec2::new(15.0, 100.0);
+
+    game.fire();
+    update_until_shots_are_gone(&mut game);
+    assert!(game.ufo.is_none());
+    assert_eq!(game.score, ufo.points());
+}
+
+#[test]
+fn the_ship_flies_and_the_game_pauses() {
+    let mut app = play_game(AsteroidsPlugin);
+    let start = game::<AsteroidsPlugin>(&app).ship.unwrap().position;
+
+    press(&mut app, KeyCode::ArrowUp);
+    run_frames(&mut app, 10);
+    release(&mut app, KeyCode::ArrowUp);
+    assert!(game::<AsteroidsPlugin>(&app).ship.unwrap().position.y > start.y);
+
+    tap(&mut app, KeyCode::Space);
+    assert_eq!(game::<AsteroidsPlugin>(&app).shots.len(), 1);
+
+    tap(&mut app, KeyCode::Escape);
+    assert_eq!(state::<AsteroidsState>(&app), AsteroidsState::InMenu);
+    let position = game::<AsteroidsPlugin>(&app).ship.unwrap().position;
+    run_frames(&mut app, 10);
+    assert_eq!(
+        game::<AsteroidsPlugin>(&app).ship.unwrap().position,
+        position
+    );
+
+    // "Resume"
+    tap(&mut app, KeyCode::Space);
+    assert_eq!(state::<AsteroidsState>(&app), AsteroidsState::InGame);
+}
+
+#[test]
+fn game_over_keeps_the_best_score_and_exit_cleans_up() {
+    // The last ship lost to a large asteroid, which is also shot
+    testing::game_over_keeps_the_best_score_and_exit_cleans_up(
+        AsteroidsPlugin,
+        |app| {
+            run_frames(app, 5);
+            let mut game = app.world_mut().resource_mut::<AsteroidsGame>();
+            game.score = 450;
+            game.lives = 1;
+            let ship = game.ship.unwrap().position;
+            game.asteroids[0].position = ship;
+        },
+        10,
+        450 + AsteroidSize::Large.points(),
+    );
+}
@@ -0,0 +1,144 @@
+//! Balls bouncing off bricks, walls and paddles, for the games playing with
+//! one
+
+use bevy::prelude::*;
+
+#[derive(Component, Clone, Copy, Debug)]
+pub struct Ball {
+    pub radius: f32,
+
+    /// Direction the ball goes in, each game moves it at its own speed
+    pub velocity: Vec2,
+
+    pub current_position: Vec2,
+    pub old_position: Vec2,
+}
+
+impl Ball {
+    /// Bounces off `rect` at the first place it touched it since the last
+    /// move, returns whether it did
+    pub fn bounce(&mut self, rect: Rect) -> bool {
+        let nb_steps = 50;
+
+        let travel_line = self.current_position - self.old_position;
+
+        for i in 0..nb_steps {
+            let step = i as f32 / (nb_steps as f32 - 1.0);
+
+            let mut new_ball = Ball {
+                current_position: self.old_position + travel_line * step,
+                ..*self
+            };
+
+            if new_ball.bounce_of_rect(rect) {
+                *self = new_ball;
+                return true;
+            }
+        }
+
+        false
+    }
+
+    pub fn bounce_of_rect(&mut self, rect: Rect) -> bool {
+        let closet_point = Vec2 {
+            x: f32::clamp(self.current_position.x, rect.min.x, rect.max.x),
+            y: f32::clamp(self.current_position.y, rect.min.y, rect.max.y),
+        };
+
+        let to_rect = closet_point - self.current_position;
+        let dist = to_rect.length();
+
+        if dist < self.radius {
+            let overlap = self.radius - dist;
+            let n = to_rect.normalize();
+            self.current_position -= n * (overlap + 0.001);
+
+            self.velocity -= n * self.velocity.dot(n) * 2.0;
+
+            return true;
+        }
+
+        false
+    }
+
+    /// Bounces off the side of `player_rect` facing the `facing` direction,
+    /// if the ball went through it during the last move. Returns whether it
+    /// did.
+    pub fn bounce_of_player_rect(&mut self, player_rect: Rect, facing: Vec2) -> bool {
+        let extended_player_rect =
+            Rect::from_center_size(player_rect.center(), player_rect.size() + self.radius);
+
+        let half_size = extended_player_rect.half_size();
+        let side_center = extended_player_rect.center() + facing * half_size.dot(facing.abs());
+        let half_side = facing.perp() * half_size.dot(facing.perp().abs());
+
+        let p11 = side_center - half_side;
+        let p12 = side_center + half_side;
+
+        let p21 = self.old_position;
+        let p22 = self.current_position;
+
+        if segment_intersets(p11, p12, p21, p22) {
+            self.bounce_off_player(player_rect, facing);
+            return true;
+        }
+
+        false
+    }
+
+    /// Sends the ball back in the `facing` direction, the further from the
+    /// middle of the side it hit the more it goes sideways
+    pub fn bounce_off_player(&mut self, player_rect: Rect, facing: Vec2) {
+        let along = facing.perp();
+        let offset = (self.current_position - player_rect.center()).dot(along);
+
+        let hit = offset / player_rect.size().dot(along.abs());
+        let hit = hit.clamp(-1.0, 1.0);
+
+        let out_dir = hit * along + hit.acos().sin() * facing;
+        self.velocity = out_dir;
+
+        let depth = player_rect.half_size().dot(facing.abs()) + self.radius;
+        self.current_position = player_rect.center() + offset * along + depth * facing;
+    }
+}
+
+#[derive(PartialEq, Eq, Debug)]
+enum PointOrientation {
+    Clockwise,
+    CounterClockwise,
+    Colinear,
+}
+
+fn segment_intersets(p11: Vec2, p12: Vec2, p21: Vec2, p22: Vec2) -> bool {
+    let ori_1 = get_orientation(p11, p12, p21);
+    let ori_2 = get_orientation(p11, p12, p22);
+
+    if ori_1 == ori_2 {
+        return false;
+    }
+
+    let ori_1 = get_orientation(p21, p22, p11);
+    let ori_2 = get_orientation(p21, p22, p12);
+
+    if ori_1 == ori_2 {
+        return false;
+    }
+
+    true
+}
+
+fn get_orientation(p1: Vec2, p2: Vec2, p3: Vec2) -> PointOrientation {
+    let diff = (p2.y - p1.y) * (p3.x - p2.x) - (p3.y - p2.y) * (p2.x - p1.x);
+
+    if diff == 0.0 {
+        PointOrientation::Colinear
+    } else if diff < 0.0 {
+        PointOrientation::Clockwise
+    } else {
+        PointOrientation::CounterClockwise
+    }
+}
+
+#[cfg(test)]
+mod tests;
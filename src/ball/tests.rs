@@ -0,0 +1,55 @@
+use super::*;
+
+fn moving_ball(from: Vec2, to: Vec2) -> Ball {
+    Ball {
+        radius: 10.0,
+        velocity: (to - from).normalize(),
+        current_position: to,
+        old_position: from,
+    }
+}
+
+#[test]
+fn bounces_off_the_side_it_hits() {
+    let rect = Rect::from_center_size(Vec2::ZERO, Vec2::splat(40.0));
+
+    // Into the left side, going right
+    let mut ball = moving_ball(Vec2::new(-40.0, 0.0), Vec2::new(-25.0, 0.0));
+    assert!(ball.bounce(rect));
+    assert!(ball.velocity.x < 0.0);
+    assert!(ball.current_position.x <= -30.0);
+
+    // Passing above it
+    let mut ball = moving_ball(Vec2::new(-40.0, 40.0), Vec2::new(40.0, 40.0));
+    assert!(!ball.bounce(rect));
+}
+
+#[test]
+fn players_send_the_ball_back_the_way_they_face() {
+    // A paddle at the bottom, facing up
+    let paddle = Rect::from_center_size(Vec2::ZERO, Vec2::new(100.0, 10.0));
+
+    let mut middle = moving_ball(Vec2::new(0.0, 30.0), Vec2::new(0.0, 5.0));
+    assert!(middle.bounce_of_player_rect(paddle, Vec2::Y));
+    assert!((middle.velocity - Vec2::Y).length() < 0.001);
+    assert_eq!(middle.current_position, Vec2::new(0.0, 15.0));
+
+    // Further right, further right
+    let mut right = moving_ball(Vec2::new(25.0, 30.0), Vec2::new(25.0, 5.0));
+    assert!(right.bounce_of_player_rect(paddle, Vec2::Y));
+    assert!(right.velocity.x > 0.0 && right.velocity.y > 0.0);
+    assert!((right.velocity.length() - 1.0).abs() < 0.001);
+
+    // A paddle on the left side, facing right
+    let paddle = Rect::from_center_size(Vec2::ZERO, Vec2::new(10.0, 100.0));
+
+    let mut above = moving_ball(Vec2::new(30.0, 25.0), Vec2::new(5.0, 25.0));
+    assert!(above.bounce_of_player_rect(paddle, Vec2::X));
+    assert!(above.velocity.x > 0.0 && above.velocity.y > 0.0);
+    assert_eq!(above.current_position, Vec2::new(15.0, 25.0));
+
+    // Going away from it
+    let mut away = moving_ball(Vec2::new(15.0, 0.0), Vec2::new(40.0, 0.0));
+    assert!(!away.bounce_of_player_rect(paddle, Vec2::X));
+    assert_eq!(away.velocity, Vec2::X);
+}
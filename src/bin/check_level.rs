@@ -0,0 +1,40 @@
+//! Plays a Breakout level with the autoplayer and reports how it went:
+//!
+//! `check_level <level file> [runs] [time limit in seconds]`
+//!
+//! Run it from the repository, where the game finds its assets.
+
+use std::{env, process::ExitCode};
+
+use retro::breakout::check_level;
+
+const DEFAULT_NB_RUNS: usize = 20;
+const DEFAULT_TIME_LIMIT: f32 = 300.0;
+
+fn main() -> ExitCode {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let Some(path) = args.first() else {
+        eprintln!("Usage: check_level <level file> [runs] [time limit in seconds]");
+        return ExitCode::FAILURE;
+    };
+
+    let nb_runs = args.get(1).map_or(Ok(DEFAULT_NB_RUNS), |arg| arg.parse());
+    let time_limit = args
+        .get(2)
+        .map_or(Ok(DEFAULT_TIME_LIMIT), |arg| arg.parse());
+    let (Ok(nb_runs), Ok(time_limit)) = (nb_runs, time_limit) else {
+        eprintln!("The number of runs and the time limit should be numbers");
+        return ExitCode::FAILURE;
+    };
+
+    match check_level(path, nb_runs, time_limit) {
+        Ok(report) => {
+            println!("{path}\n{report}");
+            ExitCode::SUCCESS
+        }
+        Err(error) => {
+            eprintln!("Could not read {path}: {error}");
+            ExitCode::FAILURE
+        }
+    }
+}
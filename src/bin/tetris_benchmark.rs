@@ -0,0 +1,38 @@
+//! Plays Tetris games with the bot and reports the lines it cleared, to
+//! tune the weights of its heuristics:
+//!
+//! `tetris_benchmark [weights file] [games] [max pieces per game]`
+//!
+//! Run it from the repository, where the default weights file is.
+
+use std::{env, process::ExitCode};
+
+use retro::tetris::{benchmark, Weights, WEIGHTS_FILE};
+
+const DEFAULT_NB_GAMES: usize = 20;
+const DEFAULT_MAX_PIECES: usize = 1000;
+
+fn main() -> ExitCode {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let path = args.first().map_or(WEIGHTS_FILE, String::as_str);
+
+    let nb_games = args.get(1).map_or(Ok(DEFAULT_NB_GAMES), |arg| arg.parse());
+    let max_pieces = args
+        .get(2)
+        .map_or(Ok(DEFAULT_MAX_PIECES), |arg| arg.parse());
+    let (Ok(nb_games), Ok(max_pieces)) = (nb_games, max_pieces) else {
+        eprintln!("The number of games and of pieces should be numbers");
+        return ExitCode::FAILURE;
+    };
+
+    match Weights::load(path) {
+        Ok(weights) => {
+            println!("{path}\n{}", benchmark(&weights, nb_games, max_pieces));
+            ExitCode::SUCCESS
+        }
+        Err(error) => {
+            eprintln!("Could not read {path}: {error}");
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -1,9 +1,35 @@
-use std::{f32::consts::PI, usize};
+use std::{f32::consts::PI, time::Duration};
 
 use bevy::color::palettes;
+use bevy::ecs::system::SystemParam;
 use bevy::prelude::*;
 
 use crate::application::{CurrentGame, GAME_AREA, GAME_SIZE, WINDOW_RESOLUTION};
+use crate::ball::Ball;
+use crate::demo::{Demo, DemoSystems};
+use crate::input::{Action, ActionSource, ActionSystem, AnalogInput, InputMap};
+use crate::launch::LaunchGame;
+use crate::menu_tree::{
+    Menu, MenuActionEvent, MenuRoot, MenuStyle, MenuTreePlugin, MenuTreeSystems,
+};
+use crate::progress::{CampaignProgress, LevelRecord};
+use crate::registry::RetroGame;
+use crate::replay::{AddReplayedResource, ReplayOrDemo};
+use crate::rng::GameRng;
+use crate::saves::SavedGames;
+use crate::scores::BestScores;
+
+mod autoplayer;
+use autoplayer::Assist;
+
+mod level;
+use level::{brick_cell, brick_position, load_levels, Level};
+
+mod level_check;
+
+mod save;
+pub use level_check::{check_level, LevelReport};
+use save::BreakoutSave;
 
 const PLAYER_SIZE: Vec2 = Vec2 { x: 150.0, y: 15.0 };
 const PLAYER_GROW_SIZE: Vec2 = Vec2 { x: 300.0, y: 15.0 };
@@ -13,6 +39,13 @@ const PLAYER_AXIS: f32 = -GAME_SIZE.y / 2.0 + 20.0;
 const PLAYER_SPEED: f32 = 300.0;
 const BALL_SPEED: f32 = 300.0;
 const UPGRADE_SPEED: f32 = 100.0;
+// Seconds the paddle keeps the size an upgrade gave it
+const UPGRADE_DURATION: f32 = 15.0;
+
+// Points for each life taken off a brick, and for each second under the par
+// time of a completed level
+const BRICK_POINTS: u32 = 10;
+const TIME_BONUS: u32 = 10;
 
 const BALL_COLOR: Color = Color::srgb(0.2, 1.0, 0.2);
 
@@ -33,12 +66,45 @@ const UPGRADE_SIZE: Vec2 = Vec2 { x: 40.0, y: 20.0 };
 
 const TOTAL_LEVELS: usize = 5;
 
+// Share of the ball speed added by each New Game+, whose normal bricks also
+// get one more life each time
+const NEW_GAME_PLUS_SPEED_UP: f32 = 0.2;
+
+// Seconds counted down before the ball moves again after a pause
+const RESUME_COUNTDOWN: f32 = 3.0;
+
 pub struct BreackoutPlugin;
 
+impl RetroGame for BreackoutPlugin {
+    const NAME: &'static str = "Breakout";
+    const ICON: &'static str = "breakout/sprites/bricks/normal_1.png";
+    const DESCRIPTION: &'static str =
+        "Bounce the ball off the paddle to break every brick, five levels with upgrades.";
+    const CONTROLS: &'static [Action] = &[
+        Action::MoveLeft,
+        Action::MoveRight,
+        Action::Launch,
+        Action::Pause,
+    ];
+    const HAS_DEMO: bool = true;
+}
+
 impl Plugin for BreackoutPlugin {
     fn build(&self, app: &mut App) {
-        app.insert_state(GameState::Exited)
+        app.add_plugins(MenuTreePlugin::<BreakoutMenuAction>::default())
+            .configure_sets(
+                Update,
+                MenuTreeSystems::<BreakoutMenuAction>::default()
+                    .run_if(in_state(GameState::InMenu)),
+            )
+            .insert_state(GameState::Exited)
             .insert_state(InGameState::Paused)
+            .init_resource::<Assist>()
+            .init_resource::<SavedGames>()
+            .init_resource::<CampaignProgress>()
+            .add_replayed_resource::<Assist>()
+            .add_replayed_resource::<SavedGames>()
+            .add_replayed_resource::<CampaignProgress>()
             .add_event::<LoadLevelEvent>()
             .add_event::<SpawnBallEvent>()
             .add_event::<SpawnUpgradeEvent>()
@@ -47,39 +113,80 @@ impl Plugin for BreackoutPlugin {
             .add_event::<DespawnUpgradeEvent>()
             .add_event::<GameOverEvent>()
             .add_event::<GameWonEvent>()
-            .add_systems(OnEnter(CurrentGame::Breakout), (load_game, load_menu))
             .add_systems(
-                OnExit(CurrentGame::Breakout),
-                (cleanup_level, cleanup_game, cleanup_menu).chain(),
+                OnEnter(BreackoutPlugin::STATE),
+                (
+                    load_game,
+                    load_menu,
+                    launch
+                        .after(load_game)
+                        .run_if(resource_exists::<LaunchGame>),
+                ),
+            )
+            .add_systems(
+                OnExit(BreackoutPlugin::STATE),
+                (cleanup_level, cleanup_game, cleanup_menu, close_breakout).chain(),
             )
             .add_systems(
                 OnEnter(GameState::InMenu),
-                show_menu.run_if(in_state(CurrentGame::Breakout)),
+                (show_menu, save_level.run_if(can_save)).run_if(in_state(BreackoutPlugin::STATE)),
             )
             .add_systems(
                 OnExit(GameState::InMenu),
-                hide_menu.run_if(in_state(CurrentGame::Breakout)),
+                hide_menu.run_if(in_state(BreackoutPlugin::STATE)),
+            )
+            .add_systems(
+                OnEnter(InGameState::Resuming),
+                start_resume_countdown.run_if(in_state(BreackoutPlugin::STATE)),
+            )
+            .add_systems(
+                OnExit(InGameState::Resuming),
+                hide_resume_countdown.run_if(in_state(BreackoutPlugin::STATE)),
             )
             .add_systems(
                 PreUpdate,
                 (
-                    (cleanup_level, (setup, load_player, load_ball, load_level))
+                    autoplayer::autoplay.in_set(DemoSystems),
+                    autoplayer::assist
+                        .after(ActionSystem)
+                        .run_if(resource_equals(ActionSource::Player))
+                        .run_if(resource_equals(Assist(true)))
+                        .run_if(in_state(GameState::InGame))
+                        .run_if(in_state(InGameState::Playing)),
+                )
+                    .run_if(in_state(BreackoutPlugin::STATE)),
+            )
+            .add_systems(
+                PreUpdate,
+                (
+                    (
+                        cleanup_level,
+                        (setup, load_player, load_ball, load_level),
+                        harden_bricks,
+                    )
                         .chain()
                         .run_if(on_event::<LoadLevelEvent>()),
                     spawn_ball.run_if(on_event::<SpawnBallEvent>()),
                     spawn_upgrade.run_if(on_event::<SpawnUpgradeEvent>()),
                 )
-                    .run_if(in_state(CurrentGame::Breakout)),
+                    .run_if(in_state(BreackoutPlugin::STATE)),
             )
             .add_systems(
                 Update,
                 (
                     (
-                        (handle_menu_navigation_input, handle_menu_select_input),
-                        update_menu,
+                        (
+                            handle_menu_action,
+                            handle_campaign_action,
+                            continue_level,
+                            resume_countdown,
+                            change_settings,
+                        )
+                            .run_if(on_event::<MenuActionEvent<BreakoutMenuAction>>()),
+                        update_shown_menu.run_if(resource_exists_and_changed::<MenuState>),
                     )
                         .chain()
-                        .run_if(in_state(GameState::InMenu)),
+                        .after(MenuTreeSystems::<BreakoutMenuAction>::default()),
                     (
                         (
                             (
@@ -93,13 +200,15 @@ impl Plugin for BreackoutPlugin {
                             handle_player_input,
                             (update_upgrades, catch_upgrade).chain(),
                             tick_upgrade_timer,
+                            tick_level_time,
                         )
                             .run_if(in_state(InGameState::Playing)),
                         handle_pause_input.run_if(in_state(InGameState::Paused)),
+                        tick_resume_countdown.run_if(in_state(InGameState::Resuming)),
                     )
                         .run_if(in_state(GameState::InGame)),
                 )
-                    .run_if(in_state(CurrentGame::Breakout)),
+                    .run_if(in_state(BreackoutPlugin::STATE)),
             )
             .add_systems(
                 PostUpdate,
@@ -107,135 +216,52 @@ impl Plugin for BreackoutPlugin {
                     despawn_ball.run_if(on_event::<DespawnBallEvent>()),
                     despawn_brick.run_if(on_event::<DespawnBrickEvent>()),
                     despawn_upgrade.run_if(on_event::<DespawnUpgradeEvent>()),
-                    (game_over, cleanup_level).run_if(on_event::<GameOverEvent>()),
-                    (game_won, cleanup_level).run_if(on_event::<GameWonEvent>()),
+                    (game_over, cleanup_level, drop_save).run_if(on_event::<GameOverEvent>()),
+                    (
+                        game_won,
+                        cleanup_level,
+                        drop_save,
+                        (record_level, complete_campaign).chain(),
+                    )
+                        .run_if(on_event::<GameWonEvent>()),
+                    save_level.run_if(on_event::<AppExit>()).run_if(can_save),
                 )
-                    .run_if(in_state(CurrentGame::Breakout)),
+                    .run_if(in_state(BreackoutPlugin::STATE)),
             );
     }
 }
 
-#[derive(Component)]
-struct Ball {
-    radius: f32,
-    velocity: Vec2,
-    current_position: Vec2,
-    old_position: Vec2,
+#[derive(Event, Default)]
+struct LoadLevelEvent {
+    level: usize,
+    // Plays the current run again from its seed instead of starting a new one
+    restart: bool,
+    // Puts the level back as it was saved instead
+    save: Option<BreakoutSave>,
 }
 
-impl Ball {
-    fn bounce(&mut self, rect: Rect) -> bool {
-        let nb_steps = 50;
-
-        let travel_line = self.current_position - self.old_position;
-
-        for i in 0..nb_steps {
-            let step = i as f32 / (nb_steps as f32 - 1.0);
-
-            let mut new_ball = Ball {
-                current_position: self.old_position + travel_line * step,
-                ..*self
-            };
-
-            if new_ball.bounce_of_rect(rect) {
-                *self = new_ball;
-                return true;
-            }
-        }
-
-        false
+impl LoadLevelEvent {
+    fn new_run(level: usize) -> Self {
+        Self { level, ..default() }
     }
 
-    fn bounce_of_rect(&mut self, rect: Rect) -> bool {
-        let closet_point = Vec2 {
-            x: f32::clamp(self.current_position.x, rect.min.x, rect.max.x),
-            y: f32::clamp(self.current_position.y, rect.min.y, rect.max.y),
-        };
-
-        let to_rect = closet_point - self.current_position;
-        let dist = to_rect.length();
-
-        if dist < self.radius {
-            let overlap = self.radius - dist;
-            let n = to_rect.normalize();
-            self.current_position -= n * (overlap + 0.001);
-
-            self.velocity -= n * self.velocity.dot(n) * 2.0;
-
-            return true;
+    fn restart(level: usize) -> Self {
+        Self {
+            level,
+            restart: true,
+            ..default()
         }
-
-        false
     }
 
-    fn bounce_of_player_rect(&mut self, player_rect: Rect) {
-        let extended_player_rect =
-            Rect::from_center_size(player_rect.center(), player_rect.size() + self.radius);
-
-        let p11 = Vec2::new(extended_player_rect.min.x, extended_player_rect.max.y);
-        let p12 = Vec2::new(extended_player_rect.max.x, extended_player_rect.max.y);
-
-        let p21 = self.old_position;
-        let p22 = self.current_position;
-
-        if segment_intersets(p11, p12, p21, p22) {
-            self.bounce_off_player(player_rect);
+    fn from_save(save: BreakoutSave) -> Self {
+        Self {
+            level: save.level,
+            save: Some(save),
+            ..default()
         }
     }
-
-    fn bounce_off_player(&mut self, player_rect: Rect) {
-        let hit_x = self.current_position.x - player_rect.center().x;
-        let hit_x = hit_x / player_rect.width();
-        let hit_x = hit_x.clamp(-1.0, 1.0);
-
-        let out_dir_x = hit_x;
-        let out_dir_y = hit_x.acos().sin();
-        let new_dir = Vec2::new(out_dir_x, out_dir_y);
-        self.velocity = new_dir;
-        self.current_position.y = PLAYER_AXIS + player_rect.height() / 2.0 + self.radius;
-    }
-}
-
-#[derive(PartialEq, Eq, Debug)]
-enum PointOrientation {
-    CW,
-    CCW,
-    Colinear,
-}
-
-fn segment_intersets(p11: Vec2, p12: Vec2, p21: Vec2, p22: Vec2) -> bool {
-    let ori_1 = get_orientation(p11, p12, p21);
-    let ori_2 = get_orientation(p11, p12, p22);
-
-    if ori_1 == ori_2 {
-        return false;
-    }
-
-    let ori_1 = get_orientation(p21, p22, p11);
-    let ori_2 = get_orientation(p21, p22, p12);
-
-    if ori_1 == ori_2 {
-        return false;
-    }
-
-    true
 }
 
-fn get_orientation(p1: Vec2, p2: Vec2, p3: Vec2) -> PointOrientation {
-    let diff = (p2.y - p1.y) * (p3.x - p2.x) - (p3.y - p2.y) * (p2.x - p1.x);
-
-    if diff == 0.0 {
-        PointOrientation::Colinear
-    } else if diff < 0.0 {
-        PointOrientation::CW
-    } else {
-        PointOrientation::CCW
-    }
-}
-
-#[derive(Event, Default)]
-struct LoadLevelEvent(usize);
-
 #[derive(Event)]
 struct SpawnBallEvent {
     location: Vec2,
@@ -271,6 +297,7 @@ struct GameAssets {
     upgrade_brick: Handle<Image>,
     grow_upgrade: Handle<Image>,
     shrink_upgrade: Handle<Image>,
+    levels: Vec<Level>,
 }
 
 #[derive(Resource)]
@@ -279,40 +306,149 @@ struct LevelLoaded(bool);
 #[derive(Resource)]
 struct LastLevelPlayed(usize);
 
+// The score and time of the level being played
+#[derive(Resource, Default)]
+struct LevelStats {
+    score: u32,
+    // Seconds played, pauses left out
+    time: f32,
+    balls_lost: u32,
+
+    // Set once the level is completed
+    result: Option<LevelRecord>,
+}
+
+impl LevelStats {
+    // Three stars for completing the level within its par time, two within
+    // twice that time
+    fn complete(&mut self, par_time: f32) -> LevelRecord {
+        let seconds_under_par = (par_time - self.time).max(0.0) as u32;
+        let stars = if self.time <= par_time {
+            3
+        } else if self.time <= 2.0 * par_time {
+            2
+        } else {
+            1
+        };
+
+        let result = LevelRecord {
+            score: self.score + seconds_under_par * TIME_BONUS,
+            time: self.time,
+            stars,
+        };
+        self.result = Some(result);
+        result
+    }
+}
+
+// A completed level of the campaign run
+#[derive(Clone, Copy, PartialEq, Debug)]
+struct LevelSummary {
+    level: usize,
+    record: LevelRecord,
+    balls_lost: u32,
+}
+
+// The levels completed since the campaign was started from "Play", a level
+// of the selection or "New Game+"
+#[derive(Resource, Default)]
+struct CampaignRun {
+    levels: Vec<LevelSummary>,
+    // Times the campaign was completed before this run, each one makes the
+    // balls faster and the bricks tougher
+    new_game_plus: u32,
+
+    // Whether the run made the best score, until its initials are entered
+    new_best: bool,
+    // Letters picked for the initials, from 0 for A
+    initials: [usize; 3],
+}
+
+impl CampaignRun {
+    fn new(new_game_plus: u32) -> Self {
+        Self {
+            new_game_plus,
+            ..default()
+        }
+    }
+
+    fn score(&self) -> u32 {
+        self.levels.iter().map(|summary| summary.record.score).sum()
+    }
+
+    fn time(&self) -> f32 {
+        self.levels.iter().map(|summary| summary.record.time).sum()
+    }
+
+    fn balls_lost(&self) -> u32 {
+        self.levels.iter().map(|summary| summary.balls_lost).sum()
+    }
+
+    // Every level from the first, a run started further only gets a summary
+    fn is_complete(&self) -> bool {
+        self.levels
+            .iter()
+            .map(|summary| summary.level)
+            .eq(1..=TOTAL_LEVELS)
+    }
+
+    fn ball_speed(&self) -> f32 {
+        BALL_SPEED * (1.0 + NEW_GAME_PLUS_SPEED_UP * self.new_game_plus as f32)
+    }
+
+    fn initials(&self) -> String {
+        self.initials
+            .iter()
+            .map(|letter| char::from(b'A' + *letter as u8))
+            .collect()
+    }
+}
+
 #[derive(Resource)]
 struct UpgradeTimer {
     timer: Timer,
 }
 
+#[derive(Resource)]
+struct ResumeCountdown {
+    timer: Timer,
+}
+
 #[derive(Component)]
-struct LastLevelCompleteText;
+struct ResumeCountdownText;
 
 #[derive(Component)]
 struct Background;
 
+#[derive(Component)]
+struct SeedText;
+
 #[derive(States, Debug, PartialEq, Eq, Hash, Clone)]
-enum GameState {
+pub(crate) enum GameState {
     Exited,
     InMenu,
     InGame,
 }
 
 #[derive(States, Debug, PartialEq, Eq, Hash, Clone)]
-enum InGameState {
+pub(crate) enum InGameState {
     Playing,
     Paused,
+    // Counting down after a pause, the ball is still frozen
+    Resuming,
 }
 
 #[derive(Component)]
 struct Player;
 
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
 enum BrickType {
     Normal,
     BallSpawner,
     Upgrade,
 }
 
-#[derive(Component)]
+#[derive(Component, Clone, PartialEq, Debug)]
 struct Brick {
     lives: i32,
     brick_type: BrickType,
@@ -344,46 +480,28 @@ fn load_game(
         Background,
     ));
 
-    // Spawn last level complete text
+    // Spawn the countdown shown when resuming a paused level
     commands.spawn((
         Text2dBundle {
-            text: Text::from_sections([
-                TextSection::new("Congratulations !!!\n", TextStyle {
-                    font_size: 50.0,
+            text: Text::from_section(
+                "",
+                TextStyle {
+                    font_size: 80.0,
                     color: palettes::basic::GREEN.into(),
                     ..Default::default()
-                }),
-                TextSection::new(
-                    "you completed the last level\n\n",
-                    TextStyle {
-                        font_size: 30.0,
-                        color: palettes::basic::GREEN.into(),
-                        ..Default::default()
-                    },
-                ),
-                TextSection::new(
-                    "I was a little too lazy to implement a special menu for this situation\nso both menu options will bring you back to the main menu ^^\"",
-                    TextStyle {
-                        font_size: 15.0,
-                        color: palettes::basic::GREEN.into(),
-                        ..Default::default()
-                    },
-                ),
-            ]).with_justify(JustifyText::Center),
-            transform: Transform::from_translation(Vec3::from((GAME_AREA.center() + Vec2::Y * 150.0, 1.0))),
-            
-            // gets visible once the player completes the last level
-            visibility: Visibility::Hidden, 
+                },
+            ),
+            transform: Transform::from_translation(Vec3::from((GAME_AREA.center(), 2.0))),
+            visibility: Visibility::Hidden,
             ..Default::default()
         },
-        LastLevelCompleteText,
+        ResumeCountdownText,
     ));
 
     // Load game assets
     let ball_sprite = asset_server.load("breakout/sprites/balls/ball.png");
     let player_sprit = asset_server.load("breakout/sprites/player/player.png");
     let brick_sprites = (1..=5)
-        .into_iter()
         .map(|i| asset_server.load(format!("breakout/sprites/bricks/normal_{i}.png")))
         .collect();
     let spawner_brick = asset_server.load("breakout/sprites/bricks/spawner.png");
@@ -399,14 +517,20 @@ fn load_game(
         upgrade_brick,
         grow_upgrade,
         shrink_upgrade,
+        levels: load_levels().expect("The levels should be in assets/breakout/levels"),
     };
 
     commands.insert_resource(game_assets);
     commands.insert_resource(LevelLoaded(false));
     commands.insert_resource(LastLevelPlayed(0));
-    let mut timer = Timer::from_seconds(15.0, TimerMode::Once);
+    commands.init_resource::<LevelStats>();
+    commands.init_resource::<CampaignRun>();
+    let mut timer = Timer::from_seconds(UPGRADE_DURATION, TimerMode::Once);
     timer.pause();
     commands.insert_resource(UpgradeTimer { timer });
+    commands.insert_resource(ResumeCountdown {
+        timer: Timer::from_seconds(RESUME_COUNTDOWN, TimerMode::Once),
+    });
 
     next_game_state.set(GameState::InMenu);
     next_in_game_state.set(InGameState::Paused);
@@ -421,7 +545,9 @@ fn cleanup_level(
     mut level_loaded: ResMut<LevelLoaded>,
 ) {
     if level_loaded.0 {
-        commands.entity(player_entity.single()).despawn();
+        for entity in &player_entity {
+            commands.entity(entity).despawn();
+        }
 
         for entity in &ball_entities {
             commands.entity(entity).despawn();
@@ -439,9 +565,12 @@ fn cleanup_level(
     }
 }
 
+// Entities of the game that are kept from one level to the next
+type GameEntities = Or<(With<ResumeCountdownText>, With<Background>)>;
+
 fn cleanup_game(
     mut commands: Commands,
-    entities: Query<Entity, Or<(With<LastLevelCompleteText>, With<Background>)>>,
+    entities: Query<Entity, GameEntities>,
     mut sprites: ResMut<Assets<Image>>,
     game_assets: Res<GameAssets>,
 ) {
@@ -462,11 +591,89 @@ fn cleanup_game(
     commands.remove_resource::<GameAssets>();
     commands.remove_resource::<LevelLoaded>();
     commands.remove_resource::<LastLevelPlayed>();
+    commands.remove_resource::<LevelStats>();
+    commands.remove_resource::<CampaignRun>();
     commands.remove_resource::<UpgradeTimer>();
+    commands.remove_resource::<ResumeCountdown>();
 }
 
-fn setup(mut next_in_game_state: ResMut<NextState<InGameState>>) {
-    next_in_game_state.set(InGameState::Paused);
+fn close_breakout(mut next_game_state: ResMut<NextState<GameState>>) {
+    next_game_state.set(GameState::Exited);
+}
+
+// Straight into the level given on the command line, if there is one
+fn launch(
+    mut commands: Commands,
+    launch_game: Res<LaunchGame>,
+    mut load_level_event: EventWriter<LoadLevelEvent>,
+    mut next_game_state: ResMut<NextState<GameState>>,
+) {
+    commands.remove_resource::<LaunchGame>();
+
+    if let Some(mode) = &launch_game.mode {
+        warn!("Breakout has no {mode} mode");
+    }
+
+    let Some(level) = launch_game.level else {
+        return;
+    };
+    if !(1..=TOTAL_LEVELS).contains(&level) {
+        warn!("Breakout has no level {level}, only levels 1 to {TOTAL_LEVELS}");
+        return;
+    }
+
+    load_level_event.send(LoadLevelEvent::new_run(level));
+    next_game_state.set(GameState::InGame);
+}
+
+// A fresh paddle, or the saved one with what was left of its upgrade. A
+// saved ball that was launched gets going again after a countdown.
+fn setup(
+    mut load_events: EventReader<LoadLevelEvent>,
+    mut upgrade_timer: ResMut<UpgradeTimer>,
+    mut level_stats: ResMut<LevelStats>,
+    mut campaign_run: ResMut<CampaignRun>,
+    mut next_in_game_state: ResMut<NextState<InGameState>>,
+) {
+    let save = load_events
+        .read()
+        .next()
+        .and_then(|event| event.save.as_ref());
+
+    *level_stats = match save {
+        Some(save) => LevelStats {
+            score: save.score,
+            time: save.time,
+            balls_lost: save.balls_lost,
+            ..default()
+        },
+        None => LevelStats::default(),
+    };
+
+    if let Some(save) = save {
+        *campaign_run = CampaignRun {
+            levels: save.run.clone(),
+            ..CampaignRun::new(save.new_game_plus)
+        };
+    }
+
+    let timer = &mut upgrade_timer.timer;
+    timer.reset();
+    match save.and_then(|save| save.upgrade_left) {
+        Some(upgrade_left) => {
+            let elapsed = timer
+                .duration()
+                .saturating_sub(Duration::from_secs_f32(upgrade_left));
+            timer.set_elapsed(elapsed);
+            timer.unpause();
+        }
+        None => timer.pause(),
+    }
+
+    next_in_game_state.set(match save {
+        Some(save) if save.launched => InGameState::Resuming,
+        _ => InGameState::Paused,
+    });
 }
 
 fn load_level(
@@ -476,6 +683,7 @@ fn load_level(
     mut level_loaded: ResMut<LevelLoaded>,
     mut last_level_played: ResMut<LastLevelPlayed>,
     mut load_events: EventReader<LoadLevelEvent>,
+    mut game_rng: ResMut<GameRng>,
 ) {
     // Clear bricks first
     for brick_entity in &bricks {
@@ -485,66 +693,45 @@ fn load_level(
     let mut event_reader = load_events.read();
 
     if let Some(load_event) = event_reader.next() {
-        let level = load_event.0;
-
-        let level_file = format!("assets/breakout/levels/level_{level}.txt");
-        let file_content = std::fs::read_to_string(level_file).unwrap();
-        let mut char_iter = file_content.split_whitespace();
-
-        let nb_cols = char_iter.next().unwrap().parse::<usize>().unwrap();
-        let nb_rows = char_iter.next().unwrap().parse::<usize>().unwrap();
-
-        for y in 0..nb_rows {
-            for x in 0..nb_cols {
-                let extended_brick_size = BRICK_SIZE + 6.0;
-                let center_offset = extended_brick_size / 2.0;
-                let position = Vec3 {
-                    x: GAME_AREA.min.x + center_offset.x + x as f32 * extended_brick_size.x,
-                    y: GAME_AREA.max.y - center_offset.y - y as f32 * extended_brick_size.y,
-                    z: 1.0,
-                };
-
-                let next_char = char_iter.next().unwrap();
-                let brick = if let Ok(lives) = next_char.parse::<i32>() {
-                    Some(Brick {
-                        lives,
-                        brick_type: BrickType::Normal,
-                    })
-                } else if next_char == "S" {
-                    Some(Brick {
-                        lives: 1,
-                        brick_type: BrickType::BallSpawner,
-                    })
-                } else if next_char == "U" {
-                    Some(Brick {
-                        lives: 1,
-                        brick_type: BrickType::Upgrade,
-                    })
+        let level = load_event.level;
+
+        let bricks = match &load_event.save {
+            Some(save) => {
+                game_rng.start_run(save.seed);
+                &save.bricks
+            }
+            None => {
+                if load_event.restart {
+                    game_rng.restart_run();
                 } else {
-                    None
-                };
-
-                if let Some(brick) = brick {
-                    commands.spawn((
-                        SpriteBundle {
-                            sprite: Sprite {
-                                custom_size: Some(BRICK_SIZE),
-                                ..Default::default()
-                            },
-                            transform: Transform::from_translation(position),
-                            texture: match brick.brick_type {
-                                BrickType::Normal => {
-                                    game_assets.normal_bricks[brick.lives as usize - 1].clone()
-                                }
-                                BrickType::BallSpawner => game_assets.spawner_brick.clone(),
-                                BrickType::Upgrade => game_assets.upgrade_brick.clone(),
-                            },
-                            ..Default::default()
-                        },
-                        brick,
-                    ));
+                    game_rng.new_run();
                 }
+                &game_assets.levels[level - 1].bricks
             }
+        };
+
+        for (cell, brick) in bricks {
+            commands.spawn((
+                SpriteBundle {
+                    sprite: Sprite {
+                        custom_size: Some(BRICK_SIZE),
+                        ..Default::default()
+                    },
+                    transform: Transform::from_translation(Vec3::from((
+                        brick_position(*cell),
+                        1.0,
+                    ))),
+                    texture: match brick.brick_type {
+                        BrickType::Normal => {
+                            game_assets.normal_bricks[brick.lives as usize - 1].clone()
+                        }
+                        BrickType::BallSpawner => game_assets.spawner_brick.clone(),
+                        BrickType::Upgrade => game_assets.upgrade_brick.clone(),
+                    },
+                    ..Default::default()
+                },
+                brick.clone(),
+            ));
         }
 
         level_loaded.0 = true;
@@ -553,19 +740,33 @@ fn load_level(
     }
 }
 
-fn load_player(mut commands: Commands, game_assets: Res<GameAssets>) {
+fn load_player(
+    mut commands: Commands,
+    mut load_events: EventReader<LoadLevelEvent>,
+    game_assets: Res<GameAssets>,
+) {
+    let (x, size) = match load_events
+        .read()
+        .next()
+        .and_then(|event| event.save.as_ref())
+    {
+        Some(save) => (save.paddle_x, Vec2::new(save.paddle_width, PLAYER_SIZE.y)),
+        None => (GAME_AREA.center().x, PLAYER_SIZE),
+    };
+
     // Spawn player
     commands.spawn((
         SpriteBundle {
             sprite: Sprite {
-                custom_size: Some(PLAYER_SIZE),
+                custom_size: Some(size),
                 ..Default::default()
             },
             texture: game_assets.player_sprit.clone(),
-            transform: Transform::from_translation(Vec3::from((
-                GAME_AREA.center() + Vec2::Y * PLAYER_AXIS,
+            transform: Transform::from_translation(Vec3::new(
+                x,
+                GAME_AREA.center().y + PLAYER_AXIS,
                 0.0,
-            ))),
+            )),
             ..Default::default()
         },
         Player,
@@ -574,6 +775,7 @@ fn load_player(mut commands: Commands, game_assets: Res<GameAssets>) {
 
 fn load_ball(
     mut commands: Commands,
+    mut load_events: EventReader<LoadLevelEvent>,
     balls: Query<Entity, With<Ball>>,
     mut ball_spawn_event: EventWriter<SpawnBallEvent>,
 ) {
@@ -582,10 +784,26 @@ fn load_ball(
         commands.entity(ball_entity).despawn();
     }
 
-    ball_spawn_event.send(SpawnBallEvent {
-        location: GAME_AREA.center() + Vec2::Y * PLAYER_AXIS + Vec2::Y * 30.0,
-        initial_velocity: Vec2::Y,
-    });
+    match load_events
+        .read()
+        .next()
+        .and_then(|event| event.save.as_ref())
+    {
+        Some(save) => {
+            for (location, initial_velocity) in &save.balls {
+                ball_spawn_event.send(SpawnBallEvent {
+                    location: *location,
+                    initial_velocity: *initial_velocity,
+                });
+            }
+        }
+        None => {
+            ball_spawn_event.send(SpawnBallEvent {
+                location: GAME_AREA.center() + Vec2::Y * PLAYER_AXIS + Vec2::Y * 30.0,
+                initial_velocity: Vec2::Y,
+            });
+        }
+    }
 }
 
 fn spawn_ball(
@@ -622,9 +840,10 @@ fn spawn_upgrade(
     mut upgrade_spawn_event: EventReader<SpawnUpgradeEvent>,
     mut commands: Commands,
     game_assets: Res<GameAssets>,
+    mut game_rng: ResMut<GameRng>,
 ) {
     for spawn_event in upgrade_spawn_event.read() {
-        let upgrade_component = if rand::random::<f32>() < 0.3 {
+        let upgrade_component = if game_rng.random::<f32>() < 0.3 {
             UpgradeComponent::Shrink
         } else {
             UpgradeComponent::Grow
@@ -655,8 +874,10 @@ fn despawn_ball(
     mut despawn_ball_event: EventReader<DespawnBallEvent>,
     mut game_over_event: EventWriter<GameOverEvent>,
     balls: Query<&Ball>,
+    mut level_stats: ResMut<LevelStats>,
 ) {
     let nb_balls_despawned = despawn_ball_event.len();
+    level_stats.balls_lost += nb_balls_despawned as u32;
     for event in despawn_ball_event.read() {
         if let Some(mut entity_commands) = commands.get_entity(event.0) {
             entity_commands.despawn();
@@ -675,6 +896,7 @@ fn despawn_brick(
     mut upgrade_spawn_event: EventWriter<SpawnUpgradeEvent>,
     mut game_won_event: EventWriter<GameWonEvent>,
     bricks: Query<(&Transform, &Brick)>,
+    mut game_rng: ResMut<GameRng>,
 ) {
     let nb_bricks_despawned = despawn_brick_event.len();
 
@@ -682,7 +904,7 @@ fn despawn_brick(
         let (brick_transform, brick) = bricks.get(event.0).unwrap();
         match brick.brick_type {
             BrickType::BallSpawner => {
-                let theta = rand::random::<f32>() * PI * 2.0;
+                let theta = game_rng.random::<f32>() * PI * 2.0;
                 let x = f32::cos(theta);
                 let y = f32::sin(theta);
 
@@ -709,13 +931,13 @@ fn despawn_brick(
     }
 }
 
-fn update_balls(time: ResMut<Time>, mut balls: Query<&mut Ball>) {
+fn update_balls(time: ResMut<Time>, campaign_run: Res<CampaignRun>, mut balls: Query<&mut Ball>) {
     let dt = time.delta().as_secs_f32();
     for mut ball in &mut balls {
         let velocity = ball.velocity;
 
         ball.old_position = ball.current_position;
-        ball.current_position += velocity * BALL_SPEED * dt;
+        ball.current_position += velocity * campaign_run.ball_speed() * dt;
     }
 }
 
@@ -757,7 +979,7 @@ fn solve_ball_player_colisions(
     let player_size = player_sprite.custom_size.unwrap();
     for mut ball in &mut balls {
         let player_rect = Rect::from_center_size(player_transform.translation.xy(), player_size);
-        ball.bounce_of_player_rect(player_rect);
+        ball.bounce_of_player_rect(player_rect, Vec2::Y);
     }
 }
 
@@ -766,6 +988,7 @@ fn solve_ball_brick_colisions(
     mut bricks: Query<(Entity, &Transform, &mut Brick, &mut Handle<Image>)>,
     game_assets: Res<GameAssets>,
     mut despawn_brick_event: EventWriter<DespawnBrickEvent>,
+    mut level_stats: ResMut<LevelStats>,
 ) {
     for mut ball in &mut balls {
         for (brick_entity, brick_transform, mut brick, mut image_handle) in &mut bricks {
@@ -773,6 +996,7 @@ fn solve_ball_brick_colisions(
 
             if ball.bounce(brick_rect) {
                 brick.lives -= 1;
+                level_stats.score += BRICK_POINTS;
                 if brick.lives == 0 {
                     despawn_brick_event.send(DespawnBrickEvent(brick_entity));
                 } else {
@@ -866,22 +1090,34 @@ fn tick_upgrade_timer(
     }
 }
 
+fn tick_level_time(time: Res<Time>, mut level_stats: ResMut<LevelStats>) {
+    level_stats.time += time.delta_seconds();
+}
+
 fn handle_player_input(
-    input: Res<ButtonInput<KeyCode>>,
+    input: Res<ButtonInput<Action>>,
+    analog_input: Res<AnalogInput>,
     time: Res<Time>,
     mut player: Query<(&mut Transform, &Sprite), With<Player>>,
-    mut next_in_game_state: ResMut<NextState<InGameState>>,
     mut next_game_state: ResMut<NextState<GameState>>,
 ) {
     let (mut player_transform, player_sprite) = player.single_mut();
     let player_size = player_sprite.custom_size.unwrap();
     let dt = time.delta().as_secs_f32();
-    if input.pressed(KeyCode::ArrowLeft) {
-        player_transform.translation.x -= PLAYER_SPEED * dt;
-    }
 
-    if input.pressed(KeyCode::ArrowRight) {
-        player_transform.translation.x += PLAYER_SPEED * dt;
+    // The paddle follows the pointer, or moves as fast as the stick is pushed
+    if let Some(pointer_x) = analog_input.pointer_x {
+        player_transform.translation.x = pointer_x;
+    } else if analog_input.move_x != 0.0 {
+        player_transform.translation.x += analog_input.move_x * PLAYER_SPEED * dt;
+    } else {
+        if input.pressed(Action::MoveLeft) {
+            player_transform.translation.x -= PLAYER_SPEED * dt;
+        }
+
+        if input.pressed(Action::MoveRight) {
+            player_transform.translation.x += PLAYER_SPEED * dt;
+        }
     }
 
     if player_transform.translation.x - player_size.x / 2.0 < GAME_AREA.min.x {
@@ -892,26 +1128,71 @@ fn handle_player_input(
         player_transform.translation.x = GAME_AREA.max.x - player_size.x / 2.0;
     }
 
-    if input.just_pressed(KeyCode::Escape) {
-        next_in_game_state.set(InGameState::Paused);
+    // Stays `Playing` behind the menu, so that resuming counts down first
+    if input.just_pressed(Action::Pause) {
         next_game_state.set(GameState::InMenu);
     }
 }
 
 fn handle_pause_input(
-    input: Res<ButtonInput<KeyCode>>,
+    input: Res<ButtonInput<Action>>,
     mut next_in_game_state: ResMut<NextState<InGameState>>,
     mut next_game_state: ResMut<NextState<GameState>>,
 ) {
-    if input.just_pressed(KeyCode::Space) {
+    if input.just_pressed(Action::Launch) || input.just_pressed(Action::Click) {
         next_in_game_state.set(InGameState::Playing);
     }
 
-    if input.just_pressed(KeyCode::Escape) {
+    if input.just_pressed(Action::Pause) {
         next_game_state.set(GameState::InMenu);
     }
 }
 
+fn start_resume_countdown(
+    mut countdown: ResMut<ResumeCountdown>,
+    mut countdown_text: Query<(&mut Text, &mut Visibility), With<ResumeCountdownText>>,
+) {
+    countdown.timer.reset();
+
+    for (mut text, mut visibility) in &mut countdown_text {
+        text.sections[0].value = format!("{RESUME_COUNTDOWN}");
+        *visibility = Visibility::Inherited;
+    }
+}
+
+fn tick_resume_countdown(
+    input: Res<ButtonInput<Action>>,
+    time: Res<Time>,
+    mut countdown: ResMut<ResumeCountdown>,
+    mut countdown_text: Query<&mut Text, With<ResumeCountdownText>>,
+    mut next_in_game_state: ResMut<NextState<InGameState>>,
+    mut next_game_state: ResMut<NextState<GameState>>,
+) {
+    // Pausing again starts the countdown over once resumed
+    if input.just_pressed(Action::Pause) {
+        next_in_game_state.set(InGameState::Playing);
+        next_game_state.set(GameState::InMenu);
+        return;
+    }
+
+    countdown.timer.tick(time.delta());
+    if countdown.timer.finished() {
+        next_in_game_state.set(InGameState::Playing);
+        return;
+    }
+
+    let seconds_left = countdown.timer.remaining_secs().ceil();
+    for mut text in &mut countdown_text {
+        text.sections[0].value = format!("{seconds_left}");
+    }
+}
+
+fn hide_resume_countdown(mut countdown_text: Query<&mut Visibility, With<ResumeCountdownText>>) {
+    for mut visibility in &mut countdown_text {
+        *visibility = Visibility::Hidden;
+    }
+}
+
 fn game_over(
     mut next_in_game_state: ResMut<NextState<InGameState>>,
     mut next_game_state: ResMut<NextState<GameState>>,
@@ -920,426 +1201,649 @@ fn game_over(
     next_in_game_state.set(InGameState::Paused);
     next_game_state.set(GameState::InMenu);
 
-    menu_state.update(0, GAME_OVER_ITEMS[0], Some(MenuNode::GameOver));
+    menu_state.menu_node = MenuNode::GameOver;
 }
 
+// The victory screen once the last level is completed
 fn game_won(
+    last_level_played: Res<LastLevelPlayed>,
     mut next_in_game_state: ResMut<NextState<InGameState>>,
     mut next_game_state: ResMut<NextState<GameState>>,
     mut menu_state: ResMut<MenuState>,
-    last_level_played: Res<LastLevelPlayed>,
-    mut last_level_complete_text: Query<&mut Visibility, With<LastLevelCompleteText>>,
 ) {
     next_in_game_state.set(InGameState::Paused);
     next_game_state.set(GameState::InMenu);
 
-    menu_state.update(0, GAME_WON_ITEMS[0], Some(MenuNode::GameWon));
+    menu_state.menu_node = if last_level_played.0 == TOTAL_LEVELS {
+        MenuNode::CampaignComplete
+    } else {
+        MenuNode::GameWon
+    };
+}
+
+// Keeps the records of the level and unlocks the next one, unless it was a
+// demo or a replay
+fn record_level(
+    game_assets: Res<GameAssets>,
+    last_level_played: Res<LastLevelPlayed>,
+    mut level_stats: ResMut<LevelStats>,
+    mut progress: ResMut<CampaignProgress>,
+    mut campaign_run: ResMut<CampaignRun>,
+    replay_or_demo: ReplayOrDemo,
+) {
+    let level = last_level_played.0;
+    let result = level_stats.complete(game_assets.levels[level - 1].par_time());
+    campaign_run.levels.push(LevelSummary {
+        level,
+        record: result,
+        balls_lost: level_stats.balls_lost,
+    });
 
-    if last_level_played.0 == TOTAL_LEVELS {
-        let mut text_visibility = last_level_complete_text.single_mut();
-        *text_visibility = Visibility::Inherited;
+    if !replay_or_demo.is_playing() {
+        progress.submit(BreackoutPlugin::NAME, level, result);
     }
 }
 
-// Menu
-
-const MENU_ITEMS: [&'static str; 3] = ["Play", "Load level", "Exit"];
-const LEVEL_SELECT_ITEMS: [&'static str; 6] = [
-    "Level 1", "Level 2", "Level 3", "Level 4", "Level 5", "Back",
-];
-const GAME_OVER_ITEMS: [&'static str; 2] = ["Retry", "Main Menu"];
-const GAME_WON_ITEMS: [&'static str; 2] = ["Next Level", "Main Menu"];
+// The total score of a run through every level is kept unless it was a demo
+// or a replay, its initials are asked for when it is the best
+fn complete_campaign(
+    last_level_played: Res<LastLevelPlayed>,
+    mut campaign_run: ResMut<CampaignRun>,
+    best_scores: Option<ResMut<BestScores>>,
+    replay_or_demo: ReplayOrDemo,
+) {
+    if last_level_played.0 != TOTAL_LEVELS
+        || !campaign_run.is_complete()
+        || replay_or_demo.is_playing()
+    {
+        return;
+    }
 
-const ITEM_BACKGROUND_SELECTED: Color = Color::srgb(0.3, 0.3, 0.3);
-const ITEM_BACKGROUND_NORMAL: Color = Color::BLACK;
+    if let Some(mut best_scores) = best_scores {
+        campaign_run.new_best = best_scores.submit(BreackoutPlugin::NAME, campaign_run.score());
+    }
+}
 
-#[derive(Resource)]
-struct MenuState {
-    item_index: usize,
+// New Game+ gives the normal bricks more lives, saved levels already have them
+fn harden_bricks(
+    mut load_events: EventReader<LoadLevelEvent>,
+    campaign_run: Res<CampaignRun>,
+    game_assets: Res<GameAssets>,
+    mut bricks: Query<(&mut Brick, &mut Handle<Image>)>,
+) {
+    let from_save = load_events
+        .read()
+        .next()
+        .is_some_and(|event| event.save.is_some());
+    if from_save || campaign_run.new_game_plus == 0 {
+        return;
+    }
 
-    current_value: String,
-    old_value: String,
-    menu_node: MenuNode,
+    let max_lives = game_assets.normal_bricks.len() as i32;
+    for (mut brick, mut texture) in &mut bricks {
+        if brick.brick_type == BrickType::Normal {
+            brick.lives = (brick.lives + campaign_run.new_game_plus as i32).min(max_lives);
+            *texture = game_assets.normal_bricks[brick.lives as usize - 1].clone();
+        }
+    }
+}
 
-    changed: bool,
+// What a save is made of
+#[derive(SystemParam)]
+struct LevelProgress<'w, 's> {
+    last_level_played: Res<'w, LastLevelPlayed>,
+    game_rng: Res<'w, GameRng>,
+    bricks: Query<'w, 's, (&'static Transform, &'static Brick)>,
+    balls: Query<'w, 's, &'static Ball>,
+    player: Query<'w, 's, (&'static Transform, &'static Sprite), With<Player>>,
+    upgrade_timer: Res<'w, UpgradeTimer>,
+    level_stats: Res<'w, LevelStats>,
+    campaign_run: Res<'w, CampaignRun>,
+    in_game_state: Res<'w, State<InGameState>>,
 }
 
-impl MenuState {
-    fn new(first_value: &str) -> Self {
-        Self {
-            item_index: 0,
-            current_value: first_value.to_string(),
-            old_value: first_value.to_string(),
-            menu_node: MenuNode::MainMenu,
-            changed: true,
-        }
+impl LevelProgress<'_, '_> {
+    fn save(&self) -> Option<BreakoutSave> {
+        let (player_transform, player_sprite) = self.player.get_single().ok()?;
+        let timer = &self.upgrade_timer.timer;
+
+        Some(BreakoutSave {
+            level: self.last_level_played.0,
+            seed: self.game_rng.seed(),
+            bricks: self
+                .bricks
+                .iter()
+                .map(|(transform, brick)| (brick_cell(transform.translation.xy()), brick.clone()))
+                .collect(),
+            balls: self
+                .balls
+                .iter()
+                .map(|ball| (ball.current_position, ball.velocity))
+                .collect(),
+            paddle_x: player_transform.translation.x,
+            paddle_width: player_sprite.custom_size.unwrap_or(PLAYER_SIZE).x,
+            upgrade_left: (!timer.paused()).then(|| timer.remaining_secs()),
+            score: self.level_stats.score,
+            time: self.level_stats.time,
+            balls_lost: self.level_stats.balls_lost,
+            new_game_plus: self.campaign_run.new_game_plus,
+            run: self.campaign_run.levels.clone(),
+            launched: *self.in_game_state.get() != InGameState::Paused,
+        })
     }
+}
 
-    fn update(&mut self, new_index: usize, new_value: &str, new_menu_node: Option<MenuNode>) {
-        self.item_index = new_index;
-        self.old_value = self.current_value.clone();
-        self.current_value = new_value.to_string();
-        if let Some(new_menu_node) = new_menu_node {
-            self.menu_node = new_menu_node;
-        }
-        self.changed = true;
+// Only levels being played, demos and replays are never saved
+fn can_save(level_loaded: Option<Res<LevelLoaded>>, replay_or_demo: ReplayOrDemo) -> bool {
+    !replay_or_demo.is_playing() && level_loaded.is_some_and(|level_loaded| level_loaded.0)
+}
+
+// Kept when the level is paused or the app closed, to be continued later
+fn save_level(level_progress: LevelProgress, mut saved_games: ResMut<SavedGames>) {
+    match level_progress.save() {
+        Some(save) => saved_games.store(BreackoutPlugin::NAME, save.to_text()),
+        None => warn!("The Breakout level could not be saved"),
     }
+}
 
-    fn get_menu_items(&self) -> &'static [&'static str] {
-        match self.menu_node {
-            MenuNode::MainMenu => &MENU_ITEMS,
-            MenuNode::LevelSelection => &LEVEL_SELECT_ITEMS,
-            MenuNode::GameOver => &GAME_OVER_ITEMS,
-            MenuNode::GameWon => &GAME_WON_ITEMS,
-        }
+// A level that is over can not be continued
+fn drop_save(replay_or_demo: ReplayOrDemo, mut saved_games: ResMut<SavedGames>) {
+    if !replay_or_demo.is_playing() {
+        saved_games.remove(BreackoutPlugin::NAME);
     }
 }
 
-#[derive(Component, Clone, Copy, PartialEq, Eq)]
+// Menu
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum BreakoutMenuAction {
+    Continue,
+    Play,
+    Resume,
+    RestartLevel,
+    LoadLevel(usize),
+    Assist(bool),
+    FollowPointer(bool),
+    Exit,
+    Retry,
+    NextLevel,
+    MainMenu,
+    NewGamePlus,
+    // A letter for one of the three initials of a new best score
+    Initial(usize, usize),
+    SubmitInitials,
+    // Lines of results and credits, they do nothing
+    Text,
+}
+
+// Which menu is shown, changing it rebuilds the menu
+#[derive(Resource)]
+struct MenuState {
+    menu_node: MenuNode,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
 enum MenuNode {
     MainMenu,
-    LevelSelection,
+    // Over the frozen level, while one is loaded
+    Pause,
     GameOver,
     GameWon,
+    // Once the last level is completed
+    CampaignComplete,
 }
 
 #[derive(Component)]
 struct MainMenuNode;
 
-#[derive(Component)]
-struct MenuItemComponent;
+// The stars of a completed level, `-` for those still to get
+fn stars_label(stars: u8) -> String {
+    (1..=3)
+        .map(|star| if star <= stars { '*' } else { '-' })
+        .collect()
+}
 
-fn load_menu(mut commands: Commands) {
-    let main_node = commands
-        .spawn((
-            NodeBundle {
-                style: Style {
-                    flex_direction: FlexDirection::Column,
-                    align_items: AlignItems::Center,
-                    justify_content: JustifyContent::Center,
+fn main_menu(
+    assist: Assist,
+    can_continue: bool,
+    progress: &CampaignProgress,
+) -> Menu<BreakoutMenuAction> {
+    let mut level_selection = Menu::new();
+    for level in 1..=TOTAL_LEVELS {
+        let unlocked = progress.is_unlocked(BreackoutPlugin::NAME, level);
+        let label = match progress.get(BreackoutPlugin::NAME, level) {
+            Some(record) => format!("Level {level}  {}", stars_label(record.stars)),
+            None if unlocked => format!("Level {level}"),
+            None => format!("Level {level}  (locked)"),
+        };
 
-                    left: Val::Px(GAME_AREA.min.x + WINDOW_RESOLUTION[0] / 2.0),
-                    bottom: Val::Px(GAME_AREA.max.y + WINDOW_RESOLUTION[1] / 2.0),
-                    width: Val::Px(GAME_AREA.width()),
-                    height: Val::Px(GAME_AREA.height()),
+        level_selection = level_selection
+            .action(&label, BreakoutMenuAction::LoadLevel(level))
+            .enabled(unlocked);
+    }
 
-                    ..Default::default()
-                },
-                ..Default::default()
-            },
-            MainMenuNode,
-        ))
-        .id();
+    let mut menu = Menu::new();
+    if can_continue {
+        menu = menu.action("Continue", BreakoutMenuAction::Continue);
+    }
 
-    let main_menu_node = spawn_menu_node(&mut commands, MenuNode::MainMenu);
-    load_menu_items(&mut commands, main_menu_node, &MENU_ITEMS);
+    menu.action("Play", BreakoutMenuAction::Play)
+        .submenu("Load level", level_selection.back("Back"))
+        .toggle("Assist", assist.0, BreakoutMenuAction::Assist)
+        .action("Exit", BreakoutMenuAction::Exit)
+}
 
-    let level_select_node = spawn_menu_node(&mut commands, MenuNode::LevelSelection);
-    load_menu_items(&mut commands, level_select_node, &LEVEL_SELECT_ITEMS);
+fn pause_menu(assist: Assist, follow_pointer: bool) -> Menu<BreakoutMenuAction> {
+    Menu::new()
+        .action("Resume", BreakoutMenuAction::Resume)
+        .action("Restart level", BreakoutMenuAction::RestartLevel)
+        .submenu(
+            "Settings",
+            Menu::new()
+                .toggle("Assist", assist.0, BreakoutMenuAction::Assist)
+                .toggle(
+                    "Paddle follows the pointer",
+                    follow_pointer,
+                    BreakoutMenuAction::FollowPointer,
+                )
+                .back("Back"),
+        )
+        .action("Quit to launcher", BreakoutMenuAction::Exit)
+        .on_back(BreakoutMenuAction::Resume)
+}
 
-    let game_over_node = spawn_menu_node(&mut commands, MenuNode::GameOver);
-    load_menu_items(&mut commands, game_over_node, &GAME_OVER_ITEMS);
+fn game_over_menu() -> Menu<BreakoutMenuAction> {
+    Menu::new()
+        .action("Retry", BreakoutMenuAction::Retry)
+        .action("Main Menu", BreakoutMenuAction::MainMenu)
+}
 
-    let game_won_node = spawn_menu_node(&mut commands, MenuNode::GameWon);
-    load_menu_items(&mut commands, game_won_node, &GAME_WON_ITEMS);
+// The results of the level below the actions, which the autoplayer expects
+// first
+fn game_won_menu(result: Option<LevelRecord>) -> Menu<BreakoutMenuAction> {
+    let mut menu = Menu::new()
+        .action("Next Level", BreakoutMenuAction::NextLevel)
+        .action("Main Menu", BreakoutMenuAction::MainMenu);
+
+    if let Some(result) = result {
+        menu = text_lines(
+            menu,
+            &[
+                format!("Score: {}", result.score),
+                format!("Time: {:.1}s", result.time),
+                format!("Stars: {}", stars_label(result.stars)),
+            ],
+        );
+    }
 
-    commands
-        .entity(main_node)
-        .add_child(main_menu_node)
-        .add_child(level_select_node)
-        .add_child(game_over_node)
-        .add_child(game_won_node);
+    menu
+}
 
-    commands.insert_resource(MenuState::new(MENU_ITEMS[0]));
+fn text_lines(mut menu: Menu<BreakoutMenuAction>, lines: &[String]) -> Menu<BreakoutMenuAction> {
+    for line in lines {
+        menu = menu.action(line, BreakoutMenuAction::Text).enabled(false);
+    }
+    menu
 }
 
-fn spawn_menu_node(commands: &mut Commands, menu_node: MenuNode) -> Entity {
+fn initials_menu(initials: [usize; 3]) -> Menu<BreakoutMenuAction> {
+    // One function per initial, as `on_change` can not capture the slot
+    let on_changes: [fn(usize) -> BreakoutMenuAction; 3] = [
+        |letter| BreakoutMenuAction::Initial(0, letter),
+        |letter| BreakoutMenuAction::Initial(1, letter),
+        |letter| BreakoutMenuAction::Initial(2, letter),
+    ];
+    let letters: Vec<String> = ('A'..='Z').map(String::from).collect();
+
+    let mut menu = Menu::new();
+    for (slot, on_change) in on_changes.into_iter().enumerate() {
+        menu = menu.choice(
+            &format!("Initial {}", slot + 1),
+            letters.clone(),
+            initials[slot],
+            on_change,
+        );
+    }
+
+    menu.action("Done", BreakoutMenuAction::SubmitInitials)
+        .back("Back")
+}
+
+// The summary of the run, then what to do next. The autoplayer taps the first
+// item, which does nothing here, until the demo ends.
+fn campaign_complete_menu(campaign_run: &CampaignRun) -> Menu<BreakoutMenuAction> {
+    let title = match campaign_run.new_game_plus {
+        0 => String::from("Campaign complete!"),
+        new_game_plus => format!("New Game+ {new_game_plus} complete!"),
+    };
+    let mut menu = text_lines(
+        Menu::new(),
+        &[
+            title,
+            format!("Total score: {}", campaign_run.score()),
+            format!("Total time: {:.1}s", campaign_run.time()),
+            format!("Balls lost: {}", campaign_run.balls_lost()),
+        ],
+    );
+
+    if campaign_run.new_best {
+        menu = menu.submenu(
+            "New best score! Enter your initials",
+            initials_menu(campaign_run.initials),
+        );
+    }
+
+    let levels: Vec<String> = campaign_run
+        .levels
+        .iter()
+        .map(|summary| {
+            format!(
+                "Level {}: {} pts, {:.1}s, {}, {} lost",
+                summary.level,
+                summary.record.score,
+                summary.record.time,
+                stars_label(summary.record.stars),
+                summary.balls_lost,
+            )
+        })
+        .collect();
+    let credits = [
+        "Breakout, part of Retro Games",
+        "Levels and sprites by the Retro Games contributors",
+        "Made with Bevy",
+        "Thanks for playing!",
+    ]
+    .map(String::from);
+
+    menu.submenu("Levels", text_lines(Menu::new(), &levels).back("Back"))
+        .submenu("Credits", text_lines(Menu::new(), &credits).back("Back"))
+        .action("New Game+", BreakoutMenuAction::NewGamePlus)
+        .action("Quit to launcher", BreakoutMenuAction::Exit)
+}
+
+fn load_menu(mut commands: Commands) {
     commands
         .spawn((
             NodeBundle {
                 style: Style {
-                    display: Display::None,
                     flex_direction: FlexDirection::Column,
                     align_items: AlignItems::Center,
                     justify_content: JustifyContent::Center,
+
+                    left: Val::Px(GAME_AREA.min.x + WINDOW_RESOLUTION[0] / 2.0),
+                    bottom: Val::Px(GAME_AREA.max.y + WINDOW_RESOLUTION[1] / 2.0),
+                    width: Val::Px(GAME_AREA.width()),
+                    height: Val::Px(GAME_AREA.height()),
+
                     ..Default::default()
                 },
+                // Dims the level behind the pause menu without hiding it
+                background_color: Color::srgba(0.0, 0.0, 0.0, 0.6).into(),
                 ..Default::default()
             },
-            menu_node,
+            MainMenuNode,
+            MenuRoot::new(
+                main_menu(Assist(false), false, &CampaignProgress::default()),
+                MenuStyle {
+                    background: Color::srgba(0.0, 0.0, 0.0, 0.0),
+                    ..Default::default()
+                },
+            ),
         ))
-        .id()
-}
-
-fn load_menu_items(commands: &mut Commands, parent_node: Entity, items_to_load: &[&str]) {
-    commands.entity(parent_node).with_children(|builder| {
-        for menu_item in items_to_load {
-            builder
-                .spawn(
-                    TextBundle::from_section(
-                        *menu_item,
-                        TextStyle {
-                            font_size: 30.0,
-                            color: palettes::basic::GREEN.into(),
-                            ..Default::default()
-                        },
-                    )
-                    .with_background_color(ITEM_BACKGROUND_NORMAL),
-                )
-                .insert(MenuItemComponent);
-        }
+        .with_children(|builder| {
+            builder.spawn((
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font_size: 20.0,
+                        color: palettes::basic::GREEN.into(),
+                        ..Default::default()
+                    },
+                ),
+                SeedText,
+            ));
+        });
+
+    commands.insert_resource(MenuState {
+        menu_node: MenuNode::MainMenu,
     });
 }
 
-fn cleanup_menu(
-    mut commands: Commands,
-    menu_items: Query<Entity, With<MenuItemComponent>>,
-    menu_nodes: Query<Entity, With<MenuNode>>,
-    main_ui_node: Query<Entity, With<MainMenuNode>>,
-) {
+fn cleanup_menu(mut commands: Commands, main_ui_node: Query<Entity, With<MainMenuNode>>) {
     commands.remove_resource::<MenuState>();
 
-    for menu_item in &menu_items {
-        commands.entity(menu_item).despawn();
+    for main_ui_entity in &main_ui_node {
+        commands.entity(main_ui_entity).despawn_recursive();
     }
-
-    for note_entity in &menu_nodes {
-        commands.entity(note_entity).despawn();
-    }
-
-    commands.entity(main_ui_node.single()).despawn();
 }
 
 fn show_menu(
     mut menu: Query<&mut Visibility, With<MainMenuNode>>,
-    mut menu_items: Query<(&mut BackgroundColor, &Text), With<MenuItemComponent>>,
-    mut selected_menu_item: ResMut<MenuState>,
+    mut seed_text: Query<&mut Text, With<SeedText>>,
+    mut menu_state: ResMut<MenuState>,
+    level_loaded: Res<LevelLoaded>,
+    game_rng: Res<GameRng>,
 ) {
-    let mut menu_visibility = menu.single_mut();
-    *menu_visibility = Visibility::Inherited;
-
-    selected_menu_item.item_index = 0;
-
-    for (mut menu_item, text) in &mut menu_items {
-        if text.sections[0].value == selected_menu_item.current_value {
-            *menu_item = BackgroundColor(ITEM_BACKGROUND_SELECTED);
-        } else {
-            *menu_item = BackgroundColor(ITEM_BACKGROUND_NORMAL);
-        }
+    if let Ok(mut menu_visibility) = menu.get_single_mut() {
+        *menu_visibility = Visibility::Inherited;
     }
-}
-
-fn hide_menu(mut menu: Query<&mut Visibility, With<MainMenuNode>>) {
-    let mut menu_visibility = menu.single_mut();
-    *menu_visibility = Visibility::Hidden;
-}
 
-fn handle_menu_navigation_input(
-    input: Res<ButtonInput<KeyCode>>,
-    mut menu_state: ResMut<MenuState>,
-) {
-    let items = menu_state.get_menu_items();
-    if input.just_pressed(KeyCode::ArrowUp) {
-        let new_index = if menu_state.item_index == 0 {
-            items.len() - 1
+    // Only shown once a level is loaded, the menu then acts as a pause menu
+    if let Ok(mut text) = seed_text.get_single_mut() {
+        text.sections[0].value = if level_loaded.0 {
+            format!("Seed: {}", game_rng.seed())
         } else {
-            menu_state.item_index - 1
+            String::new()
         };
-        menu_state.update(new_index, items[new_index], None);
     }
 
-    if input.just_pressed(KeyCode::ArrowDown) {
-        let new_index = if menu_state.item_index == items.len() - 1 {
-            0
-        } else {
-            menu_state.item_index + 1
-        };
-        menu_state.update(new_index, items[new_index], None);
+    // Rebuilt from its first item, as the pause menu once a level is loaded
+    if level_loaded.0 && menu_state.menu_node == MenuNode::MainMenu {
+        menu_state.menu_node = MenuNode::Pause;
     }
+    menu_state.set_changed();
 }
 
-fn update_menu(
-    mut menu_state: ResMut<MenuState>,
-    mut menu_nodes: Query<(&mut Style, &MenuNode)>,
-    mut menu_items: Query<(&mut BackgroundColor, &Text), With<MenuItemComponent>>,
-) {
-    if menu_state.changed {
-        for (mut background, text) in &mut menu_items {
-            if text.sections[0].value == menu_state.old_value {
-                *background = BackgroundColor(ITEM_BACKGROUND_NORMAL);
-            }
+fn hide_menu(mut menu: Query<&mut Visibility, With<MainMenuNode>>) {
+    if let Ok(mut menu_visibility) = menu.get_single_mut() {
+        *menu_visibility = Visibility::Hidden;
+    }
+}
 
-            if text.sections[0].value == menu_state.current_value {
-                *background = BackgroundColor(ITEM_BACKGROUND_SELECTED);
-            }
-        }
+// What the main and pause menus show
+#[derive(SystemParam)]
+struct MenuOptions<'w> {
+    assist: Res<'w, Assist>,
+    input_map: Res<'w, InputMap>,
+    saved_games: Res<'w, SavedGames>,
+    progress: Res<'w, CampaignProgress>,
+    demo: Option<Res<'w, Demo>>,
+}
 
-        menu_state.old_value = menu_state.current_value.clone();
+impl MenuOptions<'_> {
+    fn main_menu(&self) -> Menu<BreakoutMenuAction> {
+        // Demos neither save nor continue levels
+        let can_continue = self.saved_games.contains(BreackoutPlugin::NAME) && self.demo.is_none();
 
-        for (mut style, menu_node) in &mut menu_nodes {
-            if menu_state.menu_node == *menu_node {
-                style.display = Display::default();
-            } else {
-                style.display = Display::None;
-            }
-        }
+        main_menu(*self.assist, can_continue, &self.progress)
+    }
 
-        menu_state.changed = false;
+    fn pause_menu(&self) -> Menu<BreakoutMenuAction> {
+        pause_menu(*self.assist, self.input_map.follow_pointer)
     }
 }
 
-fn handle_menu_select_input(
-    input: Res<ButtonInput<KeyCode>>,
+fn update_shown_menu(
+    menu_state: Res<MenuState>,
+    level_stats: Res<LevelStats>,
+    campaign_run: Res<CampaignRun>,
+    menu_options: MenuOptions,
+    mut menu: Query<&mut MenuRoot<BreakoutMenuAction>>,
+) {
+    for mut menu in &mut menu {
+        menu.set_menu(match menu_state.menu_node {
+            MenuNode::MainMenu => menu_options.main_menu(),
+            MenuNode::Pause => menu_options.pause_menu(),
+            MenuNode::GameOver => game_over_menu(),
+            MenuNode::GameWon => game_won_menu(level_stats.result),
+            MenuNode::CampaignComplete => campaign_complete_menu(&campaign_run),
+        });
+    }
+}
 
+fn handle_menu_action(
+    mut menu_actions: EventReader<MenuActionEvent<BreakoutMenuAction>>,
     mut menu_state: ResMut<MenuState>,
 
-    level_loaded: Res<LevelLoaded>,
-    mut load_level_event: EventWriter<LoadLevelEvent>,
     last_level_played: Res<LastLevelPlayed>,
+    progress: Res<CampaignProgress>,
+    mut load_level_event: EventWriter<LoadLevelEvent>,
 
     mut next_state: ResMut<NextState<CurrentGame>>,
     mut next_game_state: ResMut<NextState<GameState>>,
-
-    mut last_level_complete_text: Query<&mut Visibility, With<LastLevelCompleteText>>,
 ) {
-    if menu_state.menu_node == MenuNode::LevelSelection && input.just_pressed(KeyCode::Escape) {
-        menu_state.update(0, MENU_ITEMS[0], Some(MenuNode::MainMenu));
-        return;
-    }
-
-    if !input.just_pressed(KeyCode::Space) {
-        return;
-    }
-
-    match menu_state.menu_node {
-        MenuNode::MainMenu => handle_main_menu_select_input(
-            &mut menu_state,
-            level_loaded.0,
-            &mut load_level_event,
-            &mut next_state,
-            &mut next_game_state,
-        ),
-        MenuNode::LevelSelection => handle_level_selection_menu_select_input(
-            &mut menu_state,
-            &mut load_level_event,
-            &mut next_game_state,
-        ),
-        MenuNode::GameOver => handle_game_over_menu_select_input(
-            &mut menu_state,
-            &mut load_level_event,
-            last_level_played.0,
-            &mut next_game_state,
-        ),
-        MenuNode::GameWon => {
-            handle_game_won_menu_select_input(
-                &mut menu_state,
-                &mut load_level_event,
-                last_level_played.0,
-                &mut next_game_state,
-            );
-            let mut text_visibility = last_level_complete_text.single_mut();
-            *text_visibility = Visibility::Hidden;
+    for MenuActionEvent(action) in menu_actions.read() {
+        match *action {
+            // Left to `continue_level`
+            BreakoutMenuAction::Continue => (),
+            // The campaign goes on from its first level not completed yet
+            BreakoutMenuAction::Play => {
+                let level = (1..=TOTAL_LEVELS)
+                    .find(|level| !progress.is_completed(BreackoutPlugin::NAME, *level))
+                    .unwrap_or(1);
+                load_level_event.send(LoadLevelEvent::new_run(level));
+                next_game_state.set(GameState::InGame);
+            }
+            BreakoutMenuAction::Resume => next_game_state.set(GameState::InGame),
+            BreakoutMenuAction::RestartLevel => {
+                load_level_event.send(LoadLevelEvent::restart(last_level_played.0));
+                next_game_state.set(GameState::InGame);
+            }
+            BreakoutMenuAction::LoadLevel(level) => {
+                load_level_event.send(LoadLevelEvent::new_run(level));
+                next_game_state.set(GameState::InGame);
+            }
+            BreakoutMenuAction::Assist(_)
+            | BreakoutMenuAction::FollowPointer(_)
+            | BreakoutMenuAction::Text => (),
+            // Left to `handle_campaign_action`
+            BreakoutMenuAction::NewGamePlus
+            | BreakoutMenuAction::Initial(..)
+            | BreakoutMenuAction::SubmitInitials => (),
+            BreakoutMenuAction::Exit => {
+                next_game_state.set(GameState::Exited);
+                next_state.set(CurrentGame::InMainMenu);
+            }
+            BreakoutMenuAction::Retry => {
+                load_level_event.send(LoadLevelEvent::new_run(last_level_played.0));
+                next_game_state.set(GameState::InGame);
+                menu_state.menu_node = MenuNode::MainMenu;
+            }
+            BreakoutMenuAction::NextLevel => {
+                if last_level_played.0 < TOTAL_LEVELS {
+                    load_level_event.send(LoadLevelEvent::new_run(last_level_played.0 + 1));
+                    next_game_state.set(GameState::InGame);
+                }
+                menu_state.menu_node = MenuNode::MainMenu;
+            }
+            BreakoutMenuAction::MainMenu => {
+                menu_state.menu_node = MenuNode::MainMenu;
+            }
         }
     }
 }
 
-fn handle_main_menu_select_input(
-    menu_state: &mut MenuState,
-    level_loaded: bool,
-    load_level_event: &mut EventWriter<LoadLevelEvent>,
-    next_state: &mut NextState<CurrentGame>,
-    next_game_state: &mut NextState<GameState>,
+// Starting the campaign over starts a new run, New Game+ a harder one
+fn handle_campaign_action(
+    mut menu_actions: EventReader<MenuActionEvent<BreakoutMenuAction>>,
+    mut menu_state: ResMut<MenuState>,
+    mut campaign_run: ResMut<CampaignRun>,
+    mut best_scores: Option<ResMut<BestScores>>,
+    mut load_level_event: EventWriter<LoadLevelEvent>,
+    mut next_game_state: ResMut<NextState<GameState>>,
 ) {
-    match menu_state.current_value.as_str() {
-        "Play" => {
-            if !level_loaded {
-                load_level_event.send(LoadLevelEvent(1));
+    for MenuActionEvent(action) in menu_actions.read() {
+        match *action {
+            BreakoutMenuAction::Play | BreakoutMenuAction::LoadLevel(_) => {
+                *campaign_run = CampaignRun::default();
             }
-
-            next_game_state.set(GameState::InGame);
-        }
-
-        "Load level" => {
-            menu_state.update(0, LEVEL_SELECT_ITEMS[0], Some(MenuNode::LevelSelection));
-        }
-
-        "Exit" => {
-            next_game_state.set(GameState::Exited);
-            next_state.set(CurrentGame::InMainMenu);
+            BreakoutMenuAction::NewGamePlus => {
+                *campaign_run = CampaignRun::new(campaign_run.new_game_plus + 1);
+                load_level_event.send(LoadLevelEvent::new_run(1));
+                next_game_state.set(GameState::InGame);
+                menu_state.menu_node = MenuNode::MainMenu;
+            }
+            BreakoutMenuAction::Initial(slot, letter) => campaign_run.initials[slot] = letter,
+            // The prompt goes away once the best score has its initials
+            BreakoutMenuAction::SubmitInitials => {
+                if let Some(best_scores) = best_scores.as_mut() {
+                    best_scores.set_holder(BreackoutPlugin::NAME, &campaign_run.initials());
+                }
+                campaign_run.new_best = false;
+                menu_state.set_changed();
+            }
+            _ => (),
         }
-        _ => (),
     }
 }
 
-fn handle_level_selection_menu_select_input(
-    menu_state: &mut MenuState,
-    load_level_event: &mut EventWriter<LoadLevelEvent>,
-    next_game_state: &mut NextState<GameState>,
+// Loads the saved level, a save that can not be read is dropped for a new run
+fn continue_level(
+    mut menu_actions: EventReader<MenuActionEvent<BreakoutMenuAction>>,
+    mut saved_games: ResMut<SavedGames>,
+    mut load_level_event: EventWriter<LoadLevelEvent>,
+    mut next_game_state: ResMut<NextState<GameState>>,
 ) {
-    let mut should_swap_children = false;
-
-    let selection = menu_state.current_value.clone();
+    for MenuActionEvent(action) in menu_actions.read() {
+        if *action != BreakoutMenuAction::Continue {
+            continue;
+        }
 
-    if selection.starts_with("Level") {
-        for i in 1..(LEVEL_SELECT_ITEMS.len()) {
-            if selection.ends_with(&format!("{i}")) {
-                load_level_event.send(LoadLevelEvent(i));
-                next_game_state.set(GameState::InGame);
-                should_swap_children = true;
-                break;
+        let save = saved_games
+            .get(BreackoutPlugin::NAME)
+            .and_then(BreakoutSave::from_text);
+        match save {
+            Some(save) => {
+                load_level_event.send(LoadLevelEvent::from_save(save));
+            }
+            None => {
+                warn!("The saved Breakout level could not be read, starting a new run");
+                saved_games.remove(BreackoutPlugin::NAME);
+                load_level_event.send(LoadLevelEvent::new_run(1));
             }
         }
-    }
-
-    if selection == "Back" {
-        should_swap_children = true;
-    }
-
-    if should_swap_children {
-        menu_state.update(0, MENU_ITEMS[0], Some(MenuNode::MainMenu));
+        next_game_state.set(GameState::InGame);
     }
 }
 
-fn handle_game_over_menu_select_input(
-    menu_state: &mut MenuState,
-    load_level_event: &mut EventWriter<LoadLevelEvent>,
-    last_level_played: usize,
-    next_game_state: &mut NextState<GameState>,
+fn resume_countdown(
+    mut menu_actions: EventReader<MenuActionEvent<BreakoutMenuAction>>,
+    in_game_state: Res<State<InGameState>>,
+    mut next_in_game_state: ResMut<NextState<InGameState>>,
 ) {
-    match menu_state.current_value.as_str() {
-        "Retry" => {
-            let last_level_played = last_level_played;
-            load_level_event.send(LoadLevelEvent(last_level_played));
-            next_game_state.set(GameState::InGame);
-            menu_state.update(0, MENU_ITEMS[0], Some(MenuNode::MainMenu));
-        }
-        "Main Menu" => {
-            menu_state.update(0, MENU_ITEMS[0], Some(MenuNode::MainMenu));
+    for MenuActionEvent(action) in menu_actions.read() {
+        // A ball that was not launched yet just waits for it again
+        if *action == BreakoutMenuAction::Resume && *in_game_state.get() == InGameState::Playing {
+            next_in_game_state.set(InGameState::Resuming);
         }
-        _ => (),
     }
 }
 
-fn handle_game_won_menu_select_input(
-    menu_state: &mut MenuState,
-    load_level_event: &mut EventWriter<LoadLevelEvent>,
-    last_level_played: usize,
-    next_game_state: &mut NextState<GameState>,
+fn change_settings(
+    mut menu_actions: EventReader<MenuActionEvent<BreakoutMenuAction>>,
+    mut assist: ResMut<Assist>,
+    mut input_map: ResMut<InputMap>,
 ) {
-    match menu_state.current_value.as_str() {
-        "Next Level" => {
-            if last_level_played == TOTAL_LEVELS {
-                menu_state.update(0, MENU_ITEMS[0], Some(MenuNode::MainMenu));
-                return;
+    for MenuActionEvent(action) in menu_actions.read() {
+        match *action {
+            BreakoutMenuAction::Assist(on) => assist.0 = on,
+            BreakoutMenuAction::FollowPointer(follow_pointer) => {
+                input_map.follow_pointer = follow_pointer
             }
-
-            load_level_event.send(LoadLevelEvent(last_level_played + 1));
-            next_game_state.set(GameState::InGame);
-            menu_state.update(0, MENU_ITEMS[0], Some(MenuNode::MainMenu));
-        }
-        "Main Menu" => {
-            menu_state.update(0, MENU_ITEMS[0], Some(MenuNode::MainMenu));
+            _ => (),
         }
-        _ => (),
     }
 }
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,189 @@
+//! Plays Breakout by itself: during demos, in `check_level`, and as an
+//! assist moving the paddle for the player
+
+use bevy::{ecs::system::SystemParam, prelude::*};
+
+use crate::application::GAME_AREA;
+use crate::demo::tap_action;
+use crate::input::{Action, AnalogInput};
+use crate::replay::ReplayedResource;
+
+use super::{Ball, Brick, GameState, InGameState, Player, PLAYER_SPEED};
+
+// Furthest from the center of the paddle the ball is made to hit it, as a
+// fraction of the paddle's width. It can not hit further than about half of
+// it, and flies almost flat from there.
+const MAX_AIM: f32 = 0.35;
+
+/// Whether the paddle catches the ball by itself while the player does not
+/// move it, the player still launches the ball
+#[derive(Resource, Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub(super) struct Assist(pub bool);
+
+impl ReplayedResource for Assist {
+    const KEY: &'static str = "breakout assist";
+
+    fn to_text(&self) -> String {
+        self.0.to_string()
+    }
+
+    fn from_text(text: &str) -> Option<Self> {
+        text.parse().ok().map(Self)
+    }
+}
+
+#[derive(SystemParam)]
+pub(super) struct Playfield<'w, 's> {
+    balls: Query<'w, 's, &'static Ball>,
+    bricks: Query<'w, 's, &'static Transform, With<Brick>>,
+    player: Query<'w, 's, (&'static Transform, &'static Sprite), With<Player>>,
+}
+
+impl Playfield<'_, '_> {
+    // Analog movement bringing the paddle where it catches the next ball,
+    // quantized like in the replays
+    fn move_x(&self, aim: bool, dt: f32) -> f32 {
+        let max_step = PLAYER_SPEED * dt;
+        let Some((player_x, target_x)) = self.paddle_target(aim) else {
+            return 0.0;
+        };
+        if max_step <= 0.0 {
+            return 0.0;
+        }
+
+        let move_x = ((target_x - player_x) / max_step).clamp(-1.0, 1.0);
+        (move_x * 127.0).round() / 127.0
+    }
+
+    // Where the paddle is and where it should be to catch the ball coming
+    // first, and when aiming to send it to the lowest brick
+    fn paddle_target(&self, aim: bool) -> Option<(f32, f32)> {
+        let (player_transform, player_sprite) = self.player.get_single().ok()?;
+        let player_size = player_sprite.custom_size?;
+        let player_top = player_transform.translation.y + player_size.y / 2.0;
+
+        let (landing_x, _) = self
+            .balls
+            .iter()
+            .filter_map(|ball| landing(ball, player_top + ball.radius))
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))?;
+
+        let hit_x = if aim {
+            self.aim(Vec2::new(landing_x, player_top))
+        } else {
+            0.0
+        };
+
+        // The ball goes off the paddle in the direction of where it hits it
+        Some((
+            player_transform.translation.x,
+            landing_x - hit_x * player_size.x,
+        ))
+    }
+
+    // Horizontal direction sending the ball from `from` to the lowest brick,
+    // the closest one of its row
+    fn aim(&self, from: Vec2) -> f32 {
+        let target = self
+            .bricks
+            .iter()
+            .map(|transform| transform.translation.xy())
+            .min_by(|a, b| {
+                a.y.total_cmp(&b.y)
+                    .then((a.x - from.x).abs().total_cmp(&(b.x - from.x).abs()))
+            });
+
+        target.map_or(0.0, |target| {
+            (target - from)
+                .normalize_or_zero()
+                .x
+                .clamp(-MAX_AIM, MAX_AIM)
+        })
+    }
+}
+
+// Where the ball next comes down to `catch_y`, bouncing off the walls and
+// the top of the game area but not off the bricks, and how far it travels
+// until then
+fn landing(ball: &Ball, catch_y: f32) -> Option<(f32, f32)> {
+    let velocity = ball.velocity;
+    let position = ball.current_position;
+    let top = GAME_AREA.max.y - ball.radius;
+
+    let fall = if velocity.y < 0.0 {
+        position.y - catch_y
+    } else if velocity.y > 0.0 {
+        (top - position.y) + (top - catch_y)
+    } else {
+        return None;
+    };
+
+    // Already past the paddle
+    if fall < 0.0 {
+        return None;
+    }
+
+    let distance = fall / velocity.y.abs();
+    let x = fold(
+        position.x + velocity.x * distance,
+        GAME_AREA.min.x + ball.radius,
+        GAME_AREA.max.x - ball.radius,
+    );
+
+    Some((x, distance))
+}
+
+// Brings `x` back between `min` and `max` as if it bounced off them
+fn fold(x: f32, min: f32, max: f32) -> f32 {
+    let width = max - min;
+    let offset = (x - min).rem_euclid(2.0 * width);
+
+    if offset > width {
+        max - (offset - width)
+    } else {
+        min + offset
+    }
+}
+
+/// Plays the whole game: goes through the menus, launches the ball and aims
+/// at the bricks
+pub(super) fn autoplay(
+    time: Res<Time>,
+    game_state: Res<State<GameState>>,
+    in_game_state: Res<State<InGameState>>,
+    playfield: Playfield,
+    mut input: ResMut<ButtonInput<Action>>,
+    mut analog_input: ResMut<AnalogInput>,
+) {
+    analog_input.move_x = 0.0;
+
+    match (game_state.get(), in_game_state.get()) {
+        // Play, Retry and Next Level are the first items of the menus
+        (GameState::InMenu, _) => tap_action(&mut input, Action::Confirm),
+        (GameState::InGame, InGameState::Paused) => tap_action(&mut input, Action::Launch),
+        (GameState::InGame, InGameState::Playing) => {
+            analog_input.move_x = playfield.move_x(true, time.delta_seconds());
+        }
+        (GameState::InGame, InGameState::Resuming) => (),
+        (GameState::Exited, _) => (),
+    }
+}
+
+/// Moves the paddle under the ball while the player does not move it
+pub(super) fn assist(
+    time: Res<Time>,
+    playfield: Playfield,
+    input: Res<ButtonInput<Action>>,
+    mut analog_input: ResMut<AnalogInput>,
+) {
+    let player_moves = input.any_pressed([Action::MoveLeft, Action::MoveRight])
+        || analog_input.move_x != 0.0
+        || analog_input.pointer_x.is_some();
+
+    if !player_moves {
+        analog_input.move_x = playfield.move_x(false, time.delta_seconds());
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,44 @@
+use super::*;
+
+fn ball(position: Vec2, velocity: Vec2) -> Ball {
+    Ball {
+        radius: 10.0,
+        velocity: velocity.normalize(),
+        current_position: position,
+        old_position: position,
+    }
+}
+
+#[test]
+fn predicts_where_the_ball_comes_down() {
+    let center = GAME_AREA.center();
+
+    // Straight down
+    let (x, distance) = landing(&ball(center, Vec2::NEG_Y), center.y - 100.0).unwrap();
+    assert!((x - center.x).abs() < 0.01);
+    assert!((distance - 100.0).abs() < 0.01);
+
+    // Off the right wall
+    let right = GAME_AREA.max.x - 10.0;
+    let start = Vec2::new(right - 50.0, center.y);
+    let (x, _) = landing(&ball(start, Vec2::new(1.0, -1.0)), center.y - 100.0).unwrap();
+    assert!((x - (right - 50.0)).abs() < 0.01);
+
+    // Up to the top first
+    let top = GAME_AREA.max.y - 10.0;
+    let (x, distance) = landing(&ball(center, Vec2::Y), center.y).unwrap();
+    assert!((x - center.x).abs() < 0.01);
+    assert!((distance - 2.0 * (top - center.y)).abs() < 0.01);
+
+    // Flat or already past the paddle
+    assert!(landing(&ball(center, Vec2::X), center.y - 100.0).is_none());
+    assert!(landing(&ball(center, Vec2::NEG_Y), center.y + 100.0).is_none());
+}
+
+#[test]
+fn folding_bounces_off_both_sides() {
+    assert_eq!(fold(5.0, 0.0, 10.0), 5.0);
+    assert_eq!(fold(12.0, 0.0, 10.0), 8.0);
+    assert_eq!(fold(-3.0, 0.0, 10.0), 3.0);
+    assert_eq!(fold(23.0, 0.0, 10.0), 3.0);
+}
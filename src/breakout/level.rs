@@ -0,0 +1,106 @@
+use std::{fs, io, path::Path};
+
+use bevy::prelude::*;
+
+use crate::application::GAME_AREA;
+
+use super::{Brick, BrickType, BRICK_SIZE, TOTAL_LEVELS};
+
+// Space taken by a brick in the grid, gaps included
+const CELL_SIZE: Vec2 = Vec2 {
+    x: BRICK_SIZE.x + 6.0,
+    y: BRICK_SIZE.y + 6.0,
+};
+
+// Seconds per life of the bricks to complete a level for three stars, about
+// the pace of the autoplayer
+const PAR_TIME_PER_LIFE: f32 = 1.5;
+
+/// Bricks of a level, by cell of the grid counted from the top left corner
+#[derive(Clone, Debug)]
+pub(super) struct Level {
+    pub bricks: Vec<(UVec2, Brick)>,
+}
+
+impl Level {
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        Self::from_text(&fs::read_to_string(path)?)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "invalid level file"))
+    }
+
+    // The number of columns and of rows, then one word per cell: the lives
+    // of a normal brick, `S` for a ball spawner, `U` for an upgrade or
+    // anything else for no brick
+    fn from_text(text: &str) -> Option<Self> {
+        let mut words = text.split_whitespace();
+
+        let nb_cols = words.next()?.parse::<u32>().ok()?;
+        let nb_rows = words.next()?.parse::<u32>().ok()?;
+
+        let mut bricks = Vec::new();
+        for y in 0..nb_rows {
+            for x in 0..nb_cols {
+                let word = words.next()?;
+                let brick = if let Ok(lives) = word.parse::<i32>() {
+                    // One sprite per number of lives
+                    if !(1..=5).contains(&lives) {
+                        return None;
+                    }
+
+                    Some(Brick {
+                        lives,
+                        brick_type: BrickType::Normal,
+                    })
+                } else if word == "S" {
+                    Some(Brick {
+                        lives: 1,
+                        brick_type: BrickType::BallSpawner,
+                    })
+                } else if word == "U" {
+                    Some(Brick {
+                        lives: 1,
+                        brick_type: BrickType::Upgrade,
+                    })
+                } else {
+                    None
+                };
+
+                if let Some(brick) = brick {
+                    bricks.push((UVec2::new(x, y), brick));
+                }
+            }
+        }
+
+        Some(Self { bricks })
+    }
+
+    /// Seconds to complete the level in for three stars
+    pub fn par_time(&self) -> f32 {
+        let nb_lives: i32 = self.bricks.iter().map(|(_, brick)| brick.lives).sum();
+        nb_lives as f32 * PAR_TIME_PER_LIFE
+    }
+}
+
+/// Every level of the campaign, in order
+pub(super) fn load_levels() -> io::Result<Vec<Level>> {
+    (1..=TOTAL_LEVELS)
+        .map(|level| Level::load(format!("assets/breakout/levels/level_{level}.txt")))
+        .collect()
+}
+
+pub(super) fn brick_position(cell: UVec2) -> Vec2 {
+    Vec2 {
+        x: GAME_AREA.min.x + CELL_SIZE.x / 2.0 + cell.x as f32 * CELL_SIZE.x,
+        y: GAME_AREA.max.y - CELL_SIZE.y / 2.0 - cell.y as f32 * CELL_SIZE.y,
+    }
+}
+
+pub(super) fn brick_cell(position: Vec2) -> UVec2 {
+    UVec2 {
+        x: ((position.x - GAME_AREA.min.x) / CELL_SIZE.x) as u32,
+        y: ((GAME_AREA.max.y - position.y) / CELL_SIZE.y) as u32,
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,38 @@
+use super::*;
+
+#[test]
+fn reads_brick_types_and_empty_cells() {
+    let level = Level::from_text("3 2\n 2 . S\n U x 1").unwrap();
+
+    let bricks: Vec<(UVec2, i32, BrickType)> = level
+        .bricks
+        .iter()
+        .map(|(cell, brick)| (*cell, brick.lives, brick.brick_type))
+        .collect();
+    assert_eq!(
+        bricks,
+        [
+            (UVec2::new(0, 0), 2, BrickType::Normal),
+            (UVec2::new(2, 0), 1, BrickType::BallSpawner),
+            (UVec2::new(0, 1), 1, BrickType::Upgrade),
+            (UVec2::new(2, 1), 1, BrickType::Normal),
+        ]
+    );
+
+    // Missing cells and lives without a sprite
+    assert!(Level::from_text("3 2\n 2 . S").is_none());
+    assert!(Level::from_text("1 1\n 6").is_none());
+}
+
+#[test]
+fn bricks_are_found_back_from_their_position() {
+    for cell in [UVec2::ZERO, UVec2::new(14, 0), UVec2::new(7, 7)] {
+        assert_eq!(brick_cell(brick_position(cell)), cell);
+    }
+}
+
+#[test]
+fn par_time_grows_with_the_lives_of_the_bricks() {
+    let level = Level::from_text("3 1\n 2 S .").unwrap();
+    assert_eq!(level.par_time(), 3.0 * PAR_TIME_PER_LIFE);
+}
@@ -0,0 +1,154 @@
+//! Plays a level with the autoplayer many times, so that level designers
+//! can check a new level before shipping it
+
+use std::{collections::HashSet, fmt, io, path::Path};
+
+use bevy::prelude::*;
+
+use crate::application::CurrentGame;
+use crate::headless::{headless_app, FRAME};
+use crate::input::{ActionSource, ActionSystem};
+use crate::registry::RetroGame;
+
+use super::level::{brick_cell, Level};
+use super::{autoplayer, BreackoutPlugin, Brick, GameAssets, LevelLoaded, MenuNode, MenuState};
+
+/// How the autoplayer did on a level
+#[derive(Debug)]
+pub struct LevelReport {
+    pub nb_runs: usize,
+    pub nb_completed: usize,
+
+    /// Average time taken by the completed runs, in seconds
+    pub average_time: Option<f32>,
+
+    /// Bricks broken in none of the runs, by cell of the level's grid
+    /// counted from the top left corner
+    pub unreachable_bricks: Vec<UVec2>,
+}
+
+impl LevelReport {
+    pub fn completion_rate(&self) -> f32 {
+        self.nb_completed as f32 / self.nb_runs.max(1) as f32
+    }
+}
+
+impl fmt::Display for LevelReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Completed: {} / {} ({:.0}%)",
+            self.nb_completed,
+            self.nb_runs,
+            self.completion_rate() * 100.0
+        )?;
+
+        match self.average_time {
+            Some(time) => writeln!(f, "Average time: {time:.1}s")?,
+            None => writeln!(f, "Average time: -")?,
+        }
+
+        let cells: Vec<String> = self
+            .unreachable_bricks
+            .iter()
+            .map(|cell| format!("({}, {})", cell.x, cell.y))
+            .collect();
+        if cells.is_empty() {
+            write!(f, "Unreachable bricks: none")
+        } else {
+            write!(f, "Unreachable bricks: {}", cells.join(" "))
+        }
+    }
+}
+
+/// Plays the level file at `path` `nb_runs` times, each run with its own
+/// seed. A run ends when the level is won or lost, or after `time_limit`
+/// seconds.
+pub fn check_level(
+    path: impl AsRef<Path>,
+    nb_runs: usize,
+    time_limit: f32,
+) -> io::Result<LevelReport> {
+    let level = Level::load(path)?;
+
+    let mut broken_bricks = HashSet::new();
+    let mut completion_times = Vec::new();
+    for seed in 0..nb_runs as u64 {
+        let run = play_level(&level, seed, time_limit);
+
+        broken_bricks.extend(run.broken_bricks);
+        completion_times.extend(run.completion_time);
+    }
+
+    let mut unreachable_bricks: Vec<UVec2> = level
+        .bricks
+        .iter()
+        .map(|(cell, _)| *cell)
+        .filter(|cell| !broken_bricks.contains(cell))
+        .collect();
+    unreachable_bricks.sort_by_key(|cell| (cell.y, cell.x));
+
+    Ok(LevelReport {
+        nb_runs,
+        nb_completed: completion_times.len(),
+        average_time: (!completion_times.is_empty())
+            .then(|| completion_times.iter().sum::<f32>() / completion_times.len() as f32),
+        unreachable_bricks,
+    })
+}
+
+struct Run {
+    completion_time: Option<f32>,
+    broken_bricks: HashSet<UVec2>,
+}
+
+// The autoplayer starts the level from the Breakout menu, like in a demo
+fn play_level(level: &Level, seed: u64, time_limit: f32) -> Run {
+    let mut app = headless_app(seed);
+    app.add_plugins(BreackoutPlugin)
+        .insert_resource(ActionSource::Scripted)
+        .add_systems(PreUpdate, autoplayer::autoplay.after(ActionSystem));
+
+    app.world_mut()
+        .resource_mut::<NextState<CurrentGame>>()
+        .set(BreackoutPlugin::STATE);
+    app.update();
+    app.world_mut().resource_mut::<GameAssets>().levels = vec![level.clone()];
+
+    let all_bricks: HashSet<UVec2> = level.bricks.iter().map(|(cell, _)| *cell).collect();
+    let mut remaining_bricks = all_bricks.clone();
+    let mut nb_frames_played = 0;
+    let mut completion_time = None;
+
+    for _ in 0..(time_limit / FRAME.as_secs_f32()) as usize {
+        app.update();
+
+        let world = app.world_mut();
+        match world.resource::<MenuState>().menu_node {
+            MenuNode::MainMenu | MenuNode::Pause => (),
+            MenuNode::GameOver => break,
+            MenuNode::GameWon | MenuNode::CampaignComplete => {
+                remaining_bricks.clear();
+                completion_time = Some(nb_frames_played as f32 * FRAME.as_secs_f32());
+                break;
+            }
+        }
+
+        if world.resource::<LevelLoaded>().0 {
+            nb_frames_played += 1;
+            remaining_bricks = world
+                .query_filtered::<&Transform, With<Brick>>()
+                .iter(world)
+                .map(|transform| brick_cell(transform.translation.xy()))
+                .collect();
+        }
+    }
+
+    Run {
+        completion_time,
+        broken_bricks: all_bricks.difference(&remaining_bricks).copied().collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests;
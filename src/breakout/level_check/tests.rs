@@ -0,0 +1,31 @@
+use std::{env, fs};
+
+use super::*;
+
+#[test]
+fn autoplayer_clears_a_small_level() {
+    let path = env::temp_dir().join("retro_check_level.txt");
+    fs::write(&path, "5 2\n . 1 . 2 .\n 1 . U . 1").unwrap();
+
+    let report = check_level(&path, 2, 120.0).unwrap();
+    fs::remove_file(&path).unwrap();
+
+    assert_eq!(report.nb_completed, 2, "{report}");
+    assert!(report.average_time.is_some_and(|time| time < 120.0));
+    assert!(report.unreachable_bricks.is_empty());
+}
+
+#[test]
+fn report_lists_unreachable_bricks() {
+    let report = LevelReport {
+        nb_runs: 4,
+        nb_completed: 3,
+        average_time: Some(42.25),
+        unreachable_bricks: vec![UVec2::new(3, 0), UVec2::new(1, 2)],
+    };
+
+    assert_eq!(
+        report.to_string(),
+        "Completed: 3 / 4 (75%)\nAverage time: 42.2s\nUnreachable bricks: (3, 0) (1, 2)"
+    );
+}
@@ -0,0 +1,177 @@
+use std::fmt::Write;
+
+use bevy::prelude::*;
+
+use super::{Brick, BrickType, LevelSummary, TOTAL_LEVELS, UPGRADE_DURATION};
+use crate::progress::LevelRecord;
+
+/// A level left in progress. The upgrades still falling are not kept.
+#[derive(Clone, PartialEq, Debug)]
+pub(super) struct BreakoutSave {
+    pub level: usize,
+    // Of the run, the upgrades and the spawned balls are drawn from it again
+    pub seed: u64,
+    pub bricks: Vec<(UVec2, Brick)>,
+    // Positions and velocities
+    pub balls: Vec<(Vec2, Vec2)>,
+    pub paddle_x: f32,
+    pub paddle_width: f32,
+    // Seconds before the paddle gets its size back
+    pub upgrade_left: Option<f32>,
+    // Whether the ball was launched, it waits for it again otherwise
+    pub launched: bool,
+    pub score: u32,
+    // Seconds played
+    pub time: f32,
+    pub balls_lost: u32,
+    // Of the campaign run, with the levels it completed before this one
+    pub new_game_plus: u32,
+    pub run: Vec<LevelSummary>,
+}
+
+impl BreakoutSave {
+    // `key = value` lines, a `completed` line per level of the run, a `ball`
+    // line per ball and a `brick` line per brick, with its cell and the word
+    // of the level files for it
+    pub fn to_text(&self) -> String {
+        let mut text = String::new();
+
+        let _ = writeln!(text, "level = {}", self.level);
+        let _ = writeln!(text, "seed = {}", self.seed);
+        let _ = writeln!(text, "paddle = {} {}", self.paddle_x, self.paddle_width);
+        if let Some(upgrade_left) = self.upgrade_left {
+            let _ = writeln!(text, "upgrade = {upgrade_left}");
+        }
+        let _ = writeln!(text, "launched = {}", self.launched);
+        let _ = writeln!(text, "score = {}", self.score);
+        let _ = writeln!(text, "time = {}", self.time);
+        let _ = writeln!(text, "balls_lost = {}", self.balls_lost);
+        let _ = writeln!(text, "new_game_plus = {}", self.new_game_plus);
+
+        for summary in &self.run {
+            let record = &summary.record;
+            let _ = writeln!(
+                text,
+                "completed = {} {} {} {} {}",
+                summary.level, record.score, record.time, record.stars, summary.balls_lost
+            );
+        }
+
+        for (position, velocity) in &self.balls {
+            let _ = writeln!(
+                text,
+                "ball = {} {} {} {}",
+                position.x, position.y, velocity.x, velocity.y
+            );
+        }
+
+        for (cell, brick) in &self.bricks {
+            let _ = writeln!(text, "brick = {} {} {}", cell.x, cell.y, brick_word(brick));
+        }
+
+        text
+    }
+
+    pub fn from_text(text: &str) -> Option<Self> {
+        let mut level = None;
+        let mut seed = None;
+        let mut paddle = None;
+        let mut upgrade_left = None;
+        let mut launched = None;
+        let mut score = None;
+        let mut time = None;
+        let mut balls_lost = None;
+        let mut new_game_plus = None;
+        let mut run = Vec::new();
+        let mut balls = Vec::new();
+        let mut bricks = Vec::new();
+
+        for line in text.lines().map(str::trim).filter(|line| !line.is_empty()) {
+            let (key, value) = line.split_once('=')?;
+            let words: Vec<&str> = value.split_whitespace().collect();
+
+            match (key.trim(), &words[..]) {
+                ("level", [number]) => level = Some(number.parse().ok()?),
+                ("seed", [number]) => seed = Some(number.parse().ok()?),
+                ("paddle", [x, width]) => paddle = Some((x.parse().ok()?, width.parse().ok()?)),
+                ("upgrade", [seconds]) => {
+                    let seconds = seconds.parse().ok()?;
+                    if !(0.0..=UPGRADE_DURATION).contains(&seconds) {
+                        return None;
+                    }
+                    upgrade_left = Some(seconds);
+                }
+                ("launched", [launched_value]) => launched = Some(launched_value.parse().ok()?),
+                ("score", [points]) => score = Some(points.parse().ok()?),
+                ("time", [seconds]) => time = Some(seconds.parse().ok()?),
+                ("balls_lost", [number]) => balls_lost = Some(number.parse().ok()?),
+                ("new_game_plus", [number]) => new_game_plus = Some(number.parse().ok()?),
+                ("completed", [level, score, seconds, stars, lost]) => run.push(LevelSummary {
+                    level: level.parse().ok()?,
+                    record: LevelRecord {
+                        score: score.parse().ok()?,
+                        time: seconds.parse().ok()?,
+                        stars: stars.parse().ok()?,
+                    },
+                    balls_lost: lost.parse().ok()?,
+                }),
+                ("ball", [x, y, velocity_x, velocity_y]) => balls.push((
+                    Vec2::new(x.parse().ok()?, y.parse().ok()?),
+                    Vec2::new(velocity_x.parse().ok()?, velocity_y.parse().ok()?),
+                )),
+                ("brick", [x, y, word]) => bricks.push((
+                    UVec2::new(x.parse().ok()?, y.parse().ok()?),
+                    word_brick(word)?,
+                )),
+                _ => return None,
+            }
+        }
+
+        // Without balls or bricks the level would never end
+        let level = level?;
+        if !(1..=TOTAL_LEVELS).contains(&level) || balls.is_empty() || bricks.is_empty() {
+            return None;
+        }
+
+        let (paddle_x, paddle_width) = paddle?;
+        Some(Self {
+            level,
+            seed: seed?,
+            bricks,
+            balls,
+            paddle_x,
+            paddle_width,
+            upgrade_left,
+            launched: launched?,
+            score: score?,
+            time: time?,
+            balls_lost: balls_lost?,
+            new_game_plus: new_game_plus?,
+            run,
+        })
+    }
+}
+
+fn brick_word(brick: &Brick) -> String {
+    match brick.brick_type {
+        BrickType::Normal => brick.lives.to_string(),
+        BrickType::BallSpawner => String::from("S"),
+        BrickType::Upgrade => String::from("U"),
+    }
+}
+
+fn word_brick(word: &str) -> Option<Brick> {
+    let (lives, brick_type) = match word {
+        "S" => (1, BrickType::BallSpawner),
+        "U" => (1, BrickType::Upgrade),
+        _ => (word.parse().ok()?, BrickType::Normal),
+    };
+
+    // One sprite per number of lives
+    (1..=5)
+        .contains(&lives)
+        .then_some(Brick { lives, brick_type })
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,94 @@
+use super::*;
+
+fn level_in_progress() -> BreakoutSave {
+    BreakoutSave {
+        level: 3,
+        seed: 42,
+        bricks: vec![
+            (
+                UVec2::new(0, 0),
+                Brick {
+                    lives: 4,
+                    brick_type: BrickType::Normal,
+                },
+            ),
+            (
+                UVec2::new(7, 2),
+                Brick {
+                    lives: 1,
+                    brick_type: BrickType::BallSpawner,
+                },
+            ),
+            (
+                UVec2::new(14, 7),
+                Brick {
+                    lives: 1,
+                    brick_type: BrickType::Upgrade,
+                },
+            ),
+        ],
+        balls: vec![
+            (Vec2::new(-12.5, 40.0), Vec2::new(0.6, 0.8)),
+            (Vec2::new(100.0, -3.25), Vec2::new(-0.8, -0.6)),
+        ],
+        paddle_x: 37.5,
+        paddle_width: 300.0,
+        upgrade_left: Some(9.5),
+        launched: true,
+        score: 370,
+        time: 42.75,
+        balls_lost: 1,
+        new_game_plus: 2,
+        run: vec![
+            LevelSummary {
+                level: 1,
+                record: LevelRecord {
+                    score: 1250,
+                    time: 61.5,
+                    stars: 3,
+                },
+                balls_lost: 0,
+            },
+            LevelSummary {
+                level: 2,
+                record: LevelRecord {
+                    score: 980,
+                    time: 130.25,
+                    stars: 2,
+                },
+                balls_lost: 2,
+            },
+        ],
+    }
+}
+
+#[test]
+fn text_round_trip() {
+    let save = level_in_progress();
+    assert_eq!(BreakoutSave::from_text(&save.to_text()), Some(save));
+
+    let waiting = BreakoutSave {
+        upgrade_left: None,
+        launched: false,
+        ..level_in_progress()
+    };
+    assert_eq!(BreakoutSave::from_text(&waiting.to_text()), Some(waiting));
+}
+
+#[test]
+fn broken_saves_are_not_loaded() {
+    let text = level_in_progress().to_text();
+
+    // No such level, no more balls, lives without a sprite, a missing line
+    assert!(BreakoutSave::from_text(&text.replace("level = 3", "level = 9")).is_none());
+    let no_balls: String = text
+        .lines()
+        .filter(|line| !line.starts_with("ball ="))
+        .map(|line| format!("{line}\n"))
+        .collect();
+    assert!(BreakoutSave::from_text(&no_balls).is_none());
+    assert!(BreakoutSave::from_text(&text.replace("0 0 4", "0 0 6")).is_none());
+    assert!(BreakoutSave::from_text(&text.replace("seed = 42\n", "")).is_none());
+    assert!(BreakoutSave::from_text(&text.replace("new_game_plus = 2\n", "")).is_none());
+    assert!(BreakoutSave::from_text(&text.replace(" 130.25 2 2", " 130.25 2")).is_none());
+}
@@ -0,0 +1,613 @@
+use bevy::{input::gamepad::GamepadAxisType, prelude::*};
+
+use crate::{
+    application::GAME_AREA,
+    input::{Action, ActionSource, AnalogInput},
+    saves::SavedGames,
+    scores::BestScores,
+    testing::{
+        self, connect_gamepad, count, headless_app, launched_app, menu_item, move_axis, press,
+        run_frames, run_until, state, tap, EventCounter, FRAME,
+    },
+};
+
+use super::*;
+
+// Generous bound for the ball to cross the game area a few times
+const MAX_FRAMES: usize = 1200;
+
+// With every level unlocked, so that any can be loaded from the menu
+fn breakout_app() -> App {
+    let mut app = headless_app();
+    app.add_plugins(BreackoutPlugin);
+
+    let mut progress = CampaignProgress::default();
+    for level in 1..TOTAL_LEVELS {
+        let record = LevelRecord {
+            score: 0,
+            time: 600.0,
+            stars: 1,
+        };
+        progress.submit(BreackoutPlugin::NAME, level, record);
+    }
+    app.insert_resource(progress);
+
+    testing::set_state(&mut app, BreackoutPlugin::STATE);
+    run_frames(&mut app, 2);
+
+    app
+}
+
+// Goes through "Load level" in the Breakout menu, the ball is not launched yet
+fn start_level(app: &mut App, level: usize) {
+    tap(app, KeyCode::ArrowDown);
+    tap(app, KeyCode::Space);
+
+    for _ in 1..level {
+        tap(app, KeyCode::ArrowDown);
+    }
+    tap(app, KeyCode::Space);
+
+    // The first ball is spawned from an event sent while loading the level
+    run_frames(app, 1);
+}
+
+fn launch_ball(app: &mut App) {
+    tap(app, KeyCode::Space);
+}
+
+fn level_brick_count(level: usize) -> usize {
+    let file_content =
+        std::fs::read_to_string(format!("assets/breakout/levels/level_{level}.txt")).unwrap();
+
+    file_content
+        .split_whitespace()
+        .skip(2)
+        .filter(|cell| *cell != ".")
+        .count()
+}
+
+// Keeps only the brick right above the paddle, where the ball goes first,
+// and turns it into a one hit brick of the given type
+fn keep_center_brick(app: &mut App, brick_type: BrickType) -> Entity {
+    let world = app.world_mut();
+    let mut bricks = world.query::<(Entity, &Transform, &Brick)>();
+
+    let center_brick = bricks
+        .iter(world)
+        .filter(|(_, transform, _)| (transform.translation.x - GAME_AREA.center().x).abs() < 1.0)
+        .min_by(|(_, a, _), (_, b, _)| a.translation.y.total_cmp(&b.translation.y))
+        .map(|(entity, _, _)| entity)
+        .unwrap();
+
+    let other_bricks: Vec<Entity> = bricks
+        .iter(world)
+        .map(|(entity, _, _)| entity)
+        .filter(|entity| *entity != center_brick)
+        .collect();
+
+    for entity in other_bricks {
+        world.despawn(entity);
+    }
+
+    *world.get_mut::<Brick>(center_brick).unwrap() = Brick {
+        lives: 1,
+        brick_type,
+    };
+
+    center_brick
+}
+
+// Spawns a brick in the top left corner, out of the ball's way, so that the
+// level is not won as soon as the center brick breaks
+fn spawn_corner_brick(app: &mut App) {
+    let position = GAME_AREA.min.x + BRICK_SIZE.x / 2.0;
+    app.world_mut().spawn((
+        Transform::from_xyz(position, GAME_AREA.max.y - BRICK_SIZE.y / 2.0, 1.0),
+        Handle::<Image>::default(),
+        Brick {
+            lives: 1,
+            brick_type: BrickType::Normal,
+        },
+    ));
+}
+
+#[test]
+fn loads_every_shipped_level() {
+    for level in 1..=TOTAL_LEVELS {
+        let mut app = breakout_app();
+        start_level(&mut app, level);
+
+        assert_eq!(count::<Brick>(&mut app), level_brick_count(level));
+        assert_eq!(count::<Ball>(&mut app), 1);
+        assert_eq!(count::<Player>(&mut app), 1);
+        assert_eq!(testing::state::<GameState>(&app), GameState::InGame);
+        assert_eq!(app.world().resource::<LastLevelPlayed>().0, level);
+    }
+}
+
+#[test]
+fn clearing_a_level_wins_it() {
+    let mut app = breakout_app();
+    start_level(&mut app, 1);
+    keep_center_brick(&mut app, BrickType::Normal);
+
+    let mut game_won = EventCounter::<GameWonEvent>::new(&app);
+    let mut game_over = EventCounter::<GameOverEvent>::new(&app);
+    launch_ball(&mut app);
+
+    assert!(run_until(&mut app, MAX_FRAMES, |app| game_won.update(app) > 0));
+    run_frames(&mut app, 2);
+
+    assert_eq!(game_over.update(&app), 0);
+    assert_eq!(count::<Brick>(&mut app), 0);
+    assert_eq!(testing::state::<GameState>(&app), GameState::InMenu);
+    assert!(app.world().resource::<MenuState>().menu_node == MenuNode::GameWon);
+}
+
+#[test]
+fn losing_every_ball_is_game_over() {
+    let mut app = breakout_app();
+    start_level(&mut app, 1);
+
+    let mut game_won = EventCounter::<GameWonEvent>::new(&app);
+    let mut game_over = EventCounter::<GameOverEvent>::new(&app);
+    launch_ball(&mut app);
+
+    // Keep the paddle away from the ball
+    press(&mut app, KeyCode::ArrowLeft);
+
+    assert!(run_until(&mut app, MAX_FRAMES, |app| game_over.update(app) > 0));
+    run_frames(&mut app, 2);
+
+    assert_eq!(game_won.update(&app), 0);
+    assert_eq!(count::<Ball>(&mut app), 0);
+    assert_eq!(testing::state::<GameState>(&app), GameState::InMenu);
+    assert!(app.world().resource::<MenuState>().menu_node == MenuNode::GameOver);
+}
+
+#[test]
+fn spawner_brick_adds_a_ball() {
+    let mut app = breakout_app();
+    start_level(&mut app, 1);
+    keep_center_brick(&mut app, BrickType::BallSpawner);
+    spawn_corner_brick(&mut app);
+    launch_ball(&mut app);
+
+    assert_eq!(count::<Ball>(&mut app), 1);
+    assert!(run_until(&mut app, MAX_FRAMES, |app| count::<Ball>(app) == 2));
+    assert_eq!(count::<Brick>(&mut app), 1);
+}
+
+#[test]
+fn upgrade_brick_drops_a_power_up() {
+    let mut app = breakout_app();
+    start_level(&mut app, 1);
+    keep_center_brick(&mut app, BrickType::Upgrade);
+    spawn_corner_brick(&mut app);
+    launch_ball(&mut app);
+
+    assert_eq!(count::<UpgradeComponent>(&mut app), 0);
+    assert!(run_until(&mut app, MAX_FRAMES, |app| {
+        count::<UpgradeComponent>(app) == 1
+    }));
+    assert_eq!(count::<Ball>(&mut app), 1);
+}
+
+#[test]
+fn left_stick_moves_the_paddle_as_fast_as_it_is_pushed() {
+    let mut app = breakout_app();
+    start_level(&mut app, 1);
+    launch_ball(&mut app);
+
+    let gamepad = connect_gamepad(&mut app, 0);
+    let player_x = |app: &mut App| {
+        app.world_mut()
+            .query_filtered::<&Transform, With<Player>>()
+            .single(app.world())
+            .translation
+            .x
+    };
+
+    let start_x = player_x(&mut app);
+    move_axis(&mut app, gamepad, GamepadAxisType::LeftStickX, -0.5);
+    run_frames(&mut app, 10);
+
+    let expected = 10.0 * 0.5 * PLAYER_SPEED * FRAME.as_secs_f32();
+    assert!((start_x - player_x(&mut app) - expected).abs() < 0.5);
+}
+
+#[test]
+fn paddle_follows_the_pointer_and_click_launches() {
+    let mut app = breakout_app();
+    start_level(&mut app, 1);
+
+    // Without a window, the pointer is fed like a replay would
+    app.insert_resource(ActionSource::Scripted);
+    let mut actions = app.world_mut().resource_mut::<ButtonInput<Action>>();
+    actions.press(Action::Click);
+    run_frames(&mut app, 2);
+    assert_eq!(testing::state::<InGameState>(&app), InGameState::Playing);
+
+    let player_x = |app: &mut App| {
+        app.world_mut()
+            .query_filtered::<&Transform, With<Player>>()
+            .single(app.world())
+            .translation
+            .x
+    };
+
+    let pointer_x = GAME_AREA.center().x + 100.0;
+    app.world_mut().resource_mut::<AnalogInput>().pointer_x = Some(pointer_x);
+    app.update();
+    assert_eq!(player_x(&mut app), pointer_x);
+
+    // Clamped to the game area
+    app.world_mut().resource_mut::<AnalogInput>().pointer_x = Some(GAME_AREA.min.x - 100.0);
+    app.update();
+    assert_eq!(player_x(&mut app), GAME_AREA.min.x + PLAYER_SIZE.x / 2.0);
+}
+
+#[test]
+fn command_line_starts_a_level() {
+    let app = launched_app("--game breakout --level 3 --seed 42");
+
+    assert_eq!(state::<GameState>(&app), GameState::InGame);
+    assert_eq!(app.world().resource::<LastLevelPlayed>().0, 3);
+    assert!(app.world().resource::<LevelLoaded>().0);
+
+    // The first run of the session plays from 42
+    assert_eq!(app.world().resource::<GameRng>().seed(), 42);
+
+    // Out of the campaign, the menu is shown
+    let app = launched_app("--game breakout --level 9");
+    assert_eq!(state::<GameState>(&app), GameState::InMenu);
+}
+
+#[test]
+fn the_seed_shown_in_pause_plays_the_run_again() {
+    let mut app = breakout_app();
+    // Not the first run of the session
+    app.world_mut().resource_mut::<GameRng>().new_run();
+    start_level(&mut app, 2);
+    tap(&mut app, KeyCode::Escape);
+
+    let seed_text = app
+        .world_mut()
+        .query_filtered::<&Text, With<SeedText>>()
+        .single(app.world())
+        .sections[0]
+        .value
+        .clone();
+    let seed = seed_text.strip_prefix("Seed: ").unwrap();
+
+    let mut launched = launched_app(&format!("--game breakout --level 2 --seed {seed}"));
+
+    // The same upgrades and ball angles to come
+    for app in [&app, &launched] {
+        assert_eq!(app.world().resource::<GameRng>().seed().to_string(), seed);
+    }
+    let draws = |app: &mut App| {
+        let mut game_rng = app.world_mut().resource_mut::<GameRng>();
+        [(); 8].map(|_| game_rng.random::<u64>())
+    };
+    assert_eq!(draws(&mut app), draws(&mut launched));
+}
+
+#[test]
+fn pausing_freezes_the_level_and_resuming_counts_down() {
+    let mut app = breakout_app();
+    start_level(&mut app, 1);
+    launch_ball(&mut app);
+    run_frames(&mut app, 10);
+
+    let ball_position = |app: &mut App| {
+        app.world_mut()
+            .query_filtered::<&Transform, With<Ball>>()
+            .single(app.world())
+            .translation
+    };
+    let countdown = |app: &mut App| {
+        let world = app.world_mut();
+        let (text, visibility) = world
+            .query_filtered::<(&Text, &Visibility), With<ResumeCountdownText>>()
+            .single(world);
+        (*visibility != Visibility::Hidden).then(|| text.sections[0].value.clone())
+    };
+
+    // The level stays behind the pause menu
+    tap(&mut app, KeyCode::Escape);
+    assert_eq!(state::<GameState>(&app), GameState::InMenu);
+    assert!(app.world().resource::<MenuState>().menu_node == MenuNode::Pause);
+    assert_eq!(count::<Brick>(&mut app), level_brick_count(1));
+    let position = ball_position(&mut app);
+    run_frames(&mut app, 10);
+    assert_eq!(ball_position(&mut app), position);
+
+    // "Resume"
+    tap(&mut app, KeyCode::Space);
+    assert_eq!(state::<InGameState>(&app), InGameState::Resuming);
+    assert_eq!(countdown(&mut app).as_deref(), Some("3"));
+
+    let frames_per_second = (1.0 / FRAME.as_secs_f32()).round() as usize;
+    run_frames(&mut app, frames_per_second);
+    assert_eq!(countdown(&mut app).as_deref(), Some("2"));
+    assert_eq!(ball_position(&mut app), position);
+
+    assert!(run_until(&mut app, 2 * frames_per_second, |app| {
+        state::<InGameState>(app) == InGameState::Playing
+    }));
+    assert_eq!(countdown(&mut app), None);
+    run_frames(&mut app, 1);
+    assert_ne!(ball_position(&mut app), position);
+}
+
+#[test]
+fn pause_menu_restarts_changes_settings_and_quits() {
+    let mut app = breakout_app();
+    start_level(&mut app, 2);
+    launch_ball(&mut app);
+    run_frames(&mut app, 10);
+
+    // "Restart level", the ball waits to be launched again
+    tap(&mut app, KeyCode::Escape);
+    tap(&mut app, KeyCode::ArrowDown);
+    tap(&mut app, KeyCode::Space);
+    run_frames(&mut app, 1);
+    assert_eq!(state::<GameState>(&app), GameState::InGame);
+    assert_eq!(state::<InGameState>(&app), InGameState::Paused);
+    assert_eq!(app.world().resource::<LastLevelPlayed>().0, 2);
+    assert_eq!(count::<Brick>(&mut app), level_brick_count(2));
+
+    // "Settings", then "Paddle follows the pointer" below "Assist"
+    tap(&mut app, KeyCode::Escape);
+    for key in [KeyCode::ArrowDown, KeyCode::ArrowDown, KeyCode::Space] {
+        tap(&mut app, key);
+    }
+    tap(&mut app, KeyCode::ArrowDown);
+    tap(&mut app, KeyCode::Space);
+    assert!(app.world().resource::<InputMap>().follow_pointer);
+
+    // Back to the pause menu, then "Quit to launcher" at its bottom
+    tap(&mut app, KeyCode::Escape);
+    tap(&mut app, KeyCode::ArrowUp);
+    tap(&mut app, KeyCode::Space);
+    run_frames(&mut app, 1);
+    assert_eq!(state::<CurrentGame>(&app), CurrentGame::InMainMenu);
+    assert_eq!(state::<GameState>(&app), GameState::Exited);
+}
+
+#[test]
+fn a_paused_level_is_saved_and_continued() {
+    let mut app = breakout_app();
+    start_level(&mut app, 2);
+    launch_ball(&mut app);
+    run_frames(&mut app, 10);
+
+    let bricks = |app: &mut App| {
+        let world = app.world_mut();
+        let mut bricks: Vec<(UVec2, i32)> = world
+            .query::<(&Transform, &Brick)>()
+            .iter(world)
+            .map(|(transform, brick)| (brick_cell(transform.translation.xy()), brick.lives))
+            .collect();
+        bricks.sort_by_key(|(cell, _)| (cell.y, cell.x));
+        bricks
+    };
+    let ball = |app: &mut App| {
+        let world = app.world_mut();
+        let ball = world.query::<&Ball>().single(world);
+        (ball.current_position, ball.velocity)
+    };
+
+    {
+        let world = app.world_mut();
+        let mut brick = world
+            .query::<&mut Brick>()
+            .iter_mut(world)
+            .find(|brick| brick.brick_type == BrickType::Normal && brick.lives == 3)
+            .unwrap();
+        brick.lives = 2;
+    }
+
+    // "Quit to launcher" from the pause menu, then back in
+    tap(&mut app, KeyCode::Escape);
+    let saved_bricks = bricks(&mut app);
+    let saved_ball = ball(&mut app);
+    tap(&mut app, KeyCode::ArrowUp);
+    tap(&mut app, KeyCode::Space);
+    run_frames(&mut app, 1);
+    assert!(app
+        .world()
+        .resource::<SavedGames>()
+        .contains(BreackoutPlugin::NAME));
+    testing::set_state(&mut app, BreackoutPlugin::STATE);
+    run_frames(&mut app, 2);
+
+    // "Continue", at the top of the main menu
+    tap(&mut app, KeyCode::Space);
+    run_frames(&mut app, 1);
+    assert_eq!(state::<GameState>(&app), GameState::InGame);
+    assert_eq!(state::<InGameState>(&app), InGameState::Resuming);
+    assert_eq!(app.world().resource::<LastLevelPlayed>().0, 2);
+    assert_eq!(bricks(&mut app), saved_bricks);
+    assert_eq!(ball(&mut app), saved_ball);
+
+    // A level that is over can not be continued
+    app.world_mut().send_event_default::<GameOverEvent>();
+    run_frames(&mut app, 2);
+    assert!(!app
+        .world()
+        .resource::<SavedGames>()
+        .contains(BreackoutPlugin::NAME));
+}
+
+#[test]
+fn completing_a_level_unlocks_the_next_one() {
+    let mut app = headless_app();
+    app.add_plugins(BreackoutPlugin);
+    testing::set_state(&mut app, BreackoutPlugin::STATE);
+    run_frames(&mut app, 2);
+
+    // "Load level", where "Level 2" can not be chosen yet
+    tap(&mut app, KeyCode::ArrowDown);
+    tap(&mut app, KeyCode::Space);
+    run_frames(&mut app, 1);
+    menu_item(&mut app, "Level 2  (locked)");
+    tap(&mut app, KeyCode::ArrowDown);
+    tap(&mut app, KeyCode::Space);
+    run_frames(&mut app, 1);
+    assert_eq!(state::<GameState>(&app), GameState::InMenu);
+
+    // Back to "Play", which starts the campaign from level 1
+    tap(&mut app, KeyCode::Escape);
+    tap(&mut app, KeyCode::Space);
+    run_frames(&mut app, 1);
+    assert_eq!(app.world().resource::<LastLevelPlayed>().0, 1);
+
+    keep_center_brick(&mut app, BrickType::Normal);
+    let mut game_won = EventCounter::<GameWonEvent>::new(&app);
+    launch_ball(&mut app);
+    assert!(run_until(&mut app, MAX_FRAMES, |app| game_won.update(app) > 0));
+    run_frames(&mut app, 2);
+
+    // Well under the par time
+    let progress = app.world().resource::<CampaignProgress>();
+    let record = progress.get(BreackoutPlugin::NAME, 1).unwrap();
+    assert_eq!(record.stars, 3);
+    assert!(record.score > BRICK_POINTS);
+    assert!(progress.is_unlocked(BreackoutPlugin::NAME, 2));
+    menu_item(&mut app, &format!("Score: {}", record.score));
+    menu_item(&mut app, "Stars: ***");
+
+    // "Main Menu", then "Load level" shows the stars and the next level
+    tap(&mut app, KeyCode::ArrowDown);
+    tap(&mut app, KeyCode::Space);
+    run_frames(&mut app, 1);
+    tap(&mut app, KeyCode::ArrowDown);
+    tap(&mut app, KeyCode::Space);
+    run_frames(&mut app, 1);
+    menu_item(&mut app, "Level 1  ***");
+    menu_item(&mut app, "Level 2");
+    menu_item(&mut app, "Level 3  (locked)");
+}
+
+// Plays the last level of a run whose other levels are already completed,
+// until the victory screen
+fn win_last_level(app: &mut App) {
+    start_level(app, TOTAL_LEVELS);
+    app.world_mut().resource_mut::<CampaignRun>().levels = (1..TOTAL_LEVELS)
+        .map(|level| LevelSummary {
+            level,
+            record: LevelRecord {
+                score: 1000,
+                time: 60.0,
+                stars: 3,
+            },
+            balls_lost: 1,
+        })
+        .collect();
+
+    keep_center_brick(app, BrickType::Normal);
+    let mut game_won = EventCounter::<GameWonEvent>::new(app);
+    launch_ball(app);
+    assert!(run_until(app, MAX_FRAMES, |app| game_won.update(app) > 0));
+    run_frames(app, 2);
+}
+
+#[test]
+fn completing_the_campaign_shows_its_summary_and_takes_initials() {
+    let mut app = breakout_app();
+    app.init_resource::<BestScores>();
+    win_last_level(&mut app);
+
+    assert!(app.world().resource::<MenuState>().menu_node == MenuNode::CampaignComplete);
+    let run = app.world().resource::<CampaignRun>();
+    let total_score = run.score();
+    assert!(total_score > 4000);
+    assert_eq!(run.balls_lost(), 4);
+    assert_eq!(
+        app.world()
+            .resource::<BestScores>()
+            .get(BreackoutPlugin::NAME),
+        Some(total_score)
+    );
+    menu_item(&mut app, "Campaign complete!");
+    menu_item(&mut app, &format!("Total score: {total_score}"));
+    menu_item(&mut app, "Balls lost: 4");
+
+    // Down to the prompt below the four lines of the summary
+    for _ in 0..4 {
+        tap(&mut app, KeyCode::ArrowDown);
+    }
+    tap(&mut app, KeyCode::Space);
+    run_frames(&mut app, 1);
+
+    // B, then B, then Z
+    tap(&mut app, KeyCode::Space);
+    tap(&mut app, KeyCode::ArrowDown);
+    tap(&mut app, KeyCode::ArrowRight);
+    tap(&mut app, KeyCode::ArrowDown);
+    tap(&mut app, KeyCode::ArrowLeft);
+    run_frames(&mut app, 1);
+    menu_item(&mut app, "Initial 3: Z");
+
+    // "Done"
+    tap(&mut app, KeyCode::ArrowDown);
+    tap(&mut app, KeyCode::Space);
+    run_frames(&mut app, 1);
+    assert_eq!(
+        app.world()
+            .resource::<BestScores>()
+            .holder(BreackoutPlugin::NAME),
+        Some("BBZ")
+    );
+    assert!(!app.world().resource::<CampaignRun>().new_best);
+    menu_item(&mut app, "Levels");
+}
+
+#[test]
+fn new_game_plus_starts_over_harder() {
+    let mut app = breakout_app();
+    win_last_level(&mut app);
+
+    // "Levels" below the summary, then back to "New Game+" below "Credits"
+    for _ in 0..4 {
+        tap(&mut app, KeyCode::ArrowDown);
+    }
+    tap(&mut app, KeyCode::Space);
+    run_frames(&mut app, 1);
+    menu_item(&mut app, "Level 1: 1000 pts, 60.0s, ***, 1 lost");
+    tap(&mut app, KeyCode::Escape);
+    for _ in 0..6 {
+        tap(&mut app, KeyCode::ArrowDown);
+    }
+    tap(&mut app, KeyCode::Space);
+    run_frames(&mut app, 2);
+
+    assert_eq!(state::<GameState>(&app), GameState::InGame);
+    assert_eq!(app.world().resource::<LastLevelPlayed>().0, 1);
+    let run = app.world().resource::<CampaignRun>();
+    assert_eq!(run.new_game_plus, 1);
+    assert!(run.levels.is_empty());
+    assert!(run.ball_speed() > BALL_SPEED);
+
+    // Normal bricks with one more life than in the level file
+    let mut expected_lives: Vec<(UVec2, i32)> = app.world().resource::<GameAssets>().levels[0]
+        .bricks
+        .iter()
+        .map(|(cell, brick)| match brick.brick_type {
+            BrickType::Normal => (*cell, (brick.lives + 1).min(5)),
+            _ => (*cell, brick.lives),
+        })
+        .collect();
+    let world = app.world_mut();
+    let mut lives: Vec<(UVec2, i32)> = world
+        .query::<(&Transform, &Brick)>()
+        .iter(world)
+        .map(|(transform, brick)| (brick_cell(transform.translation.xy()), brick.lives))
+        .collect();
+    expected_lives.sort_by_key(|(cell, _)| (cell.y, cell.x));
+    lives.sort_by_key(|(cell, _)| (cell.y, cell.x));
+    assert_eq!(lives, expected_lives);
+}
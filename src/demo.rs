@@ -0,0 +1,231 @@
+//! Demos: a game plays itself, its own bot pressing the actions, until the
+//! player presses anything. The launcher starts them when it is left idle.
+
+use bevy::{
+    color::palettes,
+    input::{
+        gamepad::GamepadEvent, keyboard::KeyboardInput, mouse::MouseButtonInput, touch::TouchPhase,
+        ButtonState, InputSystem,
+    },
+    prelude::*,
+};
+
+use crate::{
+    application::{CurrentGame, GAME_AREA, WINDOW_RESOLUTION},
+    input::{Action, ActionSource, ActionSystem, AnalogInput},
+};
+
+// Seconds a demo lasts before going back to the launcher
+const DEMO_DURATION: f32 = 60.0;
+
+// How far a button or a stick has to be pushed to stop a demo
+const WAKE_THRESHOLD: f32 = 0.5;
+
+pub struct DemoPlugin;
+
+impl Plugin for DemoPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<StartDemoEvent>()
+            .configure_sets(
+                PreUpdate,
+                DemoSystems
+                    .after(ActionSystem)
+                    .run_if(resource_exists::<Demo>),
+            )
+            .add_systems(
+                PreUpdate,
+                (
+                    wake_up.before(InputSystem),
+                    clear_demo_actions.after(ActionSystem).before(DemoSystems),
+                )
+                    .run_if(resource_exists::<Demo>),
+            )
+            .add_systems(
+                Last,
+                (
+                    (tick_demo, stop_demo)
+                        .chain()
+                        .run_if(resource_exists::<Demo>),
+                    start_demo.run_if(on_event::<StartDemoEvent>()),
+                )
+                    .chain(),
+            );
+    }
+}
+
+/// Plays a demo of the game, should be sent from the launcher
+#[derive(Event)]
+pub struct StartDemoEvent {
+    pub game: CurrentGame,
+}
+
+/// Exists while a demo is played, games should not keep scores or saves
+/// from it
+#[derive(Resource)]
+pub struct Demo {
+    game: CurrentGame,
+    timer: Timer,
+
+    // Whether the game was entered, leaving it ends the demo
+    started: bool,
+    stopped: bool,
+}
+
+/// Bots of the games, they press the actions in `ButtonInput<Action>` and
+/// set the `AnalogInput` during a demo. Nothing is pressed at the start of
+/// the set, an action pressed every frame is only just pressed once.
+#[derive(SystemSet, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct DemoSystems;
+
+/// Presses `action` if it is released and releases it otherwise, so that
+/// it is just pressed every other frame, like a player tapping the key
+pub fn tap_action(input: &mut ButtonInput<Action>, action: Action) {
+    if input.pressed(action) {
+        input.release(action);
+    } else {
+        input.press(action);
+    }
+}
+
+#[derive(Component)]
+struct DemoHud;
+
+fn start_demo(
+    mut commands: Commands,
+    mut start_events: EventReader<StartDemoEvent>,
+    mut next_game: ResMut<NextState<CurrentGame>>,
+    mut action_source: ResMut<ActionSource>,
+    mut input: ResMut<ButtonInput<Action>>,
+    mut analog_input: ResMut<AnalogInput>,
+) {
+    let Some(start_event) = start_events.read().last() else {
+        return;
+    };
+
+    *action_source = ActionSource::Scripted;
+    input.release_all();
+    *analog_input = AnalogInput::default();
+    next_game.set(start_event.game);
+
+    commands.insert_resource(Demo {
+        game: start_event.game,
+        timer: Timer::from_seconds(DEMO_DURATION, TimerMode::Once),
+        started: false,
+        stopped: false,
+    });
+
+    commands.spawn((
+        TextBundle::from_section(
+            "Demo   Press any key to play",
+            TextStyle {
+                font_size: 20.0,
+                color: palettes::basic::RED.into(),
+                ..Default::default()
+            },
+        )
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            left: Val::Px(GAME_AREA.min.x + WINDOW_RESOLUTION[0] / 2.0 + 10.0),
+            top: Val::Px(10.0),
+            ..Default::default()
+        }),
+        DemoHud,
+    ));
+}
+
+// Any key, click, touch or gamepad input stops the demo. It is only used
+// for that, neither the game nor the launcher see it.
+fn wake_up(
+    mut demo: ResMut<Demo>,
+    mut keyboard_events: ResMut<Events<KeyboardInput>>,
+    mut mouse_button_events: ResMut<Events<MouseButtonInput>>,
+    mut touch_events: ResMut<Events<TouchInput>>,
+    mut gamepad_events: ResMut<Events<GamepadEvent>>,
+) {
+    if keyboard_events
+        .drain()
+        .any(|event| event.state == ButtonState::Pressed)
+    {
+        demo.stopped = true;
+    }
+
+    if mouse_button_events
+        .drain()
+        .any(|event| event.state == ButtonState::Pressed)
+    {
+        demo.stopped = true;
+    }
+
+    if touch_events
+        .drain()
+        .any(|event| event.phase == TouchPhase::Started)
+    {
+        demo.stopped = true;
+    }
+
+    // Gamepads still get connected and disconnected
+    let events: Vec<GamepadEvent> = gamepad_events.drain().collect();
+    for event in events {
+        match event {
+            GamepadEvent::Button(button) => {
+                if button.value >= WAKE_THRESHOLD {
+                    demo.stopped = true;
+                }
+            }
+            GamepadEvent::Axis(axis) => {
+                if axis.value.abs() >= WAKE_THRESHOLD {
+                    demo.stopped = true;
+                }
+            }
+            GamepadEvent::Connection(_) => {
+                gamepad_events.send(event);
+            }
+        }
+    }
+}
+
+fn clear_demo_actions(mut input: ResMut<ButtonInput<Action>>) {
+    input.clear();
+}
+
+fn tick_demo(time: Res<Time>, current_game: Res<State<CurrentGame>>, mut demo: ResMut<Demo>) {
+    if *current_game.get() == demo.game {
+        demo.started = true;
+    } else if demo.started {
+        // The game went back to the launcher by itself
+        demo.stopped = true;
+    }
+
+    if demo.started && demo.timer.tick(time.delta()).finished() {
+        demo.stopped = true;
+    }
+}
+
+// Back to the launcher, where the player takes over
+fn stop_demo(
+    mut commands: Commands,
+    demo: Res<Demo>,
+    mut next_game: ResMut<NextState<CurrentGame>>,
+    mut action_source: ResMut<ActionSource>,
+    mut input: ResMut<ButtonInput<Action>>,
+    mut analog_input: ResMut<AnalogInput>,
+    hud: Query<Entity, With<DemoHud>>,
+) {
+    if !demo.stopped {
+        return;
+    }
+
+    next_game.set(CurrentGame::InMainMenu);
+    *action_source = ActionSource::Player;
+    input.release_all();
+    *analog_input = AnalogInput::default();
+
+    for entity in &hud {
+        commands.entity(entity).despawn();
+    }
+
+    commands.remove_resource::<Demo>();
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,79 @@
+use bevy::prelude::*;
+
+use crate::{
+    application::CurrentGame,
+    breakout::{BreackoutPlugin, GameState, InGameState},
+    input::ActionSource,
+    registry::RetroGame,
+    testing::{launcher_app, run_frames, run_until, state, tap},
+};
+
+use super::*;
+
+// Frames the launcher waits for, with some margin
+const IDLE_FRAMES: usize = 1300;
+
+fn wait_for_demo(app: &mut App) -> bool {
+    run_until(app, IDLE_FRAMES, |app| {
+        app.world().contains_resource::<Demo>()
+    })
+}
+
+#[test]
+fn idle_launcher_plays_a_demo_until_a_key_is_pressed() {
+    let mut app = launcher_app();
+
+    assert!(wait_for_demo(&mut app));
+    run_frames(&mut app, 1);
+    assert_eq!(state::<CurrentGame>(&app), BreackoutPlugin::STATE);
+    assert_eq!(
+        *app.world().resource::<ActionSource>(),
+        ActionSource::Scripted
+    );
+
+    // The bot starts a level and keeps the ball in play
+    assert!(run_until(&mut app, 60, |app| {
+        state::<InGameState>(app) == InGameState::Playing
+    }));
+    run_frames(&mut app, 600);
+    assert_eq!(state::<GameState>(&app), GameState::InGame);
+
+    // The key only stops the demo, the launcher does not start the game
+    tap(&mut app, KeyCode::Space);
+    run_frames(&mut app, 2);
+    assert!(!app.world().contains_resource::<Demo>());
+    assert_eq!(state::<CurrentGame>(&app), CurrentGame::InMainMenu);
+    assert_eq!(
+        *app.world().resource::<ActionSource>(),
+        ActionSource::Player
+    );
+    assert_eq!(state::<GameState>(&app), GameState::Exited);
+}
+
+#[test]
+fn demo_goes_back_to_the_launcher_after_a_while() {
+    let mut app = launcher_app();
+
+    assert!(wait_for_demo(&mut app));
+    assert!(run_until(&mut app, 60 * 65, |app| {
+        !app.world().contains_resource::<Demo>()
+    }));
+    run_frames(&mut app, 1);
+    assert_eq!(state::<CurrentGame>(&app), CurrentGame::InMainMenu);
+}
+
+#[test]
+fn only_an_idle_launcher_plays_demos() {
+    let mut app = launcher_app();
+
+    run_frames(&mut app, IDLE_FRAMES - 300);
+    tap(&mut app, KeyCode::ArrowDown);
+    tap(&mut app, KeyCode::ArrowUp);
+    run_frames(&mut app, IDLE_FRAMES - 300);
+    assert!(!app.world().contains_resource::<Demo>());
+
+    // "Watch replay" is not a game
+    tap(&mut app, KeyCode::ArrowDown);
+    tap(&mut app, KeyCode::ArrowDown);
+    assert!(!wait_for_demo(&mut app));
+}
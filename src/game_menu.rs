@@ -0,0 +1,349 @@
+//! The menus of the games played from the launcher until they are over: a
+//! main menu, a pause menu while a game is loaded, and a line of text below
+//! them with the score. Games describe theirs with `GameMenu` and add
+//! `GameMenuPlugin`, which also takes them in and out of their menu.
+
+use std::marker::PhantomData;
+
+use bevy::ecs::system::{ReadOnlySystemParam, StaticSystemParam, SystemParam, SystemParamItem};
+use bevy::prelude::*;
+use bevy::state::state::FreelyMutableState;
+
+use crate::application::{CurrentGame, GAME_AREA, WINDOW_RESOLUTION};
+use crate::launch::LaunchGame;
+use crate::menu_tree::{
+    Menu, MenuAction, MenuActionEvent, MenuRoot, MenuStyle, MenuTreePlugin, MenuTreeSystems,
+};
+use crate::registry::RetroGame;
+use crate::replay::ReplayOrDemo;
+use crate::scores::BestScores;
+
+/// A game played from its main menu, paused to its pause menu
+pub trait GameMenu: RetroGame + Sized {
+    /// Of the game, `EXITED` while another one is played
+    type State: FreelyMutableState;
+    const EXITED: Self::State;
+    const IN_MENU: Self::State;
+    const IN_GAME: Self::State;
+
+    /// The game being played, the pause menu is shown instead of the main
+    /// menu while it exists
+    type Game: Resource;
+    type LoadGameEvent: Event;
+    /// Drops the game being played
+    type CleanupGameEvent: Event + Default;
+
+    /// Actions of the items the game adds to its main menu, it handles them
+    /// itself
+    type Action: MenuAction;
+    /// What the main menu and the text below it are made from
+    type MenuData: ReadOnlySystemParam;
+
+    /// Of the items and the text
+    const COLOR: Color;
+    const BACKGROUND: Color = Color::srgba(0.0, 0.0, 0.0, 0.85);
+
+    fn main_menu(data: &SystemParamItem<Self::MenuData>) -> Menu<GameMenuAction<Self>>;
+
+    /// The score of the game in pause, or the result of the last one
+    fn status(data: &SystemParamItem<Self::MenuData>, game: Option<&Self::Game>) -> String;
+
+    /// Sent by "Play", and by "Restart" which plays the current run again
+    /// from its seed instead of starting a new one
+    fn load_game_event(restart: bool) -> Self::LoadGameEvent;
+}
+
+/// The game of a menu played for its best score, until it is over
+pub trait ScoredGame {
+    fn score(&self) -> u32;
+    fn is_over(&self) -> bool;
+}
+
+pub enum GameMenuAction<G: GameMenu> {
+    Play,
+    Resume,
+    Restart,
+    /// Drops the game in pause for the main menu
+    MainMenu,
+    /// Back to the launcher
+    Exit,
+    /// Lines of text, they do nothing
+    Text,
+    /// Of the items the game adds
+    Game(G::Action),
+}
+
+impl<G: GameMenu> Clone for GameMenuAction<G> {
+    fn clone(&self) -> Self {
+        match self {
+            Self::Play => Self::Play,
+            Self::Resume => Self::Resume,
+            Self::Restart => Self::Restart,
+            Self::MainMenu => Self::MainMenu,
+            Self::Exit => Self::Exit,
+            Self::Text => Self::Text,
+            Self::Game(action) => Self::Game(action.clone()),
+        }
+    }
+}
+
+pub struct GameMenuPlugin<G: GameMenu>(PhantomData<fn() -> G>);
+
+impl<G: GameMenu> Default for GameMenuPlugin<G> {
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<G: GameMenu> Plugin for GameMenuPlugin<G> {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(MenuTreePlugin::<GameMenuAction<G>>::default())
+            .configure_sets(
+                Update,
+                MenuTreeSystems::<GameMenuAction<G>>::default().run_if(in_state(G::IN_MENU)),
+            )
+            .insert_state(G::EXITED)
+            .add_systems(OnEnter(G::STATE), (enter_menu::<G>, load_menu::<G>))
+            .add_systems(OnExit(G::STATE), (cleanup_menu::<G>, exit_game::<G>))
+            .add_systems(OnEnter(G::IN_MENU), show_menu::<G>)
+            .add_systems(OnExit(G::IN_MENU), hide_menu::<G>)
+            .add_systems(
+                Update,
+                handle_menu_action::<G>
+                    .after(MenuTreeSystems::<GameMenuAction<G>>::default())
+                    .run_if(on_event::<MenuActionEvent<GameMenuAction<G>>>()),
+            );
+    }
+}
+
+/// Score of the last finished game of `G`
+#[derive(Resource)]
+pub struct LastScore<G>(pub Option<u32>, PhantomData<fn() -> G>);
+
+impl<G> Default for LastScore<G> {
+    fn default() -> Self {
+        Self(None, PhantomData)
+    }
+}
+
+/// Run conditions do not short-circuit, the game may not be loaded
+pub fn is_over<G: GameMenu>(game: Option<Res<G::Game>>) -> bool
+where
+    G::Game: ScoredGame,
+{
+    game.is_some_and(|game| game.is_over())
+}
+
+/// Back to the main menu, the score is kept unless it was a demo or a replay
+pub fn end_game<G: GameMenu>(
+    game: Res<G::Game>,
+    replay_or_demo: ReplayOrDemo,
+    best_scores: Option<ResMut<BestScores>>,
+    mut last_score: ResMut<LastScore<G>>,
+    mut cleanup_game_event: EventWriter<G::CleanupGameEvent>,
+    mut next_state: ResMut<NextState<G::State>>,
+) where
+    G::Game: ScoredGame,
+{
+    if !replay_or_demo.is_playing() {
+        if let Some(mut best_scores) = best_scores {
+            best_scores.submit(G::NAME, game.score());
+        }
+        last_score.0 = Some(game.score());
+    }
+
+    cleanup_game_event.send_default();
+    next_state.set(G::IN_MENU);
+}
+
+/// What the menus of a game played for its best score show
+#[derive(SystemParam)]
+pub struct Scores<'w, G: GameMenu> {
+    best_scores: Option<Res<'w, BestScores>>,
+    last_score: Res<'w, LastScore<G>>,
+}
+
+impl<G: GameMenu> Scores<'_, G> {
+    pub fn best(&self) -> Option<u32> {
+        self.best_scores
+            .as_ref()
+            .and_then(|best_scores| best_scores.get(G::NAME))
+    }
+
+    /// "Play", "High Scores" and "Exit"
+    pub fn main_menu(&self) -> Menu<GameMenuAction<G>> {
+        Menu::new()
+            .action("Play", GameMenuAction::Play)
+            .submenu("High Scores", high_scores_menu(self.best()))
+            .action("Exit", GameMenuAction::Exit)
+    }
+
+    /// With the `score` of the game in pause, if there is one
+    pub fn status(&self, score: Option<u32>) -> String {
+        match (score, self.last_score.0) {
+            (Some(score), _) => format!("Score: {score}"),
+            (None, Some(score)) => format!("Last score: {score}"),
+            (None, None) => String::new(),
+        }
+    }
+}
+
+pub fn high_scores_menu<G: GameMenu>(best_score: Option<u32>) -> Menu<GameMenuAction<G>> {
+    let best_score = match best_score {
+        Some(score) => format!("Best score: {score}"),
+        None => String::from("No score yet"),
+    };
+
+    Menu::new()
+        .action(&best_score, GameMenuAction::Text)
+        .enabled(false)
+        .back("Back")
+}
+
+fn pause_menu<G: GameMenu>() -> Menu<GameMenuAction<G>> {
+    Menu::new()
+        .action("Resume", GameMenuAction::Resume)
+        .action("Restart", GameMenuAction::Restart)
+        .action("Main Menu", GameMenuAction::MainMenu)
+        .on_back(GameMenuAction::Resume)
+}
+
+/// For games with nothing to choose from the command line, only says so
+pub fn ignore_launch<G: RetroGame>(mut commands: Commands, launch_game: Res<LaunchGame>) {
+    commands.remove_resource::<LaunchGame>();
+
+    if let Some(level) = launch_game.level {
+        warn!("{} has no level {level} to start from", G::NAME);
+    }
+    if let Some(mode) = &launch_game.mode {
+        warn!("{} has no {mode} mode", G::NAME);
+    }
+}
+
+/// Takes the game to its menu as it is entered, a launch from the command
+/// line goes on from there after it
+pub fn enter_menu<G: GameMenu>(mut next_state: ResMut<NextState<G::State>>) {
+    next_state.set(G::IN_MENU);
+}
+
+fn exit_game<G: GameMenu>(mut next_state: ResMut<NextState<G::State>>) {
+    next_state.set(G::EXITED);
+}
+
+#[derive(Component)]
+struct StatusText;
+
+fn load_menu<G: GameMenu>(mut commands: Commands, data: StaticSystemParam<G::MenuData>) {
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    justify_content: JustifyContent::Center,
+
+                    left: Val::Px(GAME_AREA.min.x + WINDOW_RESOLUTION[0] / 2.0),
+                    bottom: Val::Px(GAME_AREA.max.y + WINDOW_RESOLUTION[1] / 2.0),
+                    width: Val::Px(GAME_AREA.width()),
+                    height: Val::Px(GAME_AREA.height()),
+
+                    ..Default::default()
+                },
+                background_color: G::BACKGROUND.into(),
+                ..Default::default()
+            },
+            MenuRoot::new(
+                G::main_menu(&data),
+                MenuStyle {
+                    color: G::COLOR,
+                    background: Color::srgba(0.0, 0.0, 0.0, 0.0),
+                    ..Default::default()
+                },
+            ),
+        ))
+        .with_children(|builder| {
+            builder.spawn((
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font_size: 20.0,
+                        color: G::COLOR,
+                        ..Default::default()
+                    },
+                ),
+                StatusText,
+            ));
+        });
+}
+
+fn cleanup_menu<G: GameMenu>(
+    mut commands: Commands,
+    menu: Query<Entity, With<MenuRoot<GameMenuAction<G>>>>,
+) {
+    for menu in &menu {
+        commands.entity(menu).despawn_recursive();
+    }
+}
+
+fn show_menu<G: GameMenu>(
+    mut menu: Query<(&mut Visibility, &mut MenuRoot<GameMenuAction<G>>)>,
+    mut status_text: Query<&mut Text, With<StatusText>>,
+    game: Option<Res<G::Game>>,
+    data: StaticSystemParam<G::MenuData>,
+) {
+    if let Ok((mut visibility, mut menu)) = menu.get_single_mut() {
+        *visibility = Visibility::Inherited;
+        menu.set_menu(if game.is_some() {
+            pause_menu()
+        } else {
+            G::main_menu(&data)
+        });
+    }
+
+    if let Ok(mut status_text) = status_text.get_single_mut() {
+        status_text.sections[0].value = G::status(&data, game.as_deref());
+    }
+}
+
+fn hide_menu<G: GameMenu>(mut menu: Query<&mut Visibility, With<MenuRoot<GameMenuAction<G>>>>) {
+    if let Ok(mut visibility) = menu.get_single_mut() {
+        *visibility = Visibility::Hidden;
+    }
+}
+
+fn handle_menu_action<G: GameMenu>(
+    mut menu_actions: EventReader<MenuActionEvent<GameMenuAction<G>>>,
+    mut menu: Query<&mut MenuRoot<GameMenuAction<G>>>,
+
+    mut load_game_event: EventWriter<G::LoadGameEvent>,
+    mut cleanup_game_event: EventWriter<G::CleanupGameEvent>,
+
+    mut next_current_game: ResMut<NextState<CurrentGame>>,
+    mut next_state: ResMut<NextState<G::State>>,
+    data: StaticSystemParam<G::MenuData>,
+) {
+    for MenuActionEvent(action) in menu_actions.read() {
+        match action {
+            GameMenuAction::Play => {
+                next_state.set(G::IN_GAME);
+                load_game_event.send(G::load_game_event(false));
+            }
+            GameMenuAction::Resume => next_state.set(G::IN_GAME),
+            GameMenuAction::Restart => {
+                next_state.set(G::IN_GAME);
+                load_game_event.send(G::load_game_event(true));
+            }
+            GameMenuAction::MainMenu => {
+                cleanup_game_event.send_default();
+                for mut menu in &mut menu {
+                    menu.set_menu(G::main_menu(&data));
+                }
+            }
+            GameMenuAction::Exit => {
+                next_current_game.set(CurrentGame::InMainMenu);
+                next_state.set(G::EXITED);
+            }
+            GameMenuAction::Text | GameMenuAction::Game(_) => (),
+        }
+    }
+}
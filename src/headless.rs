@@ -0,0 +1,35 @@
+//! Apps running the games without a window or a GPU, for the tests and the
+//! tools playing the games by themselves
+
+use std::time::Duration;
+
+use bevy::{input::InputPlugin, prelude::*, state::app::StatesPlugin, time::TimeUpdateStrategy};
+
+use crate::{
+    application::{CurrentGame, InGame},
+    input::InputMapPlugin,
+    rng::GameRng,
+};
+
+pub const FRAME: Duration = Duration::from_nanos(16_666_667);
+
+/// App with everything `Application` provides except the window, the
+/// rendering and the saved input map. Every update advances the time by
+/// exactly one `FRAME`.
+pub fn headless_app(seed: u64) -> App {
+    let mut app = App::new();
+    app.add_plugins((
+        MinimalPlugins,
+        StatesPlugin,
+        InputPlugin,
+        AssetPlugin::default(),
+        InputMapPlugin,
+    ))
+    .init_asset::<Image>()
+    .insert_resource(TimeUpdateStrategy::ManualDuration(FRAME))
+    .insert_state(CurrentGame::InMainMenu)
+    .add_computed_state::<InGame>()
+    .insert_resource(GameRng::new(seed));
+
+    app
+}
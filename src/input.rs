@@ -0,0 +1,555 @@
+use std::fmt::Write as _;
+
+use bevy::{
+    ecs::system::SystemParam,
+    input::{
+        gamepad::{GamepadAxis, GamepadAxisType, GamepadButton, GamepadButtonType, Gamepads},
+        InputSystem,
+    },
+    prelude::*,
+    window::PrimaryWindow,
+};
+
+use crate::settings::SettingsFile;
+
+// How far the left stick has to be pushed to act like the d-pad
+const STICK_THRESHOLD: f32 = 0.5;
+
+/// What the player wants to do, the games read `ButtonInput<Action>`
+/// instead of the keyboard and the gamepads so that every key and button
+/// can be rebound
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Action {
+    // Menus
+    MenuUp,
+    MenuDown,
+    Confirm,
+    Back,
+
+    // Games
+    MoveLeft,
+    MoveRight,
+    MoveUp,
+    MoveDown,
+    Player1Up,
+    Player1Down,
+    Player2Up,
+    Player2Down,
+    Launch,
+    Fire,
+    Hyperspace,
+    Pause,
+    SoftDrop,
+    HardDrop,
+    RotateCW,
+    RotateCCW,
+    Hold,
+
+    // Left mouse button or touch, on top of the bound keys and buttons
+    Click,
+}
+
+impl Action {
+    pub const ALL: [Action; 22] = [
+        Action::MenuUp,
+        Action::MenuDown,
+        Action::Confirm,
+        Action::Back,
+        Action::MoveLeft,
+        Action::MoveRight,
+        Action::MoveUp,
+        Action::MoveDown,
+        Action::Player1Up,
+        Action::Player1Down,
+        Action::Player2Up,
+        Action::Player2Down,
+        Action::Launch,
+        Action::Fire,
+        Action::Hyperspace,
+        Action::Pause,
+        Action::SoftDrop,
+        Action::HardDrop,
+        Action::RotateCW,
+        Action::RotateCCW,
+        Action::Hold,
+        Action::Click,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Action::MenuUp => "Menu up",
+            Action::MenuDown => "Menu down",
+            Action::Confirm => "Confirm",
+            Action::Back => "Back",
+            Action::MoveLeft => "Move left",
+            Action::MoveRight => "Move right",
+            Action::MoveUp => "Move up",
+            Action::MoveDown => "Move down",
+            Action::Player1Up => "Player 1 up",
+            Action::Player1Down => "Player 1 down",
+            Action::Player2Up => "Player 2 up",
+            Action::Player2Down => "Player 2 down",
+            Action::Launch => "Launch ball",
+            Action::Fire => "Fire",
+            Action::Hyperspace => "Hyperspace",
+            Action::Pause => "Pause",
+            Action::SoftDrop => "Soft drop",
+            Action::HardDrop => "Hard drop",
+            Action::RotateCW => "Rotate clockwise",
+            Action::RotateCCW => "Rotate counter clockwise",
+            Action::Hold => "Hold piece",
+            Action::Click => "Click",
+        }
+    }
+
+    fn default_keys(&self) -> &'static [KeyCode] {
+        match self {
+            Action::MenuUp => &[KeyCode::ArrowUp, KeyCode::KeyW],
+            Action::MenuDown => &[KeyCode::ArrowDown, KeyCode::KeyS],
+            Action::Confirm => &[KeyCode::Space, KeyCode::Enter],
+            Action::Back => &[KeyCode::Escape],
+            Action::MoveLeft => &[KeyCode::ArrowLeft, KeyCode::KeyA],
+            Action::MoveRight => &[KeyCode::ArrowRight, KeyCode::KeyD],
+            Action::MoveUp => &[KeyCode::ArrowUp, KeyCode::KeyW],
+            Action::MoveDown => &[KeyCode::ArrowDown, KeyCode::KeyS],
+            Action::Player1Up => &[KeyCode::KeyW],
+            Action::Player1Down => &[KeyCode::KeyS],
+            Action::Player2Up => &[KeyCode::ArrowUp],
+            Action::Player2Down => &[KeyCode::ArrowDown],
+            Action::Launch => &[KeyCode::Space],
+            Action::Fire => &[KeyCode::Space],
+            Action::Hyperspace => &[KeyCode::ArrowDown, KeyCode::KeyS],
+            Action::Pause => &[KeyCode::Escape],
+            Action::SoftDrop => &[KeyCode::ArrowDown],
+            Action::HardDrop => &[KeyCode::Space],
+            Action::RotateCW => &[KeyCode::ArrowUp, KeyCode::KeyX],
+            Action::RotateCCW => &[KeyCode::KeyZ],
+            Action::Hold => &[KeyCode::KeyC],
+            Action::Click => &[],
+        }
+    }
+
+    fn default_buttons(&self) -> &'static [GamepadButtonType] {
+        match self {
+            Action::MenuUp => &[GamepadButtonType::DPadUp],
+            Action::MenuDown => &[GamepadButtonType::DPadDown],
+            Action::Confirm => &[GamepadButtonType::South],
+            Action::Back => &[GamepadButtonType::East],
+            Action::MoveLeft => &[GamepadButtonType::DPadLeft],
+            Action::MoveRight => &[GamepadButtonType::DPadRight],
+            Action::MoveUp => &[GamepadButtonType::DPadUp],
+            Action::MoveDown => &[GamepadButtonType::DPadDown],
+            Action::Player1Up => &[GamepadButtonType::DPadUp],
+            Action::Player1Down => &[GamepadButtonType::DPadDown],
+            Action::Player2Up => &[GamepadButtonType::North],
+            Action::Player2Down => &[GamepadButtonType::South],
+            Action::Launch => &[GamepadButtonType::South],
+            Action::Fire => &[GamepadButtonType::South],
+            Action::Hyperspace => &[GamepadButtonType::West],
+            Action::Pause => &[GamepadButtonType::Start],
+            Action::SoftDrop => &[GamepadButtonType::DPadDown],
+            Action::HardDrop => &[GamepadButtonType::DPadUp],
+            Action::RotateCW => &[GamepadButtonType::East],
+            Action::RotateCCW => &[GamepadButtonType::South],
+            Action::Hold => &[
+                GamepadButtonType::LeftTrigger,
+                GamepadButtonType::RightTrigger,
+            ],
+            Action::Click => &[],
+        }
+    }
+
+    fn index(&self) -> usize {
+        Action::ALL
+            .iter()
+            .position(|action| action == self)
+            .unwrap()
+    }
+
+    fn from_name(name: &str) -> Option<Action> {
+        Action::ALL
+            .into_iter()
+            .find(|action| format!("{action:?}") == name)
+    }
+}
+
+/// Keys that can be bound to an action, and written to the input map file
+pub const BINDABLE_KEYS: [KeyCode; 50] = [
+    KeyCode::KeyA,
+    KeyCode::KeyB,
+    KeyCode::KeyC,
+    KeyCode::KeyD,
+    KeyCode::KeyE,
+    KeyCode::KeyF,
+    KeyCode::KeyG,
+    KeyCode::KeyH,
+    KeyCode::KeyI,
+    KeyCode::KeyJ,
+    KeyCode::KeyK,
+    KeyCode::KeyL,
+    KeyCode::KeyM,
+    KeyCode::KeyN,
+    KeyCode::KeyO,
+    KeyCode::KeyP,
+    KeyCode::KeyQ,
+    KeyCode::KeyR,
+    KeyCode::KeyS,
+    KeyCode::KeyT,
+    KeyCode::KeyU,
+    KeyCode::KeyV,
+    KeyCode::KeyW,
+    KeyCode::KeyX,
+    KeyCode::KeyY,
+    KeyCode::KeyZ,
+    KeyCode::Digit0,
+    KeyCode::Digit1,
+    KeyCode::Digit2,
+    KeyCode::Digit3,
+    KeyCode::Digit4,
+    KeyCode::Digit5,
+    KeyCode::Digit6,
+    KeyCode::Digit7,
+    KeyCode::Digit8,
+    KeyCode::Digit9,
+    KeyCode::ArrowUp,
+    KeyCode::ArrowDown,
+    KeyCode::ArrowLeft,
+    KeyCode::ArrowRight,
+    KeyCode::Space,
+    KeyCode::Enter,
+    KeyCode::Escape,
+    KeyCode::Tab,
+    KeyCode::Backspace,
+    KeyCode::ShiftLeft,
+    KeyCode::ShiftRight,
+    KeyCode::ControlLeft,
+    KeyCode::ControlRight,
+    KeyCode::AltLeft,
+];
+
+/// Gamepad buttons that can be bound to an action
+pub const BINDABLE_BUTTONS: [GamepadButtonType; 19] = [
+    GamepadButtonType::South,
+    GamepadButtonType::East,
+    GamepadButtonType::North,
+    GamepadButtonType::West,
+    GamepadButtonType::C,
+    GamepadButtonType::Z,
+    GamepadButtonType::LeftTrigger,
+    GamepadButtonType::LeftTrigger2,
+    GamepadButtonType::RightTrigger,
+    GamepadButtonType::RightTrigger2,
+    GamepadButtonType::Select,
+    GamepadButtonType::Start,
+    GamepadButtonType::Mode,
+    GamepadButtonType::LeftThumb,
+    GamepadButtonType::RightThumb,
+    GamepadButtonType::DPadUp,
+    GamepadButtonType::DPadDown,
+    GamepadButtonType::DPadLeft,
+    GamepadButtonType::DPadRight,
+];
+
+fn key_from_name(name: &str) -> Option<KeyCode> {
+    BINDABLE_KEYS
+        .into_iter()
+        .find(|key| format!("{key:?}") == name)
+}
+
+fn button_from_name(name: &str) -> Option<GamepadButtonType> {
+    BINDABLE_BUTTONS
+        .into_iter()
+        .find(|button| format!("{button:?}") == name)
+}
+
+// Names of a text line, separated by commas
+fn names_to_text<T: std::fmt::Debug>(items: &[T]) -> String {
+    let names: Vec<String> = items.iter().map(|item| format!("{item:?}")).collect();
+    names.join(", ")
+}
+
+fn names_from_text<T>(text: &str, from_name: fn(&str) -> Option<T>) -> Option<Vec<T>> {
+    text.split(',')
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .map(from_name)
+        .collect()
+}
+
+/// Keys and gamepad buttons bound to each action. Several actions can share
+/// a key as long as they are not read at the same time, like `Confirm` and
+/// `Launch`. Every connected gamepad uses the same buttons.
+#[derive(Resource, Clone, PartialEq, Eq, Debug)]
+pub struct InputMap {
+    bindings: [Vec<KeyCode>; Action::ALL.len()],
+    button_bindings: [Vec<GamepadButtonType>; Action::ALL.len()],
+
+    /// Whether the mouse cursor or the touch sets `AnalogInput::pointer_x`
+    pub follow_pointer: bool,
+}
+
+impl Default for InputMap {
+    fn default() -> Self {
+        Self {
+            bindings: Action::ALL.map(|action| action.default_keys().to_vec()),
+            button_bindings: Action::ALL.map(|action| action.default_buttons().to_vec()),
+            follow_pointer: false,
+        }
+    }
+}
+
+impl InputMap {
+    pub fn keys(&self, action: Action) -> &[KeyCode] {
+        &self.bindings[action.index()]
+    }
+
+    pub fn buttons(&self, action: Action) -> &[GamepadButtonType] {
+        &self.button_bindings[action.index()]
+    }
+
+    /// Replaces every key of `action` with `key`
+    pub fn bind(&mut self, action: Action, key: KeyCode) {
+        self.bindings[action.index()] = vec![key];
+    }
+
+    /// Replaces every gamepad button of `action` with `button`
+    pub fn bind_button(&mut self, action: Action, button: GamepadButtonType) {
+        self.button_bindings[action.index()] = vec![button];
+    }
+
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    /// Everything triggering `action`, as shown to the player
+    pub fn bindings_text(&self, action: Action) -> String {
+        let pointer = (action == Action::Click).then(|| String::from("Mouse / Touch"));
+        let bindings: Vec<String> = pointer
+            .into_iter()
+            .chain(self.keys(action).iter().map(|key| format!("{key:?}")))
+            .chain(
+                self.buttons(action)
+                    .iter()
+                    .map(|button| format!("Pad {button:?}")),
+            )
+            .collect();
+
+        bindings.join(" / ")
+    }
+}
+
+impl SettingsFile for InputMap {
+    const PATH: &'static str = "settings/input_map.txt";
+    const NAME: &'static str = "input map";
+
+    // One `Action = Key, Key | Button, Button` line per action, then the
+    // pointer option. Actions missing from the text keep their default keys.
+    fn to_text(&self) -> String {
+        let mut text = String::new();
+
+        for action in Action::ALL {
+            let _ = writeln!(
+                text,
+                "{action:?} = {} | {}",
+                names_to_text(self.keys(action)),
+                names_to_text(self.buttons(action)),
+            );
+        }
+        let _ = writeln!(text, "FollowPointer = {}", self.follow_pointer);
+
+        text
+    }
+
+    fn from_text(text: &str) -> Option<Self> {
+        let mut input_map = Self::default();
+
+        for line in text.lines().map(str::trim).filter(|line| !line.is_empty()) {
+            let (action, keys) = line.split_once('=')?;
+            if action.trim() == "FollowPointer" {
+                input_map.follow_pointer = keys.trim().parse().ok()?;
+                continue;
+            }
+
+            let action = Action::from_name(action.trim())?;
+
+            let (keys, buttons) = match keys.split_once('|') {
+                Some((keys, buttons)) => (keys, Some(buttons)),
+                None => (keys, None),
+            };
+
+            input_map.bindings[action.index()] = names_from_text(keys, key_from_name)?;
+            if let Some(buttons) = buttons {
+                input_map.button_bindings[action.index()] =
+                    names_from_text(buttons, button_from_name)?;
+            }
+        }
+
+        Some(input_map)
+    }
+}
+
+/// Analog movement from the left stick of the gamepads, between -1 and 1,
+/// and the pointer. Games that can move at any speed read them before
+/// `MoveLeft` and `MoveRight`.
+#[derive(Resource, Clone, Copy, PartialEq, Default, Debug)]
+pub struct AnalogInput {
+    pub move_x: f32,
+
+    /// World x of the mouse cursor or of the first touch, only set when the
+    /// player chose to follow the pointer
+    pub pointer_x: Option<f32>,
+}
+
+/// Where the actions come from. When they are `Scripted`, a replay or a
+/// demo presses them itself and the keyboard is ignored.
+#[derive(Resource, Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub enum ActionSource {
+    #[default]
+    Player,
+    Scripted,
+}
+
+/// Updates `ButtonInput<Action>`, systems feeding scripted actions should
+/// run after it
+#[derive(SystemSet, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct ActionSystem;
+
+pub struct InputMapPlugin;
+
+impl Plugin for InputMapPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<InputMap>()
+            .init_resource::<ActionSource>()
+            .init_resource::<ButtonInput<Action>>()
+            .init_resource::<AnalogInput>()
+            .add_systems(
+                PreUpdate,
+                (update_actions, update_analog_input)
+                    .in_set(ActionSystem)
+                    .after(InputSystem)
+                    .run_if(resource_equals(ActionSource::Player)),
+            );
+    }
+}
+
+#[derive(SystemParam)]
+struct GamepadInput<'w> {
+    gamepads: Res<'w, Gamepads>,
+    buttons: Res<'w, ButtonInput<GamepadButton>>,
+    axes: Res<'w, Axis<GamepadAxis>>,
+}
+
+impl GamepadInput<'_> {
+    // Whether any gamepad holds `button`, the left stick also works as the
+    // d-pad
+    fn pressed(&self, button: GamepadButtonType) -> bool {
+        self.gamepads.iter().any(|gamepad| {
+            let stick = |axis_type| self.axis(gamepad, axis_type);
+
+            self.buttons.pressed(GamepadButton::new(gamepad, button))
+                || match button {
+                    GamepadButtonType::DPadUp => {
+                        stick(GamepadAxisType::LeftStickY) > STICK_THRESHOLD
+                    }
+                    GamepadButtonType::DPadDown => {
+                        stick(GamepadAxisType::LeftStickY) < -STICK_THRESHOLD
+                    }
+                    GamepadButtonType::DPadLeft => {
+                        stick(GamepadAxisType::LeftStickX) < -STICK_THRESHOLD
+                    }
+                    GamepadButtonType::DPadRight => {
+                        stick(GamepadAxisType::LeftStickX) > STICK_THRESHOLD
+                    }
+                    _ => false,
+                }
+        })
+    }
+
+    // Left stick of the first gamepad that is pushed
+    fn move_x(&self) -> f32 {
+        self.gamepads
+            .iter()
+            .map(|gamepad| self.axis(gamepad, GamepadAxisType::LeftStickX))
+            .find(|value| *value != 0.0)
+            .unwrap_or(0.0)
+    }
+
+    fn axis(&self, gamepad: Gamepad, axis_type: GamepadAxisType) -> f32 {
+        self.axes
+            .get(GamepadAxis::new(gamepad, axis_type))
+            .unwrap_or(0.0)
+    }
+}
+
+// Gamepads are read every frame, so that they work as soon as they are
+// plugged in and stop pressing anything once they are unplugged
+fn update_actions(
+    keys: Res<ButtonInput<KeyCode>>,
+    mouse_buttons: Res<ButtonInput<MouseButton>>,
+    touches: Res<Touches>,
+    gamepad_input: GamepadInput,
+    input_map: Res<InputMap>,
+    mut actions: ResMut<ButtonInput<Action>>,
+) {
+    actions.clear();
+
+    for action in Action::ALL {
+        let key_pressed = input_map.keys(action).iter().any(|key| keys.pressed(*key));
+        let button_pressed = input_map
+            .buttons(action)
+            .iter()
+            .any(|button| gamepad_input.pressed(*button));
+        let pointer_pressed = action == Action::Click
+            && (mouse_buttons.pressed(MouseButton::Left) || touches.iter().next().is_some());
+
+        if key_pressed || button_pressed || pointer_pressed {
+            actions.press(action);
+        } else {
+            actions.release(action);
+        }
+    }
+}
+
+fn update_analog_input(
+    gamepad_input: GamepadInput,
+    touches: Res<Touches>,
+    input_map: Res<InputMap>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    cameras: Query<(&Camera, &GlobalTransform)>,
+    mut analog_input: ResMut<AnalogInput>,
+) {
+    // Quantized like in the replays, so that a replay moves exactly the same
+    analog_input.move_x = (gamepad_input.move_x().clamp(-1.0, 1.0) * 127.0).round() / 127.0;
+
+    analog_input.pointer_x = None;
+    if !input_map.follow_pointer {
+        return;
+    }
+
+    let (Ok(window), Ok((camera, camera_transform))) = (windows.get_single(), cameras.get_single())
+    else {
+        return;
+    };
+
+    let pointer_position = touches
+        .iter()
+        .next()
+        .map(|touch| touch.position())
+        .or(window.cursor_position());
+
+    // The pointer is over the window, the camera may only cover a part of
+    // it, see `letterbox`
+    let viewport_position = camera
+        .logical_viewport_rect()
+        .map_or(Vec2::ZERO, |viewport| viewport.min);
+
+    // Whole pixels, like in the replays
+    analog_input.pointer_x = pointer_position
+        .and_then(|position| {
+            camera.viewport_to_world_2d(camera_transform, position - viewport_position)
+        })
+        .map(|position| position.x.round());
+}
+
+#[cfg(test)]
+mod tests;
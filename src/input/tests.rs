@@ -0,0 +1,280 @@
+use bevy::{
+    input::{
+        gamepad::{GamepadAxisType, GamepadButtonType},
+        mouse::MouseButtonInput,
+        touch::TouchPhase,
+        ButtonState,
+    },
+    prelude::*,
+};
+
+use crate::{
+    application::CurrentGame,
+    registry::RetroGame,
+    testing::{
+        connect_gamepad, disconnect_gamepad, headless_app, launcher_app, move_axis, press_button,
+        release_button, run_frames, snapshot, state, tap, tap_button,
+    },
+    tetris::TetrisPlugin,
+};
+
+use super::*;
+
+fn input_map(app: &App) -> &InputMap {
+    app.world().resource::<InputMap>()
+}
+
+fn actions(app: &App) -> &ButtonInput<Action> {
+    app.world().resource::<ButtonInput<Action>>()
+}
+
+#[test]
+fn text_round_trip() {
+    let mut input_map = InputMap::default();
+    input_map.bind(Action::HardDrop, KeyCode::KeyJ);
+    input_map.bind(Action::Back, KeyCode::Backspace);
+    input_map.bind_button(Action::Hold, GamepadButtonType::North);
+    input_map.follow_pointer = true;
+
+    assert_eq!(InputMap::from_text(&input_map.to_text()), Some(input_map));
+}
+
+#[test]
+fn missing_actions_keep_their_default_keys() {
+    let input_map = InputMap::from_text("Confirm = KeyE, Enter\n").unwrap();
+
+    assert_eq!(
+        input_map.keys(Action::Confirm),
+        [KeyCode::KeyE, KeyCode::Enter]
+    );
+    assert_eq!(input_map.keys(Action::Back), [KeyCode::Escape]);
+    assert_eq!(
+        input_map.buttons(Action::Confirm),
+        [GamepadButtonType::South]
+    );
+}
+
+#[test]
+fn unknown_names_are_rejected() {
+    assert_eq!(InputMap::from_text("Jump = Space"), None);
+    assert_eq!(InputMap::from_text("Confirm = NumpadEnter"), None);
+    assert_eq!(InputMap::from_text("Confirm Space"), None);
+    assert_eq!(InputMap::from_text("Confirm = Space | KeyA"), None);
+    assert_eq!(InputMap::from_text("FollowPointer = maybe"), None);
+}
+
+#[test]
+fn games_follow_the_input_map() {
+    let mut app = launcher_app();
+    app.world_mut()
+        .resource_mut::<InputMap>()
+        .bind(Action::MenuDown, KeyCode::KeyJ);
+
+    // The old key does nothing anymore, "Tetris" is the second item
+    tap(&mut app, KeyCode::ArrowDown);
+    tap(&mut app, KeyCode::KeyJ);
+    tap(&mut app, KeyCode::Space);
+
+    assert_eq!(state::<CurrentGame>(&app), TetrisPlugin::STATE);
+}
+
+#[test]
+fn rebinding_from_the_settings() {
+    let mut app = launcher_app();
+    let in_launcher = snapshot(&app);
+
+    // "Settings" is the fourth launcher item, "Menu up" the first action
+    for _ in 0..3 {
+        tap(&mut app, KeyCode::ArrowDown);
+    }
+    tap(&mut app, KeyCode::Space);
+    tap(&mut app, KeyCode::Space);
+    tap(&mut app, KeyCode::KeyI);
+    assert_eq!(input_map(&app).keys(Action::MenuUp), [KeyCode::KeyI]);
+
+    // Escape cancels a rebinding
+    tap(&mut app, KeyCode::Space);
+    tap(&mut app, KeyCode::Escape);
+    assert_eq!(input_map(&app).keys(Action::MenuUp), [KeyCode::KeyI]);
+
+    // Back to the launcher, where the new key moves the selection
+    tap(&mut app, KeyCode::Escape);
+    run_frames(&mut app, 1);
+    assert_eq!(snapshot(&app), in_launcher);
+
+    tap(&mut app, KeyCode::KeyI);
+    tap(&mut app, KeyCode::KeyI);
+    tap(&mut app, KeyCode::Space);
+    assert_eq!(state::<CurrentGame>(&app), TetrisPlugin::STATE);
+}
+
+#[test]
+fn gamepad_navigates_the_launcher_and_the_games() {
+    let mut app = launcher_app();
+    let gamepad = connect_gamepad(&mut app, 0);
+
+    tap_button(&mut app, gamepad, GamepadButtonType::DPadDown);
+    tap_button(&mut app, gamepad, GamepadButtonType::South);
+    run_frames(&mut app, 1);
+    assert_eq!(state::<CurrentGame>(&app), TetrisPlugin::STATE);
+
+    // "Exit" is the last item of the Tetris menu
+    tap_button(&mut app, gamepad, GamepadButtonType::DPadUp);
+    tap_button(&mut app, gamepad, GamepadButtonType::South);
+    assert_eq!(state::<CurrentGame>(&app), CurrentGame::InMainMenu);
+}
+
+#[test]
+fn left_stick_works_as_a_d_pad() {
+    let mut app = launcher_app();
+    let gamepad = connect_gamepad(&mut app, 0);
+
+    move_axis(&mut app, gamepad, GamepadAxisType::LeftStickY, -0.9);
+    app.update();
+    assert!(actions(&app).just_pressed(Action::MenuDown));
+
+    // Held, the stick does not repeat
+    move_axis(&mut app, gamepad, GamepadAxisType::LeftStickY, -1.0);
+    app.update();
+    assert!(!actions(&app).just_pressed(Action::MenuDown));
+
+    move_axis(&mut app, gamepad, GamepadAxisType::LeftStickY, 0.1);
+    app.update();
+    assert!(actions(&app).just_released(Action::MenuDown));
+
+    tap_button(&mut app, gamepad, GamepadButtonType::South);
+    assert_eq!(state::<CurrentGame>(&app), TetrisPlugin::STATE);
+}
+
+#[test]
+fn gamepads_can_be_plugged_at_any_time() {
+    let mut app = headless_app();
+    app.update();
+
+    let first_gamepad = connect_gamepad(&mut app, 0);
+    press_button(&mut app, first_gamepad, GamepadButtonType::DPadLeft);
+    app.update();
+    assert!(actions(&app).pressed(Action::MoveLeft));
+
+    // Unplugging releases whatever was held
+    disconnect_gamepad(&mut app, first_gamepad);
+    assert!(!actions(&app).pressed(Action::MoveLeft));
+
+    let second_gamepad = connect_gamepad(&mut app, 1);
+    press_button(&mut app, second_gamepad, GamepadButtonType::DPadRight);
+    app.update();
+    assert!(actions(&app).pressed(Action::MoveRight));
+
+    release_button(&mut app, second_gamepad, GamepadButtonType::DPadRight);
+    app.update();
+    assert!(!actions(&app).pressed(Action::MoveRight));
+}
+
+#[test]
+fn tetris_face_buttons() {
+    let mut app = headless_app();
+    let gamepad = connect_gamepad(&mut app, 0);
+
+    for (button, action) in [
+        (GamepadButtonType::East, Action::RotateCW),
+        (GamepadButtonType::South, Action::RotateCCW),
+        (GamepadButtonType::DPadUp, Action::HardDrop),
+        (GamepadButtonType::LeftTrigger, Action::Hold),
+        (GamepadButtonType::RightTrigger, Action::Hold),
+    ] {
+        press_button(&mut app, gamepad, button);
+        app.update();
+        assert!(actions(&app).just_pressed(action), "{button:?}");
+
+        release_button(&mut app, gamepad, button);
+        app.update();
+    }
+}
+
+#[test]
+fn left_stick_is_analog_movement() {
+    let mut app = headless_app();
+    let gamepad = connect_gamepad(&mut app, 0);
+
+    move_axis(&mut app, gamepad, GamepadAxisType::LeftStickX, -0.3);
+    app.update();
+    let analog_input = app.world().resource::<AnalogInput>();
+    assert!((analog_input.move_x + 0.3).abs() < 0.01);
+    assert!(!actions(&app).pressed(Action::MoveLeft));
+
+    move_axis(&mut app, gamepad, GamepadAxisType::LeftStickX, 0.0);
+    app.update();
+    assert_eq!(app.world().resource::<AnalogInput>().move_x, 0.0);
+}
+
+#[test]
+fn rebinding_a_gamepad_button() {
+    let mut app = launcher_app();
+    let gamepad = connect_gamepad(&mut app, 0);
+
+    // "Settings", then "Confirm", the third action
+    for _ in 0..3 {
+        tap_button(&mut app, gamepad, GamepadButtonType::DPadDown);
+    }
+    tap_button(&mut app, gamepad, GamepadButtonType::South);
+    tap_button(&mut app, gamepad, GamepadButtonType::DPadDown);
+    tap_button(&mut app, gamepad, GamepadButtonType::DPadDown);
+    tap_button(&mut app, gamepad, GamepadButtonType::South);
+    tap_button(&mut app, gamepad, GamepadButtonType::North);
+
+    assert_eq!(
+        input_map(&app).buttons(Action::Confirm),
+        [GamepadButtonType::North]
+    );
+    assert_eq!(
+        input_map(&app).keys(Action::Confirm),
+        [KeyCode::Space, KeyCode::Enter]
+    );
+}
+
+#[test]
+fn mouse_and_touch_click() {
+    let mut app = headless_app();
+    app.update();
+
+    app.world_mut().send_event(MouseButtonInput {
+        button: MouseButton::Left,
+        state: ButtonState::Pressed,
+        window: Entity::PLACEHOLDER,
+    });
+    app.update();
+    assert!(actions(&app).just_pressed(Action::Click));
+
+    app.world_mut().send_event(MouseButtonInput {
+        button: MouseButton::Left,
+        state: ButtonState::Released,
+        window: Entity::PLACEHOLDER,
+    });
+    app.update();
+    assert!(!actions(&app).pressed(Action::Click));
+
+    for phase in [TouchPhase::Started, TouchPhase::Ended] {
+        app.world_mut().send_event(TouchInput {
+            phase,
+            position: Vec2::ZERO,
+            window: Entity::PLACEHOLDER,
+            force: None,
+            id: 0,
+        });
+        app.update();
+        assert_eq!(
+            actions(&app).just_pressed(Action::Click),
+            phase == TouchPhase::Started
+        );
+    }
+}
+
+#[test]
+fn pointer_is_only_followed_when_asked() {
+    let mut app = headless_app();
+    app.world_mut().resource_mut::<AnalogInput>().pointer_x = Some(10.0);
+    app.update();
+
+    // No window to point at
+    assert_eq!(app.world().resource::<AnalogInput>().pointer_x, None);
+}
@@ -0,0 +1,64 @@
+use bevy::prelude::*;
+
+mod menu;
+use menu::MenuPlugin;
+
+mod game;
+use game::GamePlugin;
+
+use crate::game_menu::ignore_launch;
+use crate::input::Action;
+use crate::launch::LaunchGame;
+use crate::registry::RetroGame;
+
+pub struct InvadersPlugin;
+
+impl RetroGame for InvadersPlugin {
+    const NAME: &'static str = "Space Invaders";
+    const ICON: &'static str = "invaders/icon.png";
+    const DESCRIPTION: &'static str =
+        "Shoot the aliens down from behind the bunkers before they land, they speed up as they thin out.";
+    const CONTROLS: &'static [Action] = &[
+        Action::MoveLeft,
+        Action::MoveRight,
+        Action::Fire,
+        Action::Pause,
+    ];
+}
+
+impl Plugin for InvadersPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(MenuPlugin)
+            .add_plugins(GamePlugin)
+            .insert_state(InvadersInGameState::Paused)
+            .add_systems(
+                OnEnter(InvadersPlugin::STATE),
+                (
+                    pause_game,
+                    ignore_launch::<InvadersPlugin>.run_if(resource_exists::<LaunchGame>),
+                ),
+            );
+    }
+}
+
+#[derive(States, PartialEq, Eq, Hash, Clone, Debug)]
+pub enum InvadersState {
+    Exited,
+    InMenu,
+    InGame,
+}
+
+#[derive(States, PartialEq, Eq, Hash, Clone, Debug)]
+pub(crate) enum InvadersInGameState {
+    Playing,
+    // Before each wave and after losing a life, until the player fires
+    Paused,
+}
+
+// Until the player fires in the first wave
+fn pause_game(mut next_in_game_state: ResMut<NextState<InvadersInGameState>>) {
+    next_in_game_state.set(InvadersInGameState::Paused);
+}
+
+#[cfg(test)]
+mod tests;
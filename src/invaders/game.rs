@@ -0,0 +1,1090 @@
+use std::time::Duration;
+
+use bevy::{color::palettes, prelude::*};
+
+use crate::{application::GAME_AREA, game_menu, input::Action, registry::RetroGame, rng::GameRng};
+
+use super::{InvadersInGameState, InvadersPlugin, InvadersState};
+
+pub const NB_COLUMNS: usize = 11;
+pub const NB_ROWS: usize = 5;
+
+// Between the centers of two neighbouring aliens
+const ALIEN_SPACING: Vec2 = Vec2 { x: 48.0, y: 40.0 };
+
+// Center of the top left alien at the start of the first wave, every wave
+// starts a bit lower until `MAX_WAVE_DROPS`
+const FORMATION_START: Vec2 = Vec2 {
+    x: GAME_AREA.min.x + 60.0,
+    y: GAME_AREA.max.y - 110.0,
+};
+const WAVE_DROP: f32 = 20.0;
+const MAX_WAVE_DROPS: u32 = 4;
+
+// The formation steps sideways until one of its aliens gets this close to a
+// side, then drops and turns around
+const MARCH_STEP: f32 = 10.0;
+const MARCH_DROP: f32 = 20.0;
+const FORMATION_MARGIN: f32 = 20.0;
+
+// Seconds between two steps of the full formation, down to the last alien
+const MARCH_INTERVAL: f32 = 0.6;
+const MIN_MARCH_INTERVAL: f32 = 0.03;
+
+pub const CANNON_SIZE: Vec2 = Vec2 { x: 39.0, y: 24.0 };
+pub const CANNON_Y: f32 = GAME_AREA.min.y + 40.0;
+const CANNON_SPEED: f32 = 300.0;
+pub const LIVES: u32 = 3;
+
+const SHOT_SIZE: Vec2 = Vec2 { x: 3.0, y: 15.0 };
+const SHOT_SPEED: f32 = 700.0;
+
+const BULLET_SIZE: Vec2 = Vec2 { x: 3.0, y: 12.0 };
+const BULLET_SPEED: f32 = 250.0;
+const MAX_BULLETS: usize = 3;
+const FIRE_INTERVAL: f32 = 0.8;
+
+pub const NB_BUNKERS: usize = 4;
+pub const BUNKER_COLUMNS: usize = 22;
+pub const BUNKER_ROWS: usize = 16;
+pub const PIXEL_SIZE: f32 = 3.0;
+const BUNKER_Y: f32 = CANNON_Y + 50.0;
+
+// Pixels around a hit that go with it, in pixels
+const EROSION_RADIUS: i32 = 2;
+
+const UFO_SIZE: Vec2 = Vec2 { x: 48.0, y: 21.0 };
+const UFO_Y: f32 = GAME_AREA.max.y - 40.0;
+const UFO_SPEED: f32 = 150.0;
+const UFO_INTERVAL: f32 = 20.0;
+const UFO_POINTS: [u32; 4] = [50, 100, 150, 300];
+
+// The UFO stops showing up once the formation is this thin
+const UFO_MIN_ALIENS: usize = 8;
+
+const CANNON_COLOR: Color = Color::srgb(0.25, 0.9, 0.25);
+
+pub struct GamePlugin;
+
+impl Plugin for GamePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<LoadGameEvent>()
+            .add_event::<CleanupGameEvent>()
+            .init_resource::<LastScore>()
+            .add_systems(OnExit(InvadersPlugin::STATE), cleanup_game)
+            .add_systems(
+                Update,
+                (
+                    load_game.run_if(on_event::<LoadGameEvent>()),
+                    (
+                        (handle_player_input, update_game)
+                            .chain()
+                            .run_if(in_state(InvadersInGameState::Playing)),
+                        handle_pause_input.run_if(in_state(InvadersInGameState::Paused)),
+                        game_menu::end_game::<InvadersPlugin>
+                            .run_if(game_menu::is_over::<InvadersPlugin>),
+                    )
+                        .chain()
+                        .run_if(in_state(InvadersState::InGame))
+                        .run_if(resource_exists::<InvadersGame>),
+                    cleanup_game.run_if(on_event::<CleanupGameEvent>()),
+                    (
+                        draw_aliens,
+                        draw_cannon,
+                        draw_shots,
+                        draw_bunkers,
+                        draw_ufo,
+                        update_hud,
+                    )
+                        .run_if(resource_exists::<InvadersGame>),
+                )
+                    .chain(),
+            );
+    }
+}
+
+#[derive(Event, Default)]
+pub struct LoadGameEvent {
+    pub restart: bool,
+}
+
+#[derive(Event, Default)]
+pub struct CleanupGameEvent;
+
+/// Score of the last finished game
+pub type LastScore = game_menu::LastScore<InvadersPlugin>;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum AlienKind {
+    Squid,
+    Crab,
+    Octopus,
+}
+
+impl AlienKind {
+    /// Squids on the top row, then two rows of crabs and two of octopuses
+    pub fn of_row(row: usize) -> Self {
+        match row {
+            0 => AlienKind::Squid,
+            1 | 2 => AlienKind::Crab,
+            _ => AlienKind::Octopus,
+        }
+    }
+
+    pub fn points(self) -> u32 {
+        match self {
+            AlienKind::Squid => 30,
+            AlienKind::Crab => 20,
+            AlienKind::Octopus => 10,
+        }
+    }
+
+    pub fn size(self) -> Vec2 {
+        match self {
+            AlienKind::Squid => Vec2::new(24.0, 24.0),
+            AlienKind::Crab => Vec2::new(33.0, 24.0),
+            AlienKind::Octopus => Vec2::new(36.0, 24.0),
+        }
+    }
+
+    fn sprite_name(self) -> &'static str {
+        match self {
+            AlienKind::Squid => "squid",
+            AlienKind::Crab => "crab",
+            AlienKind::Octopus => "octopus",
+        }
+    }
+}
+
+/// Shelter of the cannon, shots and bullets take pixels off it
+#[derive(Clone, Debug)]
+pub struct Bunker {
+    /// Bottom left corner
+    pub origin: Vec2,
+
+    /// Whether each pixel is still there, row by row from the bottom
+    pub pixels: Vec<bool>,
+}
+
+impl Bunker {
+    fn new(origin: Vec2) -> Self {
+        let mut pixels = vec![false; BUNKER_COLUMNS * BUNKER_ROWS];
+        for y in 0..BUNKER_ROWS {
+            for x in 0..BUNKER_COLUMNS {
+                let from_top = BUNKER_ROWS - 1 - y;
+                let from_side = x.min(BUNKER_COLUMNS - 1 - x);
+
+                // Cut corners on top and an arch under the middle
+                let corner = from_side + from_top < 4;
+                let arch = y < 5 && (7..BUNKER_COLUMNS - 7).contains(&x)
+                    || y == 5 && (8..BUNKER_COLUMNS - 8).contains(&x);
+                pixels[y * BUNKER_COLUMNS + x] = !corner && !arch;
+            }
+        }
+
+        Self { origin, pixels }
+    }
+
+    pub fn rect(&self) -> Rect {
+        Rect::from_corners(
+            self.origin,
+            self.origin + Vec2::new(BUNKER_COLUMNS as f32, BUNKER_ROWS as f32) * PIXEL_SIZE,
+        )
+    }
+
+    /// Center of the pixel at `index`
+    pub fn pixel_position(&self, index: usize) -> Vec2 {
+        let coords = Vec2::new(
+            (index % BUNKER_COLUMNS) as f32,
+            (index / BUNKER_COLUMNS) as f32,
+        );
+        self.origin + (coords + 0.5) * PIXEL_SIZE
+    }
+
+    /// Index of the pixel still there under `point`, if there is one
+    pub fn solid_pixel_at(&self, point: Vec2) -> Option<usize> {
+        let coords = ((point - self.origin) / PIXEL_SIZE).floor();
+        if coords.x < 0.0
+            || coords.y < 0.0
+            || coords.x >= BUNKER_COLUMNS as f32
+            || coords.y >= BUNKER_ROWS as f32
+        {
+            return None;
+        }
+
+        let index = coords.y as usize * BUNKER_COLUMNS + coords.x as usize;
+        self.pixels[index].then_some(index)
+    }
+
+    /// Takes the pixels around the one at `index` off
+    fn erode(&mut self, index: usize) {
+        let x = (index % BUNKER_COLUMNS) as i32;
+        let y = (index / BUNKER_COLUMNS) as i32;
+
+        for dy in -EROSION_RADIUS..=EROSION_RADIUS {
+            for dx in -EROSION_RADIUS..=EROSION_RADIUS {
+                let (px, py) = (x + dx, y + dy);
+                if dx * dx + dy * dy <= EROSION_RADIUS * EROSION_RADIUS
+                    && (0..BUNKER_COLUMNS as i32).contains(&px)
+                    && (0..BUNKER_ROWS as i32).contains(&py)
+                {
+                    self.pixels[py as usize * BUNKER_COLUMNS + px as usize] = false;
+                }
+            }
+        }
+    }
+
+    /// Erodes the first pixel on the way from `from` to `to`, returns
+    /// whether there was one
+    fn hit(&mut self, from: Vec2, to: Vec2) -> bool {
+        let nb_steps = ((to - from).length() / PIXEL_SIZE).ceil() as usize + 1;
+        for i in 0..=nb_steps {
+            let point = from.lerp(to, i as f32 / nb_steps as f32);
+            if let Some(index) = self.solid_pixel_at(point) {
+                self.erode(index);
+                return true;
+            }
+        }
+
+        false
+    }
+}
+
+/// The mystery ship crossing the top of the screen
+#[derive(Clone, Copy, Debug)]
+pub struct Ufo {
+    pub x: f32,
+
+    // 1 to the right, -1 to the left
+    direction: f32,
+
+    pub points: u32,
+}
+
+impl Ufo {
+    pub fn rect(&self) -> Rect {
+        Rect::from_center_size(Vec2::new(self.x, UFO_Y), UFO_SIZE)
+    }
+}
+
+/// What happened during an update of the game
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Outcome {
+    Continue,
+    /// An alien bullet hit the cannon, the game is over if it was the last
+    /// life
+    CannonHit,
+    /// The last alien was shot, `InvadersGame::next_wave` brings the next
+    /// ones
+    WaveCleared,
+}
+
+/// The game being played
+#[derive(Resource, Clone, Debug)]
+pub struct InvadersGame {
+    /// Aliens still alive, row by row from the top
+    pub aliens: [[bool; NB_COLUMNS]; NB_ROWS],
+
+    /// Center of the top left alien, alive or not
+    pub formation: Vec2,
+
+    /// 1 to the right, -1 to the left
+    pub march_direction: f32,
+
+    /// Which of their two poses the aliens are in, it changes at every step
+    pub pose: bool,
+
+    pub cannon_x: f32,
+
+    /// The cannon's shot, only one at a time
+    pub shot: Option<Vec2>,
+
+    /// Of the aliens, falling
+    pub bullets: Vec<Vec2>,
+
+    pub bunkers: Vec<Bunker>,
+    pub ufo: Option<Ufo>,
+
+    /// From 1
+    pub wave: u32,
+
+    pub lives: u32,
+    pub score: u32,
+
+    // The cannon has no life left, or the aliens reached it
+    pub game_over: bool,
+
+    march_timer: Timer,
+    fire_timer: Timer,
+    ufo_timer: Timer,
+}
+
+impl Default for InvadersGame {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl game_menu::ScoredGame for InvadersGame {
+    fn score(&self) -> u32 {
+        self.score
+    }
+
+    fn is_over(&self) -> bool {
+        self.game_over
+    }
+}
+
+impl InvadersGame {
+    pub fn new() -> Self {
+        let mut game = Self {
+            aliens: [[true; NB_COLUMNS]; NB_ROWS],
+            formation: FORMATION_START,
+            march_direction: 1.0,
+            pose: false,
+            cannon_x: GAME_AREA.center().x,
+            shot: None,
+            bullets: Vec::new(),
+            bunkers: Vec::new(),
+            ufo: None,
+            wave: 1,
+            lives: LIVES,
+            score: 0,
+            game_over: false,
+            march_timer: Timer::from_seconds(MARCH_INTERVAL, TimerMode::Repeating),
+            fire_timer: Timer::from_seconds(FIRE_INTERVAL, TimerMode::Repeating),
+            ufo_timer: Timer::from_seconds(UFO_INTERVAL, TimerMode::Repeating),
+        };
+        game.start_wave();
+
+        game
+    }
+
+    /// A full formation again, a bit lower than the last one
+    pub fn next_wave(&mut self) {
+        self.wave += 1;
+        self.start_wave();
+    }
+
+    fn start_wave(&mut self) {
+        let nb_drops = (self.wave - 1).min(MAX_WAVE_DROPS);
+
+        self.aliens = [[true; NB_COLUMNS]; NB_ROWS];
+        self.formation = FORMATION_START - Vec2::Y * WAVE_DROP * nb_drops as f32;
+        self.march_direction = 1.0;
+        self.cannon_x = GAME_AREA.center().x;
+        self.shot = None;
+        self.bullets.clear();
+        self.ufo = None;
+        self.march_timer.reset();
+        self.fire_timer.reset();
+        self.ufo_timer.reset();
+
+        let bunker_size = Vec2::new(BUNKER_COLUMNS as f32, BUNKER_ROWS as f32) * PIXEL_SIZE;
+        self.bunkers = (0..NB_BUNKERS)
+            .map(|i| {
+                let center_x =
+                    GAME_AREA.min.x + GAME_AREA.width() * (i as f32 + 0.5) / NB_BUNKERS as f32;
+                Bunker::new(Vec2::new(center_x - bunker_size.x / 2.0, BUNKER_Y))
+            })
+            .collect();
+    }
+
+    pub fn alien_position(&self, column: usize, row: usize) -> Vec2 {
+        self.formation + Vec2::new(column as f32, -(row as f32)) * ALIEN_SPACING
+    }
+
+    pub fn alien_rect(&self, column: usize, row: usize) -> Rect {
+        Rect::from_center_size(
+            self.alien_position(column, row),
+            AlienKind::of_row(row).size(),
+        )
+    }
+
+    /// Columns and rows of the aliens still alive
+    pub fn alive_aliens(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        (0..NB_ROWS).flat_map(move |row| {
+            (0..NB_COLUMNS)
+                .filter(move |&column| self.aliens[row][column])
+                .map(move |column| (column, row))
+        })
+    }
+
+    pub fn nb_aliens(&self) -> usize {
+        self.alive_aliens().count()
+    }
+
+    /// Seconds between two steps of the formation, the fewer aliens the
+    /// faster it goes
+    pub fn march_interval(&self) -> f32 {
+        let nb_aliens = NB_COLUMNS * NB_ROWS;
+        let thinned = (nb_aliens - self.nb_aliens().max(1)) as f32 / (nb_aliens - 1) as f32;
+        MARCH_INTERVAL + (MIN_MARCH_INTERVAL - MARCH_INTERVAL) * thinned
+    }
+
+    pub fn cannon_rect(&self) -> Rect {
+        Rect::from_center_size(Vec2::new(self.cannon_x, CANNON_Y), CANNON_SIZE)
+    }
+
+    /// Moves the cannon left with -1, right with 1, staying in the game area
+    pub fn move_cannon(&mut self, direction: f32, delta_seconds: f32) {
+        let min_x = GAME_AREA.min.x + CANNON_SIZE.x / 2.0;
+        let max_x = GAME_AREA.max.x - CANNON_SIZE.x / 2.0;
+        self.cannon_x =
+            (self.cannon_x + direction * CANNON_SPEED * delta_seconds).clamp(min_x, max_x);
+    }
+
+    /// Fires unless the last shot is still on screen, returns whether it did
+    pub fn fire(&mut self) -> bool {
+        if self.shot.is_some() {
+            return false;
+        }
+
+        self.shot = Some(Vec2::new(
+            self.cannon_x,
+            CANNON_Y + (CANNON_SIZE.y + SHOT_SIZE.y) / 2.0,
+        ));
+        true
+    }
+
+    /// Moves everything by `delta_seconds`. `pick` picks among the given
+    /// number of choices, for the aliens firing and the UFO.
+    pub fn update(&mut self, delta_seconds: f32, mut pick: impl FnMut(usize) -> usize) -> Outcome {
+        let delta = Duration::from_secs_f32(delta_seconds);
+
+        self.march_timer
+            .set_duration(Duration::from_secs_f32(self.march_interval()));
+        if self.march_timer.tick(delta).just_finished() {
+            self.march();
+            if self.game_over {
+                return Outcome::Continue;
+            }
+        }
+
+        if self.fire_timer.tick(delta).just_finished() {
+            self.alien_fire(&mut pick);
+        }
+
+        self.move_ufo(delta_seconds, &mut pick);
+        self.move_shot(delta_seconds);
+
+        if self.nb_aliens() == 0 {
+            return Outcome::WaveCleared;
+        }
+
+        if self.move_bullets(delta_seconds) {
+            self.lives = self.lives.saturating_sub(1);
+            self.game_over = self.lives == 0;
+            self.cannon_x = GAME_AREA.center().x;
+            self.shot = None;
+            self.bullets.clear();
+            return Outcome::CannonHit;
+        }
+
+        Outcome::Continue
+    }
+
+    // One step sideways, or down and turning around at the sides. The game
+    // is over once the aliens reach the cannon.
+    fn march(&mut self) {
+        self.pose = !self.pose;
+
+        let step = Vec2::X * MARCH_STEP * self.march_direction;
+        let turns = self.alive_aliens().any(|(column, row)| {
+            let rect = self.alien_rect(column, row);
+            rect.max.x + step.x > GAME_AREA.max.x - FORMATION_MARGIN
+                || rect.min.x + step.x < GAME_AREA.min.x + FORMATION_MARGIN
+        });
+
+        if turns {
+            self.formation.y -= MARCH_DROP;
+            self.march_direction = -self.march_direction;
+        } else {
+            self.formation += step;
+        }
+
+        // The aliens go through the bunkers
+        let alien_rects: Vec<Rect> = self
+            .alive_aliens()
+            .map(|(column, row)| self.alien_rect(column, row))
+            .collect();
+        for bunker in &mut self.bunkers {
+            let bunker_rect = bunker.rect();
+            for rect in alien_rects
+                .iter()
+                .filter(|rect| !rect.intersect(bunker_rect).is_empty())
+            {
+                for index in 0..bunker.pixels.len() {
+                    if rect.contains(bunker.pixel_position(index)) {
+                        bunker.pixels[index] = false;
+                    }
+                }
+            }
+        }
+
+        let cannon_top = CANNON_Y + CANNON_SIZE.y / 2.0;
+        if alien_rects.iter().any(|rect| rect.min.y <= cannon_top) {
+            self.game_over = true;
+        }
+    }
+
+    // The lowest alien of a column fires
+    fn alien_fire(&mut self, pick: &mut impl FnMut(usize) -> usize) {
+        if self.bullets.len() >= MAX_BULLETS {
+            return;
+        }
+
+        let columns: Vec<usize> = (0..NB_COLUMNS)
+            .filter(|&column| (0..NB_ROWS).any(|row| self.aliens[row][column]))
+            .collect();
+        if columns.is_empty() {
+            return;
+        }
+
+        let column = columns[pick(columns.len())];
+        let row = (0..NB_ROWS)
+            .rev()
+            .find(|&row| self.aliens[row][column])
+            .unwrap();
+        let rect = self.alien_rect(column, row);
+        self.bullets
+            .push(Vec2::new(rect.center().x, rect.min.y - BULLET_SIZE.y / 2.0));
+    }
+
+    fn move_ufo(&mut self, delta_seconds: f32, pick: &mut impl FnMut(usize) -> usize) {
+        if let Some(ufo) = &mut self.ufo {
+            ufo.x += ufo.direction * UFO_SPEED * delta_seconds;
+            if ufo.x - UFO_SIZE.x / 2.0 > GAME_AREA.max.x
+                || ufo.x + UFO_SIZE.x / 2.0 < GAME_AREA.min.x
+            {
+                self.ufo = None;
+            }
+            return;
+        }
+
+        let delta = Duration::from_secs_f32(delta_seconds);
+        if self.ufo_timer.tick(delta).just_finished() && self.nb_aliens() >= UFO_MIN_ALIENS {
+            let direction = if pick(2) == 0 { 1.0 } else { -1.0 };
+            self.ufo = Some(Ufo {
+                x: GAME_AREA.center().x - direction * (GAME_AREA.width() + UFO_SIZE.x) / 2.0,
+                direction,
+                points: UFO_POINTS[pick(UFO_POINTS.len())],
+            });
+        }
+    }
+
+    // The shot goes up until it hits a bunker, an alien, the UFO or a bullet
+    fn move_shot(&mut self, delta_seconds: f32) {
+        let Some(from) = self.shot else {
+            return;
+        };
+        let to = from + Vec2::Y * SHOT_SPEED * delta_seconds;
+        let path = Rect::from_corners(from - SHOT_SIZE / 2.0, to + SHOT_SIZE / 2.0);
+        self.shot = None;
+
+        let tip = |point: Vec2| point + Vec2::Y * SHOT_SIZE.y / 2.0;
+        if self
+            .bunkers
+            .iter_mut()
+            .any(|bunker| bunker.hit(tip(from), tip(to)))
+        {
+            return;
+        }
+
+        let hit_alien = self
+            .alive_aliens()
+            .find(|&(column, row)| !self.alien_rect(column, row).intersect(path).is_empty());
+        if let Some((column, row)) = hit_alien {
+            self.aliens[row][column] = false;
+            self.score += AlienKind::of_row(row).points();
+            return;
+        }
+
+        if let Some(ufo) = self.ufo {
+            if !ufo.rect().intersect(path).is_empty() {
+                self.score += ufo.points;
+                self.ufo = None;
+                return;
+            }
+        }
+
+        if let Some(index) = self.bullets.iter().position(|&bullet| {
+            !Rect::from_center_size(bullet, BULLET_SIZE)
+                .intersect(path)
+                .is_empty()
+        }) {
+            self.bullets.swap_remove(index);
+            return;
+        }
+
+        if to.y - SHOT_SIZE.y / 2.0 < GAME_AREA.max.y {
+            self.shot = Some(to);
+        }
+    }
+
+    // The bullets go down until they hit a bunker or the ground, returns
+    // whether one hit the cannon
+    fn move_bullets(&mut self, delta_seconds: f32) -> bool {
+        let cannon_rect = self.cannon_rect();
+        let mut hits_cannon = false;
+
+        let bunkers = &mut self.bunkers;
+        self.bullets.retain_mut(|bullet| {
+            let from = *bullet;
+            let to = from - Vec2::Y * BULLET_SPEED * delta_seconds;
+            let path = Rect::from_corners(from - BULLET_SIZE / 2.0, to + BULLET_SIZE / 2.0);
+
+            let tip = |point: Vec2| point - Vec2::Y * BULLET_SIZE.y / 2.0;
+            if bunkers
+                .iter_mut()
+                .any(|bunker| bunker.hit(tip(from), tip(to)))
+            {
+                return false;
+            }
+
+            if !cannon_rect.intersect(path).is_empty() {
+                hits_cannon = true;
+                return false;
+            }
+
+            *bullet = to;
+            to.y + BULLET_SIZE.y / 2.0 > GAME_AREA.min.y
+        });
+
+        hits_cannon
+    }
+}
+
+#[derive(Component)]
+struct GameEntity;
+
+#[derive(Component)]
+struct Alien {
+    column: usize,
+    row: usize,
+}
+
+#[derive(Component)]
+struct Cannon;
+
+#[derive(Component)]
+struct Shot;
+
+// Alien bullet at this index
+#[derive(Component)]
+struct Bullet(usize);
+
+#[derive(Component)]
+struct BunkerPixel {
+    bunker: usize,
+    index: usize,
+}
+
+#[derive(Component)]
+struct UfoSprite;
+
+#[derive(Component)]
+struct Hud;
+
+// Shown while the game waits for the player to fire
+#[derive(Component)]
+struct WaveText;
+
+#[derive(Resource)]
+struct GameAssets {
+    // Two poses of each kind of alien
+    aliens: Vec<(AlienKind, [Handle<Image>; 2])>,
+}
+
+impl GameAssets {
+    fn alien(&self, kind: AlienKind, pose: bool) -> Handle<Image> {
+        let (_, poses) = self
+            .aliens
+            .iter()
+            .find(|(alien_kind, _)| *alien_kind == kind)
+            .unwrap();
+        poses[pose as usize].clone()
+    }
+}
+
+fn load_game(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut load_events: EventReader<LoadGameEvent>,
+    mut game_rng: ResMut<GameRng>,
+    entities: Query<Entity, With<GameEntity>>,
+    mut next_in_game_state: ResMut<NextState<InvadersInGameState>>,
+) {
+    if let Some(load_event) = load_events.read().last() {
+        if load_event.restart {
+            game_rng.restart_run();
+        } else {
+            game_rng.new_run();
+        }
+    }
+
+    for entity in &entities {
+        commands.entity(entity).despawn();
+    }
+
+    let game = InvadersGame::new();
+
+    let game_assets = GameAssets {
+        aliens: [AlienKind::Squid, AlienKind::Crab, AlienKind::Octopus]
+            .into_iter()
+            .map(|kind| {
+                let name = kind.sprite_name();
+                let poses = [1, 2]
+                    .map(|pose| asset_server.load(format!("invaders/sprites/{name}_{pose}.png")));
+                (kind, poses)
+            })
+            .collect(),
+    };
+
+    commands.spawn((
+        SpriteBundle {
+            sprite: Sprite {
+                color: Color::BLACK,
+                custom_size: Some(GAME_AREA.size()),
+                ..Default::default()
+            },
+            transform: Transform::from_translation(Vec3::from((GAME_AREA.center(), -1.0))),
+            ..Default::default()
+        },
+        GameEntity,
+    ));
+
+    // Ground under the cannon
+    commands.spawn((
+        SpriteBundle {
+            sprite: Sprite {
+                color: CANNON_COLOR,
+                custom_size: Some(Vec2::new(GAME_AREA.width(), 2.0)),
+                ..Default::default()
+            },
+            transform: Transform::from_translation(Vec3::new(
+                GAME_AREA.center().x,
+                GAME_AREA.min.y + 15.0,
+                -0.5,
+            )),
+            ..Default::default()
+        },
+        GameEntity,
+    ));
+
+    for (column, row) in game.alive_aliens() {
+        let kind = AlienKind::of_row(row);
+        commands.spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    custom_size: Some(kind.size()),
+                    ..Default::default()
+                },
+                texture: game_assets.alien(kind, false),
+                ..Default::default()
+            },
+            Alien { column, row },
+            GameEntity,
+        ));
+    }
+
+    commands.spawn((
+        SpriteBundle {
+            sprite: Sprite {
+                color: CANNON_COLOR,
+                custom_size: Some(CANNON_SIZE),
+                ..Default::default()
+            },
+            texture: asset_server.load("invaders/sprites/cannon.png"),
+            ..Default::default()
+        },
+        Cannon,
+        GameEntity,
+    ));
+
+    commands.spawn((
+        SpriteBundle {
+            sprite: Sprite {
+                color: Color::WHITE,
+                custom_size: Some(SHOT_SIZE),
+                ..Default::default()
+            },
+            visibility: Visibility::Hidden,
+            ..Default::default()
+        },
+        Shot,
+        GameEntity,
+    ));
+
+    for (bunker_index, bunker) in game.bunkers.iter().enumerate() {
+        for index in 0..bunker.pixels.len() {
+            commands.spawn((
+                SpriteBundle {
+                    sprite: Sprite {
+                        color: CANNON_COLOR,
+                        custom_size: Some(Vec2::splat(PIXEL_SIZE)),
+                        ..Default::default()
+                    },
+                    transform: Transform::from_translation(Vec3::from((
+                        bunker.pixel_position(index),
+                        0.0,
+                    ))),
+                    ..Default::default()
+                },
+                BunkerPixel {
+                    bunker: bunker_index,
+                    index,
+                },
+                GameEntity,
+            ));
+        }
+    }
+
+    commands.spawn((
+        SpriteBundle {
+            sprite: Sprite {
+                color: palettes::css::RED.into(),
+                custom_size: Some(UFO_SIZE),
+                ..Default::default()
+            },
+            texture: asset_server.load("invaders/sprites/ufo.png"),
+            visibility: Visibility::Hidden,
+            ..Default::default()
+        },
+        UfoSprite,
+        GameEntity,
+    ));
+
+    commands.spawn((
+        Text2dBundle {
+            text: Text::from_section(
+                "",
+                TextStyle {
+                    font_size: 24.0,
+                    color: Color::WHITE,
+                    ..Default::default()
+                },
+            ),
+            transform: Transform::from_translation(Vec3::from((
+                Vec2::new(GAME_AREA.center().x, GAME_AREA.max.y - 15.0),
+                1.0,
+            ))),
+            ..Default::default()
+        },
+        Hud,
+        GameEntity,
+    ));
+
+    commands.spawn((
+        Text2dBundle {
+            text: Text::from_section(
+                "",
+                TextStyle {
+                    font_size: 30.0,
+                    color: CANNON_COLOR,
+                    ..Default::default()
+                },
+            )
+            .with_justify(JustifyText::Center),
+            transform: Transform::from_translation(Vec3::from((
+                Vec2::new(GAME_AREA.center().x, BUNKER_Y + 120.0),
+                1.0,
+            ))),
+            ..Default::default()
+        },
+        WaveText,
+        GameEntity,
+    ));
+
+    commands.insert_resource(game);
+    commands.insert_resource(game_assets);
+    next_in_game_state.set(InvadersInGameState::Paused);
+}
+
+fn cleanup_game(mut commands: Commands, entities: Query<Entity, With<GameEntity>>) {
+    for entity in &entities {
+        commands.entity(entity).despawn();
+    }
+
+    commands.remove_resource::<InvadersGame>();
+    commands.remove_resource::<GameAssets>();
+}
+
+fn handle_player_input(
+    time: Res<Time>,
+    input: Res<ButtonInput<Action>>,
+    mut game: ResMut<InvadersGame>,
+    mut next_in_game_state: ResMut<NextState<InvadersInGameState>>,
+    mut next_invaders_state: ResMut<NextState<InvadersState>>,
+) {
+    let direction =
+        input.pressed(Action::MoveRight) as i32 - input.pressed(Action::MoveLeft) as i32;
+    game.move_cannon(direction as f32, time.delta_seconds());
+
+    if input.just_pressed(Action::Fire) {
+        game.fire();
+    }
+
+    if input.just_pressed(Action::Pause) {
+        next_in_game_state.set(InvadersInGameState::Paused);
+        next_invaders_state.set(InvadersState::InMenu);
+    }
+}
+
+// Waits for the player to fire before each wave and after losing a life
+fn handle_pause_input(
+    input: Res<ButtonInput<Action>>,
+    mut next_in_game_state: ResMut<NextState<InvadersInGameState>>,
+    mut next_invaders_state: ResMut<NextState<InvadersState>>,
+) {
+    if input.just_pressed(Action::Fire) || input.just_pressed(Action::Click) {
+        next_in_game_state.set(InvadersInGameState::Playing);
+    }
+
+    if input.just_pressed(Action::Pause) {
+        next_invaders_state.set(InvadersState::InMenu);
+    }
+}
+
+fn update_game(
+    time: Res<Time>,
+    mut game: ResMut<InvadersGame>,
+    mut game_rng: ResMut<GameRng>,
+    mut next_in_game_state: ResMut<NextState<InvadersInGameState>>,
+) {
+    let outcome = game.update(time.delta_seconds(), |nb_choices| {
+        game_rng.random_index(nb_choices)
+    });
+
+    match outcome {
+        Outcome::Continue => (),
+        Outcome::CannonHit => next_in_game_state.set(InvadersInGameState::Paused),
+        Outcome::WaveCleared => {
+            game.next_wave();
+            next_in_game_state.set(InvadersInGameState::Paused);
+        }
+    }
+}
+
+fn draw_aliens(
+    game: Res<InvadersGame>,
+    game_assets: Res<GameAssets>,
+    mut aliens: Query<(&Alien, &mut Transform, &mut Visibility, &mut Handle<Image>)>,
+) {
+    for (alien, mut transform, mut visibility, mut texture) in &mut aliens {
+        transform.translation = Vec3::from((game.alien_position(alien.column, alien.row), 0.0));
+        *visibility = if game.aliens[alien.row][alien.column] {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+
+        let pose = game_assets.alien(AlienKind::of_row(alien.row), game.pose);
+        if *texture != pose {
+            *texture = pose;
+        }
+    }
+}
+
+fn draw_cannon(game: Res<InvadersGame>, mut cannon: Query<&mut Transform, With<Cannon>>) {
+    if let Ok(mut transform) = cannon.get_single_mut() {
+        transform.translation = Vec3::new(game.cannon_x, CANNON_Y, 0.0);
+    }
+}
+
+fn draw_shots(
+    mut commands: Commands,
+    game: Res<InvadersGame>,
+    mut shot: Query<(&mut Transform, &mut Visibility), With<Shot>>,
+    mut bullets: Query<(Entity, &Bullet, &mut Transform), Without<Shot>>,
+) {
+    if let Ok((mut transform, mut visibility)) = shot.get_single_mut() {
+        match game.shot {
+            Some(position) => {
+                transform.translation = Vec3::from((position, 0.5));
+                *visibility = Visibility::Inherited;
+            }
+            None => *visibility = Visibility::Hidden,
+        }
+    }
+
+    let mut nb_bullets = 0;
+    for (entity, Bullet(index), mut transform) in &mut bullets {
+        match game.bullets.get(*index) {
+            Some(&position) => {
+                transform.translation = Vec3::from((position, 0.5));
+                nb_bullets += 1;
+            }
+            None => commands.entity(entity).despawn(),
+        }
+    }
+
+    // Bullets fired since the last frame
+    for index in nb_bullets..game.bullets.len() {
+        commands.spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    color: palettes::css::LIGHT_GRAY.into(),
+                    custom_size: Some(BULLET_SIZE),
+                    ..Default::default()
+                },
+                transform: Transform::from_translation(Vec3::from((game.bullets[index], 0.5))),
+                ..Default::default()
+            },
+            Bullet(index),
+            GameEntity,
+        ));
+    }
+}
+
+fn draw_bunkers(game: Res<InvadersGame>, mut pixels: Query<(&BunkerPixel, &mut Visibility)>) {
+    for (pixel, mut visibility) in &mut pixels {
+        let solid = game.bunkers[pixel.bunker].pixels[pixel.index];
+        let wanted = if solid {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+        if *visibility != wanted {
+            *visibility = wanted;
+        }
+    }
+}
+
+fn draw_ufo(
+    game: Res<InvadersGame>,
+    mut ufo: Query<(&mut Transform, &mut Visibility), With<UfoSprite>>,
+) {
+    if let Ok((mut transform, mut visibility)) = ufo.get_single_mut() {
+        match game.ufo {
+            Some(ufo) => {
+                transform.translation = Vec3::from((ufo.rect().center(), 0.0));
+                *visibility = Visibility::Inherited;
+            }
+            None => *visibility = Visibility::Hidden,
+        }
+    }
+}
+
+fn update_hud(
+    game: Res<InvadersGame>,
+    in_game_state: Res<State<InvadersInGameState>>,
+    mut hud: Query<&mut Text, (With<Hud>, Without<WaveText>)>,
+    mut wave_text: Query<(&mut Text, &mut Visibility), With<WaveText>>,
+) {
+    if let Ok(mut text) = hud.get_single_mut() {
+        text.sections[0].value = format!(
+            "Score: {}    Wave: {}    Lives: {}",
+            game.score, game.wave, game.lives
+        );
+    }
+
+    if let Ok((mut text, mut visibility)) = wave_text.get_single_mut() {
+        *visibility = if *in_game_state.get() == InvadersInGameState::Paused {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+        text.sections[0].value = format!("Wave {}\nFire to start", game.wave);
+    }
+}
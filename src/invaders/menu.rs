@@ -0,0 +1,47 @@
+use bevy::color::palettes;
+use bevy::ecs::system::SystemParamItem;
+use bevy::prelude::*;
+
+use crate::game_menu::{GameMenu, GameMenuAction, GameMenuPlugin, Scores};
+use crate::menu_tree::Menu;
+
+use super::{
+    game::{CleanupGameEvent, InvadersGame, LoadGameEvent},
+    InvadersPlugin, InvadersState,
+};
+
+pub struct MenuPlugin;
+
+impl Plugin for MenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(GameMenuPlugin::<InvadersPlugin>::default());
+    }
+}
+
+impl GameMenu for InvadersPlugin {
+    type State = InvadersState;
+    const EXITED: InvadersState = InvadersState::Exited;
+    const IN_MENU: InvadersState = InvadersState::InMenu;
+    const IN_GAME: InvadersState = InvadersState::InGame;
+
+    type Game = InvadersGame;
+    type LoadGameEvent = LoadGameEvent;
+    type CleanupGameEvent = CleanupGameEvent;
+
+    type Action = ();
+    type MenuData = Scores<'static, InvadersPlugin>;
+
+    const COLOR: Color = Color::Srgba(palettes::css::LIME);
+
+    fn main_menu(scores: &SystemParamItem<Self::MenuData>) -> Menu<GameMenuAction<Self>> {
+        scores.main_menu()
+    }
+
+    fn status(scores: &SystemParamItem<Self::MenuData>, game: Option<&InvadersGame>) -> String {
+        scores.status(game.map(|game| game.score))
+    }
+
+    fn load_game_event(restart: bool) -> LoadGameEvent {
+        LoadGameEvent { restart }
+    }
+}
@@ -0,0 +1,207 @@
+use bevy::prelude::*;
+
+use crate::{
+    application::GAME_AREA,
+    testing::{self, game, play_game, press, release, run_frames, state, step_until, tap},
+};
+
+use super::{
+    game::{AlienKind, InvadersGame, Outcome, CANNON_Y, LIVES, NB_COLUMNS, NB_ROWS},
+    *,
+};
+
+// Enters Space Invaders from the launcher and plays, the game then waits
+// for the player to fire
+fn play_invaders() -> App {
+    let app = play_game(InvadersPlugin);
+    assert_eq!(
+        state::<InvadersInGameState>(&app),
+        InvadersInGameState::Paused
+    );
+
+    app
+}
+
+// Updates until the shot is gone, without the aliens firing back
+fn update_until_shot_is_gone(game: &mut InvadersGame) {
+    step_until(
+        game,
+        120,
+        |game| {
+            game.bullets.clear();
+            game.update(1.0 / 60.0, |_| 0);
+        },
+        |game| game.shot.is_none(),
+    );
+}
+
+#[test]
+fn the_formation_marches_drops_at_the_sides_and_speeds_up_as_it_thins() {
+    let mut game = InvadersGame::new();
+    let start = game.formation;
+
+    for _ in 0..100 {
+        game.bullets.clear();
+        game.update(game.march_interval(), |_| 0);
+        if game.march_direction < 0.0 {
+            break;
+        }
+    }
+    assert_eq!(game.march_direction, -1.0);
+    assert!(game.formation.x > start.x);
+    assert!(game.formation.y < start.y);
+
+    let full_interval = game.march_interval();
+    game.aliens[0] = [false; NB_COLUMNS];
+    assert!(game.march_interval() < full_interval);
+
+    game.aliens = [[false; NB_COLUMNS]; NB_ROWS];
+    game.aliens[2][3] = true;
+    assert!(game.march_interval() < full_interval / 10.0);
+}
+
+#[test]
+fn the_cannon_has_one_shot_on_screen_and_scores_the_alien_it_hits() {
+    let mut game = InvadersGame::new();
+
+    assert!(game.fire());
+    assert!(!game.fire());
+
+    // The bottom row is in front of the cannon, in the middle of the area
+    update_until_shot_is_gone(&mut game);
+    assert_eq!(game.nb_aliens(), NB_COLUMNS * NB_ROWS - 1);
+    assert!((0..NB_COLUMNS).any(|column| !game.aliens[NB_ROWS - 1][column]));
+    assert_eq!(game.score, AlienKind::of_row(NB_ROWS - 1).points());
+
+    assert!(game.fire());
+}
+
+#[test]
+fn shots_and_bullets_take_pixels_off_the_bunkers() {
+    let mut game = InvadersGame::new();
+    let bunker = game.bunkers[0].rect();
+    let nb_pixels = |game: &InvadersGame| game.bunkers[0].pixels.iter().filter(|p| **p).count();
+    let full = nb_pixels(&game);
+
+    game.bullets = vec![Vec2::new(bunker.center().x, bunker.max.y + 5.0)];
+    game.update(0.1, |_| 0);
+    assert!(game.bullets.is_empty());
+    let after_bullet = nb_pixels(&game);
+    assert!(after_bullet < full);
+
+    // From under the bunker, through its arch
+    game.cannon_x = bunker.min.x + 5.0;
+    game.fire();
+    update_until_shot_is_gone(&mut game);
+    assert!(nb_pixels(&game) < after_bullet);
+    assert_eq!(game.nb_aliens(), NB_COLUMNS * NB_ROWS);
+}
+
+#[test]
+fn bullets_cost_lives_until_the_game_is_over() {
+    let mut game = InvadersGame::new();
+    game.cannon_x = GAME_AREA.min.x + 200.0;
+
+    game.bullets = vec![Vec2::new(game.cannon_x, CANNON_Y + 20.0)];
+    assert_eq!(game.update(0.1, |_| 0), Outcome::CannonHit);
+    assert_eq!(game.lives, LIVES - 1);
+    assert!(!game.game_over);
+    assert_eq!(game.cannon_x, GAME_AREA.center().x);
+
+    game.lives = 1;
+    game.bullets = vec![Vec2::new(game.cannon_x, CANNON_Y + 20.0)];
+    assert_eq!(game.update(0.1, |_| 0), Outcome::CannonHit);
+    assert!(game.game_over);
+}
+
+#[test]
+fn the_ufo_crosses_the_top_for_bonus_points() {
+    let mut game = InvadersGame::new();
+    game.aliens = [[false; NB_COLUMNS]; NB_ROWS];
+    game.aliens[0][0] = true;
+    assert!(game.ufo.is_none());
+
+    // It does not show up for the last aliens
+    game.update(30.0, |_| 0);
+    assert!(game.ufo.is_none());
+
+    let mut game = InvadersGame::new();
+    game.update(30.0, |_| 0);
+    let ufo = game.ufo.expect("The UFO should show up");
+    assert!(ufo.x < GAME_AREA.min.x);
+
+    // Going right to meet the shot, with the aliens out of the way
+    game.aliens = [[false; NB_COLUMNS]; NB_ROWS];
+    game.aliens[0][0] = true;
+    game.ufo.as_mut().unwrap().x = game.cannon_x - 110.0;
+    game.fire();
+    update_until_shot_is_gone(&mut game);
+    assert!(game.ufo.is_none());
+    assert_eq!(game.score, ufo.points);
+}
+
+#[test]
+fn a_cleared_wave_brings_a_lower_one() {
+    let mut game = InvadersGame::new();
+    game.aliens = [[false; NB_COLUMNS]; NB_ROWS];
+    assert_eq!(game.update(0.01, |_| 0), Outcome::WaveCleared);
+
+    game.next_wave();
+    assert_eq!(game.wave, 2);
+    assert_eq!(game.nb_aliens(), NB_COLUMNS * NB_ROWS);
+    assert!(game.formation.y < InvadersGame::new().formation.y);
+}
+
+#[test]
+fn the_game_waits_for_the_player_to_fire_and_pauses() {
+    let mut app = play_invaders();
+    let formation = game::<InvadersPlugin>(&app).formation;
+
+    run_frames(&mut app, 60);
+    assert_eq!(game::<InvadersPlugin>(&app).formation, formation);
+
+    tap(&mut app, KeyCode::Space);
+    assert_eq!(
+        state::<InvadersInGameState>(&app),
+        InvadersInGameState::Playing
+    );
+
+    let cannon_x = game::<InvadersPlugin>(&app).cannon_x;
+    press(&mut app, KeyCode::ArrowRight);
+    run_frames(&mut app, 10);
+    release(&mut app, KeyCode::ArrowRight);
+    assert!(game::<InvadersPlugin>(&app).cannon_x > cannon_x);
+
+    tap(&mut app, KeyCode::Space);
+    assert!(game::<InvadersPlugin>(&app).shot.is_some());
+
+    tap(&mut app, KeyCode::Escape);
+    assert_eq!(state::<InvadersState>(&app), InvadersState::InMenu);
+    assert!(app.world().contains_resource::<InvadersGame>());
+
+    // "Resume", then fire to go on
+    tap(&mut app, KeyCode::Space);
+    assert_eq!(state::<InvadersState>(&app), InvadersState::InGame);
+    assert_eq!(
+        state::<InvadersInGameState>(&app),
+        InvadersInGameState::Paused
+    );
+}
+
+#[test]
+fn game_over_keeps_the_best_score_and_exit_cleans_up() {
+    // The last life lost to a bullet on the cannon
+    testing::game_over_keeps_the_best_score_and_exit_cleans_up(
+        InvadersPlugin,
+        |app| {
+            tap(app, KeyCode::Space);
+            let mut game = app.world_mut().resource_mut::<InvadersGame>();
+            game.score = 120;
+            game.lives = 1;
+            let cannon_x = game.cannon_x;
+            game.bullets = vec![Vec2::new(cannon_x, CANNON_Y + 20.0)];
+        },
+        10,
+        120,
+    );
+}
@@ -0,0 +1,178 @@
+//! Command line options of the `retro` binary, to start straight into a
+//! game, a level or a replay instead of the launcher
+
+use std::fmt::Write;
+
+use bevy::{
+    prelude::*,
+    window::{PrimaryWindow, WindowMode},
+};
+
+use crate::application::CurrentGame;
+use crate::registry::GameRegistry;
+use crate::replay::{ReplayedResource, StartReplayEvent};
+use crate::rng::{FixedSeed, GameRng};
+
+pub const USAGE: &str = "Usage: retro [--game <name> [--level <number>] [--mode <mode>]] \
+                         [--seed <number>] [--windowed | --fullscreen] [--replay <file>]";
+
+/// What the command line asks for, as a plugin applying it at startup
+#[derive(Resource, Clone, PartialEq, Eq, Default, Debug)]
+pub struct LaunchOptions {
+    /// Name of a registered game, in any case
+    pub game: Option<String>,
+    pub level: Option<usize>,
+    pub mode: Option<String>,
+
+    /// Seed of every session, see `GameRng`
+    pub seed: Option<u64>,
+
+    pub fullscreen: Option<bool>,
+    pub replay: Option<String>,
+}
+
+impl LaunchOptions {
+    /// Reads the options from the arguments, without the program name
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Self, String> {
+        let mut options = Self::default();
+        let mut args = args.into_iter();
+
+        while let Some(arg) = args.next() {
+            let mut value = || args.next().ok_or_else(|| format!("{arg} needs a value"));
+
+            match arg.as_str() {
+                "--game" => options.game = Some(value()?),
+                "--level" => options.level = Some(parse_number(&arg, value()?)?),
+                "--mode" => options.mode = Some(value()?.to_lowercase()),
+                "--seed" => options.seed = Some(parse_number(&arg, value()?)?),
+                "--replay" => options.replay = Some(value()?),
+                "--windowed" | "--fullscreen" => {
+                    let fullscreen = arg == "--fullscreen";
+                    if options.fullscreen.is_some_and(|set| set != fullscreen) {
+                        return Err(String::from("Only one of --windowed and --fullscreen"));
+                    }
+                    options.fullscreen = Some(fullscreen);
+                }
+                _ => return Err(format!("Unknown option {arg}")),
+            }
+        }
+
+        if options.game.is_none() && (options.level.is_some() || options.mode.is_some()) {
+            return Err(String::from("--level and --mode need a --game"));
+        }
+        if options.game.is_some() && options.replay.is_some() {
+            return Err(String::from("--replay already chooses the game"));
+        }
+
+        Ok(options)
+    }
+}
+
+fn parse_number<T: std::str::FromStr>(option: &str, value: String) -> Result<T, String> {
+    value
+        .parse()
+        .map_err(|_| format!("{option} should be a positive number, not {value}"))
+}
+
+impl Plugin for LaunchOptions {
+    fn build(&self, app: &mut App) {
+        if let Some(seed) = self.seed {
+            app.insert_resource(GameRng::new(seed))
+                .insert_resource(FixedSeed(seed));
+        }
+
+        app.insert_resource(self.clone())
+            .add_systems(Startup, launch);
+    }
+}
+
+/// Level and mode the game given on the command line starts with. The game
+/// takes it when it is entered, its menu is shown if it can not start.
+#[derive(Resource, Clone, PartialEq, Eq, Debug)]
+pub struct LaunchGame {
+    pub level: Option<usize>,
+    pub mode: Option<String>,
+}
+
+// A `level = N` line and a `mode = name` line, for those given
+impl ReplayedResource for LaunchGame {
+    const KEY: &'static str = "launch";
+
+    fn to_text(&self) -> String {
+        let mut text = String::new();
+        if let Some(level) = self.level {
+            let _ = writeln!(text, "level = {level}");
+        }
+        if let Some(mode) = &self.mode {
+            let _ = writeln!(text, "mode = {mode}");
+        }
+
+        text
+    }
+
+    fn from_text(text: &str) -> Option<Self> {
+        let mut launch_game = Self {
+            level: None,
+            mode: None,
+        };
+        for line in text.lines() {
+            let (key, value) = line.split_once('=')?;
+            match key.trim() {
+                "level" => launch_game.level = Some(value.trim().parse().ok()?),
+                "mode" => launch_game.mode = Some(value.trim().to_string()),
+                _ => return None,
+            }
+        }
+
+        Some(launch_game)
+    }
+}
+
+fn launch(
+    mut commands: Commands,
+    options: Res<LaunchOptions>,
+    registry: Res<GameRegistry>,
+    mut next_game: ResMut<NextState<CurrentGame>>,
+    replay_events: Option<ResMut<Events<StartReplayEvent>>>,
+    mut window: Query<&mut Window, With<PrimaryWindow>>,
+) {
+    if let (Some(fullscreen), Ok(mut window)) = (options.fullscreen, window.get_single_mut()) {
+        window.mode = if fullscreen {
+            WindowMode::BorderlessFullscreen
+        } else {
+            WindowMode::Windowed
+        };
+    }
+
+    if let (Some(path), Some(mut replay_events)) = (&options.replay, replay_events) {
+        replay_events.send(StartReplayEvent { path: path.clone() });
+    }
+
+    let Some(name) = &options.game else {
+        return;
+    };
+
+    match registry
+        .games()
+        .iter()
+        .find(|game| game.name.eq_ignore_ascii_case(name))
+    {
+        Some(game) => {
+            next_game.set(game.state());
+            commands.insert_resource(LaunchGame {
+                level: options.level,
+                mode: options.mode.clone(),
+            });
+        }
+        None => {
+            let names: Vec<&str> = registry.games().iter().map(|game| game.name).collect();
+            error!(
+                "There is no game {name}, the games are {}",
+                names.join(", ")
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests;
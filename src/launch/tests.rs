@@ -0,0 +1,77 @@
+use crate::{
+    registry::RetroGame,
+    testing::{launched_app, state},
+    tetris::{TetrisPlugin, TetrisState},
+};
+
+use super::*;
+
+fn parse(args: &str) -> Result<LaunchOptions, String> {
+    LaunchOptions::parse(args.split_whitespace().map(String::from))
+}
+
+#[test]
+fn reads_every_option() {
+    assert_eq!(parse(""), Ok(LaunchOptions::default()));
+
+    assert_eq!(
+        parse("--game breakout --level 3 --seed 42 --fullscreen"),
+        Ok(LaunchOptions {
+            game: Some(String::from("breakout")),
+            level: Some(3),
+            seed: Some(42),
+            fullscreen: Some(true),
+            ..Default::default()
+        })
+    );
+
+    assert_eq!(
+        parse("--windowed --game tetris --mode Sprint --windowed"),
+        Ok(LaunchOptions {
+            game: Some(String::from("tetris")),
+            mode: Some(String::from("sprint")),
+            fullscreen: Some(false),
+            ..Default::default()
+        })
+    );
+
+    assert_eq!(
+        parse("--replay replays/last_run.replay").map(|options| options.replay),
+        Ok(Some(String::from("replays/last_run.replay")))
+    );
+}
+
+#[test]
+fn rejects_what_makes_no_sense() {
+    for args in [
+        "--game",
+        "--level 3",
+        "--game breakout --level three",
+        "--seed -1",
+        "--windowed --fullscreen",
+        "--game tetris --replay last_run.replay",
+        "--speed 2",
+        "breakout",
+    ] {
+        assert!(parse(args).is_err(), "{args}");
+    }
+}
+
+#[test]
+fn starts_in_the_game_with_the_seed() {
+    let app = launched_app("--game TETRIS --seed 7");
+
+    assert_eq!(state::<CurrentGame>(&app), TetrisPlugin::STATE);
+    assert_eq!(state::<TetrisState>(&app), TetrisState::InMenu);
+    assert_eq!(app.world().resource::<FixedSeed>(), &FixedSeed(7));
+    assert_eq!(app.world().resource::<GameRng>().seed(), 7);
+    assert!(!app.world().contains_resource::<LaunchGame>());
+}
+
+#[test]
+fn unknown_games_stay_in_the_launcher() {
+    let app = launched_app("--game pinball");
+
+    assert_eq!(state::<CurrentGame>(&app), CurrentGame::InMainMenu);
+    assert!(!app.world().contains_resource::<LaunchGame>());
+}
@@ -1,4 +1,26 @@
 pub mod application;
+pub mod asteroids;
+pub mod ball;
 pub mod breakout;
+pub mod demo;
+pub mod game_menu;
+pub mod headless;
+pub mod input;
+pub mod invaders;
+pub mod launch;
 pub mod menu;
+pub mod menu_tree;
+pub mod pacman;
+pub mod pong;
+pub mod progress;
+pub mod registry;
+pub mod replay;
+pub mod rng;
+pub mod saves;
+pub mod scores;
+pub mod settings;
+pub mod snake;
 pub mod tetris;
+
+#[cfg(test)]
+mod testing;
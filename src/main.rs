@@ -1,13 +1,47 @@
+use std::{env, process::ExitCode};
+
 use bevy::prelude::*;
 use retro::{
-    application::Application, breakout::BreackoutPlugin, menu::MenuPlugin, tetris::TetrisPlugin,
+    application::Application,
+    asteroids::AsteroidsPlugin,
+    breakout::BreackoutPlugin,
+    demo::DemoPlugin,
+    input::InputMapPlugin,
+    invaders::InvadersPlugin,
+    launch::{LaunchOptions, USAGE},
+    menu::MenuPlugin,
+    pacman::PacmanPlugin,
+    pong::PongPlugin,
+    registry::AddGame,
+    replay::ReplayPlugin,
+    snake::SnakePlugin,
+    tetris::TetrisPlugin,
 };
 
-fn main() {
+fn main() -> ExitCode {
+    let launch_options = match LaunchOptions::parse(env::args().skip(1)) {
+        Ok(launch_options) => launch_options,
+        Err(error) => {
+            eprintln!("{error}\n{USAGE}");
+            return ExitCode::FAILURE;
+        }
+    };
+
     App::new()
         .add_plugins(Application)
+        .add_plugins(InputMapPlugin)
         .add_plugins(MenuPlugin)
-        .add_plugins(BreackoutPlugin)
-        .add_plugins(TetrisPlugin)
+        .add_game(BreackoutPlugin)
+        .add_game(TetrisPlugin)
+        .add_game(SnakePlugin)
+        .add_game(PongPlugin)
+        .add_game(InvadersPlugin)
+        .add_game(PacmanPlugin)
+        .add_game(AsteroidsPlugin)
+        .add_plugins(ReplayPlugin)
+        .add_plugins(DemoPlugin)
+        .add_plugins(launch_options)
         .run();
+
+    ExitCode::SUCCESS
 }
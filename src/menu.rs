@@ -1,42 +1,80 @@
-use bevy::{app::AppExit, color::palettes, prelude::*};
+use bevy::{app::AppExit, prelude::*};
 
 use crate::application::{CurrentGame, MENU_AREA};
+use crate::menu_tree::{
+    Menu, MenuActionEvent, MenuRoot, MenuStyle, MenuTreePlugin, MenuTreeSystems,
+};
+use crate::registry::GameRegistry;
+use crate::replay::{StartReplayEvent, LAST_RUN_REPLAY};
 
-const ITEM_BACKGROUND_NORMAL: Color = Color::BLACK;
-const ITEM_BACKGROUND_SELECTED: Color = Color::srgb(0.3, 0.3, 0.3);
+mod controls;
+use controls::ControlsPlugin;
 
-const MENU_ITEMS: [&'static str; 4] = ["Breakout", "Tetris", "Settings", "Exit"];
+mod preview;
+use preview::PreviewPlugin;
+
+mod attract;
+use attract::AttractPlugin;
 
 pub struct MenuPlugin;
 
 impl Plugin for MenuPlugin {
     fn build(&self, app: &mut App) {
-        app.init_resource::<MenuSelection>()
-            .add_systems(Startup, setup)
-            .add_systems(
-                Update,
-                (
-                    (handle_input_change_selection, handle_input_select),
-                    update_menu_apparence,
-                )
-                    .chain()
-                    .run_if(in_state(CurrentGame::InMainMenu)),
-            );
+        app.add_plugins((
+            ControlsPlugin,
+            PreviewPlugin,
+            AttractPlugin,
+            MenuTreePlugin::<LauncherAction>::default(),
+        ))
+        .init_resource::<GameRegistry>()
+        .add_event::<StartReplayEvent>()
+        .insert_state(LauncherPage::Main)
+        .configure_sets(
+            Update,
+            MenuTreeSystems::<LauncherAction>::default()
+                .run_if(in_state(CurrentGame::InMainMenu))
+                .run_if(in_state(LauncherPage::Main)),
+        )
+        .add_systems(Startup, setup)
+        .add_systems(
+            Update,
+            handle_launcher_action
+                .after(MenuTreeSystems::<LauncherAction>::default())
+                .run_if(on_event::<MenuActionEvent<LauncherAction>>()),
+        );
     }
 }
 
-#[derive(Resource, Default)]
-struct MenuSelection {
-    selection_index: usize,
-    changed: bool,
+// What the launcher shows next to its menu
+#[derive(States, Hash, PartialEq, Eq, Clone, Debug)]
+enum LauncherPage {
+    Main,
+    Controls,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum LauncherAction {
+    Play(CurrentGame),
+    WatchReplay,
+    Settings,
+    Exit,
 }
 
-#[derive(Component)]
-struct MenuItemComponent;
+// Every registered game, then the launcher's own items
+fn launcher_menu(registry: &GameRegistry) -> Menu<LauncherAction> {
+    let mut menu = Menu::new();
+    for game in registry.games() {
+        menu = menu.action(game.name, LauncherAction::Play(game.state()));
+    }
+
+    menu.action("Watch replay", LauncherAction::WatchReplay)
+        .action("Settings", LauncherAction::Settings)
+        .action("Exit", LauncherAction::Exit)
+}
 
-fn setup(mut commands: Commands, images: Res<AssetServer>) {
-    commands
-        .spawn(ImageBundle {
+fn setup(mut commands: Commands, images: Res<AssetServer>, registry: Res<GameRegistry>) {
+    commands.spawn((
+        ImageBundle {
             style: Style {
                 width: Val::Px(MENU_AREA.width()),
                 height: Val::Percent(100.0),
@@ -50,96 +88,30 @@ fn setup(mut commands: Commands, images: Res<AssetServer>) {
                 ..Default::default()
             },
             ..Default::default()
-        })
-        .with_children(|builder| {
-            for menu_item in MENU_ITEMS {
-                builder
-                    .spawn(
-                        TextBundle::from_section(
-                            menu_item,
-                            TextStyle {
-                                font_size: 30.0,
-                                color: palettes::basic::GREEN.into(),
-                                ..Default::default()
-                            },
-                        )
-                        .with_background_color(ITEM_BACKGROUND_NORMAL),
-                    )
-                    .insert(MenuItemComponent);
-            }
-        });
-
-    commands.insert_resource(MenuSelection {
-        selection_index: 0,
-        changed: true,
-    });
-}
-
-fn update_menu_apparence(
-    menu_selection: ResMut<MenuSelection>,
-    mut menu_items: Query<(&mut BackgroundColor, &Text), With<MenuItemComponent>>,
-) {
-    if !menu_selection.changed {
-        return;
-    }
-
-    for (mut background_color, menu_text) in &mut menu_items {
-        if menu_text.sections[0].value == MENU_ITEMS[menu_selection.selection_index] {
-            background_color.0 = ITEM_BACKGROUND_SELECTED;
-        } else {
-            background_color.0 = ITEM_BACKGROUND_NORMAL;
-        }
-    }
-}
-
-fn handle_input_change_selection(
-    input: Res<ButtonInput<KeyCode>>,
-    mut menu_selection: ResMut<MenuSelection>,
-) {
-    if input.just_pressed(KeyCode::ArrowUp) {
-        let current_index = menu_selection.selection_index;
-        if current_index == 0 {
-            menu_selection.selection_index = MENU_ITEMS.len() - 1;
-        } else {
-            menu_selection.selection_index = current_index - 1;
-        }
-
-        menu_selection.changed = true;
-    }
-
-    if input.just_pressed(KeyCode::ArrowDown) {
-        let current_index = menu_selection.selection_index;
-        if current_index == MENU_ITEMS.len() - 1 {
-            menu_selection.selection_index = 0;
-        } else {
-            menu_selection.selection_index = current_index + 1;
-        }
-
-        menu_selection.changed = true;
-    }
+        },
+        MenuRoot::new(launcher_menu(&registry), MenuStyle::default()),
+    ));
 }
 
-fn handle_input_select(
-    input: Res<ButtonInput<KeyCode>>,
+fn handle_launcher_action(
+    mut launcher_actions: EventReader<MenuActionEvent<LauncherAction>>,
     mut exit_event: EventWriter<AppExit>,
-    menu_selection: ResMut<MenuSelection>,
     mut next_game: ResMut<NextState<CurrentGame>>,
+    mut start_replay_event: EventWriter<StartReplayEvent>,
+    mut next_page: ResMut<NextState<LauncherPage>>,
 ) {
-    if !input.just_pressed(KeyCode::Space) {
-        return;
-    }
-
-    match MENU_ITEMS[menu_selection.selection_index] {
-        "Breakout" => {
-            next_game.set(CurrentGame::Breakout);
-        }
-        "Tetris" => {
-            next_game.set(CurrentGame::Tetris);
-        }
-        "Settings" => (),
-        "Exit" => {
-            exit_event.send(AppExit::Success);
+    for MenuActionEvent(action) in launcher_actions.read() {
+        match action {
+            LauncherAction::Play(game) => next_game.set(*game),
+            LauncherAction::WatchReplay => {
+                start_replay_event.send(StartReplayEvent {
+                    path: LAST_RUN_REPLAY.to_string(),
+                });
+            }
+            LauncherAction::Settings => next_page.set(LauncherPage::Controls),
+            LauncherAction::Exit => {
+                exit_event.send(AppExit::Success);
+            }
         }
-        _ => panic!("Selected impossible menu item"),
-    };
+    }
 }
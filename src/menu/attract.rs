@@ -0,0 +1,87 @@
+use bevy::{
+    ecs::system::SystemParam,
+    input::{keyboard::KeyboardInput, mouse::MouseButtonInput},
+    prelude::*,
+};
+
+use crate::application::CurrentGame;
+use crate::demo::{Demo, StartDemoEvent};
+use crate::input::{Action, AnalogInput};
+use crate::registry::GameRegistry;
+
+use super::preview::PreviewedGame;
+
+// Seconds without any input in the launcher before a demo starts
+const IDLE_DELAY: f32 = 20.0;
+
+/// Attract mode: once the launcher is left idle, the highlighted game plays
+/// a demo of itself in the game area
+pub(super) struct AttractPlugin;
+
+impl Plugin for AttractPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(IdleTimer(Timer::from_seconds(IDLE_DELAY, TimerMode::Once)))
+            .add_systems(OnEnter(CurrentGame::InMainMenu), reset_idle_timer)
+            .add_systems(
+                Update,
+                tick_idle_timer
+                    .run_if(in_state(CurrentGame::InMainMenu))
+                    .run_if(not(resource_exists::<Demo>)),
+            );
+    }
+}
+
+#[derive(Resource)]
+struct IdleTimer(Timer);
+
+fn reset_idle_timer(mut idle_timer: ResMut<IdleTimer>) {
+    idle_timer.0.reset();
+}
+
+// Anything the player presses in the launcher
+#[derive(SystemParam)]
+struct LauncherInput<'w, 's> {
+    actions: Res<'w, ButtonInput<Action>>,
+    analog_input: Res<'w, AnalogInput>,
+    keyboard_events: EventReader<'w, 's, KeyboardInput>,
+    mouse_button_events: EventReader<'w, 's, MouseButtonInput>,
+}
+
+impl LauncherInput<'_, '_> {
+    fn any(&mut self) -> bool {
+        let nb_events =
+            self.keyboard_events.read().count() + self.mouse_button_events.read().count();
+
+        nb_events > 0
+            || self.actions.get_pressed().next().is_some()
+            || self.analog_input.move_x != 0.0
+    }
+}
+
+fn tick_idle_timer(
+    time: Res<Time>,
+    mut launcher_input: LauncherInput,
+    previewed_game: Res<PreviewedGame>,
+    registry: Res<GameRegistry>,
+    mut idle_timer: ResMut<IdleTimer>,
+    mut start_demo_event: EventWriter<StartDemoEvent>,
+) {
+    // Hovering another item with the cursor also counts as input
+    let any_input = launcher_input.any() || previewed_game.is_changed();
+
+    // Only the highlighted game can be shown
+    let game = previewed_game
+        .0
+        .and_then(|name| registry.get(name))
+        .filter(|game| game.has_demo);
+
+    let Some(game) = game.filter(|_| !any_input) else {
+        idle_timer.0.reset();
+        return;
+    };
+
+    if idle_timer.0.tick(time.delta()).finished() {
+        idle_timer.0.reset();
+        start_demo_event.send(StartDemoEvent { game: game.state() });
+    }
+}
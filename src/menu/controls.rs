@@ -0,0 +1,216 @@
+use bevy::{color::palettes, input::gamepad::GamepadButton, prelude::*};
+
+use crate::application::{GAME_AREA, WINDOW_RESOLUTION};
+use crate::input::{Action, InputMap, BINDABLE_BUTTONS, BINDABLE_KEYS};
+use crate::menu_tree::{
+    Menu, MenuActionEvent, MenuRoot, MenuStyle, MenuTreePlugin, MenuTreeSystems,
+};
+
+use super::LauncherPage;
+
+/// Page of the launcher's "Settings" listing the keys and gamepad buttons
+/// of every action
+pub(super) struct ControlsPlugin;
+
+impl Plugin for ControlsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(MenuTreePlugin::<ControlsAction>::default())
+            .configure_sets(
+                Update,
+                MenuTreeSystems::<ControlsAction>::default()
+                    .run_if(in_state(LauncherPage::Controls))
+                    .run_if(not_rebinding),
+            )
+            .add_systems(OnEnter(LauncherPage::Controls), load_controls)
+            .add_systems(OnExit(LauncherPage::Controls), cleanup_controls)
+            .add_systems(
+                Update,
+                (handle_rebinding, handle_controls_action, update_controls)
+                    .chain()
+                    .after(MenuTreeSystems::<ControlsAction>::default())
+                    .run_if(in_state(LauncherPage::Controls)),
+            );
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum ControlsAction {
+    Rebind(Action),
+    FollowPointer(bool),
+    Reset,
+    Close,
+}
+
+#[derive(Resource)]
+struct ControlsState {
+    rebinding: Option<Action>,
+
+    // Set once something is bound, nothing is read until every key and
+    // button is released so that it does not trigger the action it was
+    // bound to
+    wait_for_release: bool,
+}
+
+#[derive(Component)]
+struct ControlsRoot;
+
+fn not_rebinding(controls: Option<Res<ControlsState>>) -> bool {
+    controls.is_some_and(|controls| controls.rebinding.is_none() && !controls.wait_for_release)
+}
+
+fn controls_menu(input_map: &InputMap, rebinding: Option<Action>) -> Menu<ControlsAction> {
+    let mut menu = Menu::new().on_back(ControlsAction::Close);
+
+    for action in Action::ALL {
+        let label = if rebinding == Some(action) {
+            format!("{}: press a key or a button", action.label())
+        } else {
+            format!("{}: {}", action.label(), input_map.bindings_text(action))
+        };
+        menu = menu.action(&label, ControlsAction::Rebind(action));
+    }
+
+    menu.toggle(
+        "Paddle follows the pointer",
+        input_map.follow_pointer,
+        ControlsAction::FollowPointer,
+    )
+    .action("Reset to defaults", ControlsAction::Reset)
+    .back("Back")
+}
+
+fn load_controls(mut commands: Commands, input_map: Res<InputMap>) {
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    justify_content: JustifyContent::Center,
+
+                    left: Val::Px(GAME_AREA.min.x + WINDOW_RESOLUTION[0] / 2.0),
+                    bottom: Val::Px(GAME_AREA.max.y + WINDOW_RESOLUTION[1] / 2.0),
+                    width: Val::Px(GAME_AREA.width()),
+                    height: Val::Px(GAME_AREA.height()),
+
+                    ..Default::default()
+                },
+                background_color: Color::BLACK.into(),
+                ..Default::default()
+            },
+            ControlsRoot,
+        ))
+        .with_children(|builder| {
+            builder.spawn(TextBundle::from_section(
+                "Controls",
+                TextStyle {
+                    font_size: 40.0,
+                    color: palettes::basic::GREEN.into(),
+                    ..Default::default()
+                },
+            ));
+
+            builder.spawn((
+                NodeBundle {
+                    style: Style {
+                        flex_direction: FlexDirection::Column,
+                        align_items: AlignItems::Center,
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                },
+                MenuRoot::new(
+                    controls_menu(&input_map, None),
+                    MenuStyle {
+                        font_size: 24.0,
+                        ..Default::default()
+                    },
+                ),
+            ));
+        });
+
+    commands.insert_resource(ControlsState {
+        rebinding: None,
+        wait_for_release: false,
+    });
+}
+
+fn cleanup_controls(mut commands: Commands, root: Query<Entity, With<ControlsRoot>>) {
+    for root in &root {
+        commands.entity(root).despawn_recursive();
+    }
+
+    commands.remove_resource::<ControlsState>();
+}
+
+// Runs before the actions of this frame are handled, so that the key which
+// started a rebinding is not bound right away
+fn handle_rebinding(
+    keys: Res<ButtonInput<KeyCode>>,
+    gamepad_buttons: Res<ButtonInput<GamepadButton>>,
+    mut controls: ResMut<ControlsState>,
+    mut input_map: ResMut<InputMap>,
+) {
+    if controls.wait_for_release {
+        if keys.get_pressed().next().is_none() && gamepad_buttons.get_pressed().next().is_none() {
+            controls.wait_for_release = false;
+        }
+        return;
+    }
+
+    let Some(action) = controls.rebinding else {
+        return;
+    };
+
+    // Escape always cancels, so a broken map can not lock the player in
+    if keys.just_pressed(KeyCode::Escape) {
+        controls.rebinding = None;
+        controls.wait_for_release = true;
+    } else if let Some(key) = keys
+        .get_just_pressed()
+        .find(|key| BINDABLE_KEYS.contains(key))
+    {
+        input_map.bind(action, *key);
+        controls.rebinding = None;
+        controls.wait_for_release = true;
+    } else if let Some(button) = gamepad_buttons
+        .get_just_pressed()
+        .find(|button| BINDABLE_BUTTONS.contains(&button.button_type))
+    {
+        input_map.bind_button(action, button.button_type);
+        controls.rebinding = None;
+        controls.wait_for_release = true;
+    }
+}
+
+fn handle_controls_action(
+    mut controls_actions: EventReader<MenuActionEvent<ControlsAction>>,
+    mut controls: ResMut<ControlsState>,
+    mut input_map: ResMut<InputMap>,
+    mut next_page: ResMut<NextState<LauncherPage>>,
+) {
+    for MenuActionEvent(action) in controls_actions.read() {
+        match *action {
+            ControlsAction::Rebind(action) => controls.rebinding = Some(action),
+            ControlsAction::FollowPointer(follow_pointer) => {
+                input_map.follow_pointer = follow_pointer
+            }
+            ControlsAction::Reset => input_map.reset(),
+            ControlsAction::Close => next_page.set(LauncherPage::Main),
+        }
+    }
+}
+
+fn update_controls(
+    controls: Res<ControlsState>,
+    input_map: Res<InputMap>,
+    mut menu: Query<&mut MenuRoot<ControlsAction>>,
+) {
+    if !controls.is_changed() && !input_map.is_changed() {
+        return;
+    }
+
+    for mut menu in &mut menu {
+        menu.refresh_menu(controls_menu(&input_map, controls.rebinding));
+    }
+}
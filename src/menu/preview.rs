@@ -0,0 +1,193 @@
+use bevy::{color::palettes, ecs::system::SystemParam, prelude::*};
+
+use crate::application::{CurrentGame, GAME_AREA, WINDOW_RESOLUTION};
+use crate::input::InputMap;
+use crate::menu_tree::{MenuRoot, MenuTreeSystems};
+use crate::registry::GameRegistry;
+use crate::scores::BestScores;
+
+use super::{LauncherAction, LauncherPage};
+
+const THUMBNAIL_WIDTH: f32 = 200.0;
+
+/// Panel in the game area showing the game highlighted in the launcher: its
+/// thumbnail, description, controls and best score
+pub(super) struct PreviewPlugin;
+
+impl Plugin for PreviewPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PreviewedGame>()
+            .init_resource::<BestScores>()
+            .add_systems(Startup, setup_preview)
+            .add_systems(OnExit(CurrentGame::InMainMenu), hide_preview)
+            .add_systems(
+                Update,
+                (
+                    select_previewed_game
+                        .after(MenuTreeSystems::<LauncherAction>::default())
+                        .run_if(in_state(CurrentGame::InMainMenu)),
+                    update_preview.run_if(
+                        resource_changed::<PreviewedGame>
+                            .or_else(resource_changed::<InputMap>)
+                            .or_else(resource_changed::<BestScores>),
+                    ),
+                )
+                    .chain(),
+            );
+    }
+}
+
+// Name of the game shown, none while another item is highlighted or the
+// game area is used by something else
+#[derive(Resource, Default)]
+pub(super) struct PreviewedGame(pub(super) Option<&'static str>);
+
+#[derive(Component)]
+struct PreviewPanel;
+
+#[derive(Component)]
+struct PreviewThumbnail;
+
+#[derive(Component)]
+struct PreviewText;
+
+// Sections of the preview text
+const NAME_SECTION: usize = 0;
+const DESCRIPTION_SECTION: usize = 1;
+const CONTROLS_SECTION: usize = 2;
+const BEST_SCORE_SECTION: usize = 3;
+
+#[derive(SystemParam)]
+struct PreviewNodes<'w, 's> {
+    panel: Query<'w, 's, &'static mut Visibility, With<PreviewPanel>>,
+    thumbnail: Query<'w, 's, &'static mut UiImage, With<PreviewThumbnail>>,
+    text: Query<'w, 's, &'static mut Text, With<PreviewText>>,
+}
+
+fn setup_preview(mut commands: Commands) {
+    let text_style = |font_size: f32| TextStyle {
+        font_size,
+        color: palettes::basic::GREEN.into(),
+        ..Default::default()
+    };
+
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    justify_content: JustifyContent::Center,
+                    row_gap: Val::Px(20.0),
+
+                    left: Val::Px(GAME_AREA.min.x + WINDOW_RESOLUTION[0] / 2.0),
+                    bottom: Val::Px(GAME_AREA.max.y + WINDOW_RESOLUTION[1] / 2.0),
+                    width: Val::Px(GAME_AREA.width()),
+                    height: Val::Px(GAME_AREA.height()),
+
+                    ..Default::default()
+                },
+                background_color: Color::BLACK.into(),
+                visibility: Visibility::Hidden,
+                ..Default::default()
+            },
+            PreviewPanel,
+        ))
+        .with_children(|builder| {
+            builder.spawn((
+                ImageBundle {
+                    style: Style {
+                        width: Val::Px(THUMBNAIL_WIDTH),
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                },
+                PreviewThumbnail,
+            ));
+
+            builder.spawn((
+                TextBundle::from_sections([
+                    TextSection::new("", text_style(40.0)),
+                    TextSection::new("", text_style(20.0)),
+                    TextSection::new("", text_style(20.0)),
+                    TextSection::new("", text_style(24.0)),
+                ])
+                .with_text_justify(JustifyText::Center),
+                PreviewText,
+            ));
+        });
+}
+
+fn hide_preview(mut previewed_game: ResMut<PreviewedGame>) {
+    previewed_game.0 = None;
+}
+
+fn select_previewed_game(
+    menu: Query<&MenuRoot<LauncherAction>>,
+    page: Res<State<LauncherPage>>,
+    mut previewed_game: ResMut<PreviewedGame>,
+) {
+    let game = match page.get() {
+        LauncherPage::Main => menu
+            .get_single()
+            .ok()
+            .and_then(|menu| menu.selected())
+            .and_then(|item| item.action())
+            .and_then(|action| match action {
+                LauncherAction::Play(CurrentGame::Playing(name)) => Some(*name),
+                _ => None,
+            }),
+        LauncherPage::Controls => None,
+    };
+
+    if previewed_game.0 != game {
+        previewed_game.0 = game;
+    }
+}
+
+fn update_preview(
+    previewed_game: Res<PreviewedGame>,
+    registry: Res<GameRegistry>,
+    input_map: Res<InputMap>,
+    best_scores: Res<BestScores>,
+    asset_server: Res<AssetServer>,
+    mut nodes: PreviewNodes,
+) {
+    let game = previewed_game.0.and_then(|name| registry.get(name));
+
+    for mut visibility in &mut nodes.panel {
+        *visibility = match game {
+            Some(_) => Visibility::Inherited,
+            None => Visibility::Hidden,
+        };
+    }
+
+    let Some(game) = game else {
+        return;
+    };
+
+    for mut thumbnail in &mut nodes.thumbnail {
+        thumbnail.texture = asset_server.load(game.icon);
+    }
+
+    for mut text in &mut nodes.text {
+        text.sections[NAME_SECTION].value = game.name.to_string();
+        text.sections[DESCRIPTION_SECTION].value = format!("\n{}", game.description);
+        let controls: String = game
+            .controls
+            .iter()
+            .map(|action| format!("\n{}: {}", action.label(), input_map.bindings_text(*action)))
+            .collect();
+        text.sections[CONTROLS_SECTION].value = format!("\n{controls}");
+        text.sections[BEST_SCORE_SECTION].value = match best_scores.get(game.name) {
+            Some(score) => match best_scores.holder(game.name) {
+                Some(holder) => format!("\n\nBest score: {score} by {holder}"),
+                None => format!("\n\nBest score: {score}"),
+            },
+            None => String::from("\n\nBest score: none yet"),
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests;
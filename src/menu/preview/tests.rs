@@ -0,0 +1,87 @@
+use bevy::prelude::*;
+
+use crate::{
+    input::{Action, InputMap},
+    scores::BestScores,
+    testing::{launcher_app, run_frames, tap},
+};
+
+use super::*;
+
+// Text of the preview, empty while it is hidden
+fn preview_text(app: &mut App) -> String {
+    let world = app.world_mut();
+    let visibility = *world
+        .query_filtered::<&Visibility, With<PreviewPanel>>()
+        .single(world);
+    if visibility == Visibility::Hidden {
+        return String::new();
+    }
+
+    world
+        .query_filtered::<&Text, With<PreviewText>>()
+        .single(world)
+        .sections
+        .iter()
+        .map(|section| section.value.as_str())
+        .collect()
+}
+
+#[test]
+fn highlighted_game_is_previewed() {
+    let mut app = launcher_app();
+    app.update();
+    let text = preview_text(&mut app);
+    assert!(text.starts_with("Breakout\nBounce the ball"), "{text}");
+    assert!(text.contains("Launch ball: Space / Pad South"), "{text}");
+    assert!(text.ends_with("Best score: none yet"), "{text}");
+
+    tap(&mut app, KeyCode::ArrowDown);
+    let text = preview_text(&mut app);
+    assert!(text.starts_with("Tetris\nStack"), "{text}");
+    assert!(text.contains("Hold piece: KeyC"), "{text}");
+
+    // "Watch replay" is not a game
+    tap(&mut app, KeyCode::ArrowDown);
+    assert_eq!(preview_text(&mut app), "");
+}
+
+#[test]
+fn preview_follows_bindings_and_scores() {
+    let mut app = launcher_app();
+    app.world_mut()
+        .resource_mut::<InputMap>()
+        .bind(Action::Launch, KeyCode::KeyL);
+    app.world_mut()
+        .resource_mut::<BestScores>()
+        .submit("Breakout", 4200);
+    app.update();
+
+    let text = preview_text(&mut app);
+    assert!(text.contains("Launch ball: KeyL / Pad South"), "{text}");
+    assert!(text.ends_with("Best score: 4200"), "{text}");
+}
+
+#[test]
+fn preview_is_hidden_while_the_game_area_is_used() {
+    let mut app = launcher_app();
+
+    // In the game
+    tap(&mut app, KeyCode::Space);
+    run_frames(&mut app, 1);
+    assert_eq!(preview_text(&mut app), "");
+
+    // And back in the launcher
+    tap(&mut app, KeyCode::ArrowUp);
+    tap(&mut app, KeyCode::Space);
+    run_frames(&mut app, 1);
+    assert!(preview_text(&mut app).starts_with("Breakout"));
+
+    // In the settings
+    for _ in 0..3 {
+        tap(&mut app, KeyCode::ArrowDown);
+    }
+    tap(&mut app, KeyCode::Space);
+    run_frames(&mut app, 1);
+    assert_eq!(preview_text(&mut app), "");
+}
@@ -0,0 +1,612 @@
+//! Menus declared as trees of typed actions. A game builds a `Menu` of its
+//! own action type, spawns it in a `MenuRoot` node and reads the chosen
+//! actions as `MenuActionEvent`s, laying out, navigating and clicking the
+//! items is done here.
+
+use std::{marker::PhantomData, ops::RangeInclusive};
+
+use bevy::{color::palettes, prelude::*};
+
+use crate::input::{Action, ActionSource};
+
+/// What a menu sends back to its game, usually an enum of the game
+pub trait MenuAction: Clone + Send + Sync + 'static {}
+
+impl<A: Clone + Send + Sync + 'static> MenuAction for A {}
+
+/// Lays out and drives every `MenuRoot<A>`, sends their `MenuActionEvent<A>`.
+/// Games add one per action type and restrict when their menus take input
+/// by configuring `MenuTreeSystems<A>`.
+pub struct MenuTreePlugin<A: MenuAction>(PhantomData<fn() -> A>);
+
+impl<A: MenuAction> Default for MenuTreePlugin<A> {
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<A: MenuAction> Plugin for MenuTreePlugin<A> {
+    fn build(&self, app: &mut App) {
+        app.add_event::<MenuActionEvent<A>>()
+            .add_systems(
+                Update,
+                (
+                    handle_pointer_input::<A>.run_if(resource_equals(ActionSource::Player)),
+                    handle_input::<A>,
+                )
+                    .chain()
+                    .in_set(MenuTreeSystems::<A>::default()),
+            )
+            .add_systems(PostUpdate, update_menu_items::<A>);
+    }
+}
+
+/// Input handling of the menus of action type `A`, the actions they send
+/// can be read by systems running after it
+#[derive(SystemSet)]
+pub struct MenuTreeSystems<A: MenuAction>(PhantomData<fn() -> A>);
+
+impl<A: MenuAction> Default for MenuTreeSystems<A> {
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<A: MenuAction> Clone for MenuTreeSystems<A> {
+    fn clone(&self) -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<A: MenuAction> PartialEq for MenuTreeSystems<A> {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+impl<A: MenuAction> Eq for MenuTreeSystems<A> {}
+
+impl<A: MenuAction> std::hash::Hash for MenuTreeSystems<A> {
+    fn hash<H: std::hash::Hasher>(&self, _state: &mut H) {}
+}
+
+impl<A: MenuAction> std::fmt::Debug for MenuTreeSystems<A> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "MenuTreeSystems<{}>", std::any::type_name::<A>())
+    }
+}
+
+#[derive(Event)]
+pub struct MenuActionEvent<A: MenuAction>(pub A);
+
+pub struct Menu<A> {
+    items: Vec<MenuItem<A>>,
+
+    // Sent when going back from the top of the tree
+    on_back: Option<A>,
+}
+
+pub struct MenuItem<A> {
+    label: String,
+    kind: MenuItemKind<A>,
+    enabled: bool,
+}
+
+enum MenuItemKind<A> {
+    Action(A),
+    Submenu(Menu<A>),
+    Back,
+    Toggle {
+        value: bool,
+        on_change: fn(bool) -> A,
+    },
+    Slider {
+        value: i32,
+        range: RangeInclusive<i32>,
+        step: i32,
+        on_change: fn(i32) -> A,
+    },
+    Choice {
+        options: Vec<String>,
+        index: usize,
+        on_change: fn(usize) -> A,
+    },
+}
+
+impl<A: MenuAction> Menu<A> {
+    pub fn new() -> Self {
+        Self {
+            items: Vec::new(),
+            on_back: None,
+        }
+    }
+
+    pub fn action(self, label: &str, action: A) -> Self {
+        self.item(label, MenuItemKind::Action(action))
+    }
+
+    pub fn submenu(self, label: &str, submenu: Menu<A>) -> Self {
+        self.item(label, MenuItemKind::Submenu(submenu))
+    }
+
+    /// Goes back to the parent menu, or sends the `on_back` action at the top
+    pub fn back(self, label: &str) -> Self {
+        self.item(label, MenuItemKind::Back)
+    }
+
+    /// Shown as "label: On" or "label: Off", confirming it or pressing left
+    /// or right sends `on_change` with the new value
+    pub fn toggle(self, label: &str, value: bool, on_change: fn(bool) -> A) -> Self {
+        self.item(label, MenuItemKind::Toggle { value, on_change })
+    }
+
+    /// Shown as "label: value", left and right move the value by `step` and
+    /// send `on_change` with it
+    pub fn slider(
+        self,
+        label: &str,
+        value: i32,
+        range: RangeInclusive<i32>,
+        step: i32,
+        on_change: fn(i32) -> A,
+    ) -> Self {
+        self.item(
+            label,
+            MenuItemKind::Slider {
+                value: value.clamp(*range.start(), *range.end()),
+                range,
+                step,
+                on_change,
+            },
+        )
+    }
+
+    /// Shown as "label: option", left and right go through `options`,
+    /// wrapping around, and confirming goes to the next one. Each sends
+    /// `on_change` with the index of the new option. There must be at least
+    /// one option.
+    pub fn choice(
+        self,
+        label: &str,
+        options: Vec<String>,
+        index: usize,
+        on_change: fn(usize) -> A,
+    ) -> Self {
+        self.item(
+            label,
+            MenuItemKind::Choice {
+                index: index.min(options.len() - 1),
+                options,
+                on_change,
+            },
+        )
+    }
+
+    /// Greys out the last added item, it can still be selected but does
+    /// nothing
+    pub fn enabled(mut self, enabled: bool) -> Self {
+        if let Some(item) = self.items.last_mut() {
+            item.enabled = enabled;
+        }
+        self
+    }
+
+    pub fn on_back(mut self, action: A) -> Self {
+        self.on_back = Some(action);
+        self
+    }
+
+    pub fn items(&self) -> &[MenuItem<A>] {
+        &self.items
+    }
+
+    fn item(mut self, label: &str, kind: MenuItemKind<A>) -> Self {
+        self.items.push(MenuItem {
+            label: label.to_string(),
+            kind,
+            enabled: true,
+        });
+        self
+    }
+}
+
+impl<A: MenuAction> Default for Menu<A> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<A> MenuItem<A> {
+    pub fn label(&self) -> &str {
+        &self.label
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Action sent when the item is confirmed, if it is an action item
+    pub fn action(&self) -> Option<&A> {
+        match &self.kind {
+            MenuItemKind::Action(action) => Some(action),
+            _ => None,
+        }
+    }
+
+    fn text(&self) -> String {
+        match &self.kind {
+            MenuItemKind::Toggle { value, .. } => {
+                format!("{}: {}", self.label, if *value { "On" } else { "Off" })
+            }
+            MenuItemKind::Slider { value, .. } => format!("{}: {value}", self.label),
+            MenuItemKind::Choice { options, index, .. } => {
+                format!("{}: {}", self.label, options[*index])
+            }
+            _ => self.label.clone(),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct MenuStyle {
+    pub font_size: f32,
+    pub color: Color,
+    pub disabled_color: Color,
+    pub background: Color,
+    pub selected_background: Color,
+}
+
+impl Default for MenuStyle {
+    fn default() -> Self {
+        Self {
+            font_size: 30.0,
+            color: palettes::basic::GREEN.into(),
+            disabled_color: palettes::basic::GRAY.into(),
+            background: Color::BLACK,
+            selected_background: Color::srgb(0.3, 0.3, 0.3),
+        }
+    }
+}
+
+/// UI node showing a menu, the items of the opened submenu are spawned as
+/// its first children so the game can add its own nodes after them
+#[derive(Component)]
+pub struct MenuRoot<A: MenuAction> {
+    menu: Menu<A>,
+
+    // Item indices of the opened submenus, from the top of the tree
+    path: Vec<usize>,
+    selection: usize,
+
+    style: MenuStyle,
+
+    // Whether the spawned items match `path`, they are respawned otherwise
+    built: bool,
+}
+
+impl<A: MenuAction> MenuRoot<A> {
+    pub fn new(menu: Menu<A>, style: MenuStyle) -> Self {
+        Self {
+            menu,
+            path: Vec::new(),
+            selection: 0,
+            style,
+            built: false,
+        }
+    }
+
+    /// Shows another menu from its top and first item
+    pub fn set_menu(&mut self, menu: Menu<A>) {
+        self.menu = menu;
+        self.path.clear();
+        self.selection = 0;
+        self.built = false;
+    }
+
+    /// Replaces the menu by an updated version of it, staying in the same
+    /// submenu on the same item when they still exist
+    pub fn refresh_menu(&mut self, menu: Menu<A>) {
+        self.menu = menu;
+
+        let mut depth = 0;
+        let mut current = &self.menu;
+        for index in &self.path {
+            match current.items.get(*index).map(|item| &item.kind) {
+                Some(MenuItemKind::Submenu(submenu)) => current = submenu,
+                _ => break,
+            }
+            depth += 1;
+        }
+
+        if depth < self.path.len() {
+            self.path.truncate(depth);
+            self.selection = 0;
+        }
+        self.selection = self
+            .selection
+            .min(self.current().items.len().saturating_sub(1));
+        self.built = false;
+    }
+
+    /// Menu of the opened submenu
+    pub fn current(&self) -> &Menu<A> {
+        let mut current = &self.menu;
+        for index in &self.path {
+            current = match &current.items[*index].kind {
+                MenuItemKind::Submenu(submenu) => submenu,
+                _ => unreachable!("menu paths only go through submenus"),
+            };
+        }
+        current
+    }
+
+    pub fn depth(&self) -> usize {
+        self.path.len()
+    }
+
+    pub fn selection(&self) -> usize {
+        self.selection
+    }
+
+    pub fn selected(&self) -> Option<&MenuItem<A>> {
+        self.current().items.get(self.selection)
+    }
+
+    pub fn select(&mut self, index: usize) {
+        if index < self.current().items.len() {
+            self.selection = index;
+        }
+    }
+
+    /// Moves the selection by `offset` items, wrapping around
+    pub fn move_selection(&mut self, offset: isize) {
+        let nb_items = self.current().items.len() as isize;
+        if nb_items > 0 {
+            self.selection = (self.selection as isize + offset).rem_euclid(nb_items) as usize;
+        }
+    }
+
+    /// Confirms the selected item, returns the action to send if any
+    pub fn activate(&mut self) -> Option<A> {
+        let index = self.selection;
+        let item = self.current_mut().items.get_mut(index)?;
+        if !item.enabled {
+            return None;
+        }
+
+        match &mut item.kind {
+            MenuItemKind::Action(action) => Some(action.clone()),
+            MenuItemKind::Toggle { value, on_change } => {
+                *value = !*value;
+                Some(on_change(*value))
+            }
+            MenuItemKind::Slider { .. } => None,
+            MenuItemKind::Choice {
+                options,
+                index,
+                on_change,
+            } => {
+                *index = (*index + 1) % options.len();
+                Some(on_change(*index))
+            }
+            MenuItemKind::Submenu(_) => {
+                self.path.push(index);
+                self.selection = 0;
+                self.built = false;
+                None
+            }
+            MenuItemKind::Back => self.back(),
+        }
+    }
+
+    /// Goes back to the parent menu, returns the `on_back` action at the top
+    pub fn back(&mut self) -> Option<A> {
+        if self.path.pop().is_some() {
+            self.selection = 0;
+            self.built = false;
+            return None;
+        }
+
+        self.menu.on_back.clone()
+    }
+
+    /// Moves the value of the selected toggle or slider left or right,
+    /// returns the action to send if it changed
+    pub fn adjust(&mut self, direction: i32) -> Option<A> {
+        let index = self.selection;
+        let item = self.current_mut().items.get_mut(index)?;
+        if !item.enabled {
+            return None;
+        }
+
+        match &mut item.kind {
+            MenuItemKind::Toggle { value, on_change } if *value != (direction > 0) => {
+                *value = direction > 0;
+                Some(on_change(*value))
+            }
+            MenuItemKind::Slider {
+                value,
+                range,
+                step,
+                on_change,
+            } => {
+                let new_value = (*value + direction * *step).clamp(*range.start(), *range.end());
+                if new_value == *value {
+                    return None;
+                }
+                *value = new_value;
+                Some(on_change(new_value))
+            }
+            MenuItemKind::Choice {
+                options,
+                index,
+                on_change,
+            } => {
+                *index = (*index as i32 + direction).rem_euclid(options.len() as i32) as usize;
+                Some(on_change(*index))
+            }
+            _ => None,
+        }
+    }
+
+    fn current_mut(&mut self) -> &mut Menu<A> {
+        let mut current = &mut self.menu;
+        for index in &self.path {
+            current = match &mut current.items[*index].kind {
+                MenuItemKind::Submenu(submenu) => submenu,
+                _ => unreachable!("menu paths only go through submenus"),
+            };
+        }
+        current
+    }
+
+    fn item_colors(&self, index: usize) -> (Color, Color) {
+        let enabled = self.current().items[index].enabled;
+        (
+            if enabled {
+                self.style.color
+            } else {
+                self.style.disabled_color
+            },
+            if index == self.selection {
+                self.style.selected_background
+            } else {
+                self.style.background
+            },
+        )
+    }
+}
+
+#[derive(Component)]
+struct MenuItemNode {
+    root: Entity,
+    index: usize,
+}
+
+// Hovering an item selects it, clicking or touching it also confirms it
+fn handle_pointer_input<A: MenuAction>(
+    items: Query<(Ref<Interaction>, &MenuItemNode)>,
+    mut roots: Query<&mut MenuRoot<A>>,
+    mut action_events: EventWriter<MenuActionEvent<A>>,
+) {
+    for (interaction, item) in &items {
+        if !interaction.is_changed() || *interaction == Interaction::None {
+            continue;
+        }
+
+        // Items of other menus, or of a submenu that was just left
+        let Ok(mut root) = roots.get_mut(item.root) else {
+            continue;
+        };
+        if !root.built {
+            continue;
+        }
+
+        if root.selection != item.index {
+            root.select(item.index);
+        }
+
+        if *interaction == Interaction::Pressed {
+            if let Some(action) = root.activate() {
+                action_events.send(MenuActionEvent(action));
+            }
+        }
+    }
+}
+
+fn handle_input<A: MenuAction>(
+    input: Res<ButtonInput<Action>>,
+    mut roots: Query<&mut MenuRoot<A>>,
+    mut action_events: EventWriter<MenuActionEvent<A>>,
+) {
+    for mut root in &mut roots {
+        if input.just_pressed(Action::MenuUp) {
+            root.move_selection(-1);
+        }
+
+        if input.just_pressed(Action::MenuDown) {
+            root.move_selection(1);
+        }
+
+        let action = if input.just_pressed(Action::Back) {
+            root.back()
+        } else if input.just_pressed(Action::Confirm) {
+            root.activate()
+        } else if input.just_pressed(Action::MoveLeft) {
+            root.adjust(-1)
+        } else if input.just_pressed(Action::MoveRight) {
+            root.adjust(1)
+        } else {
+            None
+        };
+
+        if let Some(action) = action {
+            action_events.send(MenuActionEvent(action));
+        }
+    }
+}
+
+fn update_menu_items<A: MenuAction>(
+    mut commands: Commands,
+    mut roots: Query<(Entity, &mut MenuRoot<A>), Changed<MenuRoot<A>>>,
+    mut items: Query<(Entity, &MenuItemNode, &mut Text, &mut BackgroundColor)>,
+) {
+    for (root_entity, mut root) in &mut roots {
+        if root.built {
+            for (_, item, mut text, mut background_color) in &mut items {
+                if item.root != root_entity {
+                    continue;
+                }
+
+                let (color, background) = root.item_colors(item.index);
+                let item_text = root.current().items[item.index].text();
+                if text.sections[0].value != item_text {
+                    text.sections[0].value = item_text;
+                }
+                text.sections[0].style.color = color;
+                background_color.0 = background;
+            }
+            continue;
+        }
+
+        for (entity, item, _, _) in &items {
+            if item.root == root_entity {
+                commands.entity(entity).despawn_recursive();
+            }
+        }
+
+        let new_items: Vec<Entity> = root
+            .current()
+            .items
+            .iter()
+            .enumerate()
+            .map(|(index, item)| {
+                let (color, background) = root.item_colors(index);
+                commands
+                    .spawn((
+                        TextBundle::from_section(
+                            item.text(),
+                            TextStyle {
+                                font_size: root.style.font_size,
+                                color,
+                                ..Default::default()
+                            },
+                        )
+                        .with_background_color(background),
+                        MenuItemNode {
+                            root: root_entity,
+                            index,
+                        },
+                        Interaction::default(),
+                    ))
+                    .id()
+            })
+            .collect();
+        commands.entity(root_entity).insert_children(0, &new_items);
+
+        root.bypass_change_detection().built = true;
+    }
+}
+
+#[cfg(test)]
+mod tests;
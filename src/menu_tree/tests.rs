@@ -0,0 +1,174 @@
+use bevy::prelude::*;
+
+use crate::testing::{click, headless_app, menu_item, tap, EventCounter};
+
+use super::*;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum TestAction {
+    Play,
+    Quit,
+    Level(usize),
+    Sound(bool),
+    Volume(i32),
+    Close,
+}
+
+fn test_menu() -> Menu<TestAction> {
+    Menu::new()
+        .action("Play", TestAction::Play)
+        .submenu(
+            "Levels",
+            Menu::new()
+                .action("Level 1", TestAction::Level(1))
+                .action("Level 2", TestAction::Level(2))
+                .back("Back"),
+        )
+        .submenu(
+            "Options",
+            Menu::new().toggle("Sound", true, TestAction::Sound).slider(
+                "Volume",
+                5,
+                0..=10,
+                5,
+                TestAction::Volume,
+            ),
+        )
+        .action("Quit", TestAction::Quit)
+        .enabled(false)
+        .on_back(TestAction::Close)
+}
+
+fn labels(root: &MenuRoot<TestAction>) -> Vec<String> {
+    root.current().items().iter().map(MenuItem::text).collect()
+}
+
+#[test]
+fn selection_wraps_around() {
+    let mut root = MenuRoot::new(test_menu(), MenuStyle::default());
+
+    root.move_selection(-1);
+    assert_eq!(root.selected().unwrap().label(), "Quit");
+
+    root.move_selection(1);
+    assert_eq!(root.activate(), Some(TestAction::Play));
+}
+
+#[test]
+fn submenus_and_back_stack() {
+    let mut root = MenuRoot::new(test_menu(), MenuStyle::default());
+
+    root.move_selection(1);
+    assert_eq!(root.activate(), None);
+    assert_eq!(root.depth(), 1);
+    assert_eq!(labels(&root), ["Level 1", "Level 2", "Back"]);
+
+    root.move_selection(1);
+    assert_eq!(root.activate(), Some(TestAction::Level(2)));
+
+    // The "Back" item and going back both return to the parent menu, from
+    // its first item
+    root.move_selection(1);
+    assert_eq!(root.activate(), None);
+    assert_eq!(root.depth(), 0);
+    assert_eq!(root.selection(), 0);
+
+    root.move_selection(1);
+    root.activate();
+    assert_eq!(root.back(), None);
+    assert_eq!(root.depth(), 0);
+
+    // Going back from the top sends the menu's own action
+    assert_eq!(root.back(), Some(TestAction::Close));
+}
+
+#[test]
+fn disabled_items_do_nothing() {
+    let mut root = MenuRoot::new(test_menu(), MenuStyle::default());
+
+    root.select(3);
+    assert!(!root.selected().unwrap().is_enabled());
+    assert_eq!(root.activate(), None);
+    assert_eq!(root.adjust(1), None);
+}
+
+#[test]
+fn toggles_and_sliders() {
+    let mut root = MenuRoot::new(test_menu(), MenuStyle::default());
+    root.select(2);
+    root.activate();
+    assert_eq!(labels(&root), ["Sound: On", "Volume: 5"]);
+
+    assert_eq!(root.activate(), Some(TestAction::Sound(false)));
+    assert_eq!(root.adjust(-1), None);
+    assert_eq!(root.adjust(1), Some(TestAction::Sound(true)));
+
+    root.move_selection(1);
+    assert_eq!(root.activate(), None);
+    assert_eq!(root.adjust(1), Some(TestAction::Volume(10)));
+    assert_eq!(root.adjust(1), None);
+    assert_eq!(root.adjust(-1), Some(TestAction::Volume(5)));
+    assert_eq!(labels(&root), ["Sound: On", "Volume: 5"]);
+}
+
+#[test]
+fn choices_wrap_around() {
+    let options = vec![String::from("Easy"), String::from("Hard")];
+    let mut root = MenuRoot::new(
+        Menu::new().choice("Mode", options, 0, TestAction::Level),
+        MenuStyle::default(),
+    );
+    assert_eq!(labels(&root), ["Mode: Easy"]);
+
+    assert_eq!(root.activate(), Some(TestAction::Level(1)));
+    assert_eq!(root.activate(), Some(TestAction::Level(0)));
+    assert_eq!(root.adjust(-1), Some(TestAction::Level(1)));
+    assert_eq!(labels(&root), ["Mode: Hard"]);
+}
+
+#[test]
+fn refreshing_keeps_the_selection() {
+    let mut root = MenuRoot::new(test_menu(), MenuStyle::default());
+    root.select(2);
+    root.activate();
+    root.select(1);
+
+    root.refresh_menu(test_menu());
+    assert_eq!(root.depth(), 1);
+    assert_eq!(root.selection(), 1);
+
+    // The opened submenu is gone
+    root.refresh_menu(Menu::new().action("Play", TestAction::Play));
+    assert_eq!(root.depth(), 0);
+    assert_eq!(root.selection(), 0);
+}
+
+#[test]
+fn items_are_spawned_navigated_and_clicked() {
+    let mut app = headless_app();
+    app.add_plugins(MenuTreePlugin::<TestAction>::default());
+    app.world_mut().spawn((
+        NodeBundle::default(),
+        MenuRoot::new(test_menu(), MenuStyle::default()),
+    ));
+    app.update();
+
+    let mut actions = EventCounter::<MenuActionEvent<TestAction>>::new(&app);
+
+    tap(&mut app, KeyCode::ArrowDown);
+    tap(&mut app, KeyCode::Space);
+    let level_2 = menu_item(&mut app, "Level 2");
+    click(&mut app, level_2);
+    assert_eq!(actions.update(&app), 1);
+
+    // Escape leaves the submenu, the next one goes back from the top
+    tap(&mut app, KeyCode::Escape);
+    menu_item(&mut app, "Play");
+    tap(&mut app, KeyCode::Escape);
+    assert_eq!(actions.update(&app), 2);
+
+    // Disabled items can not be clicked
+    let quit = menu_item(&mut app, "Quit");
+    click(&mut app, quit);
+    assert_eq!(actions.update(&app), 2);
+}
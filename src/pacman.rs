@@ -0,0 +1,81 @@
+use bevy::prelude::*;
+
+mod menu;
+use menu::MenuPlugin;
+
+mod game;
+use game::{GamePlugin, LoadGameEvent};
+
+mod maze;
+
+use crate::game_menu::enter_menu;
+use crate::input::Action;
+use crate::launch::LaunchGame;
+use crate::registry::RetroGame;
+
+pub struct PacmanPlugin;
+
+impl RetroGame for PacmanPlugin {
+    const NAME: &'static str = "Pac-Man";
+    const ICON: &'static str = "pacman/icon.png";
+    const DESCRIPTION: &'static str =
+        "Eat every pellet of the maze without getting caught, power pellets turn the ghosts edible.";
+    const CONTROLS: &'static [Action] = &[
+        Action::MoveUp,
+        Action::MoveDown,
+        Action::MoveLeft,
+        Action::MoveRight,
+        Action::Pause,
+    ];
+}
+
+impl Plugin for PacmanPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(MenuPlugin)
+            .add_plugins(GamePlugin)
+            .add_systems(
+                OnEnter(PacmanPlugin::STATE),
+                launch
+                    .after(enter_menu::<PacmanPlugin>)
+                    .run_if(resource_exists::<LaunchGame>),
+            );
+    }
+}
+
+#[derive(States, PartialEq, Eq, Hash, Clone, Debug)]
+pub enum PacmanState {
+    Exited,
+    InMenu,
+    InGame,
+}
+
+// Straight into the level given on the command line, if there is one
+fn launch(
+    mut commands: Commands,
+    launch_game: Res<LaunchGame>,
+    mut load_game_event: EventWriter<LoadGameEvent>,
+    mut next_state: ResMut<NextState<PacmanState>>,
+) {
+    commands.remove_resource::<LaunchGame>();
+
+    if let Some(mode) = &launch_game.mode {
+        warn!("Pac-Man has no {mode} mode");
+    }
+
+    let Some(level) = launch_game.level else {
+        return;
+    };
+    if level == 0 {
+        warn!("Pac-Man has no level 0, the levels start at 1");
+        return;
+    }
+
+    load_game_event.send(LoadGameEvent {
+        restart: false,
+        level: level as u32,
+    });
+    next_state.set(PacmanState::InGame);
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,1177 @@
+use std::{collections::HashSet, time::Duration};
+
+use bevy::{color::palettes, prelude::*};
+
+use crate::{application::GAME_AREA, game_menu, input::Action, registry::RetroGame, rng::GameRng};
+
+use super::{
+    maze::{load_mazes, Direction, Maze, Tile},
+    PacmanPlugin, PacmanState,
+};
+
+pub const TILE_SIZE: f32 = 18.0;
+
+// From the left side of the game area to the maze, the score is on the
+// right of it
+const MAZE_MARGIN: f32 = 30.0;
+
+// In tiles per second
+const PACMAN_SPEED: f32 = 8.0;
+const GHOST_SPEED: f32 = 7.5;
+const FRIGHTENED_SPEED: f32 = 4.5;
+const TUNNEL_SPEED: f32 = 4.0;
+const EYES_SPEED: f32 = 15.0;
+
+// Every level is this much faster than the last, up to `MAX_SPEED_UP`
+const LEVEL_SPEED_UP: f32 = 0.05;
+const MAX_SPEED_UP: f32 = 1.25;
+
+const PELLET_POINTS: u32 = 10;
+const POWER_PELLET_POINTS: u32 = 50;
+
+// For the first ghost eaten after a power pellet, doubling for each next one
+const GHOST_POINTS: u32 = 200;
+
+// Seconds the ghosts stay frightened at the first level, every level takes
+// some off
+const FRIGHTENED_TIME: f32 = 6.0;
+const FRIGHTENED_TIME_DECREASE: f32 = 0.5;
+const MIN_FRIGHTENED_TIME: f32 = 1.0;
+
+/// The frightened ghosts blink for this many seconds before they recover
+pub const FRIGHTENED_BLINK_TIME: f32 = 2.0;
+
+// Seconds of each mode, the ghosts chase for good after the last one
+const MODE_SCHEDULE: [(GhostMode, f32); 7] = [
+    (GhostMode::Scatter, 7.0),
+    (GhostMode::Chase, 20.0),
+    (GhostMode::Scatter, 7.0),
+    (GhostMode::Chase, 20.0),
+    (GhostMode::Scatter, 5.0),
+    (GhostMode::Chase, 20.0),
+    (GhostMode::Scatter, 5.0),
+];
+
+// Pellets eaten in the level before each ghost leaves the house. The next
+// one also leaves after this many seconds without Pac-Man eating any.
+const RELEASE_PELLETS: [u32; 4] = [0, 0, 30, 60];
+const RELEASE_TIME: f32 = 4.0;
+
+// Clyde goes back to his corner when closer to Pac-Man than this, in tiles
+const CLYDE_SHYNESS: i32 = 8;
+
+// Pellets eaten in the level before each fruit shows up
+const FRUIT_PELLETS: [u32; 2] = [70, 170];
+const FRUIT_TIME: f32 = 9.5;
+
+// Of the fruit of each level, the last one for every level after
+const FRUIT_POINTS: [u32; 8] = [100, 300, 500, 700, 1000, 2000, 3000, 5000];
+
+/// Seconds before each life starts
+pub const READY_TIME: f32 = 2.0;
+
+pub const LIVES: u32 = 3;
+
+// Ghosts closer to Pac-Man than this catch him, or are eaten, in tiles
+const CATCH_DISTANCE: f32 = 0.5;
+
+const WALL_COLOR: Color = Color::srgb(0.15, 0.2, 0.9);
+const INNER_WALL_COLOR: Color = Color::srgb(0.02, 0.02, 0.2);
+const PELLET_COLOR: Color = Color::srgb(1.0, 0.8, 0.7);
+const PACMAN_COLOR: Color = Color::srgb(1.0, 0.9, 0.0);
+const FRIGHTENED_COLOR: Color = Color::srgb(0.1, 0.1, 0.9);
+
+pub struct GamePlugin;
+
+impl Plugin for GamePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<LoadGameEvent>()
+            .add_event::<CleanupGameEvent>()
+            .init_resource::<LastScore>()
+            .add_systems(OnExit(PacmanPlugin::STATE), cleanup_game)
+            .add_systems(
+                Update,
+                (
+                    load_game.run_if(on_event::<LoadGameEvent>()),
+                    (
+                        handle_player_input,
+                        update_game,
+                        game_menu::end_game::<PacmanPlugin>
+                            .run_if(game_menu::is_over::<PacmanPlugin>),
+                    )
+                        .chain()
+                        .run_if(in_state(PacmanState::InGame))
+                        .run_if(resource_exists::<PacmanGame>),
+                    cleanup_game.run_if(on_event::<CleanupGameEvent>()),
+                    (draw_maze, (draw_pellets, draw_actors, update_hud))
+                        .chain()
+                        .run_if(resource_exists::<PacmanGame>),
+                )
+                    .chain(),
+            );
+    }
+}
+
+#[derive(Event)]
+pub struct LoadGameEvent {
+    pub restart: bool,
+
+    /// From 1
+    pub level: u32,
+}
+
+impl Default for LoadGameEvent {
+    fn default() -> Self {
+        Self {
+            restart: false,
+            level: 1,
+        }
+    }
+}
+
+#[derive(Event, Default)]
+pub struct CleanupGameEvent;
+
+/// Score of the last finished game
+pub type LastScore = game_menu::LastScore<PacmanPlugin>;
+
+/// Every maze, read once per game
+#[derive(Resource)]
+pub struct Mazes(pub Vec<Maze>);
+
+impl Mazes {
+    /// The mazes take turns, one level each
+    pub fn for_level(&self, level: u32) -> &Maze {
+        &self.0[(level as usize - 1) % self.0.len()]
+    }
+}
+
+/// Where the ghosts go when not frightened
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum GhostMode {
+    /// Each to its own corner
+    Scatter,
+    /// After Pac-Man, each its own way
+    Chase,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum GhostName {
+    /// Goes straight for Pac-Man
+    Blinky,
+    /// Cuts Pac-Man off, aiming ahead of him
+    Pinky,
+    /// Pincers Pac-Man with Blinky
+    Inky,
+    /// Chases Pac-Man from afar, goes back to his corner when close
+    Clyde,
+}
+
+impl GhostName {
+    pub const ALL: [GhostName; 4] = [
+        GhostName::Blinky,
+        GhostName::Pinky,
+        GhostName::Inky,
+        GhostName::Clyde,
+    ];
+
+    fn index(self) -> usize {
+        self as usize
+    }
+
+    fn color(self) -> Color {
+        match self {
+            GhostName::Blinky => palettes::css::RED.into(),
+            GhostName::Pinky => palettes::css::HOT_PINK.into(),
+            GhostName::Inky => palettes::css::AQUA.into(),
+            GhostName::Clyde => palettes::css::ORANGE.into(),
+        }
+    }
+
+    /// Where the ghost goes in scatter mode, out of a maze of `size`
+    pub fn corner(self, size: IVec2) -> IVec2 {
+        match self {
+            GhostName::Blinky => IVec2::new(size.x - 3, -4),
+            GhostName::Pinky => IVec2::new(2, -4),
+            GhostName::Inky => IVec2::new(size.x - 1, size.y + 3),
+            GhostName::Clyde => IVec2::new(0, size.y + 3),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum GhostState {
+    /// Waiting to be released
+    InHouse,
+    /// On its way out of the house
+    Leaving,
+    /// Scattering, chasing or frightened in the maze
+    Roaming,
+    /// Eaten, the eyes go back to the house
+    Eyes,
+}
+
+/// Goes from tile to tile
+#[derive(Clone, Copy, Debug)]
+pub struct Mover {
+    pub tile: IVec2,
+    pub direction: Direction,
+
+    /// From 0 on `tile` to 1 on the next tile in `direction`
+    pub progress: f32,
+}
+
+impl Mover {
+    fn new(tile: IVec2, direction: Direction) -> Self {
+        Self {
+            tile,
+            direction,
+            progress: 0.0,
+        }
+    }
+
+    /// In tiles
+    pub fn position(&self) -> Vec2 {
+        self.tile.as_vec2() + self.direction.offset().as_vec2() * self.progress
+    }
+
+    /// The tile it is the closest to
+    pub fn nearest_tile(&self, maze: &Maze) -> IVec2 {
+        if self.progress < 0.5 {
+            self.tile
+        } else {
+            maze.neighbour(self.tile, self.direction)
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Ghost {
+    pub name: GhostName,
+    pub mover: Mover,
+    pub state: GhostState,
+    pub frightened: bool,
+
+    // Turns around at the next tile, when the mode changes
+    reverse: bool,
+}
+
+impl Ghost {
+    fn can_enter(&self, tile: Tile) -> bool {
+        match self.state {
+            GhostState::InHouse | GhostState::Roaming => {
+                matches!(tile, Tile::Path | Tile::Tunnel)
+            }
+            GhostState::Leaving | GhostState::Eyes => tile != Tile::Wall,
+        }
+    }
+
+    // Picks where to go from the tile it just reached
+    fn arrive(&mut self, maze: &Maze, target: IVec2, pick: &mut impl FnMut(usize) -> usize) {
+        let exit = maze.ghost_starts[0];
+        let house = maze.ghost_starts[1];
+
+        if self.state == GhostState::Eyes && self.mover.tile == house {
+            self.state = GhostState::Leaving;
+        }
+        if self.state == GhostState::Leaving && self.mover.tile == exit {
+            self.state = GhostState::Roaming;
+        }
+
+        let goal = match self.state {
+            GhostState::InHouse => return,
+            GhostState::Leaving => exit,
+            GhostState::Eyes => house,
+            GhostState::Roaming => {
+                self.mover.direction = self.roam(maze, target, pick);
+                return;
+            }
+        };
+        if let Some(direction) =
+            maze.direction_to(self.mover.tile, goal, |tile| self.can_enter(tile))
+        {
+            self.mover.direction = direction;
+        }
+    }
+
+    // Never back where it comes from unless the mode changed, the closest to
+    // the target or anywhere when frightened
+    fn roam(
+        &mut self,
+        maze: &Maze,
+        target: IVec2,
+        pick: &mut impl FnMut(usize) -> usize,
+    ) -> Direction {
+        let back = self.mover.direction.opposite();
+        if self.reverse {
+            self.reverse = false;
+            return back;
+        }
+
+        let tile = self.mover.tile;
+        let directions: Vec<Direction> = Direction::ALL
+            .into_iter()
+            .filter(|&direction| {
+                direction != back && self.can_enter(maze.tile(maze.neighbour(tile, direction)))
+            })
+            .collect();
+
+        if directions.is_empty() {
+            back
+        } else if self.frightened {
+            directions[pick(directions.len())]
+        } else {
+            directions
+                .into_iter()
+                .min_by_key(|&direction| {
+                    (maze.neighbour(tile, direction) - target).length_squared()
+                })
+                .unwrap()
+        }
+    }
+}
+
+/// What happened during an update of the game
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Outcome {
+    Continue,
+    /// A ghost caught Pac-Man, the game is over if it was the last life
+    Caught,
+    /// Every pellet was eaten, `PacmanGame::next_level` brings the next
+    /// maze
+    LevelCleared,
+}
+
+/// The game being played
+#[derive(Resource, Clone, Debug)]
+pub struct PacmanGame {
+    pub maze: Maze,
+
+    /// Left to eat
+    pub pellets: HashSet<IVec2>,
+    pub power_pellets: HashSet<IVec2>,
+
+    pub pacman: Mover,
+
+    /// Pac-Man stopped against a wall
+    pub pacman_stopped: bool,
+
+    /// Where Pac-Man turns as soon as he can
+    pub wanted_direction: Direction,
+
+    /// Blinky, Pinky, Inky and Clyde
+    pub ghosts: [Ghost; 4],
+
+    pub mode: GhostMode,
+
+    /// Shown until eaten or for `FRUIT_TIME`
+    pub fruit_shown: bool,
+
+    /// From 1
+    pub level: u32,
+
+    pub lives: u32,
+    pub score: u32,
+
+    // No life left
+    pub game_over: bool,
+
+    mode_index: usize,
+    mode_timer: Timer,
+    frightened_timer: Timer,
+
+    // Since the last power pellet
+    ghosts_eaten: u32,
+
+    // In the level
+    pellets_eaten: u32,
+
+    release_timer: Timer,
+    fruit_timer: Timer,
+    ready_timer: Timer,
+}
+
+impl game_menu::ScoredGame for PacmanGame {
+    fn score(&self) -> u32 {
+        self.score
+    }
+
+    fn is_over(&self) -> bool {
+        self.game_over
+    }
+}
+
+impl PacmanGame {
+    pub fn new(maze: Maze, level: u32) -> Self {
+        let mut game = Self {
+            pellets: HashSet::new(),
+            power_pellets: HashSet::new(),
+            pacman: Mover::new(maze.pacman_start, Direction::Left),
+            pacman_stopped: false,
+            wanted_direction: Direction::Left,
+            ghosts: GhostName::ALL.map(|name| Ghost {
+                name,
+                mover: Mover::new(maze.ghost_starts[name.index()], Direction::Left),
+                state: GhostState::InHouse,
+                frightened: false,
+                reverse: false,
+            }),
+            mode: GhostMode::Scatter,
+            fruit_shown: false,
+            level,
+            lives: LIVES,
+            score: 0,
+            game_over: false,
+            mode_index: 0,
+            mode_timer: Timer::from_seconds(MODE_SCHEDULE[0].1, TimerMode::Once),
+            frightened_timer: Timer::from_seconds(FRIGHTENED_TIME, TimerMode::Once),
+            ghosts_eaten: 0,
+            pellets_eaten: 0,
+            release_timer: Timer::from_seconds(RELEASE_TIME, TimerMode::Once),
+            fruit_timer: Timer::from_seconds(FRUIT_TIME, TimerMode::Once),
+            ready_timer: Timer::from_seconds(READY_TIME, TimerMode::Once),
+            maze,
+        };
+        game.start_level();
+
+        game
+    }
+
+    /// The next level in `maze`, a bit faster
+    pub fn next_level(&mut self, maze: Maze) {
+        self.level += 1;
+        self.maze = maze;
+        self.start_level();
+    }
+
+    fn start_level(&mut self) {
+        self.pellets = self.maze.pellets.iter().copied().collect();
+        self.power_pellets = self.maze.power_pellets.iter().copied().collect();
+        self.pellets_eaten = 0;
+        self.fruit_shown = false;
+
+        self.mode_index = 0;
+        self.mode = MODE_SCHEDULE[0].0;
+        self.mode_timer = Timer::from_seconds(MODE_SCHEDULE[0].1, TimerMode::Once);
+
+        self.start_life();
+    }
+
+    // Everyone back to their start, the pellets stay eaten
+    fn start_life(&mut self) {
+        self.pacman = Mover::new(self.maze.pacman_start, Direction::Left);
+        self.pacman_stopped = false;
+        self.wanted_direction = Direction::Left;
+
+        for ghost in &mut self.ghosts {
+            ghost.mover = Mover::new(self.maze.ghost_starts[ghost.name.index()], Direction::Left);
+            ghost.state = if ghost.name == GhostName::Blinky {
+                GhostState::Roaming
+            } else {
+                GhostState::InHouse
+            };
+            ghost.frightened = false;
+            ghost.reverse = false;
+        }
+
+        self.frightened_timer.pause();
+        self.release_timer.reset();
+        self.ready_timer.reset();
+    }
+
+    /// Waiting for the next life to start
+    pub fn is_ready(&self) -> bool {
+        !self.ready_timer.finished()
+    }
+
+    /// Frightened ghosts blink when about to recover
+    pub fn frightened_ending(&self) -> bool {
+        self.frightened_timer.remaining_secs() < FRIGHTENED_BLINK_TIME
+    }
+
+    fn speed_up(&self) -> f32 {
+        (1.0 + LEVEL_SPEED_UP * (self.level - 1) as f32).min(MAX_SPEED_UP)
+    }
+
+    fn frightened_time(&self) -> f32 {
+        (FRIGHTENED_TIME - FRIGHTENED_TIME_DECREASE * (self.level - 1) as f32)
+            .max(MIN_FRIGHTENED_TIME)
+    }
+
+    /// Points of the fruit of the level
+    pub fn fruit_points(&self) -> u32 {
+        FRUIT_POINTS[(self.level as usize - 1).min(FRUIT_POINTS.len() - 1)]
+    }
+
+    /// The tile `name` heads for in the current mode, when not frightened
+    pub fn target(&self, name: GhostName) -> IVec2 {
+        let corner = name.corner(self.maze.size);
+        if self.mode == GhostMode::Scatter {
+            return corner;
+        }
+
+        let pacman = self.pacman.tile;
+        let ahead = |nb_tiles: i32| pacman + self.pacman.direction.offset() * nb_tiles;
+        match name {
+            GhostName::Blinky => pacman,
+            GhostName::Pinky => ahead(4),
+            GhostName::Inky => {
+                let blinky = self.ghosts[GhostName::Blinky.index()].mover.tile;
+                ahead(2) * 2 - blinky
+            }
+            GhostName::Clyde => {
+                let clyde = self.ghosts[GhostName::Clyde.index()].mover.tile;
+                if (pacman - clyde).length_squared() > CLYDE_SHYNESS * CLYDE_SHYNESS {
+                    pacman
+                } else {
+                    corner
+                }
+            }
+        }
+    }
+
+    /// Where Pac-Man goes next, he turns around straight away
+    pub fn turn(&mut self, direction: Direction) {
+        self.wanted_direction = direction;
+
+        if direction == self.pacman.direction.opposite() && self.pacman.progress > 0.0 {
+            self.pacman.tile = self.maze.neighbour(self.pacman.tile, self.pacman.direction);
+            self.pacman.direction = direction;
+            self.pacman.progress = 1.0 - self.pacman.progress;
+        }
+    }
+
+    /// Moves everything by `delta_seconds`. `pick` picks among the given
+    /// number of choices, for the frightened ghosts.
+    pub fn update(&mut self, delta_seconds: f32, mut pick: impl FnMut(usize) -> usize) -> Outcome {
+        let delta = Duration::from_secs_f32(delta_seconds);
+        if self.game_over {
+            return Outcome::Continue;
+        }
+        if self.is_ready() {
+            self.ready_timer.tick(delta);
+            return Outcome::Continue;
+        }
+
+        self.update_modes(delta);
+
+        self.move_pacman(delta_seconds);
+        self.eat();
+        if self.pellets.is_empty() && self.power_pellets.is_empty() {
+            return Outcome::LevelCleared;
+        }
+
+        self.release_ghosts(delta);
+        for index in 0..self.ghosts.len() {
+            self.move_ghost(index, delta_seconds, &mut pick);
+        }
+
+        self.collide()
+    }
+
+    fn update_modes(&mut self, delta: Duration) {
+        if self.fruit_shown && self.fruit_timer.tick(delta).just_finished() {
+            self.fruit_shown = false;
+        }
+
+        // The schedule waits while the ghosts are frightened
+        if self.ghosts.iter().any(|ghost| ghost.frightened) {
+            if self.frightened_timer.tick(delta).just_finished() {
+                for ghost in &mut self.ghosts {
+                    ghost.frightened = false;
+                }
+            }
+            return;
+        }
+
+        if self.mode_timer.tick(delta).just_finished() {
+            self.mode_index += 1;
+            self.mode = match MODE_SCHEDULE.get(self.mode_index) {
+                Some(&(mode, seconds)) => {
+                    self.mode_timer = Timer::from_seconds(seconds, TimerMode::Once);
+                    mode
+                }
+                None => GhostMode::Chase,
+            };
+            self.reverse_ghosts();
+        }
+    }
+
+    fn reverse_ghosts(&mut self) {
+        for ghost in &mut self.ghosts {
+            if ghost.state == GhostState::Roaming {
+                ghost.reverse = true;
+            }
+        }
+    }
+
+    fn move_pacman(&mut self, delta_seconds: f32) {
+        let mut distance = PACMAN_SPEED * self.speed_up() * delta_seconds;
+        let maze = &self.maze;
+        let can_enter = |tile: IVec2, direction: Direction| {
+            matches!(
+                maze.tile(maze.neighbour(tile, direction)),
+                Tile::Path | Tile::Tunnel
+            )
+        };
+
+        let pacman = &mut self.pacman;
+        if self.pacman_stopped || pacman.progress == 0.0 {
+            if can_enter(pacman.tile, self.wanted_direction) {
+                pacman.direction = self.wanted_direction;
+            }
+            self.pacman_stopped = !can_enter(pacman.tile, pacman.direction);
+            if self.pacman_stopped {
+                return;
+            }
+        }
+
+        while distance > 0.0 {
+            let remaining = 1.0 - pacman.progress;
+            if distance < remaining {
+                pacman.progress += distance;
+                return;
+            }
+
+            distance -= remaining;
+            pacman.tile = maze.neighbour(pacman.tile, pacman.direction);
+            pacman.progress = 0.0;
+
+            if can_enter(pacman.tile, self.wanted_direction) {
+                pacman.direction = self.wanted_direction;
+            } else if !can_enter(pacman.tile, pacman.direction) {
+                self.pacman_stopped = true;
+                return;
+            }
+        }
+    }
+
+    // What is on the tile Pac-Man is on
+    fn eat(&mut self) {
+        let tile = self.pacman.nearest_tile(&self.maze);
+
+        if self.pellets.remove(&tile) {
+            self.score += PELLET_POINTS;
+        } else if self.power_pellets.remove(&tile) {
+            self.score += POWER_PELLET_POINTS;
+            self.frighten_ghosts();
+        } else {
+            if self.fruit_shown && tile == self.maze.fruit {
+                self.score += self.fruit_points();
+                self.fruit_shown = false;
+            }
+            return;
+        }
+
+        self.pellets_eaten += 1;
+        self.release_timer.reset();
+        if FRUIT_PELLETS.contains(&self.pellets_eaten) {
+            self.fruit_shown = true;
+            self.fruit_timer.reset();
+        }
+    }
+
+    fn frighten_ghosts(&mut self) {
+        for ghost in &mut self.ghosts {
+            if ghost.state != GhostState::Eyes {
+                ghost.frightened = true;
+                ghost.reverse = ghost.state == GhostState::Roaming;
+            }
+        }
+
+        self.ghosts_eaten = 0;
+        self.frightened_timer = Timer::from_seconds(self.frightened_time(), TimerMode::Once);
+    }
+
+    // The next ghost in the house leaves once enough pellets are eaten, or
+    // after a while without any eaten
+    fn release_ghosts(&mut self, delta: Duration) {
+        let timed_out = self.release_timer.tick(delta).finished();
+
+        let Some(ghost) = self
+            .ghosts
+            .iter_mut()
+            .find(|ghost| ghost.state == GhostState::InHouse)
+        else {
+            return;
+        };
+
+        if timed_out || self.pellets_eaten >= RELEASE_PELLETS[ghost.name.index()] {
+            ghost.state = GhostState::Leaving;
+            ghost.arrive(&self.maze, IVec2::ZERO, &mut |_| 0);
+            self.release_timer.reset();
+        }
+    }
+
+    fn move_ghost(
+        &mut self,
+        index: usize,
+        delta_seconds: f32,
+        pick: &mut impl FnMut(usize) -> usize,
+    ) {
+        let target = self.target(self.ghosts[index].name);
+        let speed_up = self.speed_up();
+        let maze = &self.maze;
+        let ghost = &mut self.ghosts[index];
+
+        let speed = match ghost.state {
+            GhostState::InHouse => return,
+            GhostState::Eyes => EYES_SPEED,
+            _ if maze.tile(ghost.mover.tile) == Tile::Tunnel => TUNNEL_SPEED * speed_up,
+            _ if ghost.frightened => FRIGHTENED_SPEED * speed_up,
+            _ => GHOST_SPEED * speed_up,
+        };
+
+        let mut distance = speed * delta_seconds;
+        while distance > 0.0 {
+            let remaining = 1.0 - ghost.mover.progress;
+            if distance < remaining {
+                ghost.mover.progress += distance;
+                return;
+            }
+
+            distance -= remaining;
+            ghost.mover.tile = maze.neighbour(ghost.mover.tile, ghost.mover.direction);
+            ghost.mover.progress = 0.0;
+            ghost.arrive(maze, target, pick);
+        }
+    }
+
+    // Frightened ghosts Pac-Man runs into are eaten, the others catch him
+    fn collide(&mut self) -> Outcome {
+        let pacman = self.pacman.position();
+
+        for index in 0..self.ghosts.len() {
+            let ghost = &mut self.ghosts[index];
+            if matches!(ghost.state, GhostState::InHouse | GhostState::Eyes)
+                || ghost.mover.position().distance(pacman) >= CATCH_DISTANCE
+            {
+                continue;
+            }
+
+            if ghost.frightened {
+                ghost.state = GhostState::Eyes;
+                ghost.frightened = false;
+                self.score += GHOST_POINTS << self.ghosts_eaten;
+                self.ghosts_eaten += 1;
+                continue;
+            }
+
+            self.lives = self.lives.saturating_sub(1);
+            self.game_over = self.lives == 0;
+            if !self.game_over {
+                self.start_life();
+            }
+            return Outcome::Caught;
+        }
+
+        Outcome::Continue
+    }
+}
+
+#[derive(Component)]
+struct GameEntity;
+
+// Wall or pellet of the maze of this level
+#[derive(Component, Clone, Copy)]
+struct MazeSprite {
+    level: u32,
+}
+
+#[derive(Component)]
+struct Pellet(IVec2);
+
+#[derive(Component)]
+struct PacmanSprite;
+
+#[derive(Component)]
+struct GhostBody(usize);
+
+#[derive(Component)]
+struct GhostEyes(usize);
+
+#[derive(Component)]
+struct Fruit;
+
+#[derive(Component)]
+struct Hud;
+
+#[derive(Component)]
+struct ReadyText;
+
+// Top left corner of a maze of `size`, centered vertically
+fn maze_origin(size: IVec2) -> Vec2 {
+    Vec2::new(
+        GAME_AREA.min.x + MAZE_MARGIN,
+        GAME_AREA.center().y + size.y as f32 * TILE_SIZE / 2.0,
+    )
+}
+
+// Of the center of a tile, `position` is in tiles
+fn world_position(size: IVec2, position: Vec2) -> Vec2 {
+    maze_origin(size) + Vec2::new(position.x + 0.5, -(position.y + 0.5)) * TILE_SIZE
+}
+
+fn load_game(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut load_events: EventReader<LoadGameEvent>,
+    mut game_rng: ResMut<GameRng>,
+    game: Option<Res<PacmanGame>>,
+    entities: Query<Entity, With<GameEntity>>,
+) {
+    let mut level = 1;
+    if let Some(load_event) = load_events.read().last() {
+        level = load_event.level;
+        if load_event.restart {
+            game_rng.restart_run();
+            // "Restart" plays the level of the game it ends again
+            if let Some(game) = &game {
+                level = game.level;
+            }
+        } else {
+            game_rng.new_run();
+        }
+    }
+
+    for entity in &entities {
+        commands.entity(entity).despawn();
+    }
+
+    let mazes = Mazes(load_mazes().expect("The mazes should be in assets/pacman/mazes"));
+    commands.insert_resource(PacmanGame::new(mazes.for_level(level).clone(), level));
+    commands.insert_resource(mazes);
+
+    commands.spawn((
+        SpriteBundle {
+            sprite: Sprite {
+                color: Color::BLACK,
+                custom_size: Some(GAME_AREA.size()),
+                ..Default::default()
+            },
+            transform: Transform::from_translation(Vec3::from((GAME_AREA.center(), -1.0))),
+            ..Default::default()
+        },
+        GameEntity,
+    ));
+
+    let actor_size = Some(Vec2::splat(TILE_SIZE * 1.6));
+    commands.spawn((
+        SpriteBundle {
+            sprite: Sprite {
+                color: PACMAN_COLOR,
+                custom_size: actor_size,
+                ..Default::default()
+            },
+            texture: asset_server.load("pacman/sprites/pacman.png"),
+            ..Default::default()
+        },
+        PacmanSprite,
+        GameEntity,
+    ));
+
+    for index in 0..GhostName::ALL.len() {
+        commands.spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    custom_size: actor_size,
+                    ..Default::default()
+                },
+                texture: asset_server.load("pacman/sprites/ghost.png"),
+                ..Default::default()
+            },
+            GhostBody(index),
+            GameEntity,
+        ));
+        commands.spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    custom_size: actor_size,
+                    ..Default::default()
+                },
+                texture: asset_server.load("pacman/sprites/eyes.png"),
+                ..Default::default()
+            },
+            GhostEyes(index),
+            GameEntity,
+        ));
+    }
+
+    commands.spawn((
+        SpriteBundle {
+            sprite: Sprite {
+                custom_size: actor_size,
+                ..Default::default()
+            },
+            texture: asset_server.load("pacman/sprites/fruit.png"),
+            visibility: Visibility::Hidden,
+            ..Default::default()
+        },
+        Fruit,
+        GameEntity,
+    ));
+
+    commands.spawn((
+        Text2dBundle {
+            text: Text::from_section(
+                "",
+                TextStyle {
+                    font_size: 26.0,
+                    color: Color::WHITE,
+                    ..Default::default()
+                },
+            ),
+            transform: Transform::from_translation(Vec3::new(
+                GAME_AREA.max.x - 120.0,
+                GAME_AREA.center().y,
+                1.0,
+            )),
+            ..Default::default()
+        },
+        Hud,
+        GameEntity,
+    ));
+
+    commands.spawn((
+        Text2dBundle {
+            text: Text::from_section(
+                "READY!",
+                TextStyle {
+                    font_size: 22.0,
+                    color: PACMAN_COLOR,
+                    ..Default::default()
+                },
+            ),
+            ..Default::default()
+        },
+        ReadyText,
+        GameEntity,
+    ));
+}
+
+fn cleanup_game(mut commands: Commands, entities: Query<Entity, With<GameEntity>>) {
+    for entity in &entities {
+        commands.entity(entity).despawn();
+    }
+
+    commands.remove_resource::<PacmanGame>();
+    commands.remove_resource::<Mazes>();
+}
+
+fn handle_player_input(
+    input: Res<ButtonInput<Action>>,
+    mut game: ResMut<PacmanGame>,
+    mut next_pacman_state: ResMut<NextState<PacmanState>>,
+) {
+    for (action, direction) in [
+        (Action::MoveUp, Direction::Up),
+        (Action::MoveDown, Direction::Down),
+        (Action::MoveLeft, Direction::Left),
+        (Action::MoveRight, Direction::Right),
+    ] {
+        if input.just_pressed(action) {
+            game.turn(direction);
+        }
+    }
+
+    if input.just_pressed(Action::Pause) {
+        next_pacman_state.set(PacmanState::InMenu);
+    }
+}
+
+fn update_game(
+    time: Res<Time>,
+    mazes: Res<Mazes>,
+    mut game: ResMut<PacmanGame>,
+    mut game_rng: ResMut<GameRng>,
+) {
+    let outcome = game.update(time.delta_seconds(), |nb_choices| {
+        game_rng.random_index(nb_choices)
+    });
+
+    if outcome == Outcome::LevelCleared {
+        let maze = mazes.for_level(game.level + 1).clone();
+        game.next_level(maze);
+    }
+}
+
+// Spawns the walls and the pellets of the level's maze, once per level
+fn draw_maze(
+    mut commands: Commands,
+    game: Res<PacmanGame>,
+    maze_sprites: Query<(Entity, &MazeSprite)>,
+) {
+    if maze_sprites
+        .iter()
+        .next()
+        .is_some_and(|(_, sprite)| sprite.level == game.level)
+    {
+        return;
+    }
+
+    for (entity, _) in &maze_sprites {
+        commands.entity(entity).despawn();
+    }
+
+    let maze = &game.maze;
+    let square = |color: Color, size: f32, coords: IVec2| SpriteBundle {
+        sprite: Sprite {
+            color,
+            custom_size: Some(Vec2::splat(size)),
+            ..Default::default()
+        },
+        transform: Transform::from_translation(Vec3::from((
+            world_position(maze.size, coords.as_vec2()),
+            -0.5,
+        ))),
+        ..Default::default()
+    };
+    let level = MazeSprite { level: game.level };
+
+    for y in 0..maze.size.y {
+        for x in 0..maze.size.x {
+            let coords = IVec2::new(x, y);
+            match maze.tile(coords) {
+                Tile::Wall => {
+                    // Bright along the paths, dark inside of the walls
+                    let inner = Direction::ALL
+                        .into_iter()
+                        .all(|direction| maze.tile(coords + direction.offset()) == Tile::Wall);
+                    let color = if inner { INNER_WALL_COLOR } else { WALL_COLOR };
+                    commands.spawn((square(color, TILE_SIZE, coords), level, GameEntity));
+                }
+                Tile::Door => {
+                    let mut door = square(palettes::css::PINK.into(), TILE_SIZE, coords);
+                    door.sprite.custom_size = Some(Vec2::new(TILE_SIZE, TILE_SIZE / 4.0));
+                    commands.spawn((door, level, GameEntity));
+                }
+                _ => (),
+            }
+        }
+    }
+
+    for &coords in &maze.pellets {
+        commands.spawn((
+            square(PELLET_COLOR, TILE_SIZE / 4.0, coords),
+            Pellet(coords),
+            level,
+            GameEntity,
+        ));
+    }
+    for &coords in &maze.power_pellets {
+        commands.spawn((
+            square(PELLET_COLOR, TILE_SIZE * 0.6, coords),
+            Pellet(coords),
+            level,
+            GameEntity,
+        ));
+    }
+}
+
+fn draw_pellets(game: Res<PacmanGame>, mut pellets: Query<(&Pellet, &mut Visibility)>) {
+    for (Pellet(coords), mut visibility) in &mut pellets {
+        let wanted = if game.pellets.contains(coords) || game.power_pellets.contains(coords) {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+        if *visibility != wanted {
+            *visibility = wanted;
+        }
+    }
+}
+
+// The sprites moved by `draw_actors`, each query kept apart from the others
+type EyesFilter = (Without<PacmanSprite>, Without<GhostBody>);
+type FruitFilter = (
+    With<Fruit>,
+    Without<PacmanSprite>,
+    Without<GhostBody>,
+    Without<GhostEyes>,
+);
+
+fn draw_actors(
+    game: Res<PacmanGame>,
+    mut pacman: Query<&mut Transform, With<PacmanSprite>>,
+    mut bodies: Query<
+        (&GhostBody, &mut Transform, &mut Sprite, &mut Visibility),
+        Without<PacmanSprite>,
+    >,
+    mut eyes: Query<(&GhostEyes, &mut Transform, &mut Visibility), EyesFilter>,
+    mut fruit: Query<(&mut Transform, &mut Visibility), FruitFilter>,
+) {
+    let size = game.maze.size;
+
+    if let Ok(mut transform) = pacman.get_single_mut() {
+        transform.translation = Vec3::from((world_position(size, game.pacman.position()), 1.0));
+        let angle =
+            Vec2::X.angle_between(game.pacman.direction.offset().as_vec2() * Vec2::new(1.0, -1.0));
+        transform.rotation = Quat::from_rotation_z(angle);
+    }
+
+    // Blinking white when about to recover
+    let blink_white = game.frightened_ending()
+        && ((game.frightened_timer.elapsed_secs() * 4.0) as u32).is_multiple_of(2);
+    for (GhostBody(index), mut transform, mut sprite, mut visibility) in &mut bodies {
+        let ghost = &game.ghosts[*index];
+        transform.translation = Vec3::from((world_position(size, ghost.mover.position()), 1.0));
+        *visibility = if ghost.state == GhostState::Eyes {
+            Visibility::Hidden
+        } else {
+            Visibility::Inherited
+        };
+        sprite.color = match (ghost.frightened, blink_white) {
+            (true, true) => Color::WHITE,
+            (true, false) => FRIGHTENED_COLOR,
+            (false, _) => ghost.name.color(),
+        };
+    }
+
+    for (GhostEyes(index), mut transform, mut visibility) in &mut eyes {
+        let ghost = &game.ghosts[*index];
+        transform.translation = Vec3::from((world_position(size, ghost.mover.position()), 1.5));
+        *visibility = if ghost.frightened {
+            Visibility::Hidden
+        } else {
+            Visibility::Inherited
+        };
+    }
+
+    if let Ok((mut transform, mut visibility)) = fruit.get_single_mut() {
+        transform.translation = Vec3::from((world_position(size, game.maze.fruit.as_vec2()), 0.5));
+        *visibility = if game.fruit_shown {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+    }
+}
+
+fn update_hud(
+    game: Res<PacmanGame>,
+    mut hud: Query<&mut Text, (With<Hud>, Without<ReadyText>)>,
+    mut ready_text: Query<(&mut Transform, &mut Visibility), With<ReadyText>>,
+) {
+    if let Ok(mut text) = hud.get_single_mut() {
+        text.sections[0].value = format!(
+            "Score\n{}\n\nLevel {}\n\nLives {}",
+            game.score, game.level, game.lives
+        );
+    }
+
+    // Under the ghost house, where the fruits show up
+    if let Ok((mut transform, mut visibility)) = ready_text.get_single_mut() {
+        transform.translation = Vec3::from((
+            world_position(
+                game.maze.size,
+                game.maze.fruit.as_vec2() + Vec2::new(0.5, 0.0),
+            ),
+            2.0,
+        ));
+        *visibility = if game.is_ready() {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+    }
+}
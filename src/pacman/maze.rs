@@ -0,0 +1,238 @@
+use std::{collections::VecDeque, fs, io, path::Path};
+
+use bevy::prelude::*;
+
+pub const NB_MAZES: u32 = 2;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Tile {
+    Wall,
+    Path,
+    /// Path where the ghosts slow down, going out of the maze on one side
+    /// comes back from the other
+    Tunnel,
+    /// Of the ghost house, only the ghosts leaving it or going back to it
+    /// go through
+    Door,
+    House,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Direction {
+    Up,
+    Left,
+    Down,
+    Right,
+}
+
+impl Direction {
+    /// In the order the ghosts prefer them when two are as good
+    pub const ALL: [Direction; 4] = [
+        Direction::Up,
+        Direction::Left,
+        Direction::Down,
+        Direction::Right,
+    ];
+
+    /// In tiles, rows go down
+    pub fn offset(self) -> IVec2 {
+        match self {
+            Direction::Up => IVec2::NEG_Y,
+            Direction::Left => IVec2::NEG_X,
+            Direction::Down => IVec2::Y,
+            Direction::Right => IVec2::X,
+        }
+    }
+
+    pub fn opposite(self) -> Self {
+        match self {
+            Direction::Up => Direction::Down,
+            Direction::Left => Direction::Right,
+            Direction::Down => Direction::Up,
+            Direction::Right => Direction::Left,
+        }
+    }
+}
+
+/// Tiles of a maze and where everything starts, by tile counted from the
+/// top left corner
+#[derive(Clone, Debug)]
+pub struct Maze {
+    pub size: IVec2,
+    tiles: Vec<Tile>,
+
+    pub pellets: Vec<IVec2>,
+    pub power_pellets: Vec<IVec2>,
+
+    pub pacman_start: IVec2,
+
+    /// Blinky's start above the door of the ghost house, then the starts of
+    /// Pinky, Inky and Clyde in the house
+    pub ghost_starts: [IVec2; 4],
+
+    /// Where the fruits show up
+    pub fruit: IVec2,
+}
+
+impl Maze {
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        Self::from_text(&fs::read_to_string(path)?)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "invalid maze file"))
+    }
+
+    // The number of columns and of rows, then one line per row with one
+    // character per tile: `#` for a wall, `.` for a pellet, `o` for a power
+    // pellet, `-` for an empty path, `T` for a tunnel, `=` for the door of
+    // the ghost house and `H` for inside of it. `P` is where Pac-Man starts
+    // and `F` where the fruits show up, both on an empty path. `1` is where
+    // Blinky starts on an empty path, `2`, `3` and `4` where Pinky, Inky and
+    // Clyde start inside of the house.
+    fn from_text(text: &str) -> Option<Self> {
+        let mut lines = text.lines().filter(|line| !line.trim().is_empty());
+
+        let mut header = lines.next()?.split_whitespace();
+        let nb_cols = header.next()?.parse::<i32>().ok()?;
+        let nb_rows = header.next()?.parse::<i32>().ok()?;
+
+        let mut tiles = Vec::new();
+        let mut pellets = Vec::new();
+        let mut power_pellets = Vec::new();
+        let mut pacman_start = None;
+        let mut ghost_starts = [None; 4];
+        let mut fruit = None;
+
+        for y in 0..nb_rows {
+            let line: Vec<char> = lines.next()?.chars().collect();
+            if line.len() != nb_cols as usize {
+                return None;
+            }
+
+            for (x, character) in line.into_iter().enumerate() {
+                let coords = IVec2::new(x as i32, y);
+                let tile = match character {
+                    '#' => Tile::Wall,
+                    '.' => {
+                        pellets.push(coords);
+                        Tile::Path
+                    }
+                    'o' => {
+                        power_pellets.push(coords);
+                        Tile::Path
+                    }
+                    '-' => Tile::Path,
+                    'T' => Tile::Tunnel,
+                    '=' => Tile::Door,
+                    'H' => Tile::House,
+                    'P' => {
+                        pacman_start = Some(coords);
+                        Tile::Path
+                    }
+                    'F' => {
+                        fruit = Some(coords);
+                        Tile::Path
+                    }
+                    '1' => {
+                        ghost_starts[0] = Some(coords);
+                        Tile::Path
+                    }
+                    '2'..='4' => {
+                        ghost_starts[character as usize - '1' as usize] = Some(coords);
+                        Tile::House
+                    }
+                    _ => return None,
+                };
+                tiles.push(tile);
+            }
+        }
+
+        let [blinky, pinky, inky, clyde] = ghost_starts;
+        Some(Self {
+            size: IVec2::new(nb_cols, nb_rows),
+            tiles,
+            pellets,
+            power_pellets,
+            pacman_start: pacman_start?,
+            ghost_starts: [blinky?, pinky?, inky?, clyde?],
+            fruit: fruit?,
+        })
+    }
+
+    /// Tiles outside of the maze are walls
+    pub fn tile(&self, coords: IVec2) -> Tile {
+        if coords.cmplt(IVec2::ZERO).any() || coords.cmpge(self.size).any() {
+            return Tile::Wall;
+        }
+
+        self.tiles[(coords.y * self.size.x + coords.x) as usize]
+    }
+
+    /// The next tile in `direction`, coming back from the other side out of
+    /// a tunnel
+    pub fn neighbour(&self, coords: IVec2, direction: Direction) -> IVec2 {
+        let next = coords + direction.offset();
+        if self.tile(coords) == Tile::Tunnel {
+            IVec2::new(next.x.rem_euclid(self.size.x), next.y)
+        } else {
+            next
+        }
+    }
+
+    /// Tiles on the shortest way from `from` to `to`, `to` included and
+    /// `from` not, going only through the tiles `can_enter` lets in
+    pub fn path(
+        &self,
+        from: IVec2,
+        to: IVec2,
+        can_enter: impl Fn(Tile) -> bool,
+    ) -> Option<Vec<IVec2>> {
+        let index = |coords: IVec2| (coords.y * self.size.x + coords.x) as usize;
+        let mut came_from: Vec<Option<IVec2>> = vec![None; self.tiles.len()];
+        let mut to_visit = VecDeque::from([from]);
+
+        while let Some(coords) = to_visit.pop_front() {
+            if coords == to {
+                let mut path = Vec::new();
+                let mut step = to;
+                while step != from {
+                    path.push(step);
+                    step = came_from[index(step)]?;
+                }
+                path.reverse();
+                return Some(path);
+            }
+
+            for direction in Direction::ALL {
+                let next = self.neighbour(coords, direction);
+                if next != from && can_enter(self.tile(next)) && came_from[index(next)].is_none() {
+                    came_from[index(next)] = Some(coords);
+                    to_visit.push_back(next);
+                }
+            }
+        }
+
+        None
+    }
+
+    /// First step on the shortest way from `from` to `to`
+    pub fn direction_to(
+        &self,
+        from: IVec2,
+        to: IVec2,
+        can_enter: impl Fn(Tile) -> bool,
+    ) -> Option<Direction> {
+        let next = *self.path(from, to, can_enter)?.first()?;
+        Direction::ALL
+            .into_iter()
+            .find(|&direction| self.neighbour(from, direction) == next)
+    }
+}
+
+/// Every maze, in the order of the levels they are played at
+pub fn load_mazes() -> io::Result<Vec<Maze>> {
+    (1..=NB_MAZES)
+        .map(|maze| Maze::load(format!("assets/pacman/mazes/maze_{maze}.txt")))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests;
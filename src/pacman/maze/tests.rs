@@ -0,0 +1,136 @@
+use super::*;
+
+// A tunnel along the top, the house under its door in the middle
+const SMALL_MAZE: &str = "
+9 7
+#########
+T1-.P.-FT
+#.##=##.#
+#.#234#.#
+#.#####.#
+#o.....o#
+#########
+";
+
+fn small_maze() -> Maze {
+    Maze::from_text(SMALL_MAZE).expect("The maze should be valid")
+}
+
+fn is_path(tile: Tile) -> bool {
+    matches!(tile, Tile::Path | Tile::Tunnel)
+}
+
+#[test]
+fn mazes_are_read_from_text() {
+    let maze = small_maze();
+
+    assert_eq!(maze.size, IVec2::new(9, 7));
+    assert_eq!(maze.pellets.len(), 13);
+    assert_eq!(maze.power_pellets, vec![IVec2::new(1, 5), IVec2::new(7, 5)]);
+    assert_eq!(maze.pacman_start, IVec2::new(4, 1));
+    assert_eq!(
+        maze.ghost_starts,
+        [
+            IVec2::new(1, 1),
+            IVec2::new(3, 3),
+            IVec2::new(4, 3),
+            IVec2::new(5, 3)
+        ]
+    );
+    assert_eq!(maze.fruit, IVec2::new(7, 1));
+
+    assert_eq!(maze.tile(IVec2::new(0, 1)), Tile::Tunnel);
+    assert_eq!(maze.tile(IVec2::new(4, 2)), Tile::Door);
+    assert_eq!(maze.tile(IVec2::new(4, 3)), Tile::House);
+    assert_eq!(maze.tile(IVec2::new(-1, 1)), Tile::Wall);
+    assert_eq!(maze.tile(IVec2::new(4, 7)), Tile::Wall);
+}
+
+#[test]
+fn invalid_mazes_are_refused() {
+    // A row too short
+    assert!(Maze::from_text(&SMALL_MAZE.replace("#o.....o#", "#o....o#")).is_none());
+    // No Pac-Man
+    assert!(Maze::from_text(&SMALL_MAZE.replace('P', ".")).is_none());
+    // An unknown tile
+    assert!(Maze::from_text(&SMALL_MAZE.replace('F', "?")).is_none());
+}
+
+#[test]
+fn tunnels_come_back_from_the_other_side() {
+    let maze = small_maze();
+
+    assert_eq!(
+        maze.neighbour(IVec2::new(0, 1), Direction::Left),
+        IVec2::new(8, 1)
+    );
+    assert_eq!(
+        maze.neighbour(IVec2::new(8, 1), Direction::Right),
+        IVec2::new(0, 1)
+    );
+    assert_eq!(
+        maze.neighbour(IVec2::new(1, 1), Direction::Left),
+        IVec2::new(0, 1)
+    );
+    assert_eq!(
+        maze.neighbour(IVec2::new(1, 2), Direction::Left),
+        IVec2::new(0, 2)
+    );
+}
+
+#[test]
+fn the_shortest_path_goes_through_the_tunnel() {
+    let maze = small_maze();
+    let from = IVec2::new(1, 1);
+    let to = IVec2::new(7, 1);
+
+    assert_eq!(
+        maze.path(from, to, is_path),
+        Some(vec![IVec2::new(0, 1), IVec2::new(8, 1), to])
+    );
+    assert_eq!(maze.direction_to(from, to, is_path), Some(Direction::Left));
+
+    assert_eq!(maze.path(from, from, is_path), Some(Vec::new()));
+    assert_eq!(maze.direction_to(from, from, is_path), None);
+}
+
+#[test]
+fn only_the_ghosts_go_through_the_door() {
+    let maze = small_maze();
+    let [_, pinky, inky, _] = maze.ghost_starts;
+    let exit = IVec2::new(4, 1);
+
+    assert_eq!(maze.path(inky, exit, is_path), None);
+    assert_eq!(maze.path(exit, inky, is_path), None);
+
+    let through_door = |tile: Tile| tile != Tile::Wall;
+    assert_eq!(
+        maze.path(inky, exit, through_door),
+        Some(vec![IVec2::new(4, 2), exit])
+    );
+    assert_eq!(
+        maze.direction_to(pinky, exit, through_door),
+        Some(Direction::Right)
+    );
+}
+
+#[test]
+fn every_pellet_of_the_mazes_can_be_eaten() {
+    let mazes = load_mazes().expect("The mazes should be in assets/pacman/mazes");
+    assert_eq!(mazes.len(), NB_MAZES as usize);
+
+    for maze in mazes {
+        for &pellet in maze.pellets.iter().chain(&maze.power_pellets) {
+            assert!(
+                maze.path(maze.pacman_start, pellet, is_path).is_some(),
+                "{pellet} should be reachable"
+            );
+        }
+
+        for &start in &maze.ghost_starts[1..] {
+            assert!(maze
+                .path(start, maze.ghost_starts[0], |tile| tile != Tile::Wall)
+                .is_some());
+        }
+    }
+}
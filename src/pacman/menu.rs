@@ -0,0 +1,52 @@
+use bevy::color::palettes;
+use bevy::ecs::system::SystemParamItem;
+use bevy::prelude::*;
+
+use crate::game_menu::{GameMenu, GameMenuAction, GameMenuPlugin, Scores};
+use crate::menu_tree::Menu;
+
+use super::{
+    game::{CleanupGameEvent, LoadGameEvent, PacmanGame},
+    PacmanPlugin, PacmanState,
+};
+
+pub struct MenuPlugin;
+
+impl Plugin for MenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(GameMenuPlugin::<PacmanPlugin>::default());
+    }
+}
+
+impl GameMenu for PacmanPlugin {
+    type State = PacmanState;
+    const EXITED: PacmanState = PacmanState::Exited;
+    const IN_MENU: PacmanState = PacmanState::InMenu;
+    const IN_GAME: PacmanState = PacmanState::InGame;
+
+    type Game = PacmanGame;
+    type LoadGameEvent = LoadGameEvent;
+    type CleanupGameEvent = CleanupGameEvent;
+
+    type Action = ();
+    type MenuData = Scores<'static, PacmanPlugin>;
+
+    const COLOR: Color = Color::Srgba(palettes::css::YELLOW);
+
+    fn main_menu(scores: &SystemParamItem<Self::MenuData>) -> Menu<GameMenuAction<Self>> {
+        scores.main_menu()
+    }
+
+    fn status(scores: &SystemParamItem<Self::MenuData>, game: Option<&PacmanGame>) -> String {
+        scores.status(game.map(|game| game.score))
+    }
+
+    // From the first level, the command line can start from another one and
+    // "Restart" keeps the level being played
+    fn load_game_event(restart: bool) -> LoadGameEvent {
+        LoadGameEvent {
+            restart,
+            ..Default::default()
+        }
+    }
+}
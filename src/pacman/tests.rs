@@ -0,0 +1,253 @@
+use bevy::prelude::*;
+
+use crate::testing::{self, game, launched_app, play_game, run_frames, run_until, state, tap};
+
+use super::{
+    game::{GhostMode, GhostName, GhostState, Outcome, PacmanGame, LIVES, READY_TIME},
+    maze::{load_mazes, Direction, Maze},
+    *,
+};
+
+fn first_maze() -> Maze {
+    load_mazes()
+        .expect("The mazes should be in assets/pacman/mazes")
+        .swap_remove(0)
+}
+
+// A new game past its "READY!"
+fn ready_game() -> PacmanGame {
+    let mut game = PacmanGame::new(first_maze(), 1);
+    game.update(READY_TIME, |_| 0);
+    assert!(!game.is_ready());
+
+    game
+}
+
+// Updates the game frame by frame until it gives `outcome`
+fn run_game_until(game: &mut PacmanGame, max_frames: usize, outcome: Outcome) -> bool {
+    (0..max_frames).any(|_| game.update(1.0 / 60.0, |_| 0) == outcome)
+}
+
+#[test]
+fn each_ghost_has_its_own_target() {
+    let mut game = PacmanGame::new(first_maze(), 1);
+    let size = game.maze.size;
+
+    assert_eq!(game.mode, GhostMode::Scatter);
+    for name in GhostName::ALL {
+        assert_eq!(game.target(name), name.corner(size));
+    }
+
+    game.mode = GhostMode::Chase;
+    game.pacman.tile = IVec2::new(6, 5);
+    game.pacman.direction = Direction::Right;
+    game.ghosts[0].mover.tile = IVec2::new(1, 1);
+    game.ghosts[3].mover.tile = IVec2::new(21, 26);
+
+    assert_eq!(game.target(GhostName::Blinky), IVec2::new(6, 5));
+    assert_eq!(game.target(GhostName::Pinky), IVec2::new(10, 5));
+    // Twice as far from Blinky as two tiles ahead of Pac-Man
+    assert_eq!(game.target(GhostName::Inky), IVec2::new(15, 9));
+    assert_eq!(game.target(GhostName::Clyde), IVec2::new(6, 5));
+
+    // Clyde is shy up close
+    game.ghosts[3].mover.tile = IVec2::new(6, 8);
+    assert_eq!(game.target(GhostName::Clyde), GhostName::Clyde.corner(size));
+}
+
+#[test]
+fn the_game_waits_before_each_life() {
+    let mut game = PacmanGame::new(first_maze(), 1);
+    let pacman = game.pacman.tile;
+
+    game.update(READY_TIME / 2.0, |_| 0);
+    assert!(game.is_ready());
+    assert_eq!(game.pacman.tile, pacman);
+
+    game.update(READY_TIME / 2.0, |_| 0);
+    assert!(!game.is_ready());
+    assert_eq!(game.pacman.tile, pacman);
+}
+
+#[test]
+fn pacman_eats_the_pellets_on_his_way_and_stops_at_walls() {
+    let mut game = ready_game();
+    let pellets = game.pellets.len();
+    let start = game.pacman.tile;
+
+    // Seven pellets on the left, then a wall
+    for _ in 0..60 {
+        game.update(1.0 / 60.0, |_| 0);
+    }
+    assert!(game.pacman_stopped);
+    assert_eq!(game.pacman.tile, start - IVec2::new(7, 0));
+    assert_eq!(game.pellets.len(), pellets - 7);
+    assert_eq!(game.score, 70);
+
+    // Up the corridor
+    game.turn(Direction::Up);
+    for _ in 0..30 {
+        game.update(1.0 / 60.0, |_| 0);
+    }
+    assert!(!game.pacman_stopped);
+    assert_eq!(game.pacman.direction, Direction::Up);
+    assert!(game.pacman.tile.y < start.y);
+}
+
+#[test]
+fn pacman_turns_around_straight_away() {
+    let mut game = ready_game();
+    for _ in 0..5 {
+        game.update(1.0 / 60.0, |_| 0);
+    }
+    let position = game.pacman.position();
+
+    game.turn(Direction::Right);
+    assert_eq!(game.pacman.direction, Direction::Right);
+    assert!(game.pacman.position().distance(position) < 0.001);
+}
+
+#[test]
+fn the_ghosts_leave_the_house_through_the_door() {
+    let mut game = ready_game();
+    let exit = game.maze.ghost_starts[0];
+
+    // Pinky leaves straight away
+    game.update(1.0 / 60.0, |_| 0);
+    let pinky = game.ghosts[GhostName::Pinky as usize];
+    assert_eq!(pinky.state, GhostState::Leaving);
+    assert_eq!(pinky.mover.direction, Direction::Up);
+    assert_eq!(
+        game.ghosts[GhostName::Inky as usize].state,
+        GhostState::InHouse
+    );
+
+    for _ in 0..60 {
+        game.update(1.0 / 60.0, |_| 0);
+    }
+    let pinky = game.ghosts[GhostName::Pinky as usize];
+    assert_eq!(pinky.state, GhostState::Roaming);
+    assert!(pinky.mover.tile.y <= exit.y);
+}
+
+#[test]
+fn power_pellets_make_the_ghosts_edible() {
+    let mut game = ready_game();
+    let power_pellet = game.maze.power_pellets[0];
+
+    // Right under it
+    game.pacman.tile = power_pellet + IVec2::Y;
+    game.pacman.direction = Direction::Up;
+    game.wanted_direction = Direction::Up;
+
+    let score = game.score;
+    game.update(0.1, |_| 0);
+    assert!(!game.power_pellets.contains(&power_pellet));
+    assert!(game.ghosts.iter().all(|ghost| ghost.frightened));
+    assert_eq!(game.score, score + 50);
+
+    let blinky = &mut game.ghosts[GhostName::Blinky as usize];
+    blinky.mover = game.pacman;
+    assert_eq!(game.update(0.01, |_| 0), Outcome::Continue);
+    let blinky = game.ghosts[GhostName::Blinky as usize];
+    assert_eq!(blinky.state, GhostState::Eyes);
+    assert!(!blinky.frightened);
+    assert_eq!(game.score, score + 50 + 200);
+    assert_eq!(game.lives, LIVES);
+}
+
+#[test]
+fn ghosts_cost_lives_until_the_game_is_over() {
+    let mut game = ready_game();
+
+    game.ghosts[GhostName::Blinky as usize].mover = game.pacman;
+    assert_eq!(game.update(0.01, |_| 0), Outcome::Caught);
+    assert_eq!(game.lives, LIVES - 1);
+    assert!(!game.game_over);
+    assert!(game.is_ready());
+    assert_eq!(game.pacman.tile, game.maze.pacman_start);
+
+    game.update(READY_TIME, |_| 0);
+    game.lives = 1;
+    game.ghosts[GhostName::Blinky as usize].mover = game.pacman;
+    assert_eq!(game.update(0.01, |_| 0), Outcome::Caught);
+    assert!(game.game_over);
+}
+
+#[test]
+fn eating_every_pellet_clears_the_level() {
+    let mut game = ready_game();
+    let next_to_pacman = game.maze.pacman_start + IVec2::NEG_X;
+    game.pellets.retain(|&pellet| pellet == next_to_pacman);
+    game.power_pellets.clear();
+
+    assert!(run_game_until(&mut game, 60, Outcome::LevelCleared));
+
+    let mazes = load_mazes().unwrap();
+    game.next_level(mazes[1].clone());
+    assert_eq!(game.level, 2);
+    assert_eq!(game.pellets.len(), mazes[1].pellets.len());
+    assert!(game.is_ready());
+}
+
+#[test]
+fn the_game_pauses_and_resumes() {
+    let mut app = play_game(PacmanPlugin);
+    let start = game::<PacmanPlugin>(&app).pacman.tile;
+
+    run_frames(&mut app, 10);
+    assert!(game::<PacmanPlugin>(&app).is_ready());
+
+    assert!(run_until(&mut app, 600, |app| game::<PacmanPlugin>(app)
+        .pacman
+        .tile
+        != start));
+
+    tap(&mut app, KeyCode::Escape);
+    assert_eq!(state::<PacmanState>(&app), PacmanState::InMenu);
+    assert!(app.world().contains_resource::<PacmanGame>());
+    let position = game::<PacmanPlugin>(&app).pacman.position();
+    run_frames(&mut app, 10);
+    assert_eq!(game::<PacmanPlugin>(&app).pacman.position(), position);
+
+    // "Resume"
+    tap(&mut app, KeyCode::Space);
+    assert_eq!(state::<PacmanState>(&app), PacmanState::InGame);
+}
+
+#[test]
+fn restart_plays_the_level_given_on_the_command_line_again() {
+    let mut app = launched_app("--game pac-man --level 3");
+    assert_eq!(state::<PacmanState>(&app), PacmanState::InGame);
+    assert_eq!(game::<PacmanPlugin>(&app).level, 3);
+
+    // "Restart"
+    tap(&mut app, KeyCode::Escape);
+    tap(&mut app, KeyCode::ArrowDown);
+    tap(&mut app, KeyCode::Space);
+    run_frames(&mut app, 1);
+
+    assert_eq!(state::<PacmanState>(&app), PacmanState::InGame);
+    let game = game::<PacmanPlugin>(&app);
+    assert_eq!(game.level, 3);
+    assert_eq!(game.score, 0);
+    assert!(game.is_ready());
+}
+
+#[test]
+fn game_over_keeps_the_best_score_and_exit_cleans_up() {
+    // The last life lost to Blinky
+    testing::game_over_keeps_the_best_score_and_exit_cleans_up(
+        PacmanPlugin,
+        |app| {
+            let mut game = app.world_mut().resource_mut::<PacmanGame>();
+            game.score = 1230;
+            game.lives = 1;
+            game.update(READY_TIME, |_| 0);
+            let pacman = game.pacman;
+            game.ghosts[GhostName::Blinky as usize].mover = pacman;
+        },
+        10,
+        1230,
+    );
+}
@@ -0,0 +1,89 @@
+use bevy::prelude::*;
+
+mod menu;
+use menu::MenuPlugin;
+
+mod game;
+use game::{GamePlugin, LoadGameEvent, Players, PongOptions};
+
+use crate::demo::DemoSystems;
+use crate::game_menu::enter_menu;
+use crate::input::Action;
+use crate::launch::LaunchGame;
+use crate::registry::RetroGame;
+
+pub struct PongPlugin;
+
+impl RetroGame for PongPlugin {
+    const NAME: &'static str = "Pong";
+    const ICON: &'static str = "pong/icon.png";
+    const DESCRIPTION: &'static str =
+        "Send the ball past the other paddle, against the CPU or a friend on the same keyboard.";
+    const CONTROLS: &'static [Action] = &[
+        Action::MoveUp,
+        Action::MoveDown,
+        Action::Player1Up,
+        Action::Player1Down,
+        Action::Player2Up,
+        Action::Player2Down,
+        Action::Pause,
+    ];
+    const HAS_DEMO: bool = true;
+}
+
+impl Plugin for PongPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(MenuPlugin)
+            .add_plugins(GamePlugin)
+            .add_systems(
+                PreUpdate,
+                game::autoplay
+                    .in_set(DemoSystems)
+                    .run_if(in_state(PongPlugin::STATE)),
+            )
+            .add_systems(
+                OnEnter(PongPlugin::STATE),
+                launch
+                    .after(enter_menu::<PongPlugin>)
+                    .run_if(resource_exists::<LaunchGame>),
+            );
+    }
+}
+
+#[derive(States, PartialEq, Eq, Hash, Clone, Debug)]
+pub enum PongState {
+    Exited,
+    InMenu,
+    InGame,
+}
+
+// Straight into a game against the CPU or between two players, if the
+// command line says so
+fn launch(
+    mut commands: Commands,
+    launch_game: Res<LaunchGame>,
+    mut options: ResMut<PongOptions>,
+    mut load_game_event: EventWriter<LoadGameEvent>,
+    mut next_state: ResMut<NextState<PongState>>,
+) {
+    commands.remove_resource::<LaunchGame>();
+
+    if let Some(level) = launch_game.level {
+        warn!("Pong has no level {level} to start from");
+    }
+
+    let Some(name) = &launch_game.mode else {
+        return;
+    };
+    let Some(players) = Players::from_name(name) else {
+        warn!("Pong has no {name} mode, only cpu and versus");
+        return;
+    };
+
+    options.players = players;
+    load_game_event.send_default();
+    next_state.set(PongState::InGame);
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,565 @@
+use std::f32::consts::PI;
+
+use bevy::{color::palettes, prelude::*};
+
+use crate::{
+    application::GAME_AREA,
+    ball::Ball,
+    demo::tap_action,
+    input::Action,
+    registry::RetroGame,
+    replay::{AddReplayedResource, ReplayedResource},
+    rng::GameRng,
+};
+
+use super::{PongPlugin, PongState};
+
+pub const PADDLE_SIZE: Vec2 = Vec2 { x: 15.0, y: 100.0 };
+
+// From the sides of the game area to the middle of the paddles
+const PADDLE_MARGIN: f32 = 40.0;
+
+pub const PADDLE_SPEED: f32 = 400.0;
+
+const BALL_RADIUS: f32 = 8.0;
+const BALL_SPEED: f32 = 350.0;
+const BALL_SPEED_UP: f32 = 1.05;
+const MAX_BALL_SPEED: f32 = 800.0;
+
+// Seconds the ball waits in the middle before each serve
+const SERVE_DELAY: f32 = 1.0;
+const MAX_SERVE_ANGLE: f32 = PI / 6.0;
+
+// Speed of the CPU paddle at each level, from `PADDLE_SPEED`
+const CPU_SPEEDS: [f32; 5] = [0.35, 0.5, 0.65, 0.8, 1.0];
+pub const CPU_LEVELS: i32 = CPU_SPEEDS.len() as i32;
+
+// The CPU paddle stays still while the ball is this close to its middle
+const CPU_DEAD_ZONE: f32 = PADDLE_SIZE.y * 0.2;
+
+pub struct GamePlugin;
+
+impl Plugin for GamePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<LoadGameEvent>()
+            .add_event::<CleanupGameEvent>()
+            .init_resource::<PongOptions>()
+            .add_replayed_resource::<PongOptions>()
+            .init_resource::<LastResult>()
+            .add_systems(OnExit(PongPlugin::STATE), cleanup_game)
+            .add_systems(
+                Update,
+                (
+                    load_game.run_if(on_event::<LoadGameEvent>()),
+                    (
+                        handle_player_input,
+                        move_ball,
+                        solve_ball_colisions,
+                        score_points,
+                        end_game.run_if(is_over),
+                    )
+                        .chain()
+                        .run_if(in_state(PongState::InGame))
+                        .run_if(resource_exists::<PongGame>),
+                    cleanup_game.run_if(on_event::<CleanupGameEvent>()),
+                    (update_ball_transform, update_score_text).run_if(resource_exists::<PongGame>),
+                )
+                    .chain(),
+            );
+    }
+}
+
+#[derive(Event, Default)]
+pub struct LoadGameEvent {
+    pub restart: bool,
+}
+
+#[derive(Event, Default)]
+pub struct CleanupGameEvent;
+
+#[derive(Component, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Side {
+    Left,
+    Right,
+}
+
+impl Side {
+    fn index(self) -> usize {
+        match self {
+            Side::Left => 0,
+            Side::Right => 1,
+        }
+    }
+
+    pub fn opponent(self) -> Side {
+        match self {
+            Side::Left => Side::Right,
+            Side::Right => Side::Left,
+        }
+    }
+
+    // Towards the middle of the field
+    fn facing(self) -> Vec2 {
+        match self {
+            Side::Left => Vec2::X,
+            Side::Right => Vec2::NEG_X,
+        }
+    }
+
+    pub fn paddle_x(self) -> f32 {
+        match self {
+            Side::Left => GAME_AREA.min.x + PADDLE_MARGIN,
+            Side::Right => GAME_AREA.max.x - PADDLE_MARGIN,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub enum Players {
+    /// Against the CPU, which plays on the right
+    #[default]
+    One,
+    /// On the same keyboard, W and S on the left and the arrows on the right
+    Two,
+}
+
+impl Players {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "cpu" => Some(Players::One),
+            "versus" => Some(Players::Two),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Players::One => "cpu",
+            Players::Two => "versus",
+        }
+    }
+}
+
+#[derive(Resource, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct PongOptions {
+    pub players: Players,
+
+    /// From 1 to `CPU_LEVELS`, how fast the CPU paddle moves
+    pub cpu_level: i32,
+
+    pub points_to_win: i32,
+}
+
+impl Default for PongOptions {
+    fn default() -> Self {
+        Self {
+            players: Players::One,
+            cpu_level: 3,
+            points_to_win: 7,
+        }
+    }
+}
+
+// A `key = value` line for each option
+impl ReplayedResource for PongOptions {
+    const KEY: &'static str = "pong options";
+
+    fn to_text(&self) -> String {
+        format!(
+            "players = {}\ncpu_level = {}\npoints_to_win = {}\n",
+            self.players.name(),
+            self.cpu_level,
+            self.points_to_win
+        )
+    }
+
+    fn from_text(text: &str) -> Option<Self> {
+        let mut options = Self::default();
+        for line in text.lines() {
+            let (key, value) = line.split_once('=')?;
+            let value = value.trim();
+            match key.trim() {
+                "players" => options.players = Players::from_name(value)?,
+                "cpu_level" => options.cpu_level = value.parse().ok()?,
+                "points_to_win" => options.points_to_win = value.parse().ok()?,
+                _ => return None,
+            }
+        }
+
+        Some(options)
+    }
+}
+
+/// Final score of the last finished game and who played it
+#[derive(Resource, Default)]
+pub struct LastResult(pub Option<([u32; 2], Players)>);
+
+/// Score and serve of the game being played, the ball and the paddles are
+/// entities
+#[derive(Resource, Debug)]
+pub struct PongGame {
+    /// Of the left and the right player
+    pub scores: [u32; 2],
+
+    /// Side the ball is served to, the one that lost the last point
+    pub serve_to: Side,
+
+    pub ball_speed: f32,
+
+    // The ball waits in the middle until it finishes
+    serve_timer: Timer,
+}
+
+impl PongGame {
+    pub fn new(serve_to: Side) -> Self {
+        Self {
+            scores: [0, 0],
+            serve_to,
+            ball_speed: BALL_SPEED,
+            serve_timer: Timer::from_seconds(SERVE_DELAY, TimerMode::Once),
+        }
+    }
+
+    pub fn score(&self, side: Side) -> u32 {
+        self.scores[side.index()]
+    }
+
+    /// Whether the ball is waiting to be served
+    pub fn is_serving(&self) -> bool {
+        !self.serve_timer.finished()
+    }
+
+    fn win_point(&mut self, side: Side) {
+        self.scores[side.index()] += 1;
+        self.serve_to = side.opponent();
+        self.ball_speed = BALL_SPEED;
+        self.serve_timer.reset();
+    }
+}
+
+/// Where the paddle on `side` moves to meet `ball`: 1 up, -1 down or 0 to
+/// stay. It goes back to the middle while the ball goes away.
+pub fn follow_ball(side: Side, paddle_y: f32, ball: &Ball) -> f32 {
+    let target = if ball.velocity.dot(side.facing()) < 0.0 {
+        ball.current_position.y
+    } else {
+        GAME_AREA.center().y
+    };
+
+    let distance = target - paddle_y;
+    if distance.abs() < CPU_DEAD_ZONE {
+        0.0
+    } else {
+        distance.signum()
+    }
+}
+
+/// Part of `PADDLE_SPEED` the CPU paddle moves at
+pub fn cpu_speed(cpu_level: i32) -> f32 {
+    CPU_SPEEDS[(cpu_level.clamp(1, CPU_LEVELS) - 1) as usize]
+}
+
+/// Plays on the left against the CPU: starts a game from the menu and
+/// follows the ball like the CPU does
+pub(super) fn autoplay(
+    pong_state: Res<State<PongState>>,
+    paddles: Query<(&Side, &Transform)>,
+    balls: Query<&Ball>,
+    mut input: ResMut<ButtonInput<Action>>,
+) {
+    match pong_state.get() {
+        // "1 Player" is the first item of the menu
+        PongState::InMenu => tap_action(&mut input, Action::Confirm),
+        PongState::InGame => {
+            input.release_all();
+
+            let (Some((_, transform)), Ok(ball)) = (
+                paddles.iter().find(|(side, _)| **side == Side::Left),
+                balls.get_single(),
+            ) else {
+                return;
+            };
+            let direction = follow_ball(Side::Left, transform.translation.y, ball);
+            if direction > 0.0 {
+                input.press(Action::MoveUp);
+            } else if direction < 0.0 {
+                input.press(Action::MoveDown);
+            }
+        }
+        PongState::Exited => (),
+    }
+}
+
+#[derive(Component)]
+struct GameEntity;
+
+#[derive(Component)]
+struct ScoreText(Side);
+
+fn load_game(
+    mut commands: Commands,
+    mut load_events: EventReader<LoadGameEvent>,
+    mut game_rng: ResMut<GameRng>,
+    entities: Query<Entity, With<GameEntity>>,
+) {
+    if let Some(load_event) = load_events.read().last() {
+        if load_event.restart {
+            game_rng.restart_run();
+        } else {
+            game_rng.new_run();
+        }
+    }
+
+    for entity in &entities {
+        commands.entity(entity).despawn();
+    }
+
+    let serve_to = if game_rng.random() {
+        Side::Left
+    } else {
+        Side::Right
+    };
+    commands.insert_resource(PongGame::new(serve_to));
+
+    commands.spawn((
+        SpriteBundle {
+            sprite: Sprite {
+                color: Color::BLACK,
+                custom_size: Some(GAME_AREA.size()),
+                ..Default::default()
+            },
+            transform: Transform::from_translation(Vec3::from((GAME_AREA.center(), -1.0))),
+            ..Default::default()
+        },
+        GameEntity,
+    ));
+
+    // Dashed line in the middle
+    let nb_dashes = 15;
+    let dash_height = GAME_AREA.height() / nb_dashes as f32;
+    for i in 0..nb_dashes {
+        commands.spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    color: palettes::css::DIM_GRAY.into(),
+                    custom_size: Some(Vec2::new(4.0, dash_height / 2.0)),
+                    ..Default::default()
+                },
+                transform: Transform::from_translation(Vec3::new(
+                    GAME_AREA.center().x,
+                    GAME_AREA.min.y + (i as f32 + 0.5) * dash_height,
+                    -0.5,
+                )),
+                ..Default::default()
+            },
+            GameEntity,
+        ));
+    }
+
+    for side in [Side::Left, Side::Right] {
+        commands.spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    color: Color::WHITE,
+                    custom_size: Some(PADDLE_SIZE),
+                    ..Default::default()
+                },
+                transform: Transform::from_translation(Vec3::new(
+                    side.paddle_x(),
+                    GAME_AREA.center().y,
+                    0.0,
+                )),
+                ..Default::default()
+            },
+            side,
+            GameEntity,
+        ));
+
+        commands.spawn((
+            Text2dBundle {
+                text: Text::from_section(
+                    "0",
+                    TextStyle {
+                        font_size: 60.0,
+                        color: Color::WHITE,
+                        ..Default::default()
+                    },
+                ),
+                transform: Transform::from_translation(Vec3::new(
+                    GAME_AREA.center().x - side.facing().x * 80.0,
+                    GAME_AREA.max.y - 50.0,
+                    0.0,
+                )),
+                ..Default::default()
+            },
+            ScoreText(side),
+            GameEntity,
+        ));
+    }
+
+    commands.spawn((
+        SpriteBundle {
+            sprite: Sprite {
+                color: Color::WHITE,
+                custom_size: Some(Vec2::splat(BALL_RADIUS * 2.0)),
+                ..Default::default()
+            },
+            transform: Transform::from_translation(Vec3::from((GAME_AREA.center(), 1.0))),
+            ..Default::default()
+        },
+        Ball {
+            radius: BALL_RADIUS,
+            velocity: Vec2::ZERO,
+            current_position: GAME_AREA.center(),
+            old_position: GAME_AREA.center(),
+        },
+        GameEntity,
+    ));
+}
+
+fn cleanup_game(mut commands: Commands, entities: Query<Entity, With<GameEntity>>) {
+    for entity in &entities {
+        commands.entity(entity).despawn();
+    }
+
+    commands.remove_resource::<PongGame>();
+}
+
+fn handle_player_input(
+    time: Res<Time>,
+    input: Res<ButtonInput<Action>>,
+    options: Res<PongOptions>,
+    mut paddles: Query<(&Side, &mut Transform)>,
+    balls: Query<&Ball>,
+    mut next_pong_state: ResMut<NextState<PongState>>,
+) {
+    if input.just_pressed(Action::Pause) {
+        next_pong_state.set(PongState::InMenu);
+        return;
+    }
+
+    let axis = |up, down| input.pressed(up) as i32 as f32 - input.pressed(down) as i32 as f32;
+
+    for (side, mut transform) in &mut paddles {
+        let speed = match (options.players, side) {
+            (Players::One, Side::Left) => axis(Action::MoveUp, Action::MoveDown),
+            (Players::One, Side::Right) => balls.get_single().map_or(0.0, |ball| {
+                follow_ball(*side, transform.translation.y, ball) * cpu_speed(options.cpu_level)
+            }),
+            (Players::Two, Side::Left) => axis(Action::Player1Up, Action::Player1Down),
+            (Players::Two, Side::Right) => axis(Action::Player2Up, Action::Player2Down),
+        };
+
+        let max_y = GAME_AREA.max.y - PADDLE_SIZE.y / 2.0;
+        let min_y = GAME_AREA.min.y + PADDLE_SIZE.y / 2.0;
+        transform.translation.y = (transform.translation.y
+            + speed * PADDLE_SPEED * time.delta_seconds())
+        .clamp(min_y, max_y);
+    }
+}
+
+fn move_ball(
+    time: Res<Time>,
+    mut game: ResMut<PongGame>,
+    mut balls: Query<&mut Ball>,
+    mut game_rng: ResMut<GameRng>,
+) {
+    let Ok(mut ball) = balls.get_single_mut() else {
+        return;
+    };
+
+    if game.is_serving() {
+        if !game.serve_timer.tick(time.delta()).just_finished() {
+            return;
+        }
+
+        let angle = (game_rng.random::<f32>() * 2.0 - 1.0) * MAX_SERVE_ANGLE;
+        ball.velocity = Vec2::from_angle(angle) * -game.serve_to.facing().x;
+    }
+
+    let velocity = ball.velocity;
+    ball.old_position = ball.current_position;
+    ball.current_position += velocity * game.ball_speed * time.delta_seconds();
+}
+
+fn solve_ball_colisions(
+    mut game: ResMut<PongGame>,
+    mut balls: Query<&mut Ball>,
+    paddles: Query<(&Side, &Transform)>,
+) {
+    let Ok(mut ball) = balls.get_single_mut() else {
+        return;
+    };
+
+    // Top and bottom
+    if ball.current_position.y + ball.radius > GAME_AREA.max.y {
+        ball.current_position.y = GAME_AREA.max.y - ball.radius;
+        ball.velocity.y *= -1.0;
+    }
+    if ball.current_position.y - ball.radius < GAME_AREA.min.y {
+        ball.current_position.y = GAME_AREA.min.y + ball.radius;
+        ball.velocity.y *= -1.0;
+    }
+
+    for (side, transform) in &paddles {
+        let paddle_rect = Rect::from_center_size(transform.translation.xy(), PADDLE_SIZE);
+        if ball.bounce_of_player_rect(paddle_rect, side.facing()) {
+            game.ball_speed = (game.ball_speed * BALL_SPEED_UP).min(MAX_BALL_SPEED);
+        }
+    }
+}
+
+// A point for the other side when the ball leaves the field on one side
+fn score_points(mut game: ResMut<PongGame>, mut balls: Query<&mut Ball>) {
+    let Ok(mut ball) = balls.get_single_mut() else {
+        return;
+    };
+
+    let scorer = if ball.current_position.x < GAME_AREA.min.x {
+        Side::Right
+    } else if ball.current_position.x > GAME_AREA.max.x {
+        Side::Left
+    } else {
+        return;
+    };
+
+    game.win_point(scorer);
+    ball.velocity = Vec2::ZERO;
+    ball.current_position = GAME_AREA.center();
+    ball.old_position = GAME_AREA.center();
+}
+
+// Once a side has the points to win
+fn is_over(game: Option<Res<PongGame>>, options: Res<PongOptions>) -> bool {
+    game.is_some_and(|game| {
+        game.scores
+            .iter()
+            .any(|score| *score >= options.points_to_win as u32)
+    })
+}
+
+// Back to the main menu, which shows the final score
+fn end_game(
+    game: Res<PongGame>,
+    options: Res<PongOptions>,
+    mut last_result: ResMut<LastResult>,
+    mut cleanup_game_event: EventWriter<CleanupGameEvent>,
+    mut next_pong_state: ResMut<NextState<PongState>>,
+) {
+    last_result.0 = Some((game.scores, options.players));
+
+    cleanup_game_event.send_default();
+    next_pong_state.set(PongState::InMenu);
+}
+
+fn update_ball_transform(mut balls: Query<(&Ball, &mut Transform)>) {
+    for (ball, mut transform) in &mut balls {
+        transform.translation = Vec3::from((ball.current_position, 1.0));
+    }
+}
+
+fn update_score_text(game: Res<PongGame>, mut texts: Query<(&ScoreText, &mut Text)>) {
+    for (ScoreText(side), mut text) in &mut texts {
+        text.sections[0].value = game.score(*side).to_string();
+    }
+}
@@ -0,0 +1,123 @@
+use bevy::ecs::system::{SystemParam, SystemParamItem};
+use bevy::prelude::*;
+
+use crate::game_menu::{GameMenu, GameMenuAction, GameMenuPlugin};
+use crate::menu_tree::{Menu, MenuActionEvent, MenuTreeSystems};
+
+use super::{
+    game::{
+        CleanupGameEvent, LastResult, LoadGameEvent, Players, PongGame, PongOptions, CPU_LEVELS,
+    },
+    PongPlugin, PongState,
+};
+
+pub struct MenuPlugin;
+
+impl Plugin for MenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(GameMenuPlugin::<PongPlugin>::default())
+            .add_systems(
+                Update,
+                set_options
+                    .after(MenuTreeSystems::<GameMenuAction<PongPlugin>>::default())
+                    .run_if(on_event::<MenuActionEvent<GameMenuAction<PongPlugin>>>()),
+            );
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum PongMenuAction {
+    Play(Players),
+    CpuLevel(i32),
+    PointsToWin(i32),
+}
+
+// What the main menu and the result below it show
+#[derive(SystemParam)]
+pub struct MainMenuOptions<'w> {
+    options: Res<'w, PongOptions>,
+    last_result: Res<'w, LastResult>,
+}
+
+impl GameMenu for PongPlugin {
+    type State = PongState;
+    const EXITED: PongState = PongState::Exited;
+    const IN_MENU: PongState = PongState::InMenu;
+    const IN_GAME: PongState = PongState::InGame;
+
+    type Game = PongGame;
+    type LoadGameEvent = LoadGameEvent;
+    type CleanupGameEvent = CleanupGameEvent;
+
+    type Action = PongMenuAction;
+    type MenuData = MainMenuOptions<'static>;
+
+    const COLOR: Color = Color::WHITE;
+
+    fn main_menu(options: &SystemParamItem<Self::MenuData>) -> Menu<GameMenuAction<Self>> {
+        let options = &options.options;
+        Menu::new()
+            .action(
+                "1 Player",
+                GameMenuAction::Game(PongMenuAction::Play(Players::One)),
+            )
+            .action(
+                "2 Players",
+                GameMenuAction::Game(PongMenuAction::Play(Players::Two)),
+            )
+            .slider("CPU level", options.cpu_level, 1..=CPU_LEVELS, 1, |level| {
+                GameMenuAction::Game(PongMenuAction::CpuLevel(level))
+            })
+            .slider(
+                "Points to win",
+                options.points_to_win,
+                3..=21,
+                1,
+                |points| GameMenuAction::Game(PongMenuAction::PointsToWin(points)),
+            )
+            .action("Exit", GameMenuAction::Exit)
+    }
+
+    fn status(options: &SystemParamItem<Self::MenuData>, game: Option<&PongGame>) -> String {
+        match (game, options.last_result.0) {
+            (Some(game), _) => format!("{} - {}", game.scores[0], game.scores[1]),
+            (None, Some(([left, right], players))) => {
+                let winner = match (players, left > right) {
+                    (Players::One, true) => "You win",
+                    (Players::One, false) => "The CPU wins",
+                    (Players::Two, true) => "Player 1 wins",
+                    (Players::Two, false) => "Player 2 wins",
+                };
+                format!("{winner} {left} - {right}")
+            }
+            (None, None) => String::new(),
+        }
+    }
+
+    fn load_game_event(restart: bool) -> LoadGameEvent {
+        LoadGameEvent { restart }
+    }
+}
+
+// What the actions change besides the menu, the players also start a game
+fn set_options(
+    mut menu_actions: EventReader<MenuActionEvent<GameMenuAction<PongPlugin>>>,
+    mut options: ResMut<PongOptions>,
+    mut load_game_event: EventWriter<LoadGameEvent>,
+    mut next_state: ResMut<NextState<PongState>>,
+) {
+    for MenuActionEvent(action) in menu_actions.read() {
+        let GameMenuAction::Game(action) = *action else {
+            continue;
+        };
+        match action {
+            PongMenuAction::Play(players) => {
+                options.players = players;
+                load_game_event.send_default();
+                next_state.set(PongState::InGame);
+            }
+            PongMenuAction::CpuLevel(level) => options.cpu_level = level,
+            PongMenuAction::PointsToWin(points) => options.points_to_win = points,
+        }
+    }
+}
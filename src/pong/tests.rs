@@ -0,0 +1,170 @@
+use bevy::prelude::*;
+
+use crate::{
+    application::{CurrentGame, GAME_AREA},
+    ball::Ball,
+    testing::{
+        game, game_app, play, play_game, press, release, run_frames, run_until, snapshot, state,
+        tap,
+    },
+};
+
+use super::{
+    game::{follow_ball, LastResult, PongGame, Side, PADDLE_SIZE},
+    *,
+};
+
+fn paddle_y(app: &mut App, side: Side) -> f32 {
+    let mut paddles = app.world_mut().query::<(&Side, &Transform)>();
+    paddles
+        .iter(app.world())
+        .find(|(paddle, _)| **paddle == side)
+        .map(|(_, transform)| transform.translation.y)
+        .unwrap()
+}
+
+fn set_ball(app: &mut App, position: Vec2, velocity: Vec2) {
+    let mut balls = app.world_mut().query::<&mut Ball>();
+    let mut ball = balls.single_mut(app.world_mut());
+    ball.current_position = position;
+    ball.old_position = position;
+    ball.velocity = velocity;
+}
+
+fn ball(app: &mut App) -> Ball {
+    let mut balls = app.world_mut().query::<&Ball>();
+    *balls.single(app.world())
+}
+
+#[test]
+fn the_cpu_follows_the_ball_coming_to_it() {
+    let ball = |y: f32, velocity_x: f32| Ball {
+        radius: 8.0,
+        velocity: Vec2::new(velocity_x, 0.0),
+        current_position: Vec2::new(GAME_AREA.center().x, y),
+        old_position: Vec2::new(GAME_AREA.center().x, y),
+    };
+    let middle = GAME_AREA.center().y;
+
+    assert_eq!(
+        follow_ball(Side::Right, middle, &ball(middle + 200.0, 1.0)),
+        1.0
+    );
+    assert_eq!(
+        follow_ball(Side::Right, middle, &ball(middle - 200.0, 1.0)),
+        -1.0
+    );
+    assert_eq!(
+        follow_ball(Side::Right, middle, &ball(middle + 5.0, 1.0)),
+        0.0
+    );
+
+    // Going to the other side, back to the middle
+    assert_eq!(
+        follow_ball(Side::Right, middle + 200.0, &ball(middle + 200.0, -1.0)),
+        -1.0
+    );
+    assert_eq!(
+        follow_ball(Side::Left, middle, &ball(middle + 200.0, -1.0)),
+        1.0
+    );
+}
+
+#[test]
+fn two_players_share_the_keyboard() {
+    // "2 Players"
+    let mut app = game_app(PongPlugin);
+    play::<PongPlugin>(&mut app, 1);
+    let left = paddle_y(&mut app, Side::Left);
+    let right = paddle_y(&mut app, Side::Right);
+
+    press(&mut app, KeyCode::KeyW);
+    run_frames(&mut app, 10);
+    release(&mut app, KeyCode::KeyW);
+    assert!(paddle_y(&mut app, Side::Left) > left);
+    assert_eq!(paddle_y(&mut app, Side::Right), right);
+
+    press(&mut app, KeyCode::ArrowDown);
+    run_frames(&mut app, 10);
+    release(&mut app, KeyCode::ArrowDown);
+    assert!(paddle_y(&mut app, Side::Right) < right);
+}
+
+#[test]
+fn the_ball_bounces_off_the_paddles_faster() {
+    let mut app = play_game(PongPlugin);
+    let speed = game::<PongPlugin>(&app).ball_speed;
+
+    // Let the serve go, then send the ball at the left paddle
+    run_frames(&mut app, 70);
+    let left = Vec2::new(Side::Left.paddle_x(), paddle_y(&mut app, Side::Left));
+    set_ball(
+        &mut app,
+        left + Vec2::X * (PADDLE_SIZE.x + 20.0),
+        Vec2::NEG_X,
+    );
+
+    assert!(run_until(&mut app, 10, |app| ball(app).velocity.x > 0.0));
+    assert!(game::<PongPlugin>(&app).ball_speed > speed);
+}
+
+#[test]
+fn points_are_won_past_the_paddles_and_served_to_the_loser() {
+    let mut app = play_game(PongPlugin);
+    assert!(game::<PongPlugin>(&app).is_serving());
+
+    run_frames(&mut app, 70);
+    assert!(!game::<PongPlugin>(&app).is_serving());
+    set_ball(&mut app, Vec2::new(GAME_AREA.max.x - 1.0, 0.0), Vec2::X);
+
+    assert!(run_until(&mut app, 10, |app| game::<PongPlugin>(app)
+        .score(Side::Left)
+        == 1));
+    assert_eq!(game::<PongPlugin>(&app).score(Side::Right), 0);
+    assert_eq!(game::<PongPlugin>(&app).serve_to, Side::Right);
+    assert!(game::<PongPlugin>(&app).is_serving());
+    assert_eq!(ball(&mut app).current_position, GAME_AREA.center());
+}
+
+#[test]
+fn the_game_ends_at_the_points_to_win_and_exit_cleans_up() {
+    let mut app = game_app(PongPlugin);
+    let in_launcher = snapshot(&app);
+    play::<PongPlugin>(&mut app, 0);
+
+    run_frames(&mut app, 70);
+    app.world_mut().resource_mut::<PongGame>().scores = [6, 3];
+    set_ball(&mut app, Vec2::new(GAME_AREA.max.x - 1.0, 0.0), Vec2::X);
+
+    assert!(run_until(&mut app, 10, |app| {
+        state::<PongState>(app) == PongState::InMenu
+    }));
+    run_frames(&mut app, 1);
+    assert!(!app.world().contains_resource::<PongGame>());
+    assert_eq!(
+        app.world().resource::<LastResult>().0,
+        Some(([7, 3], Players::One))
+    );
+
+    // "Exit", below the players and the sliders
+    for _ in 0..4 {
+        tap(&mut app, KeyCode::ArrowDown);
+    }
+    tap(&mut app, KeyCode::Space);
+    run_frames(&mut app, 1);
+    assert_eq!(state::<CurrentGame>(&app), CurrentGame::InMainMenu);
+    assert_eq!(snapshot(&app), in_launcher);
+}
+
+#[test]
+fn pausing_keeps_the_game() {
+    let mut app = play_game(PongPlugin);
+
+    tap(&mut app, KeyCode::Escape);
+    assert_eq!(state::<PongState>(&app), PongState::InMenu);
+    assert!(app.world().contains_resource::<PongGame>());
+
+    // "Resume"
+    tap(&mut app, KeyCode::Space);
+    assert_eq!(state::<PongState>(&app), PongState::InGame);
+}
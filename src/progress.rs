@@ -0,0 +1,116 @@
+use std::{collections::BTreeMap, fmt::Write};
+
+use bevy::prelude::*;
+
+use crate::settings::SettingsFile;
+
+/// Best results on a level of a campaign, each kept on its own: the best
+/// time may come from another run than the best score
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct LevelRecord {
+    pub score: u32,
+    /// Seconds taken to complete the level
+    pub time: f32,
+    /// From 1 to 3
+    pub stars: u8,
+}
+
+/// Levels completed in the campaign of each game, by game name then level
+/// number from 1. A level is unlocked once the one before it is completed.
+#[derive(Resource, Clone, PartialEq, Default, Debug)]
+pub struct CampaignProgress {
+    games: BTreeMap<String, BTreeMap<usize, LevelRecord>>,
+}
+
+impl CampaignProgress {
+    pub fn get(&self, game: &str, level: usize) -> Option<LevelRecord> {
+        self.games.get(game)?.get(&level).copied()
+    }
+
+    pub fn is_completed(&self, game: &str, level: usize) -> bool {
+        self.get(game, level).is_some()
+    }
+
+    pub fn is_unlocked(&self, game: &str, level: usize) -> bool {
+        level <= 1 || self.is_completed(game, level - 1)
+    }
+
+    /// Keeps whatever `record` beats in the records of `level`, returns
+    /// whether it beat any
+    pub fn submit(&mut self, game: &str, level: usize, record: LevelRecord) -> bool {
+        let levels = self.games.entry(game.to_string()).or_default();
+        let Some(best) = levels.get_mut(&level) else {
+            levels.insert(level, record);
+            return true;
+        };
+
+        let beaten =
+            record.score > best.score || record.time < best.time || record.stars > best.stars;
+        best.score = best.score.max(record.score);
+        best.time = best.time.min(record.time);
+        best.stars = best.stars.max(record.stars);
+
+        beaten
+    }
+}
+
+impl SettingsFile for CampaignProgress {
+    const PATH: &'static str = "settings/campaign_progress.txt";
+    const NAME: &'static str = "campaign progress";
+
+    // Each game under a `[Game]` line, then one `level = score time stars`
+    // line per completed level
+    fn to_text(&self) -> String {
+        let mut text = String::new();
+
+        for (game, levels) in &self.games {
+            let _ = writeln!(text, "[{game}]");
+            for (level, record) in levels {
+                let _ = writeln!(
+                    text,
+                    "{level} = {} {} {}",
+                    record.score, record.time, record.stars
+                );
+            }
+        }
+
+        text
+    }
+
+    fn from_text(text: &str) -> Option<Self> {
+        let mut progress = Self::default();
+        let mut current = None;
+
+        for line in text.lines().map(str::trim).filter(|line| !line.is_empty()) {
+            if let Some(game) = line
+                .strip_prefix('[')
+                .and_then(|line| line.strip_suffix(']'))
+            {
+                current = Some(game.to_string());
+                progress.games.entry(game.to_string()).or_default();
+                continue;
+            }
+
+            let (level, record) = line.split_once('=')?;
+            let words: Vec<&str> = record.split_whitespace().collect();
+            let [score, time, stars] = words[..] else {
+                return None;
+            };
+            let record = LevelRecord {
+                score: score.parse().ok()?,
+                time: time.parse().ok()?,
+                stars: stars.parse().ok()?,
+            };
+
+            progress
+                .games
+                .get_mut(current.as_ref()?)?
+                .insert(level.trim().parse().ok()?, record);
+        }
+
+        Some(progress)
+    }
+}
+
+#[cfg(test)]
+mod tests;
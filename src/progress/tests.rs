@@ -0,0 +1,45 @@
+use super::*;
+
+fn record(score: u32, time: f32, stars: u8) -> LevelRecord {
+    LevelRecord { score, time, stars }
+}
+
+#[test]
+fn levels_unlock_one_after_the_other() {
+    let mut progress = CampaignProgress::default();
+    assert!(progress.is_unlocked("Breakout", 1));
+    assert!(!progress.is_unlocked("Breakout", 2));
+
+    progress.submit("Breakout", 1, record(500, 60.0, 2));
+    assert!(progress.is_unlocked("Breakout", 2));
+    assert!(!progress.is_unlocked("Breakout", 3));
+    assert!(!progress.is_unlocked("Pong", 2));
+}
+
+#[test]
+fn each_record_is_kept_on_its_own() {
+    let mut progress = CampaignProgress::default();
+
+    assert!(progress.submit("Breakout", 1, record(500, 60.0, 2)));
+    assert!(!progress.submit("Breakout", 1, record(400, 70.0, 1)));
+    assert!(progress.submit("Breakout", 1, record(450, 50.0, 1)));
+    assert!(progress.submit("Breakout", 1, record(800, 90.0, 3)));
+
+    assert_eq!(progress.get("Breakout", 1), Some(record(800, 50.0, 3)));
+    assert_eq!(progress.get("Breakout", 2), None);
+}
+
+#[test]
+fn text_round_trip() {
+    let mut progress = CampaignProgress::default();
+    progress.submit("Breakout", 1, record(1200, 83.5, 3));
+    progress.submit("Breakout", 2, record(900, 140.25, 1));
+    progress.submit("Pong", 1, record(7, 45.0, 2));
+
+    assert_eq!(
+        CampaignProgress::from_text(&progress.to_text()),
+        Some(progress)
+    );
+    assert_eq!(CampaignProgress::from_text("1 = 1200 83.5 3"), None);
+    assert_eq!(CampaignProgress::from_text("[Breakout]\n1 = 1200 3"), None);
+}
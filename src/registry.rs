@@ -0,0 +1,91 @@
+//! Games register themselves with `App::add_game`, the launcher lists every
+//! registered game without knowing about them
+
+use bevy::prelude::*;
+
+use crate::application::CurrentGame;
+use crate::input::Action;
+
+/// Plugin of a game playable from the launcher
+pub trait RetroGame: Plugin {
+    /// Shown in the launcher, also identifies the game in replays
+    const NAME: &'static str;
+
+    /// Asset path of the image standing for the game
+    const ICON: &'static str;
+
+    const DESCRIPTION: &'static str;
+
+    /// Actions the game reads, listed with their keys in the launcher
+    const CONTROLS: &'static [Action];
+
+    /// Whether the game has a bot in `DemoSystems`, the launcher then plays
+    /// demos of it when left idle
+    const HAS_DEMO: bool = false;
+
+    /// State of the launcher while the game is played, the game sets itself
+    /// up and cleans up after itself in `OnEnter` and `OnExit` of it
+    const STATE: CurrentGame = CurrentGame::Playing(Self::NAME);
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct GameInfo {
+    pub name: &'static str,
+    pub icon: &'static str,
+    pub description: &'static str,
+    pub controls: &'static [Action],
+    pub has_demo: bool,
+}
+
+impl GameInfo {
+    pub fn of<G: RetroGame>() -> Self {
+        Self {
+            name: G::NAME,
+            icon: G::ICON,
+            description: G::DESCRIPTION,
+            controls: G::CONTROLS,
+            has_demo: G::HAS_DEMO,
+        }
+    }
+
+    pub fn state(&self) -> CurrentGame {
+        CurrentGame::Playing(self.name)
+    }
+}
+
+/// Registered games, in registration order
+#[derive(Resource, Default)]
+pub struct GameRegistry {
+    games: Vec<GameInfo>,
+}
+
+impl GameRegistry {
+    pub fn games(&self) -> &[GameInfo] {
+        &self.games
+    }
+
+    pub fn get(&self, name: &str) -> Option<&GameInfo> {
+        self.games.iter().find(|game| game.name == name)
+    }
+}
+
+pub trait AddGame {
+    /// Adds the game's plugin and lists it in the launcher
+    fn add_game<G: RetroGame>(&mut self, game: G) -> &mut Self;
+}
+
+impl AddGame for App {
+    fn add_game<G: RetroGame>(&mut self, game: G) -> &mut Self {
+        self.init_resource::<GameRegistry>();
+
+        let mut registry = self.world_mut().resource_mut::<GameRegistry>();
+        assert!(
+            registry.get(G::NAME).is_none(),
+            "{} is registered twice",
+            G::NAME
+        );
+        registry.games.push(GameInfo::of::<G>());
+
+        self.add_plugins(game)
+    }
+}
@@ -0,0 +1,775 @@
+use std::{fs, io, mem, path::Path, time::Duration};
+
+use bevy::{
+    app::MainScheduleOrder,
+    color::palettes,
+    ecs::{
+        schedule::{InternedScheduleLabel, ScheduleLabel},
+        system::SystemParam,
+    },
+    input::{keyboard::KeyboardInput, ButtonState, InputSystem},
+    prelude::*,
+    time::TimeUpdateStrategy,
+    window::{PresentMode, PrimaryWindow},
+};
+
+use crate::{
+    application::{CurrentGame, InGame, GAME_AREA, WINDOW_RESOLUTION},
+    demo::Demo,
+    input::{Action, ActionSource, ActionSystem, AnalogInput, InputMap},
+    launch::LaunchGame,
+    registry::GameRegistry,
+    rng::{FixedSeed, GameRng},
+    settings::SettingsFile,
+};
+
+pub const LAST_RUN_REPLAY: &str = "replays/last_run.replay";
+
+const REPLAY_MAGIC: &[u8; 4] = b"RTRP";
+const REPLAY_VERSION: u8 = 1;
+
+// Bytes of a tick in a replay file
+const TICK_SIZE: usize = 11;
+
+// Stored instead of the pointer x when the pointer is not followed
+const NO_POINTER: i16 = i16::MIN;
+
+// Seconds skipped by one scrub step
+const SCRUB_STEP: f32 = 5.0;
+
+// Ticks played per frame when fast-forwarding
+const FAST_FORWARD_TICKS: usize = 8;
+
+// Frames spent in the launcher before a replay starts over, so that the
+// games' own states are back to `Exited` like when the run was recorded
+const RESTART_FRAMES: u8 = 2;
+
+pub struct ReplayPlugin;
+
+impl Plugin for ReplayPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<StartReplayEvent>()
+            .add_replayed_resource::<LaunchGame>()
+            .add_replayed_resource::<InputMap>()
+            .add_systems(
+                OnExit(CurrentGame::InMainMenu),
+                keep_starting_resources.run_if(not(
+                    resource_exists::<Playback>.or_else(resource_exists::<Demo>)
+                )),
+            )
+            .add_systems(
+                OnEnter(InGame),
+                start_recording.run_if(not(
+                    resource_exists::<Playback>.or_else(resource_exists::<Demo>)
+                )),
+            )
+            .add_systems(
+                OnEnter(CurrentGame::InMainMenu),
+                save_recording.run_if(resource_exists::<Recording>),
+            )
+            .add_systems(
+                PreUpdate,
+                (
+                    handle_playback_input.before(InputSystem),
+                    apply_playback_actions.after(ActionSystem),
+                )
+                    .run_if(resource_exists::<Playback>),
+            )
+            .add_systems(
+                Last,
+                (
+                    (record_tick, save_recording.run_if(on_event::<AppExit>()))
+                        .chain()
+                        .run_if(resource_exists::<Recording>),
+                    (update_replay_hud, advance_playback, stop_playback)
+                        .chain()
+                        .run_if(resource_exists::<Playback>),
+                    start_playback.run_if(on_event::<StartReplayEvent>()),
+                )
+                    .chain(),
+            )
+            .add_systems(
+                FastForward,
+                fast_forward.run_if(resource_exists::<Playback>),
+            );
+    }
+
+    // Once every plugin added its schedules to the frame
+    fn finish(&self, app: &mut App) {
+        let mut order = app.world_mut().resource_mut::<MainScheduleOrder>();
+        let frame_schedules = FrameSchedules(order.labels.clone());
+        order.insert_after(Last, FastForward);
+
+        app.insert_resource(frame_schedules);
+    }
+}
+
+/// Runs after `Last` to play the extra ticks of a fast-forwarded frame
+#[derive(ScheduleLabel, Clone, PartialEq, Eq, Hash, Debug)]
+struct FastForward;
+
+// The schedules of a frame, `FastForward` runs them again for each extra
+// tick since `MainScheduleOrder` is taken while the frame runs
+#[derive(Resource)]
+struct FrameSchedules(Vec<InternedScheduleLabel>);
+
+/// State a run depends on besides its seed and its inputs, like the options
+/// chosen in a menu or the levels unlocked. Replays keep it as it was when
+/// their run started and put it back while they are played.
+pub trait ReplayedResource: Resource + Sized {
+    /// Names it in the replay files
+    const KEY: &'static str;
+
+    fn to_text(&self) -> String;
+
+    /// `None` if `text` is not what `to_text` writes
+    fn from_text(text: &str) -> Option<Self>;
+}
+
+impl<S: SettingsFile> ReplayedResource for S {
+    const KEY: &'static str = S::NAME;
+
+    fn to_text(&self) -> String {
+        SettingsFile::to_text(self)
+    }
+
+    fn from_text(text: &str) -> Option<Self> {
+        <S as SettingsFile>::from_text(text)
+    }
+}
+
+pub trait AddReplayedResource {
+    /// Keeps `R` in the replays, each game adds the resources its runs
+    /// depend on
+    fn add_replayed_resource<R: ReplayedResource>(&mut self) -> &mut Self;
+}
+
+impl AddReplayedResource for App {
+    fn add_replayed_resource<R: ReplayedResource>(&mut self) -> &mut Self {
+        let mut replayed = self
+            .world_mut()
+            .get_resource_or_insert_with(ReplayedResources::default);
+        if !replayed.0.iter().any(|resource| resource.key == R::KEY) {
+            replayed.0.push(ReplayedType::of::<R>());
+        }
+
+        self
+    }
+}
+
+#[derive(Resource, Default)]
+struct ReplayedResources(Vec<ReplayedType>);
+
+struct ReplayedType {
+    key: &'static str,
+    to_text: fn(&World) -> Option<String>,
+    // Inserts the resource read from the text, or removes it without one
+    put_back: fn(&mut World, Option<&str>),
+}
+
+impl ReplayedType {
+    fn of<R: ReplayedResource>() -> Self {
+        Self {
+            key: R::KEY,
+            to_text: |world| world.get_resource::<R>().map(R::to_text),
+            put_back: |world, text| match text.map(R::from_text) {
+                Some(Some(resource)) => world.insert_resource(resource),
+                Some(None) => warn!("The replay has an invalid {}", R::KEY),
+                None => {
+                    world.remove_resource::<R>();
+                }
+            },
+        }
+    }
+}
+
+// Text of each replayed resource in the world, with its key
+fn replayed_resources(world: &World) -> Vec<(String, String)> {
+    let Some(replayed) = world.get_resource::<ReplayedResources>() else {
+        return Vec::new();
+    };
+
+    replayed
+        .0
+        .iter()
+        .filter_map(|resource| {
+            (resource.to_text)(world).map(|text| (resource.key.to_string(), text))
+        })
+        .collect()
+}
+
+// Puts the replayed resources as they are in `resources`, those missing
+// from it are removed. The ones already there are left untouched.
+fn put_back_replayed_resources(world: &mut World, resources: &[(String, String)]) {
+    world.resource_scope(|world, replayed: Mut<ReplayedResources>| {
+        for resource in &replayed.0 {
+            let text = resources
+                .iter()
+                .find(|(key, _)| key == resource.key)
+                .map(|(_, text)| text.as_str());
+            if (resource.to_text)(world).as_deref() != text {
+                (resource.put_back)(world, text);
+            }
+        }
+    });
+}
+
+/// Plays the replay stored at `path`, should be sent from the launcher
+#[derive(Event)]
+pub struct StartReplayEvent {
+    pub path: String,
+}
+
+/// A run of one game: the seed the `GameRng` was created with, the replayed
+/// resources as they were when it started, and the actions held and the
+/// analog movement during each frame of the run
+#[derive(PartialEq, Debug)]
+pub struct Replay {
+    // Name of the registered game
+    game: String,
+    seed: u64,
+    // Text of each replayed resource, with its key
+    resources: Vec<(String, String)>,
+    ticks: Vec<ReplayTick>,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+struct ReplayTick {
+    delta: Duration,
+
+    // One bit per action, in the order of `Action::ALL`
+    actions: u32,
+
+    // `AnalogInput::move_x` scaled to -127..=127
+    move_x: i8,
+
+    // `AnalogInput::pointer_x` or `NO_POINTER`
+    pointer_x: i16,
+}
+
+impl Replay {
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        if let Some(parent) = path.as_ref().parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        fs::write(path, self.to_bytes()?)
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        Self::from_bytes(&fs::read(path)?)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "invalid replay file"))
+    }
+
+    // Layout: magic, version, game name length and name, seed, resource
+    // count, then for each resource its key length and key and its text
+    // length and text, then the tick count, then for each tick its delta in
+    // nanoseconds, its action bits, its analog movement and its pointer x.
+    // Numbers are little endian.
+    fn to_bytes(&self) -> io::Result<Vec<u8>> {
+        let too_long = |what| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("{what} too long for a replay file"),
+            )
+        };
+        let game_len = u8::try_from(self.game.len()).map_err(|_| too_long("game name"))?;
+        let nb_resources =
+            u8::try_from(self.resources.len()).map_err(|_| too_long("resource list"))?;
+        let mut bytes = Vec::with_capacity(19 + self.game.len() + self.ticks.len() * TICK_SIZE);
+
+        bytes.extend_from_slice(REPLAY_MAGIC);
+        bytes.push(REPLAY_VERSION);
+        bytes.push(game_len);
+        bytes.extend_from_slice(self.game.as_bytes());
+        bytes.extend_from_slice(&self.seed.to_le_bytes());
+
+        bytes.push(nb_resources);
+        for (key, text) in &self.resources {
+            let key_len = u8::try_from(key.len()).map_err(|_| too_long("resource key"))?;
+            let text_len = u32::try_from(text.len()).map_err(|_| too_long("resource"))?;
+            bytes.push(key_len);
+            bytes.extend_from_slice(key.as_bytes());
+            bytes.extend_from_slice(&text_len.to_le_bytes());
+            bytes.extend_from_slice(text.as_bytes());
+        }
+
+        bytes.extend_from_slice(&(self.ticks.len() as u32).to_le_bytes());
+
+        for tick in &self.ticks {
+            // Frames longer than 4 seconds are cut short
+            let delta = u32::try_from(tick.delta.as_nanos()).unwrap_or(u32::MAX);
+            bytes.extend_from_slice(&delta.to_le_bytes());
+            bytes.extend_from_slice(&tick.actions.to_le_bytes());
+            bytes.extend_from_slice(&tick.move_x.to_le_bytes());
+            bytes.extend_from_slice(&tick.pointer_x.to_le_bytes());
+        }
+
+        Ok(bytes)
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        // The next `len` bytes as text
+        fn split_text(bytes: &[u8], len: usize) -> Option<(String, &[u8])> {
+            if bytes.len() < len {
+                return None;
+            }
+            let (text, bytes) = bytes.split_at(len);
+            Some((String::from_utf8(text.to_vec()).ok()?, bytes))
+        }
+
+        let (magic, bytes) = bytes.split_first_chunk::<4>()?;
+        let (&[version, game_len], bytes) = bytes.split_first_chunk::<2>()?;
+        if magic != REPLAY_MAGIC || version != REPLAY_VERSION {
+            return None;
+        }
+
+        let (game, bytes) = split_text(bytes, game_len as usize)?;
+        let (seed, bytes) = bytes.split_first_chunk::<8>()?;
+
+        let (&[nb_resources], mut bytes) = bytes.split_first_chunk::<1>()?;
+        let mut resources = Vec::with_capacity(nb_resources as usize);
+        for _ in 0..nb_resources {
+            let (&[key_len], rest) = bytes.split_first_chunk::<1>()?;
+            let (key, rest) = split_text(rest, key_len as usize)?;
+            let (text_len, rest) = rest.split_first_chunk::<4>()?;
+            let (text, rest) = split_text(rest, u32::from_le_bytes(*text_len) as usize)?;
+
+            resources.push((key, text));
+            bytes = rest;
+        }
+
+        let (nb_ticks, mut bytes) = bytes.split_first_chunk::<4>()?;
+
+        let nb_ticks = u32::from_le_bytes(*nb_ticks) as usize;
+        if bytes.len() != nb_ticks * TICK_SIZE {
+            return None;
+        }
+
+        let mut ticks = Vec::with_capacity(nb_ticks);
+        for _ in 0..nb_ticks {
+            let (delta, rest) = bytes.split_first_chunk::<4>()?;
+            let (actions, rest) = rest.split_first_chunk::<4>()?;
+            let (move_x, rest) = rest.split_first_chunk::<1>()?;
+            let (pointer_x, rest) = rest.split_first_chunk::<2>()?;
+
+            ticks.push(ReplayTick {
+                delta: Duration::from_nanos(u32::from_le_bytes(*delta) as u64),
+                actions: u32::from_le_bytes(*actions),
+                move_x: i8::from_le_bytes(*move_x),
+                pointer_x: i16::from_le_bytes(*pointer_x),
+            });
+            bytes = rest;
+        }
+
+        Some(Self {
+            game,
+            seed: u64::from_le_bytes(*seed),
+            resources,
+            ticks,
+        })
+    }
+
+    // Index of the first tick played after `seconds` of the run
+    fn tick_at(&self, seconds: f32) -> usize {
+        let mut elapsed = 0.0;
+        for (index, tick) in self.ticks.iter().enumerate() {
+            if elapsed >= seconds {
+                return index;
+            }
+            elapsed += tick.delta.as_secs_f32();
+        }
+
+        self.ticks.len()
+    }
+
+    fn duration(&self) -> f32 {
+        self.ticks.iter().map(|tick| tick.delta.as_secs_f32()).sum()
+    }
+}
+
+fn pressed_actions(input: &ButtonInput<Action>) -> u32 {
+    Action::ALL
+        .iter()
+        .enumerate()
+        .filter(|(_, action)| input.pressed(**action))
+        .fold(0, |actions, (bit, _)| actions | 1 << bit)
+}
+
+#[derive(Resource)]
+struct Recording(Replay);
+
+// The replayed resources as they were when the launcher was left for a game,
+// before the game changes them
+#[derive(Resource)]
+struct StartingResources(Vec<(String, String)>);
+
+/// Exists while a replay is played, games should not keep scores, saves or
+/// progress from it
+#[derive(Resource)]
+pub struct Playback {
+    replay: Replay,
+
+    // State of the replayed game
+    game: CurrentGame,
+
+    // Tick fed to the games during the current frame
+    cursor: usize,
+
+    fast_forward: bool,
+    seek_target: Option<usize>,
+    restart_frames: Option<u8>,
+    stopped: bool,
+
+    // The player's replayed resources, put back once the playback stops
+    kept_resources: Vec<(String, String)>,
+}
+
+impl Playback {
+    fn current_tick(&self) -> Option<&ReplayTick> {
+        match self.restart_frames {
+            None => self.replay.ticks.get(self.cursor),
+            Some(_) => None,
+        }
+    }
+
+    fn elapsed(&self) -> f32 {
+        self.replay.ticks[..self.cursor.min(self.replay.ticks.len())]
+            .iter()
+            .map(|tick| tick.delta.as_secs_f32())
+            .sum()
+    }
+
+    // Seeking forward fast-forwards from the current tick, seeking backward
+    // plays the replay again from the start up to the target
+    fn seek(&mut self, target: usize, next_game: &mut NextState<CurrentGame>) {
+        self.seek_target = Some(target);
+
+        if target <= self.cursor {
+            self.restart_frames = Some(RESTART_FRAMES);
+            next_game.set(CurrentGame::InMainMenu);
+        }
+    }
+
+    fn is_fast(&self) -> bool {
+        self.fast_forward || self.seek_target.is_some()
+    }
+}
+
+/// Whether a demo or a replay plays the game instead of the player
+#[derive(SystemParam)]
+pub struct ReplayOrDemo<'w> {
+    demo: Option<Res<'w, Demo>>,
+    playback: Option<Res<'w, Playback>>,
+}
+
+impl ReplayOrDemo<'_> {
+    pub fn is_playing(&self) -> bool {
+        self.demo.is_some() || self.playback.is_some()
+    }
+}
+
+#[derive(Component)]
+struct ReplayHud;
+
+fn keep_starting_resources(world: &mut World) {
+    let resources = replayed_resources(world);
+    world.insert_resource(StartingResources(resources));
+}
+
+fn start_recording(
+    mut commands: Commands,
+    current_game: Res<State<CurrentGame>>,
+    mut game_rng: ResMut<GameRng>,
+    fixed_seed: Option<Res<FixedSeed>>,
+    starting_resources: Option<ResMut<StartingResources>>,
+) {
+    let CurrentGame::Playing(game) = current_game.get() else {
+        return;
+    };
+
+    let seed = fixed_seed.map_or_else(rand::random, |fixed_seed| fixed_seed.0);
+    *game_rng = GameRng::new(seed);
+
+    commands.remove_resource::<StartingResources>();
+    commands.insert_resource(Recording(Replay {
+        game: game.to_string(),
+        seed,
+        resources: starting_resources
+            .map(|mut starting_resources| mem::take(&mut starting_resources.0))
+            .unwrap_or_default(),
+        ticks: Vec::new(),
+    }));
+}
+
+fn record_tick(
+    time: Res<Time>,
+    input: Res<ButtonInput<Action>>,
+    analog_input: Res<AnalogInput>,
+    mut recording: ResMut<Recording>,
+) {
+    recording.0.ticks.push(ReplayTick {
+        delta: time.delta(),
+        actions: pressed_actions(&input),
+        move_x: (analog_input.move_x * 127.0).round() as i8,
+        pointer_x: analog_input.pointer_x.map_or(NO_POINTER, |x| x as i16),
+    });
+}
+
+// Once the run is over, back in the launcher or as the app closes
+fn save_recording(mut commands: Commands, recording: Res<Recording>) {
+    if let Err(error) = recording.0.save(LAST_RUN_REPLAY) {
+        error!("Could not save replay to {LAST_RUN_REPLAY}: {error}");
+    }
+
+    commands.remove_resource::<Recording>();
+}
+
+fn start_playback(
+    mut commands: Commands,
+    mut start_events: EventReader<StartReplayEvent>,
+    mut game_rng: ResMut<GameRng>,
+    mut next_game: ResMut<NextState<CurrentGame>>,
+    mut time_update_strategy: ResMut<TimeUpdateStrategy>,
+    mut action_source: ResMut<ActionSource>,
+    registry: Res<GameRegistry>,
+) {
+    let Some(start_event) = start_events.read().last() else {
+        return;
+    };
+
+    let replay = match Replay::load(&start_event.path) {
+        Ok(replay) => replay,
+        Err(error) => {
+            warn!("Could not load replay {}: {error}", start_event.path);
+            return;
+        }
+    };
+
+    let Some(game) = registry.get(&replay.game).map(|game| game.state()) else {
+        warn!(
+            "Replay {} is of an unknown game: {}",
+            start_event.path, replay.game
+        );
+        return;
+    };
+
+    let Some(first_tick) = replay.ticks.first() else {
+        return;
+    };
+
+    *game_rng = GameRng::new(replay.seed);
+    *time_update_strategy = TimeUpdateStrategy::ManualDuration(first_tick.delta);
+    *action_source = ActionSource::Scripted;
+    next_game.set(game);
+
+    commands.insert_resource(Playback {
+        replay,
+        game,
+        cursor: 0,
+        fast_forward: false,
+        seek_target: None,
+        restart_frames: None,
+        stopped: false,
+        kept_resources: Vec::new(),
+    });
+    commands.add(|world: &mut World| {
+        let kept_resources = replayed_resources(world);
+        world.resource_mut::<Playback>().kept_resources = kept_resources;
+        put_back_starting_resources(world);
+    });
+
+    commands.spawn((
+        TextBundle::from_section(
+            "",
+            TextStyle {
+                font_size: 20.0,
+                color: palettes::basic::RED.into(),
+                ..Default::default()
+            },
+        )
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            left: Val::Px(GAME_AREA.min.x + WINDOW_RESOLUTION[0] / 2.0 + 10.0),
+            top: Val::Px(10.0),
+            ..Default::default()
+        }),
+        ReplayHud,
+    ));
+}
+
+// Live keys only control the playback, the games never see them
+fn handle_playback_input(
+    mut keyboard_events: ResMut<Events<KeyboardInput>>,
+    mut playback: ResMut<Playback>,
+    mut next_game: ResMut<NextState<CurrentGame>>,
+) {
+    for event in keyboard_events.drain() {
+        if event.state != ButtonState::Pressed {
+            continue;
+        }
+
+        match event.key_code {
+            KeyCode::Escape => playback.stopped = true,
+            KeyCode::Space => playback.fast_forward = !playback.fast_forward,
+            KeyCode::ArrowLeft | KeyCode::ArrowRight => {
+                let step = if event.key_code == KeyCode::ArrowLeft {
+                    -SCRUB_STEP
+                } else {
+                    SCRUB_STEP
+                };
+                let target = playback.replay.tick_at(playback.elapsed() + step);
+                playback.seek(target, &mut next_game);
+            }
+            _ => (),
+        }
+    }
+}
+
+fn apply_playback_actions(
+    playback: Res<Playback>,
+    mut input: ResMut<ButtonInput<Action>>,
+    mut analog_input: ResMut<AnalogInput>,
+) {
+    let (actions, move_x, pointer_x) = playback.current_tick().map_or((0, 0, NO_POINTER), |tick| {
+        (tick.actions, tick.move_x, tick.pointer_x)
+    });
+
+    input.clear();
+    analog_input.move_x = move_x as f32 / 127.0;
+    analog_input.pointer_x = (pointer_x != NO_POINTER).then_some(pointer_x as f32);
+
+    for (bit, action) in Action::ALL.iter().enumerate() {
+        if actions & 1 << bit != 0 {
+            input.press(*action);
+        } else {
+            input.release(*action);
+        }
+    }
+}
+
+// The replay's own, for its run to start as it was recorded
+fn put_back_starting_resources(world: &mut World) {
+    world.resource_scope(|world, playback: Mut<Playback>| {
+        put_back_replayed_resources(world, &playback.replay.resources);
+    });
+}
+
+fn advance_playback(
+    mut commands: Commands,
+    mut playback: ResMut<Playback>,
+    mut game_rng: ResMut<GameRng>,
+    mut next_game: ResMut<NextState<CurrentGame>>,
+    mut time_update_strategy: ResMut<TimeUpdateStrategy>,
+    mut windows: Query<&mut Window, With<PrimaryWindow>>,
+) {
+    match playback.restart_frames {
+        Some(0) => {
+            *game_rng = GameRng::new(playback.replay.seed);
+            commands.add(put_back_starting_resources);
+            next_game.set(playback.game);
+            playback.cursor = 0;
+            playback.restart_frames = None;
+        }
+        Some(frames) => {
+            playback.restart_frames = Some(frames - 1);
+            *time_update_strategy = TimeUpdateStrategy::ManualDuration(Duration::ZERO);
+            return;
+        }
+        None => playback.cursor += 1,
+    }
+
+    if playback
+        .seek_target
+        .is_some_and(|target| playback.cursor >= target)
+    {
+        playback.seek_target = None;
+    }
+
+    let present_mode = if playback.is_fast() {
+        PresentMode::AutoNoVsync
+    } else {
+        PresentMode::AutoVsync
+    };
+
+    for mut window in &mut windows {
+        if window.present_mode != present_mode {
+            window.present_mode = present_mode;
+        }
+    }
+
+    match playback.replay.ticks.get(playback.cursor) {
+        Some(tick) => {
+            *time_update_strategy = TimeUpdateStrategy::ManualDuration(tick.delta);
+        }
+        None => playback.stopped = true,
+    }
+}
+
+// Plays the ticks after the one of the frame, without rendering them
+fn fast_forward(world: &mut World) {
+    for _ in 1..FAST_FORWARD_TICKS {
+        let playing = world
+            .get_resource::<Playback>()
+            .is_some_and(|playback| playback.is_fast() && !playback.stopped);
+        if !playing {
+            return;
+        }
+
+        world.resource_scope(|world, frame_schedules: Mut<FrameSchedules>| {
+            for &label in &frame_schedules.0 {
+                let _ = world.try_run_schedule(label);
+            }
+        });
+    }
+}
+
+// The player takes over from wherever the replay stopped, with their own
+// replayed resources back
+fn stop_playback(
+    mut commands: Commands,
+    mut playback: ResMut<Playback>,
+    mut time_update_strategy: ResMut<TimeUpdateStrategy>,
+    mut action_source: ResMut<ActionSource>,
+    mut input: ResMut<ButtonInput<Action>>,
+    mut windows: Query<&mut Window, With<PrimaryWindow>>,
+    hud: Query<Entity, With<ReplayHud>>,
+) {
+    if !playback.stopped {
+        return;
+    }
+
+    *time_update_strategy = TimeUpdateStrategy::Automatic;
+    *action_source = ActionSource::Player;
+    input.release_all();
+
+    for mut window in &mut windows {
+        window.present_mode = PresentMode::AutoVsync;
+    }
+
+    for entity in &hud {
+        commands.entity(entity).despawn();
+    }
+
+    let kept_resources = mem::take(&mut playback.kept_resources);
+    commands.add(move |world: &mut World| {
+        put_back_replayed_resources(world, &kept_resources);
+    });
+    commands.remove_resource::<Playback>();
+}
+
+fn update_replay_hud(playback: Res<Playback>, mut hud: Query<&mut Text, With<ReplayHud>>) {
+    let Ok(mut text) = hud.get_single_mut() else {
+        return;
+    };
+
+    text.sections[0].value = format!(
+        "Replay {:.0}s / {:.0}s{}   Space: fast-forward  Left/Right: scrub  Escape: stop",
+        playback.elapsed(),
+        playback.replay.duration(),
+        if playback.is_fast() { " >>" } else { "" },
+    );
+}
+
+#[cfg(test)]
+mod tests;
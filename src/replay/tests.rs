@@ -0,0 +1,318 @@
+use std::env;
+
+use crate::{
+    breakout::{BreackoutPlugin, GameState},
+    game_menu::LastScore,
+    launch::LaunchGame,
+    progress::{CampaignProgress, LevelRecord},
+    registry::RetroGame,
+    saves::SavedGames,
+    scores::BestScores,
+    snake::{SnakePlugin, SnakeState},
+    testing::{game, game_app, play, press, release, run_frames, run_until, set_state, state, tap},
+    tetris::{TetrisPlugin, TetrisState},
+};
+
+use super::*;
+
+fn replay() -> Replay {
+    Replay {
+        game: String::from("Snake"),
+        seed: 0x0123_4567_89ab_cdef,
+        resources: vec![(String::from("snake arena"), String::from("wrapping"))],
+        ticks: vec![
+            ReplayTick {
+                delta: Duration::from_nanos(16_666_667),
+                actions: 0b101,
+                move_x: -127,
+                pointer_x: NO_POINTER,
+            },
+            ReplayTick {
+                delta: Duration::from_millis(33),
+                actions: 0,
+                move_x: 64,
+                pointer_x: -300,
+            },
+        ],
+    }
+}
+
+// `game` from the launcher, recording the runs
+fn replay_app<G: RetroGame>(game: G) -> App {
+    let mut app = game_app(game);
+    app.add_plugins(ReplayPlugin);
+    app.finish();
+
+    app
+}
+
+// Where the snake is and what it ate
+fn end_state(app: &App) -> (Vec<IVec2>, IVec2, u32) {
+    let game = game::<SnakePlugin>(app);
+    (game.body.iter().copied().collect(), game.food, game.score)
+}
+
+// Starts playing the recording of `app` in `playback_app`, returns its number
+// of ticks
+fn start_play_back(app: &mut App, playback_app: &mut App, name: &str) -> usize {
+    let Recording(replay) = app.world_mut().remove_resource::<Recording>().unwrap();
+    let path = env::temp_dir().join(format!("retro_{name}_{}.replay", std::process::id()));
+    replay.save(&path).unwrap();
+
+    playback_app.world_mut().send_event(StartReplayEvent {
+        path: path.to_string_lossy().into_owned(),
+    });
+    run_frames(playback_app, 1);
+    assert!(playback_app.world().contains_resource::<Playback>());
+
+    fs::remove_file(path).unwrap();
+    replay.ticks.len()
+}
+
+// Updates `playback_app` until the playback is over, returns the number of
+// frames it took
+fn finish_play_back(playback_app: &mut App, max_frames: usize) -> usize {
+    (1..=max_frames)
+        .find(|_| {
+            playback_app.update();
+            !playback_app.world().contains_resource::<Playback>()
+        })
+        .expect("The playback should be over")
+}
+
+// Plays the recording of `app` in `playback_app`, with fast-forward or not,
+// and returns it once the playback is over
+fn play_back(app: &mut App, mut playback_app: App, name: &str, fast_forward: bool) -> App {
+    let nb_ticks = start_play_back(app, &mut playback_app, name);
+    if fast_forward {
+        tap(&mut playback_app, KeyCode::Space);
+    }
+
+    let frames = finish_play_back(&mut playback_app, nb_ticks + 10);
+    if fast_forward {
+        assert!(frames < nb_ticks / 2);
+    }
+
+    playback_app
+}
+
+// Where each sprite is and how it looks, the same runs draw the same
+fn sprites(app: &mut App) -> Vec<String> {
+    let world = app.world_mut();
+    let mut sprites: Vec<String> = world
+        .query::<(&Transform, &Sprite, &Visibility)>()
+        .iter(world)
+        .map(|(transform, sprite, visibility)| {
+            format!(
+                "{:?} {:?} {visibility:?}",
+                transform.translation, sprite.color
+            )
+        })
+        .collect();
+    sprites.sort();
+
+    sprites
+}
+
+// Plays a bit of Snake, turning around
+fn record_run() -> App {
+    let mut app = replay_app(SnakePlugin);
+    play::<SnakePlugin>(&mut app, 0);
+    assert!(app.world().contains_resource::<Recording>());
+
+    for key_code in [KeyCode::ArrowUp, KeyCode::ArrowLeft, KeyCode::ArrowDown] {
+        press(&mut app, key_code);
+        run_frames(&mut app, 20);
+        release(&mut app, key_code);
+        run_frames(&mut app, 20);
+    }
+
+    app
+}
+
+#[test]
+fn replays_round_trip_through_bytes() {
+    let bytes = replay().to_bytes().unwrap();
+
+    assert_eq!(Replay::from_bytes(&bytes), Some(replay()));
+}
+
+#[test]
+fn corrupt_replays_are_rejected() {
+    let bytes = replay().to_bytes().unwrap();
+
+    for len in 0..bytes.len() {
+        assert_eq!(Replay::from_bytes(&bytes[..len]), None, "{len} bytes");
+    }
+
+    let mut longer = bytes.clone();
+    longer.push(0);
+    assert_eq!(Replay::from_bytes(&longer), None);
+
+    let mut other_version = bytes.clone();
+    other_version[4] = REPLAY_VERSION + 1;
+    assert_eq!(Replay::from_bytes(&other_version), None);
+
+    let mut other_magic = bytes.clone();
+    other_magic[0] = b'X';
+    assert_eq!(Replay::from_bytes(&other_magic), None);
+
+    // More ticks than there are bytes for
+    let tick_count =
+        6 + replay().game.len() + 8 + 1 + 1 + "snake arena".len() + 4 + "wrapping".len();
+    let mut too_many_ticks = bytes;
+    too_many_ticks[tick_count..tick_count + 4].copy_from_slice(&u32::MAX.to_le_bytes());
+    assert_eq!(Replay::from_bytes(&too_many_ticks), None);
+}
+
+#[test]
+fn game_names_too_long_are_not_saved() {
+    let replay = Replay {
+        game: "a".repeat(256),
+        ..replay()
+    };
+
+    assert!(replay.to_bytes().is_err());
+}
+
+#[test]
+fn a_recorded_run_plays_back_to_the_same_end() {
+    let mut app = record_run();
+    let recorded = end_state(&app);
+
+    let playback_app = play_back(&mut app, replay_app(SnakePlugin), "playback", false);
+    assert_eq!(end_state(&playback_app), recorded);
+}
+
+#[test]
+fn fast_forward_plays_several_ticks_per_frame_to_the_same_end() {
+    let mut app = record_run();
+    let recorded = end_state(&app);
+
+    let playback_app = play_back(&mut app, replay_app(SnakePlugin), "fast_forward", true);
+    assert_eq!(end_state(&playback_app), recorded);
+}
+
+#[test]
+fn replays_leave_the_saved_games_alone() {
+    // A Breakout level played from "Play", then paused which saves it
+    let mut app = replay_app(BreackoutPlugin);
+    for _ in 0..2 {
+        tap(&mut app, KeyCode::Space);
+        run_frames(&mut app, 1);
+    }
+    tap(&mut app, KeyCode::Space);
+    run_frames(&mut app, 20);
+    tap(&mut app, KeyCode::Escape);
+    assert!(app
+        .world()
+        .resource::<SavedGames>()
+        .contains(BreackoutPlugin::NAME));
+
+    let playback_app = play_back(&mut app, replay_app(BreackoutPlugin), "saves", false);
+    assert_eq!(state::<GameState>(&playback_app), GameState::InMenu);
+    assert_eq!(
+        playback_app.world().resource::<SavedGames>(),
+        &SavedGames::default()
+    );
+}
+
+#[test]
+fn replays_keep_no_scores() {
+    // Straight into the right wall
+    let mut app = replay_app(SnakePlugin);
+    play::<SnakePlugin>(&mut app, 0);
+    assert!(run_until(&mut app, 600, |app| {
+        state::<SnakeState>(app) == SnakeState::InMenu
+    }));
+    assert_eq!(app.world().resource::<LastScore<SnakePlugin>>().0, Some(0));
+
+    let playback_app = play_back(&mut app, replay_app(SnakePlugin), "scores", false);
+    assert_eq!(state::<SnakeState>(&playback_app), SnakeState::InMenu);
+    assert_eq!(
+        playback_app.world().resource::<LastScore<SnakePlugin>>().0,
+        None
+    );
+    assert_eq!(
+        playback_app
+            .world()
+            .resource::<BestScores>()
+            .get(SnakePlugin::NAME),
+        None
+    );
+}
+
+#[test]
+fn scrubbing_back_plays_the_run_again_to_the_same_end() {
+    let mut app = record_run();
+    let recorded = end_state(&app);
+
+    let mut playback_app = replay_app(SnakePlugin);
+    let nb_ticks = start_play_back(&mut app, &mut playback_app, "scrub");
+    run_frames(&mut playback_app, nb_ticks / 2);
+    tap(&mut playback_app, KeyCode::ArrowLeft);
+
+    // From the start again, less than the scrub step in
+    let frames = finish_play_back(&mut playback_app, nb_ticks + 10);
+    assert!(frames > nb_ticks / 2);
+    assert_eq!(end_state(&playback_app), recorded);
+}
+
+#[test]
+fn breakout_replays_start_from_the_progress_and_saves_of_their_run() {
+    // With the first level completed, "Play" goes on from the second one
+    let mut app = replay_app(BreackoutPlugin);
+    let mut progress = CampaignProgress::default();
+    let record = LevelRecord {
+        score: 0,
+        time: 600.0,
+        stars: 1,
+    };
+    progress.submit(BreackoutPlugin::NAME, 1, record);
+    app.insert_resource(progress);
+
+    for _ in 0..2 {
+        tap(&mut app, KeyCode::Space);
+        run_frames(&mut app, 1);
+    }
+    tap(&mut app, KeyCode::Space);
+    run_frames(&mut app, 60);
+    let recorded = sprites(&mut app);
+
+    // The player of the replay has a level to continue, from the first one
+    let mut playback_app = replay_app(BreackoutPlugin);
+    let mut saved_games = SavedGames::default();
+    saved_games.store(BreackoutPlugin::NAME, String::from("level = 1\n"));
+    playback_app.insert_resource(saved_games.clone());
+
+    let mut playback_app = play_back(&mut app, playback_app, "breakout", false);
+    assert_eq!(sprites(&mut playback_app), recorded);
+    assert_eq!(playback_app.world().resource::<SavedGames>(), &saved_games);
+    assert_eq!(
+        playback_app.world().resource::<CampaignProgress>(),
+        &CampaignProgress::default()
+    );
+}
+
+#[test]
+fn tetris_replays_start_in_the_mode_given_on_the_command_line() {
+    let mut app = replay_app(TetrisPlugin);
+    app.insert_resource(LaunchGame {
+        level: None,
+        mode: Some(String::from("sprint")),
+    });
+    set_state(&mut app, TetrisPlugin::STATE);
+    run_frames(&mut app, 2);
+    assert_eq!(state::<TetrisState>(&app), TetrisState::InGame);
+
+    for key_code in [KeyCode::ArrowLeft, KeyCode::ArrowUp, KeyCode::Space] {
+        tap(&mut app, key_code);
+        run_frames(&mut app, 30);
+    }
+    let recorded = sprites(&mut app);
+
+    let mut playback_app = play_back(&mut app, replay_app(TetrisPlugin), "tetris", false);
+    assert_eq!(state::<TetrisState>(&playback_app), TetrisState::InGame);
+    assert_eq!(sprites(&mut playback_app), recorded);
+    assert!(!playback_app.world().contains_resource::<LaunchGame>());
+}
@@ -0,0 +1,83 @@
+use bevy::prelude::*;
+use rand::{
+    distributions::{Distribution, Standard},
+    rngs::StdRng,
+    seq::SliceRandom,
+    Rng, SeedableRng,
+};
+
+/// Source of randomness shared by every game.
+///
+/// Each run starts from a seed, so replaying a run with the same seed and
+/// the same inputs gives the same result. The first run plays from the seed
+/// the `GameRng` was created with and the seeds of the next ones are drawn
+/// from it, so a whole session can be replayed from that single seed, and
+/// the seed a game shows for its run replays that run.
+#[derive(Resource)]
+pub struct GameRng {
+    seeds: StdRng,
+    next_seed: u64,
+    seed: u64,
+    rng: StdRng,
+}
+
+impl GameRng {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seeds: StdRng::seed_from_u64(seed),
+            next_seed: seed,
+            seed,
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Starts a new run with a fresh seed
+    pub fn new_run(&mut self) {
+        let seed = self.next_seed;
+        self.next_seed = self.seeds.gen();
+        self.start_run(seed);
+    }
+
+    /// Starts a new run from the given seed
+    pub fn start_run(&mut self, seed: u64) {
+        self.seed = seed;
+        self.rng = StdRng::seed_from_u64(seed);
+    }
+
+    /// Starts the current run over from its seed
+    pub fn restart_run(&mut self) {
+        self.start_run(self.seed);
+    }
+
+    pub fn random<T>(&mut self) -> T
+    where
+        Standard: Distribution<T>,
+    {
+        self.rng.gen()
+    }
+
+    /// Random index of a slice of `len` items, `len` must not be 0
+    pub fn random_index(&mut self, len: usize) -> usize {
+        self.rng.gen_range(0..len)
+    }
+
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        items.shuffle(&mut self.rng);
+    }
+}
+
+/// Seeds every session with the same seed instead of a random one, so that
+/// runs can be reproduced: its first run plays from that seed, see
+/// `LaunchOptions`
+#[derive(Resource, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct FixedSeed(pub u64);
+
+impl Default for GameRng {
+    fn default() -> Self {
+        Self::new(rand::random())
+    }
+}
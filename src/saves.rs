@@ -0,0 +1,85 @@
+use std::{collections::BTreeMap, fmt::Write};
+
+use bevy::prelude::*;
+
+use crate::settings::SettingsFile;
+
+// Written at the top of the file, saves from another version are dropped.
+// Bump it when a game changes what it saves.
+const SAVES_VERSION: u32 = 1;
+
+/// Games left in progress, by game name, each in the text its game wrote.
+/// Games store theirs when paused or left, and offer to continue it.
+#[derive(Resource, Clone, PartialEq, Eq, Default, Debug)]
+pub struct SavedGames {
+    games: BTreeMap<String, String>,
+}
+
+impl SavedGames {
+    pub fn get(&self, game: &str) -> Option<&str> {
+        self.games.get(game).map(String::as_str)
+    }
+
+    pub fn contains(&self, game: &str) -> bool {
+        self.games.contains_key(game)
+    }
+
+    /// Replaces the save of `game`, its lines must not start with `[`
+    pub fn store(&mut self, game: &str, save: String) {
+        self.games.insert(game.to_string(), save);
+    }
+
+    pub fn remove(&mut self, game: &str) -> Option<String> {
+        self.games.remove(game)
+    }
+}
+
+impl SettingsFile for SavedGames {
+    const PATH: &'static str = "settings/saved_games.txt";
+    const NAME: &'static str = "saved games";
+
+    // A `version = N` line, then each save under a `[Game]` line
+    fn to_text(&self) -> String {
+        let mut text = String::new();
+        let _ = writeln!(text, "version = {SAVES_VERSION}");
+
+        for (game, save) in &self.games {
+            let _ = writeln!(text, "[{game}]");
+            for line in save.lines() {
+                let _ = writeln!(text, "{line}");
+            }
+        }
+
+        text
+    }
+
+    fn from_text(text: &str) -> Option<Self> {
+        let mut lines = text.lines();
+
+        let (key, version) = lines.next()?.split_once('=')?;
+        if key.trim() != "version" || version.trim().parse::<u32>().ok()? != SAVES_VERSION {
+            return None;
+        }
+
+        let mut saved_games = Self::default();
+        let mut current = None;
+        for line in lines {
+            if let Some(game) = line
+                .strip_prefix('[')
+                .and_then(|line| line.strip_suffix(']'))
+            {
+                current = Some(game.to_string());
+                saved_games.games.insert(game.to_string(), String::new());
+            } else if !line.trim().is_empty() {
+                let save = saved_games.games.get_mut(current.as_ref()?)?;
+                save.push_str(line);
+                save.push('\n');
+            }
+        }
+
+        Some(saved_games)
+    }
+}
+
+#[cfg(test)]
+mod tests;
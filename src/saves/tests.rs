@@ -0,0 +1,32 @@
+use super::*;
+
+#[test]
+fn text_round_trip() {
+    let mut saved_games = SavedGames::default();
+    saved_games.store("Breakout", String::from("level = 2\nball = 1 2 3 4\n"));
+    saved_games.store("Tetris", String::from("score = 1200\n"));
+
+    assert_eq!(
+        SavedGames::from_text(&saved_games.to_text()),
+        Some(saved_games)
+    );
+}
+
+#[test]
+fn saves_of_another_version_are_dropped() {
+    let text = SavedGames::default().to_text();
+    assert_eq!(SavedGames::from_text(&text), Some(SavedGames::default()));
+
+    let other_version = text.replace(
+        &format!("version = {SAVES_VERSION}"),
+        &format!("version = {}", SAVES_VERSION + 1),
+    );
+    assert_eq!(SavedGames::from_text(&other_version), None);
+    assert_eq!(SavedGames::from_text("[Tetris]\nscore = 1200\n"), None);
+
+    // Lines before any game
+    assert_eq!(
+        SavedGames::from_text(&format!("version = {SAVES_VERSION}\nscore = 1200\n")),
+        None
+    );
+}
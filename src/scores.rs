@@ -0,0 +1,91 @@
+use std::{collections::BTreeMap, fmt::Write};
+
+use bevy::prelude::*;
+
+use crate::settings::SettingsFile;
+
+/// Best score of each game, by game name. Games submit their scores at the
+/// end of a run and the launcher shows them, with the name of whoever made
+/// them when the game asked for it.
+#[derive(Resource, Clone, PartialEq, Eq, Default, Debug)]
+pub struct BestScores {
+    scores: BTreeMap<String, u32>,
+    holders: BTreeMap<String, String>,
+}
+
+impl BestScores {
+    pub fn get(&self, game: &str) -> Option<u32> {
+        self.scores.get(game).copied()
+    }
+
+    /// Keeps `score` if it beats the best score of `game`, returns whether
+    /// it did
+    pub fn submit(&mut self, game: &str, score: u32) -> bool {
+        if self.get(game).is_some_and(|best| best >= score) {
+            return false;
+        }
+
+        self.scores.insert(game.to_string(), score);
+        self.holders.remove(game);
+        true
+    }
+
+    pub fn holder(&self, game: &str) -> Option<&str> {
+        self.holders.get(game).map(String::as_str)
+    }
+
+    /// Names whoever made the best score of `game`, in a single word
+    pub fn set_holder(&mut self, game: &str, name: &str) {
+        if self.scores.contains_key(game) {
+            self.holders.insert(game.to_string(), name.to_string());
+        }
+    }
+}
+
+impl SettingsFile for BestScores {
+    const PATH: &'static str = "settings/best_scores.txt";
+    const NAME: &'static str = "best scores";
+
+    // One `Game = score` line per game, the holder after the score
+    fn to_text(&self) -> String {
+        let mut text = String::new();
+
+        for (game, score) in &self.scores {
+            match self.holder(game) {
+                Some(holder) => {
+                    let _ = writeln!(text, "{game} = {score} {holder}");
+                }
+                None => {
+                    let _ = writeln!(text, "{game} = {score}");
+                }
+            }
+        }
+
+        text
+    }
+
+    fn from_text(text: &str) -> Option<Self> {
+        let mut best_scores = Self::default();
+
+        for line in text.lines().map(str::trim).filter(|line| !line.is_empty()) {
+            let (game, score) = line.rsplit_once('=')?;
+            let game = game.trim().to_string();
+            let mut words = score.split_whitespace();
+
+            best_scores
+                .scores
+                .insert(game.clone(), words.next()?.parse().ok()?);
+            if let Some(holder) = words.next() {
+                best_scores.holders.insert(game, holder.to_string());
+            }
+            if words.next().is_some() {
+                return None;
+            }
+        }
+
+        Some(best_scores)
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,43 @@
+use super::*;
+
+#[test]
+fn only_better_scores_are_kept() {
+    let mut best_scores = BestScores::default();
+
+    assert!(best_scores.submit("Breakout", 120));
+    assert!(!best_scores.submit("Breakout", 80));
+    assert!(!best_scores.submit("Breakout", 120));
+    assert!(best_scores.submit("Breakout", 300));
+
+    assert_eq!(best_scores.get("Breakout"), Some(300));
+    assert_eq!(best_scores.get("Tetris"), None);
+}
+
+#[test]
+fn text_round_trip() {
+    let mut best_scores = BestScores::default();
+    best_scores.submit("Breakout", 1200);
+    best_scores.submit("Space Invaders", 45);
+    best_scores.set_holder("Breakout", "ABC");
+
+    assert_eq!(
+        BestScores::from_text(&best_scores.to_text()),
+        Some(best_scores)
+    );
+    assert_eq!(BestScores::from_text("Breakout = lots"), None);
+    assert_eq!(BestScores::from_text("Breakout = 1200 A B"), None);
+}
+
+#[test]
+fn a_better_score_takes_the_holder_away() {
+    let mut best_scores = BestScores::default();
+    best_scores.set_holder("Breakout", "ABC");
+    assert_eq!(best_scores.holder("Breakout"), None);
+
+    best_scores.submit("Breakout", 120);
+    best_scores.set_holder("Breakout", "ABC");
+    assert_eq!(best_scores.holder("Breakout"), Some("ABC"));
+
+    best_scores.submit("Breakout", 300);
+    assert_eq!(best_scores.holder("Breakout"), None);
+}
@@ -0,0 +1,84 @@
+//! Resources kept between sessions, each in its own text file. The
+//! application reads them with `App::add_settings_file` and writes them back
+//! whenever they change.
+
+use std::{fs, io, path::Path};
+
+use bevy::prelude::*;
+
+use crate::replay::Playback;
+
+/// Resource kept in a text file
+pub trait SettingsFile: Resource + Default + Sized {
+    /// Where the file is, from the working directory
+    const PATH: &'static str;
+
+    /// What the file holds, for the errors about it
+    const NAME: &'static str;
+
+    fn to_text(&self) -> String;
+
+    /// `None` if `text` is not what `to_text` writes
+    fn from_text(text: &str) -> Option<Self>;
+
+    fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        if let Some(parent) = path.as_ref().parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        fs::write(path, self.to_text())
+    }
+
+    fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        Self::from_text(&fs::read_to_string(path)?).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("invalid {} file", Self::NAME),
+            )
+        })
+    }
+}
+
+pub trait AddSettingsFile {
+    /// Inserts `S` as read from its file, or its default without one, and
+    /// saves it to the file whenever it changes. A replay puts back the
+    /// settings of its run while it is played, those are not saved.
+    fn add_settings_file<S: SettingsFile>(&mut self) -> &mut Self;
+}
+
+impl AddSettingsFile for App {
+    fn add_settings_file<S: SettingsFile>(&mut self) -> &mut Self {
+        self.insert_resource(load_settings::<S>()).add_systems(
+            Last,
+            save_settings::<S>.run_if(
+                not(resource_exists::<Playback>)
+                    .and_then(resource_changed::<S>)
+                    .and_then(not(resource_added::<S>)),
+            ),
+        )
+    }
+}
+
+fn load_settings<S: SettingsFile>() -> S {
+    match S::load(S::PATH) {
+        Ok(settings) => settings,
+        Err(error) => {
+            if error.kind() != io::ErrorKind::NotFound {
+                warn!(
+                    "Could not load {}, starting from the defaults: {error}",
+                    S::PATH
+                );
+            }
+            S::default()
+        }
+    }
+}
+
+fn save_settings<S: SettingsFile>(settings: Res<S>) {
+    if let Err(error) = settings.save(S::PATH) {
+        error!("Could not save {}: {error}", S::PATH);
+    }
+}
+
+#[cfg(test)]
+mod tests;
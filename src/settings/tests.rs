@@ -0,0 +1,24 @@
+use std::env;
+
+use crate::scores::BestScores;
+
+use super::*;
+
+#[test]
+fn settings_files_round_trip_and_reject_invalid_text() {
+    let dir = env::temp_dir().join(format!("retro_settings_{}", std::process::id()));
+    let path = dir.join("best_scores.txt");
+
+    let mut best_scores = BestScores::default();
+    best_scores.submit("Tetris", 1200);
+    best_scores.save(&path).unwrap();
+    assert_eq!(BestScores::load(&path).unwrap(), best_scores);
+
+    fs::write(&path, "Tetris = lots").unwrap();
+    let error = BestScores::load(&path).unwrap_err();
+    assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+
+    fs::remove_dir_all(dir).unwrap();
+    let error = BestScores::load(&path).unwrap_err();
+    assert_eq!(error.kind(), io::ErrorKind::NotFound);
+}
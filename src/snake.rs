@@ -0,0 +1,79 @@
+use bevy::prelude::*;
+
+mod menu;
+use menu::MenuPlugin;
+
+mod game;
+use game::{Arena, GamePlugin, LoadGameEvent};
+
+use crate::game_menu::enter_menu;
+use crate::input::Action;
+use crate::launch::LaunchGame;
+use crate::registry::RetroGame;
+
+pub struct SnakePlugin;
+
+impl RetroGame for SnakePlugin {
+    const NAME: &'static str = "Snake";
+    const ICON: &'static str = "snake/icon.png";
+    const DESCRIPTION: &'static str =
+        "Eat to grow longer, without running into the walls or your own tail.";
+    const CONTROLS: &'static [Action] = &[
+        Action::MoveUp,
+        Action::MoveDown,
+        Action::MoveLeft,
+        Action::MoveRight,
+        Action::Pause,
+    ];
+}
+
+impl Plugin for SnakePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(MenuPlugin)
+            .add_plugins(GamePlugin)
+            .add_systems(
+                OnEnter(SnakePlugin::STATE),
+                launch
+                    .after(enter_menu::<SnakePlugin>)
+                    .run_if(resource_exists::<LaunchGame>),
+            );
+    }
+}
+
+#[derive(States, PartialEq, Eq, Hash, Clone, Debug)]
+pub enum SnakeState {
+    Exited,
+    InMenu,
+    InGame,
+}
+
+// Straight into the arena given as the mode on the command line, if there
+// is one
+fn launch(
+    mut commands: Commands,
+    launch_game: Res<LaunchGame>,
+    mut arena: ResMut<Arena>,
+    mut load_game_event: EventWriter<LoadGameEvent>,
+    mut next_state: ResMut<NextState<SnakeState>>,
+) {
+    commands.remove_resource::<LaunchGame>();
+
+    if let Some(level) = launch_game.level {
+        warn!("Snake has no level {level} to start from");
+    }
+
+    let Some(name) = &launch_game.mode else {
+        return;
+    };
+    let Some(launch_arena) = Arena::from_name(name) else {
+        warn!("Snake has no {name} mode, only walled and wrapping");
+        return;
+    };
+
+    *arena = launch_arena;
+    load_game_event.send_default();
+    next_state.set(SnakeState::InGame);
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,441 @@
+use std::{collections::VecDeque, time::Duration};
+
+use bevy::{color::palettes, prelude::*};
+
+use crate::{
+    application::GAME_AREA,
+    game_menu,
+    input::Action,
+    registry::RetroGame,
+    replay::{AddReplayedResource, ReplayedResource},
+    rng::GameRng,
+};
+
+use super::{SnakePlugin, SnakeState};
+
+pub const NB_COLS: i32 = 32;
+pub const NB_ROWS: i32 = 24;
+pub const CELL_SIZE: f32 = 24.0;
+
+// Bottom left corner of the arena, centered in the game area
+const ARENA_ORIGIN: Vec2 = Vec2 {
+    x: GAME_AREA.min.x + (GAME_AREA.max.x - GAME_AREA.min.x - NB_COLS as f32 * CELL_SIZE) / 2.0,
+    y: GAME_AREA.min.y + (GAME_AREA.max.y - GAME_AREA.min.y - NB_ROWS as f32 * CELL_SIZE) / 2.0,
+};
+
+const START_LENGTH: usize = 3;
+
+// Seconds between two steps of the snake at the start, it speeds up with
+// every food eaten
+const STEP_INTERVAL: f32 = 0.15;
+const STEP_SPEED_UP: f32 = 0.97;
+const MIN_STEP_INTERVAL: f32 = 0.05;
+
+// Cells the snake grows by for each food
+const GROWTH: usize = 1;
+const FOOD_POINTS: u32 = 10;
+
+// Turns pressed faster than the snake steps are kept for the next steps, so
+// that quick U-turns are not lost
+const MAX_BUFFERED_TURNS: usize = 2;
+
+const SNAKE_COLOR: Color = Color::srgb(0.3, 0.85, 0.3);
+const HEAD_COLOR: Color = Color::srgb(0.6, 1.0, 0.6);
+
+pub struct GamePlugin;
+
+impl Plugin for GamePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<LoadGameEvent>()
+            .add_event::<CleanupGameEvent>()
+            .init_resource::<Arena>()
+            .add_replayed_resource::<Arena>()
+            .init_resource::<LastScore>()
+            .add_systems(OnExit(SnakePlugin::STATE), cleanup_game)
+            .add_systems(
+                Update,
+                (
+                    load_game.run_if(on_event::<LoadGameEvent>()),
+                    (
+                        handle_player_input,
+                        step,
+                        game_menu::end_game::<SnakePlugin>
+                            .run_if(game_menu::is_over::<SnakePlugin>),
+                    )
+                        .chain()
+                        .run_if(in_state(SnakeState::InGame))
+                        .run_if(resource_exists::<SnakeGame>),
+                    cleanup_game.run_if(on_event::<CleanupGameEvent>()),
+                    (draw_snake, update_hud).run_if(resource_exists::<SnakeGame>),
+                )
+                    .chain(),
+            );
+    }
+}
+
+#[derive(Event, Default)]
+pub struct LoadGameEvent {
+    pub restart: bool,
+}
+
+#[derive(Event, Default)]
+pub struct CleanupGameEvent;
+
+/// What happens at the edges of the arena
+#[derive(Resource, Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub enum Arena {
+    /// Running into them ends the game
+    #[default]
+    Walled,
+    /// The snake comes back from the other side
+    Wrapping,
+}
+
+impl Arena {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "walled" => Some(Arena::Walled),
+            "wrapping" => Some(Arena::Wrapping),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Arena::Walled => "walled",
+            Arena::Wrapping => "wrapping",
+        }
+    }
+}
+
+impl ReplayedResource for Arena {
+    const KEY: &'static str = "snake arena";
+
+    fn to_text(&self) -> String {
+        self.name().to_string()
+    }
+
+    fn from_text(text: &str) -> Option<Self> {
+        Self::from_name(text)
+    }
+}
+
+/// Score of the last finished game
+pub type LastScore = game_menu::LastScore<SnakePlugin>;
+
+/// The game being played
+#[derive(Resource, Clone, Debug)]
+pub struct SnakeGame {
+    /// Cells of the snake, head first
+    pub body: VecDeque<IVec2>,
+    pub direction: IVec2,
+    pub food: IVec2,
+    pub arena: Arena,
+    pub score: u32,
+
+    // The snake ran into something, or filled the whole arena
+    pub game_over: bool,
+
+    // Directions to take at the next steps, oldest first
+    turns: VecDeque<IVec2>,
+
+    // Cells still to grow by, the tail stays in place while there are some
+    growth: usize,
+
+    step_timer: Timer,
+}
+
+impl game_menu::ScoredGame for SnakeGame {
+    fn score(&self) -> u32 {
+        self.score
+    }
+
+    fn is_over(&self) -> bool {
+        self.game_over
+    }
+}
+
+impl SnakeGame {
+    /// The food is put on one of the free cells picked by `pick`, given
+    /// their number
+    pub fn new(arena: Arena, pick: impl FnOnce(usize) -> usize) -> Self {
+        let head = IVec2::new(NB_COLS / 2, NB_ROWS / 2);
+
+        let mut game = Self {
+            body: (0..START_LENGTH as i32)
+                .map(|offset| head - IVec2::X * offset)
+                .collect(),
+            direction: IVec2::X,
+            food: IVec2::ZERO,
+            arena,
+            score: 0,
+            game_over: false,
+            turns: VecDeque::new(),
+            growth: 0,
+            step_timer: Timer::from_seconds(STEP_INTERVAL, TimerMode::Repeating),
+        };
+        game.place_food(pick);
+
+        game
+    }
+
+    pub fn in_bounds(coords: IVec2) -> bool {
+        coords.x >= 0 && coords.x < NB_COLS && coords.y >= 0 && coords.y < NB_ROWS
+    }
+
+    pub fn head(&self) -> IVec2 {
+        self.body[0]
+    }
+
+    fn step_interval(&self) -> f32 {
+        let nb_eaten = (self.score / FOOD_POINTS) as i32;
+        (STEP_INTERVAL * STEP_SPEED_UP.powi(nb_eaten)).max(MIN_STEP_INTERVAL)
+    }
+
+    /// Turns at the next free step, turning back onto itself is ignored
+    pub fn turn(&mut self, direction: IVec2) {
+        let last = self.turns.back().copied().unwrap_or(self.direction);
+        if self.turns.len() < MAX_BUFFERED_TURNS && direction != last && direction != -last {
+            self.turns.push_back(direction);
+        }
+    }
+
+    /// Moves the snake by one cell, eating the food in its way
+    pub fn step(&mut self, pick: impl FnOnce(usize) -> usize) {
+        if let Some(turn) = self.turns.pop_front() {
+            self.direction = turn;
+        }
+
+        let mut head = self.head() + self.direction;
+        match self.arena {
+            Arena::Walled if !Self::in_bounds(head) => {
+                self.game_over = true;
+                return;
+            }
+            Arena::Walled => (),
+            Arena::Wrapping => head = head.rem_euclid(IVec2::new(NB_COLS, NB_ROWS)),
+        }
+
+        let eats = head == self.food;
+        if eats {
+            self.growth += GROWTH;
+        }
+
+        // The tail moves out of the way unless the snake grows
+        let nb_blocking = if self.growth > 0 {
+            self.body.len()
+        } else {
+            self.body.len() - 1
+        };
+        if self.body.iter().take(nb_blocking).any(|&cell| cell == head) {
+            self.game_over = true;
+            return;
+        }
+
+        if self.growth > 0 {
+            self.growth -= 1;
+        } else {
+            self.body.pop_back();
+        }
+        self.body.push_front(head);
+
+        if eats {
+            self.score += FOOD_POINTS;
+            self.place_food(pick);
+        }
+    }
+
+    fn place_food(&mut self, pick: impl FnOnce(usize) -> usize) {
+        let free_cells: Vec<IVec2> = (0..NB_ROWS)
+            .flat_map(|y| (0..NB_COLS).map(move |x| IVec2::new(x, y)))
+            .filter(|cell| !self.body.contains(cell))
+            .collect();
+
+        if free_cells.is_empty() {
+            self.game_over = true;
+            return;
+        }
+        self.food = free_cells[pick(free_cells.len())];
+    }
+}
+
+#[derive(Component)]
+struct GameEntity;
+
+// Cell of the snake at this index from the head
+#[derive(Component)]
+struct Segment(usize);
+
+#[derive(Component)]
+struct Food;
+
+#[derive(Component)]
+struct Hud;
+
+fn load_game(
+    mut commands: Commands,
+    mut load_events: EventReader<LoadGameEvent>,
+    mut game_rng: ResMut<GameRng>,
+    arena: Res<Arena>,
+    entities: Query<Entity, With<GameEntity>>,
+) {
+    if let Some(load_event) = load_events.read().last() {
+        if load_event.restart {
+            game_rng.restart_run();
+        } else {
+            game_rng.new_run();
+        }
+    }
+
+    for entity in &entities {
+        commands.entity(entity).despawn();
+    }
+
+    commands.insert_resource(SnakeGame::new(*arena, |nb_cells| {
+        game_rng.random_index(nb_cells)
+    }));
+
+    let arena_size = Vec2::new(NB_COLS as f32, NB_ROWS as f32) * CELL_SIZE;
+    let border = if *arena == Arena::Walled { 6.0 } else { 0.0 };
+    commands.spawn((
+        SpriteBundle {
+            sprite: Sprite {
+                color: palettes::css::DARK_OLIVEGREEN.into(),
+                custom_size: Some(arena_size + border),
+                ..Default::default()
+            },
+            transform: Transform::from_translation(Vec3::from((
+                ARENA_ORIGIN + arena_size / 2.0,
+                -1.0,
+            ))),
+            ..Default::default()
+        },
+        GameEntity,
+    ));
+    commands.spawn((
+        SpriteBundle {
+            sprite: Sprite {
+                color: Color::srgb(0.05, 0.1, 0.05),
+                custom_size: Some(arena_size),
+                ..Default::default()
+            },
+            transform: Transform::from_translation(Vec3::from((
+                ARENA_ORIGIN + arena_size / 2.0,
+                -0.5,
+            ))),
+            ..Default::default()
+        },
+        GameEntity,
+    ));
+
+    commands.spawn((cell_sprite(palettes::css::RED.into()), Food, GameEntity));
+
+    commands.spawn((
+        Text2dBundle {
+            text: Text::from_section(
+                "",
+                TextStyle {
+                    font_size: 24.0,
+                    color: palettes::css::LIGHT_GREEN.into(),
+                    ..Default::default()
+                },
+            ),
+            transform: Transform::from_translation(Vec3::from((
+                Vec2::new(GAME_AREA.center().x, GAME_AREA.max.y + 40.0),
+                0.0,
+            ))),
+            ..Default::default()
+        },
+        Hud,
+        GameEntity,
+    ));
+}
+
+fn cell_sprite(color: Color) -> SpriteBundle {
+    SpriteBundle {
+        sprite: Sprite {
+            color,
+            custom_size: Some(Vec2::splat(CELL_SIZE - 2.0)),
+            ..Default::default()
+        },
+        ..Default::default()
+    }
+}
+
+fn cell_position(coords: IVec2) -> Vec2 {
+    ARENA_ORIGIN + (coords.as_vec2() + 0.5) * CELL_SIZE
+}
+
+fn cleanup_game(mut commands: Commands, entities: Query<Entity, With<GameEntity>>) {
+    for entity in &entities {
+        commands.entity(entity).despawn();
+    }
+
+    commands.remove_resource::<SnakeGame>();
+}
+
+fn handle_player_input(
+    input: Res<ButtonInput<Action>>,
+    mut game: ResMut<SnakeGame>,
+    mut next_snake_state: ResMut<NextState<SnakeState>>,
+) {
+    for (action, direction) in [
+        (Action::MoveUp, IVec2::Y),
+        (Action::MoveDown, IVec2::NEG_Y),
+        (Action::MoveLeft, IVec2::NEG_X),
+        (Action::MoveRight, IVec2::X),
+    ] {
+        if input.just_pressed(action) {
+            game.turn(direction);
+        }
+    }
+
+    if input.just_pressed(Action::Pause) {
+        next_snake_state.set(SnakeState::InMenu);
+    }
+}
+
+fn step(time: Res<Time>, mut game: ResMut<SnakeGame>, mut game_rng: ResMut<GameRng>) {
+    let interval = game.step_interval();
+    game.step_timer
+        .set_duration(Duration::from_secs_f32(interval));
+    if game.step_timer.tick(time.delta()).just_finished() {
+        game.step(|nb_cells| game_rng.random_index(nb_cells));
+    }
+}
+
+fn draw_snake(
+    mut commands: Commands,
+    game: Res<SnakeGame>,
+    mut segments: Query<(Entity, &Segment, &mut Transform, &mut Sprite), Without<Food>>,
+    mut food: Query<&mut Transform, With<Food>>,
+) {
+    let mut nb_segments = 0;
+    for (entity, Segment(index), mut transform, mut sprite) in &mut segments {
+        match game.body.get(*index) {
+            Some(&coords) => {
+                transform.translation = Vec3::from((cell_position(coords), 0.0));
+                sprite.color = if *index == 0 { HEAD_COLOR } else { SNAKE_COLOR };
+                nb_segments += 1;
+            }
+            None => commands.entity(entity).despawn(),
+        }
+    }
+
+    // Cells the snake grew by
+    for index in nb_segments..game.body.len() {
+        let mut sprite = cell_sprite(SNAKE_COLOR);
+        sprite.transform.translation = Vec3::from((cell_position(game.body[index]), 0.0));
+        commands.spawn((sprite, Segment(index), GameEntity));
+    }
+
+    if let Ok(mut transform) = food.get_single_mut() {
+        transform.translation = Vec3::from((cell_position(game.food), 0.0));
+    }
+}
+
+fn update_hud(game: Res<SnakeGame>, mut hud: Query<&mut Text, With<Hud>>) {
+    if let Ok(mut text) = hud.get_single_mut() {
+        text.sections[0].value = format!("Score: {}    Length: {}", game.score, game.body.len());
+    }
+}
@@ -0,0 +1,88 @@
+use bevy::color::palettes;
+use bevy::ecs::system::{SystemParam, SystemParamItem};
+use bevy::prelude::*;
+
+use crate::game_menu::{high_scores_menu, GameMenu, GameMenuAction, GameMenuPlugin, Scores};
+use crate::menu_tree::{Menu, MenuActionEvent, MenuTreeSystems};
+
+use super::{
+    game::{Arena, CleanupGameEvent, LoadGameEvent, SnakeGame},
+    SnakePlugin, SnakeState,
+};
+
+pub struct MenuPlugin;
+
+impl Plugin for MenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(GameMenuPlugin::<SnakePlugin>::default())
+            .add_systems(
+                Update,
+                set_options
+                    .after(MenuTreeSystems::<GameMenuAction<SnakePlugin>>::default())
+                    .run_if(on_event::<MenuActionEvent<GameMenuAction<SnakePlugin>>>()),
+            );
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SnakeMenuAction {
+    Walls(bool),
+}
+
+// What the main menu shows
+#[derive(SystemParam)]
+pub struct MainMenuOptions<'w> {
+    arena: Res<'w, Arena>,
+    scores: Scores<'w, SnakePlugin>,
+}
+
+impl GameMenu for SnakePlugin {
+    type State = SnakeState;
+    const EXITED: SnakeState = SnakeState::Exited;
+    const IN_MENU: SnakeState = SnakeState::InMenu;
+    const IN_GAME: SnakeState = SnakeState::InGame;
+
+    type Game = SnakeGame;
+    type LoadGameEvent = LoadGameEvent;
+    type CleanupGameEvent = CleanupGameEvent;
+
+    type Action = SnakeMenuAction;
+    type MenuData = MainMenuOptions<'static>;
+
+    const COLOR: Color = Color::Srgba(palettes::css::LIGHT_GREEN);
+    const BACKGROUND: Color = Color::srgba(0.05, 0.1, 0.05, 0.9);
+
+    fn main_menu(options: &SystemParamItem<Self::MenuData>) -> Menu<GameMenuAction<Self>> {
+        Menu::new()
+            .action("Play", GameMenuAction::Play)
+            .toggle("Walls", *options.arena == Arena::Walled, |walls| {
+                GameMenuAction::Game(SnakeMenuAction::Walls(walls))
+            })
+            .submenu("High Scores", high_scores_menu(options.scores.best()))
+            .action("Exit", GameMenuAction::Exit)
+    }
+
+    fn status(options: &SystemParamItem<Self::MenuData>, game: Option<&SnakeGame>) -> String {
+        options.scores.status(game.map(|game| game.score))
+    }
+
+    fn load_game_event(restart: bool) -> LoadGameEvent {
+        LoadGameEvent { restart }
+    }
+}
+
+// What the actions change besides the menu and the game
+fn set_options(
+    mut menu_actions: EventReader<MenuActionEvent<GameMenuAction<SnakePlugin>>>,
+    mut arena: ResMut<Arena>,
+) {
+    for MenuActionEvent(action) in menu_actions.read() {
+        if let GameMenuAction::Game(SnakeMenuAction::Walls(walls)) = *action {
+            *arena = if walls {
+                Arena::Walled
+            } else {
+                Arena::Wrapping
+            };
+        }
+    }
+}
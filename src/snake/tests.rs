@@ -0,0 +1,114 @@
+use bevy::prelude::*;
+
+use crate::testing::{self, game, play_game, run_frames, run_until, state, tap};
+
+use super::{
+    game::{Arena, SnakeGame, NB_COLS},
+    *,
+};
+
+// Game whose food is on the first free cell
+fn new_game(arena: Arena) -> SnakeGame {
+    SnakeGame::new(arena, |_| 0)
+}
+
+#[test]
+fn the_snake_steps_and_turns() {
+    let mut game = new_game(Arena::Walled);
+    let head = game.head();
+    let length = game.body.len();
+
+    game.step(|_| 0);
+    assert_eq!(game.head(), head + IVec2::X);
+    assert_eq!(game.body.len(), length);
+
+    // Back onto itself is ignored, two quick turns are kept for two steps
+    game.turn(IVec2::NEG_X);
+    game.turn(IVec2::Y);
+    game.turn(IVec2::NEG_X);
+    game.step(|_| 0);
+    assert_eq!(game.head(), head + IVec2::new(1, 1));
+    game.step(|_| 0);
+    assert_eq!(game.head(), head + IVec2::new(0, 1));
+    assert_eq!(game.direction, IVec2::NEG_X);
+}
+
+#[test]
+fn food_grows_the_snake() {
+    let mut game = new_game(Arena::Walled);
+    let length = game.body.len();
+    game.food = game.head() + IVec2::X;
+
+    game.step(|_| 0);
+    assert_eq!(game.body.len(), length + 1);
+    assert!(game.score > 0);
+    assert!(!game.body.contains(&game.food));
+}
+
+#[test]
+fn walls_end_the_game_unless_the_arena_wraps() {
+    let mut walled = new_game(Arena::Walled);
+    let mut wrapping = new_game(Arena::Wrapping);
+    for _ in 0..NB_COLS {
+        walled.step(|_| 0);
+        wrapping.step(|_| 0);
+    }
+
+    assert!(walled.game_over);
+    assert!(!wrapping.game_over);
+    assert_eq!(wrapping.head(), new_game(Arena::Wrapping).head());
+}
+
+#[test]
+fn running_into_itself_ends_the_game() {
+    let mut game = new_game(Arena::Wrapping);
+    game.food = game.head() + IVec2::X;
+    game.step(|_| 0);
+    game.food = game.head() + IVec2::X;
+    game.step(|_| 0);
+    assert_eq!(game.body.len(), 5);
+
+    for direction in [IVec2::Y, IVec2::NEG_X, IVec2::NEG_Y] {
+        game.turn(direction);
+        game.step(|_| 0);
+    }
+    assert!(game.game_over);
+}
+
+#[test]
+fn the_snake_moves_on_its_own_and_turns_with_the_keys() {
+    let mut app = play_game(SnakePlugin);
+    let head = game::<SnakePlugin>(&app).head();
+
+    tap(&mut app, KeyCode::ArrowUp);
+    assert!(run_until(&mut app, 30, |app| game::<SnakePlugin>(app)
+        .head()
+        != head));
+    assert_eq!(game::<SnakePlugin>(&app).head(), head + IVec2::Y);
+}
+
+#[test]
+fn game_over_keeps_the_best_score_and_exit_cleans_up() {
+    // Straight into the right wall
+    testing::game_over_keeps_the_best_score_and_exit_cleans_up(
+        SnakePlugin,
+        |app| app.world_mut().resource_mut::<SnakeGame>().score = 120,
+        600,
+        120,
+    );
+}
+
+#[test]
+fn pausing_shows_the_menu_and_keeps_the_game() {
+    let mut app = play_game(SnakePlugin);
+
+    tap(&mut app, KeyCode::Escape);
+    assert_eq!(state::<SnakeState>(&app), SnakeState::InMenu);
+    let head = game::<SnakePlugin>(&app).head();
+    run_frames(&mut app, 30);
+    assert_eq!(game::<SnakePlugin>(&app).head(), head);
+
+    // "Resume"
+    tap(&mut app, KeyCode::Space);
+    assert_eq!(state::<SnakeState>(&app), SnakeState::InGame);
+}
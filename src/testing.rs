@@ -0,0 +1,383 @@
+//! Helpers to run the games headless, without a window or a GPU, and drive
+//! them with scripted key presses, gamepad events and pointer interactions
+
+use std::collections::BTreeSet;
+
+use bevy::{
+    ecs::event::ManualEventReader,
+    input::{
+        gamepad::{
+            GamepadAxisChangedEvent, GamepadAxisType, GamepadButtonChangedEvent, GamepadButtonType,
+            GamepadConnection, GamepadConnectionEvent, GamepadEvent, GamepadInfo,
+        },
+        keyboard::{Key, KeyboardInput, NativeKey},
+        mouse::MouseButtonInput,
+        ButtonState,
+    },
+    prelude::*,
+    state::state::FreelyMutableState,
+};
+
+use crate::{
+    application::CurrentGame,
+    breakout::BreackoutPlugin,
+    demo::DemoPlugin,
+    game_menu::{GameMenu, ScoredGame},
+    headless,
+    launch::LaunchOptions,
+    menu::MenuPlugin,
+    pacman::PacmanPlugin,
+    registry::{AddGame, RetroGame},
+    tetris::TetrisPlugin,
+};
+
+pub use crate::headless::FRAME;
+
+pub const SEED: u64 = 42;
+
+/// `headless::headless_app` with demos, seeded with `SEED`
+pub fn headless_app() -> App {
+    let mut app = headless::headless_app(SEED);
+    app.add_plugins(DemoPlugin);
+
+    app
+}
+
+/// Headless app with the launcher, Breakout and Tetris, settled in the
+/// launcher
+pub fn launcher_app() -> App {
+    let mut app = headless_app();
+    app.add_plugins(MenuPlugin)
+        .add_game(BreackoutPlugin)
+        .add_game(TetrisPlugin);
+    app.update();
+
+    app
+}
+
+/// Headless app with the launcher and only `game`, settled in the launcher
+/// with the game as its first item
+pub fn game_app<G: RetroGame>(game: G) -> App {
+    let mut app = headless_app();
+    app.add_plugins(MenuPlugin).add_game(game);
+    app.update();
+
+    app
+}
+
+/// Enters `G` from the launcher of `game_app`, then starts a game with the
+/// item of its main menu `nb_down` items below the first
+pub fn play<G: GameMenu>(app: &mut App, nb_down: usize) {
+    tap(app, KeyCode::Space);
+    run_frames(app, 1);
+    assert_eq!(state::<G::State>(app), G::IN_MENU);
+
+    for _ in 0..nb_down {
+        tap(app, KeyCode::ArrowDown);
+    }
+    tap(app, KeyCode::Space);
+    run_frames(app, 1);
+    assert_eq!(state::<G::State>(app), G::IN_GAME);
+}
+
+/// `game_app` with a game of `game` started from "Play"
+pub fn play_game<G: GameMenu>(game: G) -> App {
+    let mut app = game_app(game);
+    play::<G>(&mut app, 0);
+
+    app
+}
+
+/// The game being played
+pub fn game<G: GameMenu>(app: &App) -> &G::Game {
+    app.world().resource::<G::Game>()
+}
+
+/// Plays a game of `game` that `end` sets up to be over within `max_frames`,
+/// then checks that the game is dropped, that "High Scores" shows
+/// `best_score` and that "Exit" leaves the launcher as it was
+pub fn game_over_keeps_the_best_score_and_exit_cleans_up<G: GameMenu>(
+    game: G,
+    end: impl FnOnce(&mut App),
+    max_frames: usize,
+    best_score: u32,
+) where
+    G::Game: ScoredGame,
+{
+    let mut app = game_app(game);
+    let in_launcher = snapshot(&app);
+    play::<G>(&mut app, 0);
+    end(&mut app);
+
+    assert!(run_until(&mut app, max_frames, |app| {
+        state::<G::State>(app) == G::IN_MENU
+    }));
+    run_frames(&mut app, 1);
+    assert!(!app.world().contains_resource::<G::Game>());
+
+    // "High Scores" and "Exit" end the main menu, which wraps around
+    tap(&mut app, KeyCode::ArrowUp);
+    tap(&mut app, KeyCode::ArrowUp);
+    tap(&mut app, KeyCode::Space);
+    menu_item(&mut app, &format!("Best score: {best_score}"));
+    tap(&mut app, KeyCode::Escape);
+
+    tap(&mut app, KeyCode::ArrowUp);
+    tap(&mut app, KeyCode::Space);
+    run_frames(&mut app, 1);
+    assert_eq!(state::<CurrentGame>(&app), CurrentGame::InMainMenu);
+    assert_eq!(state::<G::State>(&app), G::EXITED);
+    assert_eq!(snapshot(&app), in_launcher);
+}
+
+/// Steps `game` until `done` holds, panics if it still does not after
+/// `max_steps`
+#[track_caller]
+pub fn step_until<T>(
+    game: &mut T,
+    max_steps: usize,
+    mut step: impl FnMut(&mut T),
+    mut done: impl FnMut(&T) -> bool,
+) {
+    for _ in 0..max_steps {
+        step(game);
+        if done(game) {
+            return;
+        }
+    }
+    panic!("The game should be done stepping within {max_steps} steps");
+}
+
+/// `launcher_app` started with the command line `args`, settled where they
+/// lead
+pub fn launched_app(args: &str) -> App {
+    let launch_options = LaunchOptions::parse(args.split_whitespace().map(String::from))
+        .expect("The arguments should be valid");
+
+    let mut app = headless_app();
+    app.add_plugins(MenuPlugin)
+        .add_game(BreackoutPlugin)
+        .add_game(TetrisPlugin)
+        .add_game(PacmanPlugin)
+        .add_plugins(launch_options);
+    run_frames(&mut app, 3);
+
+    app
+}
+
+pub fn press(app: &mut App, key_code: KeyCode) {
+    send_key(app, key_code, ButtonState::Pressed);
+}
+
+pub fn release(app: &mut App, key_code: KeyCode) {
+    send_key(app, key_code, ButtonState::Released);
+}
+
+/// Presses and releases a key, one update each
+pub fn tap(app: &mut App, key_code: KeyCode) {
+    press(app, key_code);
+    app.update();
+    release(app, key_code);
+    app.update();
+}
+
+pub fn connect_gamepad(app: &mut App, id: usize) -> Gamepad {
+    let gamepad = Gamepad::new(id);
+    let info = GamepadInfo {
+        name: format!("Test gamepad {id}"),
+    };
+
+    send_gamepad_event(
+        app,
+        GamepadConnectionEvent::new(gamepad, GamepadConnection::Connected(info)).into(),
+    );
+    app.update();
+
+    gamepad
+}
+
+pub fn disconnect_gamepad(app: &mut App, gamepad: Gamepad) {
+    send_gamepad_event(
+        app,
+        GamepadConnectionEvent::new(gamepad, GamepadConnection::Disconnected).into(),
+    );
+    app.update();
+}
+
+pub fn press_button(app: &mut App, gamepad: Gamepad, button: GamepadButtonType) {
+    send_gamepad_event(
+        app,
+        GamepadButtonChangedEvent::new(gamepad, button, 1.0).into(),
+    );
+}
+
+pub fn release_button(app: &mut App, gamepad: Gamepad, button: GamepadButtonType) {
+    send_gamepad_event(
+        app,
+        GamepadButtonChangedEvent::new(gamepad, button, 0.0).into(),
+    );
+}
+
+/// Presses and releases a gamepad button, one update each
+pub fn tap_button(app: &mut App, gamepad: Gamepad, button: GamepadButtonType) {
+    press_button(app, gamepad, button);
+    app.update();
+    release_button(app, gamepad, button);
+    app.update();
+}
+
+pub fn move_axis(app: &mut App, gamepad: Gamepad, axis: GamepadAxisType, value: f32) {
+    send_gamepad_event(
+        app,
+        GamepadAxisChangedEvent::new(gamepad, axis, value).into(),
+    );
+}
+
+/// Item of a shown menu with the given text. There is no UI layout in a
+/// headless app, so the items are hovered and clicked by setting their
+/// `Interaction` like the UI would.
+pub fn menu_item(app: &mut App, label: &str) -> Entity {
+    let world = app.world_mut();
+    let mut items = world.query_filtered::<(Entity, &Text), With<Interaction>>();
+    let mut styles = world.query::<(&Style, Option<&Parent>)>();
+
+    let shown_items: Vec<Entity> = items
+        .iter(world)
+        .filter(|(_, text)| text.sections[0].value == label)
+        .map(|(entity, _)| entity)
+        .filter(|entity| {
+            let mut node = Some(*entity);
+            while let Some(entity) = node {
+                let (style, parent) = styles.get(world, entity).unwrap();
+                if style.display == Display::None {
+                    return false;
+                }
+                node = parent.map(|parent| parent.get());
+            }
+            true
+        })
+        .collect();
+
+    assert_eq!(shown_items.len(), 1, "menu item {label}");
+    shown_items[0]
+}
+
+pub fn hover(app: &mut App, entity: Entity) {
+    *app.world_mut().get_mut::<Interaction>(entity).unwrap() = Interaction::Hovered;
+    app.update();
+}
+
+/// Clicks the item with the left mouse button, one update to press and one
+/// to release
+pub fn click(app: &mut App, entity: Entity) {
+    *app.world_mut().get_mut::<Interaction>(entity).unwrap() = Interaction::Pressed;
+    send_mouse_button(app, ButtonState::Pressed);
+    app.update();
+
+    if let Some(mut interaction) = app.world_mut().get_mut::<Interaction>(entity) {
+        *interaction = Interaction::Hovered;
+    }
+    send_mouse_button(app, ButtonState::Released);
+    app.update();
+}
+
+pub fn run_frames(app: &mut App, nb_frames: usize) {
+    for _ in 0..nb_frames {
+        app.update();
+    }
+}
+
+/// Updates the app until `condition` holds, returns false if it still does
+/// not after `max_frames` updates
+pub fn run_until(
+    app: &mut App,
+    max_frames: usize,
+    mut condition: impl FnMut(&mut App) -> bool,
+) -> bool {
+    for _ in 0..max_frames {
+        app.update();
+        if condition(app) {
+            return true;
+        }
+    }
+
+    false
+}
+
+pub fn count<C: Component>(app: &mut App) -> usize {
+    app.world_mut()
+        .query_filtered::<(), With<C>>()
+        .iter(app.world())
+        .count()
+}
+
+pub fn set_state<S: FreelyMutableState>(app: &mut App, state: S) {
+    app.world_mut().resource_mut::<NextState<S>>().set(state);
+}
+
+pub fn state<S: States>(app: &App) -> S {
+    app.world().resource::<State<S>>().get().clone()
+}
+
+/// What lives in the world at some point, two snapshots taken in the
+/// launcher should match if a game session cleaned up after itself
+#[derive(Debug, PartialEq, Eq)]
+pub struct WorldSnapshot {
+    nb_entities: u32,
+    resources: BTreeSet<String>,
+}
+
+pub fn snapshot(app: &App) -> WorldSnapshot {
+    let world = app.world();
+
+    WorldSnapshot {
+        nb_entities: world.entities().len(),
+        resources: world
+            .iter_resources()
+            .map(|(info, _)| info.name().to_string())
+            .collect(),
+    }
+}
+
+/// Counts the events of type `E` sent since the counter was created, must be
+/// updated at least every other frame to not miss any
+pub struct EventCounter<E: Event> {
+    reader: ManualEventReader<E>,
+    count: usize,
+}
+
+impl<E: Event> EventCounter<E> {
+    pub fn new(app: &App) -> Self {
+        Self {
+            reader: app.world().resource::<Events<E>>().get_reader(),
+            count: 0,
+        }
+    }
+
+    pub fn update(&mut self, app: &App) -> usize {
+        let events = app.world().resource::<Events<E>>();
+        self.count += self.reader.read(events).count();
+        self.count
+    }
+}
+
+fn send_key(app: &mut App, key_code: KeyCode, state: ButtonState) {
+    app.world_mut().send_event(KeyboardInput {
+        key_code,
+        logical_key: Key::Unidentified(NativeKey::Unidentified),
+        state,
+        window: Entity::PLACEHOLDER,
+    });
+}
+
+fn send_gamepad_event(app: &mut App, event: GamepadEvent) {
+    app.world_mut().send_event(event);
+}
+
+fn send_mouse_button(app: &mut App, state: ButtonState) {
+    app.world_mut().send_event(MouseButtonInput {
+        button: MouseButton::Left,
+        state,
+        window: Entity::PLACEHOLDER,
+    });
+}
@@ -4,28 +4,76 @@ mod menu;
 use menu::MenuPlugin;
 
 mod game;
-use game::GamePlugin;
+use game::{GamePlugin, LoadGameEvent, TetrisMode};
 
 mod grid;
+pub use grid::GameGrid;
 
 mod tetromino;
 
-use crate::application::CurrentGame;
+mod save;
+
+mod ai;
+use ai::Hint;
+pub use ai::{Evaluation, Heuristic, Weights, WEIGHTS_FILE};
+
+mod benchmark;
+pub use benchmark::{benchmark, BenchmarkReport};
+
+use crate::demo::DemoSystems;
+use crate::input::Action;
+use crate::launch::LaunchGame;
+use crate::registry::RetroGame;
+use crate::replay::AddReplayedResource;
 
 pub struct TetrisPlugin;
 
+impl RetroGame for TetrisPlugin {
+    const NAME: &'static str = "Tetris";
+    const ICON: &'static str = "tetris/menu_background.png";
+    const DESCRIPTION: &'static str = "Stack the falling pieces to clear full lines.";
+    const CONTROLS: &'static [Action] = &[
+        Action::MoveLeft,
+        Action::MoveRight,
+        Action::SoftDrop,
+        Action::HardDrop,
+        Action::RotateCW,
+        Action::RotateCCW,
+        Action::Hold,
+        Action::Pause,
+    ];
+    const HAS_DEMO: bool = true;
+}
+
 impl Plugin for TetrisPlugin {
     fn build(&self, app: &mut App) {
         app.add_plugins(MenuPlugin)
             .add_plugins(GamePlugin)
             .insert_state(TetrisState::Exited)
-            .add_systems(OnEnter(CurrentGame::Tetris), setup_tetris)
-            .add_systems(OnExit(CurrentGame::Tetris), close_tetris);
+            .init_resource::<Hint>()
+            .add_replayed_resource::<Hint>()
+            .insert_resource(ai::load_weights())
+            .add_systems(
+                PreUpdate,
+                ai::autoplay
+                    .in_set(DemoSystems)
+                    .run_if(in_state(TetrisPlugin::STATE)),
+            )
+            .add_systems(
+                OnEnter(TetrisPlugin::STATE),
+                (
+                    setup_tetris,
+                    launch
+                        .after(setup_tetris)
+                        .run_if(resource_exists::<LaunchGame>),
+                ),
+            )
+            .add_systems(OnExit(TetrisPlugin::STATE), close_tetris);
     }
 }
 
 #[derive(States, PartialEq, Eq, Hash, Clone, Debug)]
-enum TetrisState {
+pub(crate) enum TetrisState {
     Exited,
     InMenu,
     InGame,
@@ -35,6 +83,36 @@ fn setup_tetris(mut _commands: Commands, mut next_state: ResMut<NextState<Tetris
     next_state.set(TetrisState::InMenu);
 }
 
+// Straight into the mode given on the command line, if there is one
+fn launch(
+    mut commands: Commands,
+    launch_game: Res<LaunchGame>,
+    mut mode: ResMut<TetrisMode>,
+    mut load_game_event: EventWriter<LoadGameEvent>,
+    mut next_state: ResMut<NextState<TetrisState>>,
+) {
+    commands.remove_resource::<LaunchGame>();
+
+    if let Some(level) = launch_game.level {
+        warn!("Tetris has no level {level} to start from, the levels come with the lines");
+    }
+
+    let Some(name) = &launch_game.mode else {
+        return;
+    };
+    let Some(launch_mode) = TetrisMode::from_name(name) else {
+        warn!("Tetris has no {name} mode, only marathon and sprint");
+        return;
+    };
+
+    *mode = launch_mode;
+    load_game_event.send_default();
+    next_state.set(TetrisState::InGame);
+}
+
 fn close_tetris(mut next_state: ResMut<NextState<TetrisState>>) {
     next_state.set(TetrisState::Exited);
 }
+
+#[cfg(test)]
+mod tests;
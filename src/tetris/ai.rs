@@ -0,0 +1,337 @@
+//! Plays Tetris by itself: during demos, in `benchmark`, and as a hint
+//! showing the player where the piece fits best
+
+use std::{fs, io, path::Path};
+
+use bevy::prelude::*;
+
+use crate::demo::tap_action;
+use crate::input::Action;
+use crate::replay::ReplayedResource;
+
+use super::{
+    game::{block_position, HintBlock, TetrisGame},
+    grid::{GameGrid, NB_COLS},
+    tetromino::{Rotation, Tetromino, TetrominoShape},
+    TetrisState,
+};
+
+pub const WEIGHTS_FILE: &str = "assets/tetris/ai_weights.txt";
+
+// Seconds between two actions of the bot, so that a demo can be followed
+const ACTION_DELAY: f32 = 0.05;
+
+/// Whether the best place for the falling piece is shown
+#[derive(Resource, Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub(super) struct Hint(pub bool);
+
+impl ReplayedResource for Hint {
+    const KEY: &'static str = "tetris hint";
+
+    fn to_text(&self) -> String {
+        self.0.to_string()
+    }
+
+    fn from_text(text: &str) -> Option<Self> {
+        text.parse().ok().map(Self)
+    }
+}
+
+/// Scores the grid left by a placement, the bot picks the placement with
+/// the highest score
+pub trait Evaluation {
+    /// `nb_lines` is the number of lines the placement cleared
+    fn evaluate(&self, grid: &GameGrid, nb_lines: u32) -> f32;
+}
+
+/// What `Weights` can take into account
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Heuristic {
+    /// Empty cells with a block above them
+    Holes,
+    /// Heights of the columns, added up
+    AggregateHeight,
+    /// Height differences between neighboring columns, added up
+    Bumpiness,
+    CompletedLines,
+}
+
+impl Heuristic {
+    pub const ALL: [Heuristic; 4] = [
+        Heuristic::Holes,
+        Heuristic::AggregateHeight,
+        Heuristic::Bumpiness,
+        Heuristic::CompletedLines,
+    ];
+
+    pub fn measure(&self, grid: &GameGrid, nb_lines: u32) -> f32 {
+        let heights = (0..NB_COLS).map(|x| grid.column_height(x));
+
+        let measure = match self {
+            Heuristic::Holes => (0..NB_COLS)
+                .map(|x| {
+                    (0..grid.column_height(x))
+                        .filter(|&y| grid.is_free(IVec2::new(x as i32, y as i32)))
+                        .count()
+                })
+                .sum(),
+            Heuristic::AggregateHeight => heights.sum(),
+            Heuristic::Bumpiness => heights
+                .clone()
+                .zip(heights.skip(1))
+                .map(|(left, right)| left.abs_diff(right))
+                .sum(),
+            Heuristic::CompletedLines => nb_lines as usize,
+        };
+
+        measure as f32
+    }
+
+    fn from_name(name: &str) -> Option<Heuristic> {
+        Heuristic::ALL
+            .into_iter()
+            .find(|heuristic| format!("{heuristic:?}") == name)
+    }
+}
+
+/// A weighted sum of heuristics, the ones without a weight are left out
+#[derive(Resource, Clone, PartialEq, Debug)]
+pub struct Weights {
+    weights: Vec<(Heuristic, f32)>,
+}
+
+impl Default for Weights {
+    fn default() -> Self {
+        Self {
+            weights: vec![
+                (Heuristic::Holes, -0.36),
+                (Heuristic::AggregateHeight, -0.51),
+                (Heuristic::Bumpiness, -0.18),
+                (Heuristic::CompletedLines, 0.76),
+            ],
+        }
+    }
+}
+
+impl Weights {
+    pub fn get(&self, heuristic: Heuristic) -> f32 {
+        self.weights
+            .iter()
+            .find(|(weighted, _)| *weighted == heuristic)
+            .map_or(0.0, |(_, weight)| *weight)
+    }
+
+    pub fn set(&mut self, heuristic: Heuristic, weight: f32) {
+        self.weights.retain(|(weighted, _)| *weighted != heuristic);
+        self.weights.push((heuristic, weight));
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        Self::from_text(&fs::read_to_string(path)?)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "invalid weights file"))
+    }
+
+    // One `Heuristic = weight` line per heuristic
+    fn from_text(text: &str) -> Option<Self> {
+        let mut weights = Self {
+            weights: Vec::new(),
+        };
+
+        for line in text.lines().map(str::trim).filter(|line| !line.is_empty()) {
+            let (heuristic, weight) = line.split_once('=')?;
+            weights.set(
+                Heuristic::from_name(heuristic.trim())?,
+                weight.trim().parse().ok()?,
+            );
+        }
+
+        Some(weights)
+    }
+}
+
+impl Evaluation for Weights {
+    fn evaluate(&self, grid: &GameGrid, nb_lines: u32) -> f32 {
+        self.weights
+            .iter()
+            .map(|(heuristic, weight)| weight * heuristic.measure(grid, nb_lines))
+            .sum()
+    }
+}
+
+pub(super) fn load_weights() -> Weights {
+    match Weights::load(WEIGHTS_FILE) {
+        Ok(weights) => weights,
+        Err(error) => {
+            warn!("Could not load {WEIGHTS_FILE}, using the default weights: {error}");
+            Weights::default()
+        }
+    }
+}
+
+/// Where the piece goes, after holding it or not
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub(super) struct Placement {
+    pub hold: bool,
+    pub tetromino: Tetromino,
+}
+
+/// Best placement of the falling piece, or of the one it would be swapped
+/// with when it can be held
+pub(super) fn best_placement(
+    grid: &GameGrid,
+    game: &TetrisGame,
+    evaluation: &impl Evaluation,
+) -> Option<Placement> {
+    let mut candidates = vec![(false, game.piece.shape)];
+    if game.can_hold {
+        candidates.push((true, game.held.unwrap_or(game.next)));
+    }
+
+    candidates
+        .into_iter()
+        .filter_map(|(hold, shape)| {
+            best_drop(grid, shape, evaluation)
+                .map(|(tetromino, score)| (Placement { hold, tetromino }, score))
+        })
+        .fold(
+            None,
+            |best: Option<(Placement, f32)>, (placement, score)| match best {
+                Some((_, best_score)) if best_score >= score => best,
+                _ => Some((placement, score)),
+            },
+        )
+        .map(|(placement, _)| placement)
+}
+
+/// Best place to drop a piece of `shape`, with its score
+pub(super) fn best_drop(
+    grid: &GameGrid,
+    shape: TetrominoShape,
+    evaluation: &impl Evaluation,
+) -> Option<(Tetromino, f32)> {
+    let mut best: Option<(Tetromino, f32)> = None;
+
+    for tetromino in drops(grid, shape) {
+        let mut after = grid.clone();
+        let nb_lines = after.lock(&tetromino);
+        let score = evaluation.evaluate(&after, nb_lines);
+
+        if best.is_none_or(|(_, best_score)| score > best_score) {
+            best = Some((tetromino, score));
+        }
+    }
+
+    best
+}
+
+// Every place a piece of `shape` can be dropped to, turned at the top of
+// the grid then moved sideways
+fn drops(grid: &GameGrid, shape: TetrominoShape) -> Vec<Tetromino> {
+    let mut drops = Vec::new();
+
+    for rotation in Rotation::ALL {
+        let turned = Tetromino::spawn(shape).rotated(rotation);
+        if !grid.fits(&turned) {
+            continue;
+        }
+
+        // Straight down from where it was turned, then from each column on
+        // its left and on its right
+        for (start, direction) in [(turned, IVec2::NEG_X), (turned.moved(IVec2::X), IVec2::X)] {
+            let mut moved = start;
+            while grid.fits(&moved) {
+                drops.push(grid.dropped(&moved));
+                moved = moved.moved(direction);
+            }
+        }
+    }
+
+    drops
+}
+
+// What brings the falling piece to `placement`, one action at a time
+fn next_action(game: &TetrisGame, placement: &Placement) -> Action {
+    let piece = game.piece;
+    let target = placement.tetromino;
+
+    if placement.hold {
+        Action::Hold
+    } else if piece.rotation != target.rotation {
+        if piece.rotation.rotate_left() == target.rotation {
+            Action::RotateCCW
+        } else {
+            Action::RotateCW
+        }
+    } else if piece.coords.x < target.coords.x {
+        Action::MoveRight
+    } else if piece.coords.x > target.coords.x {
+        Action::MoveLeft
+    } else {
+        Action::HardDrop
+    }
+}
+
+/// Plays the whole game: starts it from the menu and places every piece
+pub(super) fn autoplay(
+    time: Res<Time>,
+    tetris_state: Res<State<TetrisState>>,
+    grid: Option<Res<GameGrid>>,
+    game: Option<Res<TetrisGame>>,
+    weights: Res<Weights>,
+    mut input: ResMut<ButtonInput<Action>>,
+    mut cooldown: Local<f32>,
+) {
+    match tetris_state.get() {
+        // "Play" is the first item of the menu
+        TetrisState::InMenu => tap_action(&mut input, Action::Confirm),
+        TetrisState::InGame => {
+            input.release_all();
+
+            *cooldown -= time.delta_seconds();
+            if *cooldown > 0.0 {
+                return;
+            }
+
+            let (Some(grid), Some(game)) = (grid, game) else {
+                return;
+            };
+            if let Some(placement) = best_placement(&grid, &game, weights.as_ref()) {
+                input.press(next_action(&game, &placement));
+                *cooldown = ACTION_DELAY;
+            }
+        }
+        TetrisState::Exited => (),
+    }
+}
+
+/// Shows where the falling piece fits best, without holding it
+pub(super) fn draw_hint(
+    hint: Res<Hint>,
+    grid: Res<GameGrid>,
+    game: Res<TetrisGame>,
+    weights: Res<Weights>,
+    mut blocks: Query<(&mut Transform, &mut Sprite, &mut Visibility), With<HintBlock>>,
+) {
+    let best = hint
+        .0
+        .then(|| best_drop(&grid, game.piece.shape, weights.as_ref()))
+        .flatten();
+
+    let Some((tetromino, _)) = best else {
+        for (_, _, mut visibility) in &mut blocks {
+            *visibility = Visibility::Hidden;
+        }
+        return;
+    };
+
+    for ((mut transform, mut sprite, mut visibility), coords) in
+        blocks.iter_mut().zip(tetromino.blocks())
+    {
+        transform.translation = Vec3::from((block_position(coords), -0.1));
+        sprite.color = tetromino.shape.color().with_alpha(0.3);
+        *visibility = Visibility::Inherited;
+    }
+}
+
+#[cfg(test)]
+mod tests;
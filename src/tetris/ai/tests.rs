@@ -0,0 +1,144 @@
+use bevy::prelude::*;
+
+use crate::{
+    application::CurrentGame,
+    demo::Demo,
+    registry::RetroGame,
+    testing::{launcher_app, run_frames, run_until, state, tap},
+    tetris::{benchmark, TetrisPlugin},
+};
+
+use super::*;
+
+fn grid_with(blocks: &[(i32, i32)]) -> GameGrid {
+    let mut grid = GameGrid::new();
+    for (x, y) in blocks {
+        grid.set(IVec2::new(*x, *y), TetrominoShape::O);
+    }
+
+    grid
+}
+
+#[test]
+fn measures_the_heuristics() {
+    // Heights 2, 0, 3 then 0, with a hole under the block at (2, 2)
+    let grid = grid_with(&[(0, 0), (0, 1), (2, 0), (2, 2)]);
+
+    assert_eq!(Heuristic::Holes.measure(&grid, 0), 1.0);
+    assert_eq!(Heuristic::AggregateHeight.measure(&grid, 0), 5.0);
+    assert_eq!(Heuristic::Bumpiness.measure(&grid, 0), 2.0 + 3.0 + 3.0);
+    assert_eq!(Heuristic::CompletedLines.measure(&grid, 2), 2.0);
+}
+
+#[test]
+fn reads_the_weights() {
+    let weights = Weights::from_text("Holes = -2\n\n  Bumpiness=0.5  \n").unwrap();
+    assert_eq!(weights.get(Heuristic::Holes), -2.0);
+    assert_eq!(weights.get(Heuristic::Bumpiness), 0.5);
+    assert_eq!(weights.get(Heuristic::CompletedLines), 0.0);
+
+    assert!(Weights::from_text("Luck = 1").is_none());
+    assert!(Weights::from_text("Holes = a lot").is_none());
+
+    // The shipped weights are the default ones
+    assert_eq!(Weights::load(WEIGHTS_FILE).unwrap(), Weights::default());
+}
+
+#[test]
+fn completes_lines_and_holds_for_a_better_piece() {
+    // A well on the right, two rows deep
+    let mut blocks = Vec::new();
+    for y in 0..2 {
+        for x in 0..NB_COLS as i32 - 1 {
+            blocks.push((x, y));
+        }
+    }
+    let grid = grid_with(&blocks);
+    let weights = Weights::default();
+
+    let (tetromino, _) = best_drop(&grid, TetrominoShape::I, &weights).unwrap();
+    assert_eq!(tetromino.blocks().map(|coords| coords.x), [9; 4]);
+
+    let mut bag = TetrominoShape::ALL.into_iter().cycle();
+    let mut game = TetrisGame::new(|| bag.next().unwrap());
+    game.piece = Tetromino::spawn(TetrominoShape::O);
+    game.held = Some(TetrominoShape::I);
+
+    let placement = best_placement(&grid, &game, &weights).unwrap();
+    assert!(placement.hold);
+    assert_eq!(next_action(&game, &placement), Action::Hold);
+
+    // Without hold, the O is brought over the stack
+    game.can_hold = false;
+    let placement = best_placement(&grid, &game, &weights).unwrap();
+    assert!(!placement.hold);
+    assert!(placement
+        .tetromino
+        .blocks()
+        .iter()
+        .all(|coords| coords.y == 2 || coords.y == 3));
+}
+
+#[test]
+fn benchmark_plays_long_games() {
+    let report = benchmark(&Weights::default(), 2, 300);
+
+    assert_eq!(report.nb_games, 2);
+    assert_eq!(report.nb_topped_out, 0);
+    assert!(report.average_lines > 100.0, "{report}");
+
+    // Filling the holes on purpose tops out quickly
+    let mut weights = Weights::default();
+    weights.set(Heuristic::Holes, 1.0);
+    let report = benchmark(&weights, 2, 300);
+    assert_eq!(report.nb_topped_out, 2, "{report}");
+}
+
+#[test]
+fn demo_clears_lines() {
+    let mut app = launcher_app();
+
+    // Tetris is the second game of the launcher
+    tap(&mut app, KeyCode::ArrowDown);
+    assert!(run_until(&mut app, 1300, |app| {
+        app.world().contains_resource::<Demo>()
+    }));
+    assert!(run_until(&mut app, 60, |app| {
+        state::<TetrisState>(app) == TetrisState::InGame
+    }));
+    assert_eq!(state::<CurrentGame>(&app), TetrisPlugin::STATE);
+
+    run_frames(&mut app, 60 * 30);
+    let game = app.world().resource::<TetrisGame>();
+    assert!(game.lines >= 5, "{} lines", game.lines);
+    assert!(app.world().contains_resource::<Demo>());
+}
+
+#[test]
+fn hint_shows_the_best_drop() {
+    let mut app = launcher_app();
+
+    tap(&mut app, KeyCode::ArrowDown);
+    tap(&mut app, KeyCode::Space);
+    run_frames(&mut app, 1);
+    app.world_mut().resource_mut::<Hint>().0 = true;
+    tap(&mut app, KeyCode::Space);
+    run_frames(&mut app, 1);
+    assert_eq!(state::<TetrisState>(&app), TetrisState::InGame);
+
+    let world = app.world_mut();
+    let grid = world.resource::<GameGrid>().clone();
+    let game = world.resource::<TetrisGame>().clone();
+    let (best, _) = best_drop(&grid, game.piece.shape, &Weights::default()).unwrap();
+
+    let mut shown: Vec<Vec2> = world
+        .query_filtered::<(&Transform, &Visibility), With<HintBlock>>()
+        .iter(world)
+        .filter(|(_, visibility)| **visibility != Visibility::Hidden)
+        .map(|(transform, _)| transform.translation.xy())
+        .collect();
+    let mut expected = best.blocks().map(block_position).to_vec();
+    shown.sort_by(|a, b| a.x.total_cmp(&b.x).then(a.y.total_cmp(&b.y)));
+    expected.sort_by(|a, b| a.x.total_cmp(&b.x).then(a.y.total_cmp(&b.y)));
+    assert_eq!(shown, expected);
+}
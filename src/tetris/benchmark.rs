@@ -0,0 +1,86 @@
+//! Plays many games with the bot to compare evaluations. The pieces are put
+//! in place directly instead of being moved there, so it takes no time.
+
+use std::fmt;
+
+use crate::rng::GameRng;
+
+use super::{
+    ai::{best_placement, Evaluation},
+    game::TetrisGame,
+    grid::GameGrid,
+    tetromino::TetrominoBag,
+};
+
+/// How the bot did over the games
+#[derive(Debug)]
+pub struct BenchmarkReport {
+    pub nb_games: usize,
+
+    /// Games lost before reaching the piece limit
+    pub nb_topped_out: usize,
+
+    pub average_lines: f32,
+    pub average_score: f32,
+}
+
+impl fmt::Display for BenchmarkReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Games: {} ({} topped out)",
+            self.nb_games, self.nb_topped_out
+        )?;
+        writeln!(f, "Average lines: {:.1}", self.average_lines)?;
+        write!(f, "Average score: {:.0}", self.average_score)
+    }
+}
+
+/// Plays `nb_games` games, each one with its own seed, until the stack
+/// tops out or `max_pieces` pieces were placed
+pub fn benchmark(
+    evaluation: &impl Evaluation,
+    nb_games: usize,
+    max_pieces: usize,
+) -> BenchmarkReport {
+    let games: Vec<TetrisGame> = (0..nb_games as u64)
+        .map(|seed| play_game(evaluation, seed, max_pieces))
+        .collect();
+
+    let average = |value: fn(&TetrisGame) -> u32| {
+        games.iter().map(|game| value(game) as f32).sum::<f32>() / nb_games.max(1) as f32
+    };
+
+    BenchmarkReport {
+        nb_games,
+        nb_topped_out: games.iter().filter(|game| game.game_over).count(),
+        average_lines: average(|game| game.lines),
+        average_score: average(|game| game.score),
+    }
+}
+
+fn play_game(evaluation: &impl Evaluation, seed: u64, max_pieces: usize) -> TetrisGame {
+    let mut game_rng = GameRng::new(seed);
+    let mut bag = TetrominoBag::default();
+    let mut grid = GameGrid::new();
+    let mut game = TetrisGame::new(|| bag.next(&mut game_rng));
+
+    for _ in 0..max_pieces {
+        if game.game_over {
+            break;
+        }
+
+        let Some(placement) = best_placement(&grid, &game, evaluation) else {
+            game.game_over = true;
+            break;
+        };
+
+        if placement.hold {
+            game.hold(&grid, || bag.next(&mut game_rng));
+        }
+        game.piece = placement.tetromino;
+        game.lock(&mut grid, || bag.next(&mut game_rng));
+    }
+
+    game
+}
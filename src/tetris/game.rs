@@ -1,8 +1,66 @@
 use std::time::Duration;
 
-use bevy::{prelude::*, time::common_conditions::on_timer};
+use bevy::{color::palettes, ecs::system::SystemParam, prelude::*};
 
-use super::{grid::GameGrid, TetrisState};
+use crate::{
+    application::GAME_AREA,
+    input::Action,
+    registry::RetroGame,
+    replay::{AddReplayedResource, ReplayOrDemo, ReplayedResource},
+    rng::GameRng,
+    saves::SavedGames,
+    scores::BestScores,
+};
+
+use super::{
+    ai,
+    grid::{GameGrid, NB_COLS, NB_ROWS},
+    save::TetrisSave,
+    tetromino::{Rotation, Tetromino, TetrominoBag, TetrominoShape},
+    TetrisPlugin, TetrisState,
+};
+
+pub const BLOCK_SIZE: f32 = 32.0;
+
+// Bottom left corner of the grid, centered in the game area
+const GRID_ORIGIN: Vec2 = Vec2 {
+    x: GAME_AREA.min.x + (GAME_AREA.max.x - GAME_AREA.min.x - NB_COLS as f32 * BLOCK_SIZE) / 2.0,
+    y: GAME_AREA.min.y + (GAME_AREA.max.y - GAME_AREA.min.y - NB_ROWS as f32 * BLOCK_SIZE) / 2.0,
+};
+
+// Where the next and the held pieces are shown, on each side of the grid
+const NEXT_POSITION: Vec2 = Vec2 {
+    x: GRID_ORIGIN.x + NB_COLS as f32 * BLOCK_SIZE + 100.0,
+    y: GRID_ORIGIN.y + NB_ROWS as f32 * BLOCK_SIZE - 80.0,
+};
+const HOLD_POSITION: Vec2 = Vec2 {
+    x: GRID_ORIGIN.x - 100.0,
+    y: NEXT_POSITION.y,
+};
+
+// Seconds between two steps of the falling piece at the first level, it
+// speeds up at every level
+const FALL_INTERVAL: f32 = 0.5;
+const FALL_SPEED_UP: f32 = 0.85;
+const MIN_FALL_INTERVAL: f32 = 0.05;
+const SOFT_DROP_INTERVAL: f32 = 0.05;
+
+const LINES_PER_LEVEL: u32 = 10;
+
+pub const SPRINT_LINES: u32 = 40;
+
+// Points for clearing 1, 2, 3 and 4 lines at once, times the level
+const LINE_POINTS: [u32; 5] = [0, 100, 300, 500, 800];
+
+// Tried in turn when a rotation is blocked, so that pieces can turn against
+// a wall or the stack
+const ROTATION_KICKS: [IVec2; 5] = [
+    IVec2::ZERO,
+    IVec2::NEG_X,
+    IVec2::X,
+    IVec2::new(-2, 0),
+    IVec2::new(2, 0),
+];
 
 pub struct GamePlugin;
 
@@ -10,52 +68,665 @@ impl Plugin for GamePlugin {
     fn build(&self, app: &mut App) {
         app.add_event::<LoadGameEvent>()
             .add_event::<CleanupGameEvent>()
+            .init_resource::<TetrisMode>()
+            .init_resource::<SprintResult>()
+            .init_resource::<SavedGames>()
+            .add_replayed_resource::<TetrisMode>()
+            .add_replayed_resource::<SavedGames>()
+            .add_systems(OnExit(TetrisPlugin::STATE), cleanup_game)
+            .add_systems(
+                OnEnter(TetrisState::InMenu),
+                (hide_board, save_game.run_if(can_save)),
+            )
+            .add_systems(
+                PostUpdate,
+                save_game.run_if(on_event::<AppExit>()).run_if(can_save),
+            )
+            .add_systems(OnEnter(TetrisState::InGame), show_board)
             .add_systems(
                 Update,
                 (
                     load_game.run_if(on_event::<LoadGameEvent>()),
+                    (
+                        handle_player_input,
+                        fall,
+                        (end_game, drop_save).chain().run_if(is_over),
+                    )
+                        .chain()
+                        .run_if(in_state(TetrisState::InGame))
+                        .run_if(resource_exists::<TetrisGame>),
                     cleanup_game.run_if(on_event::<CleanupGameEvent>()),
-                    (update.run_if(on_timer(Duration::from_secs_f32(0.5))))
-                        .run_if(in_state(TetrisState::InGame)),
-                ),
+                    (draw_grid, ai::draw_hint, draw_previews, update_hud)
+                        .run_if(in_state(TetrisState::InGame))
+                        .run_if(resource_exists::<TetrisGame>),
+                )
+                    .chain(),
             );
     }
 }
 
 #[derive(Event, Default)]
-pub struct LoadGameEvent;
+pub struct LoadGameEvent {
+    pub restart: bool,
+    // Continues the saved game instead
+    pub from_save: bool,
+}
 
 #[derive(Event, Default)]
 pub struct CleanupGameEvent;
 
-fn load_game(mut commands: Commands) {
-    commands.insert_resource(GameGrid::new());
+#[derive(Resource, Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub enum TetrisMode {
+    /// Until the stack tops out, faster at every level
+    #[default]
+    Marathon,
+    /// `SPRINT_LINES` lines as fast as possible
+    Sprint,
+}
+
+impl TetrisMode {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "marathon" => Some(TetrisMode::Marathon),
+            "sprint" => Some(TetrisMode::Sprint),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            TetrisMode::Marathon => "marathon",
+            TetrisMode::Sprint => "sprint",
+        }
+    }
+}
+
+impl ReplayedResource for TetrisMode {
+    const KEY: &'static str = "tetris mode";
+
+    fn to_text(&self) -> String {
+        self.name().to_string()
+    }
+
+    fn from_text(text: &str) -> Option<Self> {
+        Self::from_name(text)
+    }
+}
+
+/// Seconds the last finished sprint took
+#[derive(Resource, Default)]
+pub struct SprintResult(pub Option<f32>);
+
+/// The falling piece and everything but the grid about the game being played
+#[derive(Resource, Clone, Debug)]
+pub struct TetrisGame {
+    pub piece: Tetromino,
+    pub next: TetrominoShape,
+    pub held: Option<TetrominoShape>,
+
+    // Only once per piece
+    pub can_hold: bool,
+
+    pub score: u32,
+    pub lines: u32,
+
+    // Seconds played, pauses left out
+    pub time: f32,
+
+    // The next piece did not fit in the grid
+    pub game_over: bool,
+
+    fall_timer: Timer,
+}
+
+impl TetrisGame {
+    /// The pieces are dealt by `next_shape`
+    pub fn new(mut next_shape: impl FnMut() -> TetrominoShape) -> Self {
+        Self {
+            piece: Tetromino::spawn(next_shape()),
+            next: next_shape(),
+            held: None,
+            can_hold: true,
+            score: 0,
+            lines: 0,
+            time: 0.0,
+            game_over: false,
+            fall_timer: Timer::from_seconds(FALL_INTERVAL, TimerMode::Repeating),
+        }
+    }
+
+    pub fn level(&self) -> u32 {
+        self.lines / LINES_PER_LEVEL + 1
+    }
+
+    fn fall_interval(&self) -> f32 {
+        (FALL_INTERVAL * FALL_SPEED_UP.powi(self.level() as i32 - 1)).max(MIN_FALL_INTERVAL)
+    }
+
+    pub fn try_move(&mut self, grid: &GameGrid, offset: IVec2) -> bool {
+        let moved = self.piece.moved(offset);
+        if !grid.fits(&moved) {
+            return false;
+        }
+
+        self.piece = moved;
+        true
+    }
+
+    pub fn try_rotate(&mut self, grid: &GameGrid, rotation: Rotation) -> bool {
+        let rotated = self.piece.rotated(rotation);
+
+        match ROTATION_KICKS
+            .into_iter()
+            .map(|kick| rotated.moved(kick))
+            .find(|kicked| grid.fits(kicked))
+        {
+            Some(kicked) => {
+                self.piece = kicked;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Swaps the piece with the held one, or with the next one if none is
+    /// held yet
+    pub fn hold(&mut self, grid: &GameGrid, next_shape: impl FnOnce() -> TetrominoShape) {
+        if !self.can_hold {
+            return;
+        }
+
+        match self.held.replace(self.piece.shape) {
+            Some(held) => {
+                self.piece = Tetromino::spawn(held);
+                self.game_over = !grid.fits(&self.piece);
+            }
+            None => self.spawn(grid, next_shape),
+        }
+
+        self.can_hold = false;
+    }
+
+    pub fn hard_drop(
+        &mut self,
+        grid: &mut GameGrid,
+        next_shape: impl FnOnce() -> TetrominoShape,
+    ) -> u32 {
+        let dropped = grid.dropped(&self.piece);
+        self.score += 2 * (self.piece.coords.y - dropped.coords.y) as u32;
+        self.piece = dropped;
+
+        self.lock(grid, next_shape)
+    }
+
+    /// Leaves the piece where it is and brings the next one, returns how
+    /// many lines were cleared
+    pub fn lock(
+        &mut self,
+        grid: &mut GameGrid,
+        next_shape: impl FnOnce() -> TetrominoShape,
+    ) -> u32 {
+        let nb_lines = grid.lock(&self.piece);
+        self.score += LINE_POINTS[nb_lines as usize] * self.level();
+        self.lines += nb_lines;
+
+        self.spawn(grid, next_shape);
+        nb_lines
+    }
+
+    fn spawn(&mut self, grid: &GameGrid, next_shape: impl FnOnce() -> TetrominoShape) {
+        self.piece = Tetromino::spawn(self.next);
+        self.next = next_shape();
+        self.can_hold = true;
+        self.game_over = !grid.fits(&self.piece);
+        self.fall_timer.reset();
+    }
+}
+
+// The game being played, with what deals its pieces
+#[derive(SystemParam)]
+struct Board<'w> {
+    grid: ResMut<'w, GameGrid>,
+    game: ResMut<'w, TetrisGame>,
+    bag: ResMut<'w, TetrominoBag>,
+    game_rng: ResMut<'w, GameRng>,
+}
+
+impl Board<'_> {
+    fn hold(&mut self) {
+        let Self {
+            grid,
+            game,
+            bag,
+            game_rng,
+        } = self;
+        game.hold(grid, || bag.next(game_rng));
+    }
+
+    fn hard_drop(&mut self) {
+        let Self {
+            grid,
+            game,
+            bag,
+            game_rng,
+        } = self;
+        game.hard_drop(grid, || bag.next(game_rng));
+    }
+
+    fn lock(&mut self) {
+        let Self {
+            grid,
+            game,
+            bag,
+            game_rng,
+        } = self;
+        game.lock(grid, || bag.next(game_rng));
+    }
+}
+
+#[derive(Component)]
+pub(super) struct GameEntity;
+
+// A cell of the grid, shows the block in it or the falling piece
+#[derive(Component)]
+struct CellBlock(IVec2);
+
+#[derive(Component)]
+pub(super) struct HintBlock;
+
+#[derive(Component)]
+enum PreviewBlock {
+    Next,
+    Hold,
+}
+
+#[derive(Component)]
+struct Hud;
+
+fn load_game(
+    mut commands: Commands,
+    mut load_events: EventReader<LoadGameEvent>,
+    mut game_rng: ResMut<GameRng>,
+    mut mode: ResMut<TetrisMode>,
+    mut saved_games: ResMut<SavedGames>,
+    assets: Res<AssetServer>,
+    entities: Query<Entity, With<GameEntity>>,
+) {
+    let mut save = None;
+    if let Some(load_event) = load_events.read().last() {
+        if load_event.from_save {
+            save = saved_games
+                .get(TetrisPlugin::NAME)
+                .and_then(TetrisSave::from_text);
+            if save.is_none() {
+                warn!("The saved Tetris game could not be read, starting a new one");
+                saved_games.remove(TetrisPlugin::NAME);
+            }
+        }
+
+        match &save {
+            Some(save) => game_rng.start_run(save.seed),
+            None if load_event.restart => game_rng.restart_run(),
+            None => game_rng.new_run(),
+        }
+    }
+
+    for entity in &entities {
+        commands.entity(entity).despawn();
+    }
+
+    match save {
+        Some(save) => {
+            *mode = save.mode;
+            commands.insert_resource(save.game);
+            commands.insert_resource(save.bag);
+            commands.insert_resource(save.grid);
+        }
+        None => {
+            let mut bag = TetrominoBag::default();
+            commands.insert_resource(TetrisGame::new(|| bag.next(&mut game_rng)));
+            commands.insert_resource(bag);
+            commands.insert_resource(GameGrid::new());
+        }
+    }
+
+    commands.spawn((
+        SpriteBundle {
+            texture: assets.load("tetris/game_background.png"),
+            transform: Transform::from_translation(Vec3::from((GAME_AREA.center(), -1.0))),
+            ..Default::default()
+        },
+        GameEntity,
+    ));
+
+    commands.spawn((
+        SpriteBundle {
+            sprite: Sprite {
+                color: Color::srgba(0.0, 0.0, 0.0, 0.8),
+                custom_size: Some(Vec2::new(NB_COLS as f32, NB_ROWS as f32) * BLOCK_SIZE),
+                ..Default::default()
+            },
+            transform: Transform::from_translation(Vec3::from((
+                GRID_ORIGIN + Vec2::new(NB_COLS as f32, NB_ROWS as f32) * BLOCK_SIZE / 2.0,
+                -0.5,
+            ))),
+            ..Default::default()
+        },
+        GameEntity,
+    ));
+
+    for y in 0..NB_ROWS as i32 {
+        for x in 0..NB_COLS as i32 {
+            let coords = IVec2::new(x, y);
+            commands.spawn((
+                block_sprite(block_position(coords), Visibility::Hidden),
+                CellBlock(coords),
+                GameEntity,
+            ));
+        }
+    }
+
+    for _ in 0..4 {
+        commands.spawn((
+            block_sprite(Vec2::ZERO, Visibility::Hidden),
+            HintBlock,
+            GameEntity,
+        ));
+        commands.spawn((
+            block_sprite(Vec2::ZERO, Visibility::Inherited),
+            PreviewBlock::Next,
+            GameEntity,
+        ));
+        commands.spawn((
+            block_sprite(Vec2::ZERO, Visibility::Hidden),
+            PreviewBlock::Hold,
+            GameEntity,
+        ));
+    }
+
+    let text_style = TextStyle {
+        font_size: 24.0,
+        color: palettes::css::ORANGE.into(),
+        ..Default::default()
+    };
+    for (label, position) in [("Next", NEXT_POSITION), ("Hold", HOLD_POSITION)] {
+        commands.spawn((
+            Text2dBundle {
+                text: Text::from_section(label, text_style.clone()),
+                transform: Transform::from_translation(Vec3::from((
+                    position + Vec2::Y * 2.5 * BLOCK_SIZE,
+                    0.0,
+                ))),
+                ..Default::default()
+            },
+            GameEntity,
+        ));
+    }
+
+    commands.spawn((
+        Text2dBundle {
+            text: Text::from_section("", text_style),
+            transform: Transform::from_translation(Vec3::from((
+                NEXT_POSITION - Vec2::Y * 5.0 * BLOCK_SIZE,
+                0.0,
+            ))),
+            ..Default::default()
+        },
+        Hud,
+        GameEntity,
+    ));
+}
+
+fn block_sprite(position: Vec2, visibility: Visibility) -> SpriteBundle {
+    SpriteBundle {
+        sprite: Sprite {
+            custom_size: Some(Vec2::splat(BLOCK_SIZE - 2.0)),
+            ..Default::default()
+        },
+        transform: Transform::from_translation(Vec3::from((position, 0.0))),
+        visibility,
+        ..Default::default()
+    }
+}
+
+pub(super) fn block_position(coords: IVec2) -> Vec2 {
+    GRID_ORIGIN + (coords.as_vec2() + 0.5) * BLOCK_SIZE
 }
 
-fn cleanup_game(mut commands: Commands) {
+fn cleanup_game(mut commands: Commands, entities: Query<Entity, With<GameEntity>>) {
+    for entity in &entities {
+        commands.entity(entity).despawn();
+    }
+
     commands.remove_resource::<GameGrid>();
+    commands.remove_resource::<TetrominoBag>();
+    commands.remove_resource::<TetrisGame>();
+}
+
+fn handle_player_input(
+    input: Res<ButtonInput<Action>>,
+    mut board: Board,
+    mut next_tetris_state: ResMut<NextState<TetrisState>>,
+) {
+    if input.just_pressed(Action::Pause) {
+        next_tetris_state.set(TetrisState::InMenu);
+        return;
+    }
+
+    if input.just_pressed(Action::Hold) {
+        board.hold();
+    }
+
+    let Board { grid, game, .. } = &mut board;
+    if input.just_pressed(Action::MoveLeft) {
+        game.try_move(grid, IVec2::NEG_X);
+    }
+    if input.just_pressed(Action::MoveRight) {
+        game.try_move(grid, IVec2::X);
+    }
+    if input.just_pressed(Action::RotateCW) {
+        let rotation = game.piece.rotation.rotate_right();
+        game.try_rotate(grid, rotation);
+    }
+    if input.just_pressed(Action::RotateCCW) {
+        let rotation = game.piece.rotation.rotate_left();
+        game.try_rotate(grid, rotation);
+    }
+
+    if input.just_pressed(Action::HardDrop) {
+        board.hard_drop();
+    }
+}
+
+// The board is hidden while paused, so that the pause can not be used to
+// think the next moves over
+fn hide_board(mut entities: Query<&mut Visibility, With<GameEntity>>) {
+    for mut visibility in &mut entities {
+        *visibility = Visibility::Hidden;
+    }
+}
+
+// Only the blocks that should be seen are shown again by the draw systems
+fn show_board(mut entities: Query<&mut Visibility, With<GameEntity>>) {
+    for mut visibility in &mut entities {
+        *visibility = Visibility::Inherited;
+    }
+}
+
+fn fall(time: Res<Time>, input: Res<ButtonInput<Action>>, mut board: Board) {
+    let soft_drop = input.pressed(Action::SoftDrop);
+    let interval = if soft_drop {
+        board.game.fall_interval().min(SOFT_DROP_INTERVAL)
+    } else {
+        board.game.fall_interval()
+    };
+
+    let Board { grid, game, .. } = &mut board;
+    game.time += time.delta_seconds();
+    game.fall_timer
+        .set_duration(Duration::from_secs_f32(interval));
+    if !game.fall_timer.tick(time.delta()).just_finished() {
+        return;
+    }
+
+    if game.try_move(grid, IVec2::NEG_Y) {
+        if soft_drop {
+            game.score += 1;
+        }
+    } else {
+        board.lock();
+    }
+}
+
+fn is_sprint_finished(game: &TetrisGame, mode: TetrisMode) -> bool {
+    mode == TetrisMode::Sprint && game.lines >= SPRINT_LINES
+}
+
+// Topped out, or every line of a sprint cleared
+fn is_over(game: Option<Res<TetrisGame>>, mode: Res<TetrisMode>) -> bool {
+    game.is_some_and(|game| game.game_over || is_sprint_finished(&game, *mode))
+}
+
+// Back to the main menu. The marathon scores are kept and the sprint times
+// shown, unless it was a demo or a replay.
+fn end_game(
+    game: Res<TetrisGame>,
+    mode: Res<TetrisMode>,
+    replay_or_demo: ReplayOrDemo,
+    best_scores: Option<ResMut<BestScores>>,
+    mut sprint_result: ResMut<SprintResult>,
+    mut cleanup_game_event: EventWriter<CleanupGameEvent>,
+    mut next_tetris_state: ResMut<NextState<TetrisState>>,
+) {
+    if !replay_or_demo.is_playing() {
+        match *mode {
+            TetrisMode::Marathon => {
+                if let Some(mut best_scores) = best_scores {
+                    best_scores.submit(TetrisPlugin::NAME, game.score);
+                }
+            }
+            TetrisMode::Sprint => {
+                if is_sprint_finished(&game, *mode) {
+                    sprint_result.0 = Some(game.time);
+                }
+            }
+        }
+    }
+
+    cleanup_game_event.send_default();
+    next_tetris_state.set(TetrisState::InMenu);
+}
+
+// Only games still being played, demos and replays are never saved
+fn can_save(
+    game: Option<Res<TetrisGame>>,
+    mode: Res<TetrisMode>,
+    replay_or_demo: ReplayOrDemo,
+) -> bool {
+    !replay_or_demo.is_playing() && game.is_some() && !is_over(game, mode)
+}
+
+// Kept when the game is paused or the app closed, to be continued later
+fn save_game(
+    game: Res<TetrisGame>,
+    grid: Res<GameGrid>,
+    bag: Res<TetrominoBag>,
+    mode: Res<TetrisMode>,
+    game_rng: Res<GameRng>,
+    mut saved_games: ResMut<SavedGames>,
+) {
+    let save = TetrisSave {
+        mode: *mode,
+        seed: game_rng.seed(),
+        game: game.clone(),
+        grid: grid.clone(),
+        bag: bag.clone(),
+    };
+    saved_games.store(TetrisPlugin::NAME, save.to_text());
+}
+
+// A finished game can not be continued
+fn drop_save(replay_or_demo: ReplayOrDemo, mut saved_games: ResMut<SavedGames>) {
+    if !replay_or_demo.is_playing() {
+        saved_games.remove(TetrisPlugin::NAME);
+    }
+}
+
+fn draw_grid(
+    game: Res<TetrisGame>,
+    grid: Res<GameGrid>,
+    mut cells: Query<(&CellBlock, &mut Sprite, &mut Visibility)>,
+) {
+    let piece_blocks = game.piece.blocks();
+
+    for (CellBlock(coords), mut sprite, mut visibility) in &mut cells {
+        let shape = if piece_blocks.contains(coords) {
+            Some(game.piece.shape)
+        } else {
+            grid.get(*coords)
+        };
+
+        match shape {
+            Some(shape) => {
+                sprite.color = shape.color();
+                *visibility = Visibility::Inherited;
+            }
+            None => *visibility = Visibility::Hidden,
+        }
+    }
 }
 
-#[derive(Clone, Copy, PartialEq, Eq)]
-pub enum MoveDirection {
-    Up,
-    Down,
-    Left,
-    Right,
+fn draw_previews(
+    game: Res<TetrisGame>,
+    mut blocks: Query<(&PreviewBlock, &mut Transform, &mut Sprite, &mut Visibility)>,
+) {
+    let mut next_blocks = preview_positions(game.next, NEXT_POSITION).into_iter();
+    let mut held_blocks = game
+        .held
+        .map(|held| preview_positions(held, HOLD_POSITION))
+        .into_iter()
+        .flatten();
+
+    for (preview, mut transform, mut sprite, mut visibility) in &mut blocks {
+        let (shape, position) = match preview {
+            PreviewBlock::Next => (Some(game.next), next_blocks.next()),
+            PreviewBlock::Hold => (game.held, held_blocks.next()),
+        };
+
+        match (shape, position) {
+            (Some(shape), Some(position)) => {
+                transform.translation = Vec3::from((position, 0.0));
+                sprite.color = shape.color();
+                *visibility = Visibility::Inherited;
+            }
+            _ => *visibility = Visibility::Hidden,
+        }
+    }
 }
 
-fn update() {
-    println!("Update");
+// Blocks of `shape` centered on `center`
+fn preview_positions(shape: TetrominoShape, center: Vec2) -> [Vec2; 4] {
+    let blocks = shape
+        .get_local_coords(Rotation::R0)
+        .map(|local_coords| local_coords.as_vec2());
+    let middle = blocks.iter().fold(Vec2::ZERO, |sum, block| sum + *block) / 4.0;
+
+    blocks.map(|block| center + (block - middle) * BLOCK_SIZE)
 }
 
-fn _draw_wireframe(mut _gizmos: Gizmos) {
-    //    for x in 0..NB_COLS {
-    //        for y in 0..NB_ROWS {
-    //            let position = Vec2 {
-    //                x: BLOCK_AREA.min.x + x as f32 * 32.0 + 16.0,
-    //                y: BLOCK_AREA.min.y + y as f32 * 32.0 + 16.0,
-    //            };
-    //            gizmos.rect_2d(position, 0.0, vec2(32.0, 32.0), Color::RED);
-    //        }
-    //    }
+fn update_hud(game: Res<TetrisGame>, mode: Res<TetrisMode>, mut hud: Query<&mut Text, With<Hud>>) {
+    if let Ok(mut text) = hud.get_single_mut() {
+        text.sections[0].value = match *mode {
+            TetrisMode::Marathon => format!(
+                "Score: {}\nLines: {}\nLevel: {}",
+                game.score,
+                game.lines,
+                game.level()
+            ),
+            TetrisMode::Sprint => format!(
+                "Lines: {} / {SPRINT_LINES}\nTime: {:.1}s",
+                game.lines, game.time
+            ),
+        };
+    }
 }
@@ -1,13 +1,15 @@
 use bevy::prelude::*;
 
-use super::game::MoveDirection;
+use super::tetromino::{Tetromino, TetrominoShape};
 
 pub const NB_ROWS: usize = 16;
 pub const NB_COLS: usize = 10;
 
-#[derive(Resource)]
+/// Blocks left by the locked pieces, by shape for their color. Row 0 is at
+/// the bottom.
+#[derive(Resource, Clone, PartialEq, Eq, Default, Debug)]
 pub struct GameGrid {
-    cells: [[Option<Entity>; NB_COLS]; NB_ROWS],
+    cells: [[Option<TetrominoShape>; NB_COLS]; NB_ROWS],
 }
 
 impl GameGrid {
@@ -17,22 +19,20 @@ impl GameGrid {
         }
     }
 
-    pub fn get(&self, coords: IVec2) -> Option<Entity> {
-        if let Some(row) = self.cells.get(coords.y as usize) {
-            if let Some(cell) = row.get(coords.x as usize) {
-                return *cell;
-            }
+    pub fn get(&self, coords: IVec2) -> Option<TetrominoShape> {
+        if !GameGrid::in_bounds(coords) {
+            return None;
         }
 
-        None
+        self.cells[coords.y as usize][coords.x as usize]
     }
 
-    pub fn set(&mut self, coords: IVec2, entity: Entity) {
+    pub fn set(&mut self, coords: IVec2, shape: TetrominoShape) {
         assert!(self.is_free(coords));
 
         let x = coords.x as usize;
         let y = coords.y as usize;
-        self.cells[y][x] = Some(entity);
+        self.cells[y][x] = Some(shape);
     }
 
     pub fn is_free(&self, coords: IVec2) -> bool {
@@ -40,34 +40,56 @@ impl GameGrid {
     }
 
     pub fn in_bounds(coords: IVec2) -> bool {
-        coords.x < 0 || coords.x >= NB_COLS as i32 || coords.y < 0 || coords.y >= NB_ROWS as i32
+        coords.x >= 0 && coords.x < NB_COLS as i32 && coords.y >= 0 && coords.y < NB_ROWS as i32
+    }
+
+    pub fn fits(&self, tetromino: &Tetromino) -> bool {
+        tetromino
+            .blocks()
+            .into_iter()
+            .all(|coords| self.is_free(coords))
     }
 
-    pub fn can_move_in_direction(&self, coords: IVec2, direction: MoveDirection) -> bool {
-        let new_coords = match direction {
-            MoveDirection::Up => IVec2 {
-                y: coords.y + 1,
-                ..coords
-            },
-            MoveDirection::Down => IVec2 {
-                y: coords.y - 1,
-                ..coords
-            },
-            MoveDirection::Left => IVec2 {
-                x: coords.x - 1,
-                ..coords
-            },
-            MoveDirection::Right => IVec2 {
-                x: coords.x + 1,
-                ..coords
-            },
-        };
-
-        // Special case: the block is still above the grid and needs to fall down
-        if direction == MoveDirection::Down && new_coords.y >= NB_ROWS as i32 {
-            return true;
+    /// Where `tetromino` lands if dropped straight down
+    pub fn dropped(&self, tetromino: &Tetromino) -> Tetromino {
+        let mut dropped = *tetromino;
+        while self.fits(&dropped.moved(IVec2::NEG_Y)) {
+            dropped = dropped.moved(IVec2::NEG_Y);
         }
 
-        self.is_free(new_coords)
+        dropped
+    }
+
+    /// Leaves the blocks of `tetromino` in the grid and clears the full rows,
+    /// returns how many there were
+    pub fn lock(&mut self, tetromino: &Tetromino) -> u32 {
+        for coords in tetromino.blocks() {
+            self.set(coords, tetromino.shape);
+        }
+
+        let mut nb_cleared = 0;
+        let mut y = 0;
+        while y < NB_ROWS - nb_cleared {
+            if self.cells[y].iter().all(Option::is_some) {
+                self.cells[y..].rotate_left(1);
+                self.cells[NB_ROWS - 1] = [None; NB_COLS];
+                nb_cleared += 1;
+            } else {
+                y += 1;
+            }
+        }
+
+        nb_cleared as u32
+    }
+
+    /// Rows of the highest block of the column, 0 if it is empty
+    pub fn column_height(&self, x: usize) -> usize {
+        (0..NB_ROWS)
+            .rev()
+            .find(|&y| self.cells[y][x].is_some())
+            .map_or(0, |y| y + 1)
     }
 }
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,64 @@
+use super::*;
+use crate::tetris::tetromino::Rotation;
+
+fn fill_row(grid: &mut GameGrid, y: i32, except: &[i32]) {
+    for x in 0..NB_COLS as i32 {
+        if !except.contains(&x) {
+            grid.set(IVec2::new(x, y), TetrominoShape::O);
+        }
+    }
+}
+
+#[test]
+fn bounds_are_the_grid() {
+    assert!(GameGrid::in_bounds(IVec2::ZERO));
+    assert!(GameGrid::in_bounds(IVec2::new(9, 15)));
+    assert!(!GameGrid::in_bounds(IVec2::new(-1, 0)));
+    assert!(!GameGrid::in_bounds(IVec2::new(10, 0)));
+    assert!(!GameGrid::in_bounds(IVec2::new(0, 16)));
+}
+
+#[test]
+fn full_rows_are_cleared_and_the_rest_falls() {
+    let mut grid = GameGrid::new();
+    fill_row(&mut grid, 0, &[9]);
+    fill_row(&mut grid, 1, &[9]);
+    fill_row(&mut grid, 2, &[0, 9]);
+    grid.set(IVec2::new(4, 3), TetrominoShape::T);
+
+    // A vertical I in the well completes the two bottom rows
+    let piece = Tetromino {
+        coords: IVec2::new(7, 0),
+        rotation: Rotation::R90,
+        shape: TetrominoShape::I,
+    };
+    assert_eq!(piece.blocks().map(|coords| coords.x), [9; 4]);
+    let dropped = grid.dropped(&piece.moved(IVec2::Y * 10));
+    assert_eq!(dropped, piece);
+
+    assert_eq!(grid.lock(&dropped), 2);
+    assert_eq!(grid.get(IVec2::new(0, 0)), None);
+    assert_eq!(grid.get(IVec2::new(1, 0)), Some(TetrominoShape::O));
+    assert_eq!(grid.get(IVec2::new(9, 0)), Some(TetrominoShape::I));
+    assert_eq!(grid.get(IVec2::new(4, 1)), Some(TetrominoShape::T));
+    assert_eq!(grid.column_height(4), 2);
+    assert_eq!(grid.column_height(0), 0);
+}
+
+#[test]
+fn every_shape_turns_in_its_box() {
+    for shape in TetrominoShape::ALL {
+        let mut tetromino = Tetromino::spawn(shape);
+        assert!(GameGrid::new().fits(&tetromino), "{shape:?}");
+
+        for _ in 0..4 {
+            let mut blocks = tetromino.blocks().to_vec();
+            blocks.sort_by_key(|coords| (coords.x, coords.y));
+            blocks.dedup();
+            assert_eq!(blocks.len(), 4, "{shape:?}");
+
+            tetromino = tetromino.rotated(tetromino.rotation.rotate_right());
+        }
+        assert_eq!(tetromino, Tetromino::spawn(shape));
+    }
+}
@@ -0,0 +1,172 @@
+use std::fmt::Write;
+
+use bevy::prelude::*;
+
+use super::{
+    game::{TetrisGame, TetrisMode},
+    grid::{GameGrid, NB_COLS, NB_ROWS},
+    tetromino::{Rotation, Tetromino, TetrominoBag, TetrominoShape},
+};
+
+/// A Tetris game left in progress, with the bag its pieces are dealt from
+#[derive(Clone, Debug)]
+pub(super) struct TetrisSave {
+    pub mode: TetrisMode,
+    // Of the run, the bags still to come are shuffled from it again
+    pub seed: u64,
+    pub game: TetrisGame,
+    pub grid: GameGrid,
+    pub bag: TetrominoBag,
+}
+
+impl TetrisSave {
+    // `key = value` lines, the grid as one `row` line per row from the top:
+    // a letter per block, `.` for empty cells
+    pub fn to_text(&self) -> String {
+        let mut text = String::new();
+        let game = &self.game;
+        let piece = &game.piece;
+
+        let _ = writeln!(text, "mode = {}", self.mode.name());
+        let _ = writeln!(text, "seed = {}", self.seed);
+        let _ = writeln!(text, "score = {}", game.score);
+        let _ = writeln!(text, "lines = {}", game.lines);
+        let _ = writeln!(text, "time = {}", game.time);
+        let _ = writeln!(
+            text,
+            "piece = {} {} {} {}",
+            shape_letter(piece.shape),
+            piece.coords.x,
+            piece.coords.y,
+            rotation_index(piece.rotation),
+        );
+        let _ = writeln!(text, "next = {}", shape_letter(game.next));
+        if let Some(held) = game.held {
+            let _ = writeln!(text, "held = {}", shape_letter(held));
+        }
+        let _ = writeln!(text, "can_hold = {}", game.can_hold);
+        let bag: String = self.bag.shapes.iter().copied().map(shape_letter).collect();
+        let _ = writeln!(text, "bag = {bag}");
+
+        for y in (0..NB_ROWS as i32).rev() {
+            let row: String = (0..NB_COLS as i32)
+                .map(|x| self.grid.get(IVec2::new(x, y)).map_or('.', shape_letter))
+                .collect();
+            let _ = writeln!(text, "row = {row}");
+        }
+
+        text
+    }
+
+    pub fn from_text(text: &str) -> Option<Self> {
+        let mut mode = None;
+        let mut seed = None;
+        let mut score = None;
+        let mut lines = None;
+        let mut time = None;
+        let mut piece = None;
+        let mut next = None;
+        let mut held = None;
+        let mut can_hold = None;
+        let mut bag = None;
+        let mut rows = Vec::new();
+
+        for line in text.lines().map(str::trim).filter(|line| !line.is_empty()) {
+            let (key, value) = line.split_once('=')?;
+            let value = value.trim();
+
+            match key.trim() {
+                "mode" => mode = Some(TetrisMode::from_name(value)?),
+                "seed" => seed = Some(value.parse().ok()?),
+                "score" => score = Some(value.parse().ok()?),
+                "lines" => lines = Some(value.parse().ok()?),
+                "time" => time = Some(value.parse().ok()?),
+                "piece" => {
+                    let words: Vec<&str> = value.split_whitespace().collect();
+                    let [shape, x, y, rotation] = words[..] else {
+                        return None;
+                    };
+                    piece = Some(Tetromino {
+                        coords: IVec2::new(x.parse().ok()?, y.parse().ok()?),
+                        rotation: *Rotation::ALL.get(rotation.parse::<usize>().ok()?)?,
+                        shape: letter_shape(shape.parse().ok()?)?,
+                    });
+                }
+                "next" => next = Some(letter_shape(value.parse().ok()?)?),
+                "held" => held = Some(letter_shape(value.parse().ok()?)?),
+                "can_hold" => can_hold = Some(value.parse().ok()?),
+                "bag" => bag = Some(value.chars().map(letter_shape).collect::<Option<_>>()?),
+                "row" => rows.push(value),
+                _ => return None,
+            }
+        }
+
+        if rows.len() != NB_ROWS {
+            return None;
+        }
+        let mut grid = GameGrid::new();
+        for (y, row) in rows.iter().rev().enumerate() {
+            if row.chars().count() != NB_COLS {
+                return None;
+            }
+            for (x, letter) in row.chars().enumerate() {
+                if letter != '.' {
+                    grid.set(IVec2::new(x as i32, y as i32), letter_shape(letter)?);
+                }
+            }
+        }
+
+        // A piece in the blocks or out of the grid, or a bag with no shape
+        // to deal, is not a game that could have been saved
+        let piece = piece?;
+        let bag: Vec<TetrominoShape> = bag?;
+        if !grid.fits(&piece) || bag.is_empty() {
+            return None;
+        }
+
+        let mut game = TetrisGame::new(|| piece.shape);
+        game.piece = piece;
+        game.next = next?;
+        game.held = held;
+        game.can_hold = can_hold?;
+        game.score = score?;
+        game.lines = lines?;
+        game.time = time?;
+
+        Some(Self {
+            mode: mode?,
+            seed: seed?,
+            game,
+            grid,
+            bag: TetrominoBag { shapes: bag },
+        })
+    }
+}
+
+fn shape_letter(shape: TetrominoShape) -> char {
+    match shape {
+        TetrominoShape::I => 'I',
+        TetrominoShape::O => 'O',
+        TetrominoShape::T => 'T',
+        TetrominoShape::S => 'S',
+        TetrominoShape::Z => 'Z',
+        TetrominoShape::J => 'J',
+        TetrominoShape::L => 'L',
+    }
+}
+
+fn letter_shape(letter: char) -> Option<TetrominoShape> {
+    TetrominoShape::ALL
+        .into_iter()
+        .find(|shape| shape_letter(*shape) == letter)
+}
+
+fn rotation_index(rotation: Rotation) -> usize {
+    Rotation::ALL
+        .iter()
+        .position(|other| *other == rotation)
+        .unwrap()
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,77 @@
+use super::*;
+
+// A marathon a few pieces in, with a piece held and blocks at the bottom
+fn save() -> TetrisSave {
+    let mut bag = TetrominoBag {
+        shapes: vec![TetrominoShape::S, TetrominoShape::I, TetrominoShape::O],
+    };
+    let mut game = TetrisGame::new(|| bag.shapes.pop().unwrap());
+    game.piece.coords.y -= 3;
+    game.piece.rotation = game.piece.rotation.rotate_right();
+    game.held = Some(TetrominoShape::T);
+    game.can_hold = false;
+    game.score = 1200;
+    game.lines = 7;
+    game.time = 83.5;
+
+    let mut grid = GameGrid::new();
+    grid.set(IVec2::new(0, 0), TetrominoShape::J);
+    grid.set(IVec2::new(1, 0), TetrominoShape::J);
+    grid.set(IVec2::new(NB_COLS as i32 - 1, 2), TetrominoShape::Z);
+
+    TetrisSave {
+        mode: TetrisMode::Marathon,
+        seed: 42,
+        game,
+        grid,
+        bag,
+    }
+}
+
+#[test]
+fn text_round_trip() {
+    let save = save();
+    let text = save.to_text();
+    let loaded = TetrisSave::from_text(&text).unwrap();
+
+    assert_eq!(loaded.to_text(), text);
+    assert_eq!(loaded.mode, save.mode);
+    assert_eq!(loaded.seed, save.seed);
+    assert_eq!(loaded.grid, save.grid);
+    assert_eq!(loaded.bag, save.bag);
+    assert_eq!(loaded.game.piece, save.game.piece);
+    assert_eq!(loaded.game.next, save.game.next);
+    assert_eq!(loaded.game.held, save.game.held);
+    assert_eq!(loaded.game.score, save.game.score);
+}
+
+#[test]
+fn broken_saves_are_not_loaded() {
+    let text = save().to_text();
+
+    // A row too many, an unknown shape, a missing line
+    assert!(TetrisSave::from_text(&format!("{text}row = ..........\n")).is_none());
+    assert!(TetrisSave::from_text(&text.replace("held = T", "held = X")).is_none());
+    assert!(TetrisSave::from_text(&text.replace("seed = 42\n", "")).is_none());
+}
+
+#[test]
+fn pieces_that_do_not_fit_are_not_loaded() {
+    let mut in_blocks = save();
+    for block in in_blocks.game.piece.blocks() {
+        in_blocks.grid.set(block, TetrominoShape::Z);
+    }
+    assert!(TetrisSave::from_text(&in_blocks.to_text()).is_none());
+
+    let mut outside = save();
+    outside.game.piece.coords.x = -3;
+    assert!(TetrisSave::from_text(&outside.to_text()).is_none());
+}
+
+#[test]
+fn empty_bags_are_not_loaded() {
+    let mut empty_bag = save();
+    empty_bag.bag.shapes.clear();
+
+    assert!(TetrisSave::from_text(&empty_bag.to_text()).is_none());
+}
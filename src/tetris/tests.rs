@@ -0,0 +1,324 @@
+use bevy::prelude::*;
+
+use crate::{
+    application::CurrentGame,
+    saves::SavedGames,
+    scores::BestScores,
+    testing::{launched_app, launcher_app, menu_item, run_frames, run_until, snapshot, state, tap},
+};
+
+use super::{
+    game::{GameEntity, SprintResult, TetrisGame, TetrisMode, SPRINT_LINES},
+    grid::{GameGrid, NB_ROWS},
+    tetromino::{Rotation, Tetromino, TetrominoShape},
+    *,
+};
+
+fn play_tetris() -> App {
+    let mut app = launcher_app();
+    enter_tetris(&mut app);
+
+    tap(&mut app, KeyCode::Space);
+    run_frames(&mut app, 1);
+    assert_eq!(state::<TetrisState>(&app), TetrisState::InGame);
+
+    app
+}
+
+fn enter_tetris(app: &mut App) {
+    tap(app, KeyCode::ArrowDown);
+    tap(app, KeyCode::Space);
+    run_frames(app, 1);
+    assert_eq!(state::<TetrisState>(app), TetrisState::InMenu);
+}
+
+fn game(app: &App) -> &TetrisGame {
+    app.world().resource::<TetrisGame>()
+}
+
+// Whether any block, text or background of the game can be seen
+fn board_shown(app: &mut App) -> bool {
+    app.world_mut()
+        .query_filtered::<&Visibility, With<GameEntity>>()
+        .iter(app.world())
+        .any(|visibility| *visibility != Visibility::Hidden)
+}
+
+#[test]
+fn pieces_move_turn_and_fall() {
+    let mut app = play_tetris();
+    let start = game(&app).piece;
+
+    tap(&mut app, KeyCode::ArrowLeft);
+    assert_eq!(game(&app).piece.coords.x, start.coords.x - 1);
+
+    tap(&mut app, KeyCode::KeyX);
+    assert_eq!(game(&app).piece.rotation, start.rotation.rotate_right());
+    tap(&mut app, KeyCode::KeyZ);
+    assert_eq!(game(&app).piece.rotation, start.rotation);
+
+    // One row every half second at the first level
+    let y = game(&app).piece.coords.y;
+    run_frames(&mut app, 31);
+    assert_eq!(game(&app).piece.coords.y, y - 1);
+}
+
+#[test]
+fn hard_drop_locks_the_piece_and_brings_the_next_one() {
+    let mut app = play_tetris();
+    let dropped = app
+        .world()
+        .resource::<GameGrid>()
+        .dropped(&game(&app).piece);
+    let next = game(&app).next;
+
+    tap(&mut app, KeyCode::Space);
+    let grid = app.world().resource::<GameGrid>();
+    for coords in dropped.blocks() {
+        assert_eq!(grid.get(coords), Some(dropped.shape));
+    }
+    assert_eq!(game(&app).piece, Tetromino::spawn(next));
+    assert!(game(&app).score > 0);
+}
+
+#[test]
+fn a_piece_is_held_once() {
+    let mut app = play_tetris();
+    let first = game(&app).piece.shape;
+    let next = game(&app).next;
+
+    tap(&mut app, KeyCode::KeyC);
+    assert_eq!(game(&app).held, Some(first));
+    assert_eq!(game(&app).piece.shape, next);
+
+    tap(&mut app, KeyCode::KeyC);
+    assert_eq!(game(&app).held, Some(first));
+    assert_eq!(game(&app).piece.shape, next);
+
+    // Until the piece is locked
+    tap(&mut app, KeyCode::Space);
+    tap(&mut app, KeyCode::KeyC);
+    assert_eq!(game(&app).piece, Tetromino::spawn(first));
+}
+
+#[test]
+fn topping_out_ends_the_game_and_keeps_the_score() {
+    let mut app = play_tetris();
+
+    // A column of pieces piled up in the middle
+    let mut grid = GameGrid::new();
+    for y in 0..NB_ROWS as i32 - 2 {
+        grid.set(IVec2::new(4, y), TetrominoShape::I);
+    }
+    app.insert_resource(grid);
+    app.world_mut().resource_mut::<TetrisGame>().score = 1234;
+
+    assert!(run_until(&mut app, 60, |app| {
+        state::<TetrisState>(app) == TetrisState::InMenu
+    }));
+    run_frames(&mut app, 1);
+    assert!(!app.world().contains_resource::<GameGrid>());
+    assert!(!app.world().contains_resource::<TetrisGame>());
+    assert_eq!(
+        app.world().resource::<BestScores>().get(TetrisPlugin::NAME),
+        Some(1234)
+    );
+}
+
+#[test]
+fn rotations_kick_off_the_walls() {
+    let grid = GameGrid::new();
+    let mut bag = TetrominoShape::ALL.into_iter().cycle();
+    let mut game = TetrisGame::new(|| bag.next().unwrap());
+
+    // A vertical I against the left wall
+    game.piece = Tetromino {
+        coords: IVec2::new(-2, 4),
+        rotation: Rotation::R90,
+        shape: TetrominoShape::I,
+    };
+    assert!(grid.fits(&game.piece));
+
+    assert!(game.try_rotate(&grid, Rotation::R180));
+    assert!(game
+        .piece
+        .blocks()
+        .iter()
+        .all(|coords| GameGrid::in_bounds(*coords)));
+}
+
+#[test]
+fn sprints_end_after_the_lines() {
+    let mut app = launched_app("--game tetris --mode sprint");
+    assert_eq!(state::<TetrisState>(&app), TetrisState::InGame);
+    assert_eq!(*app.world().resource::<TetrisMode>(), TetrisMode::Sprint);
+
+    run_frames(&mut app, 60);
+    app.world_mut().resource_mut::<TetrisGame>().lines = SPRINT_LINES;
+    run_frames(&mut app, 2);
+    assert_eq!(state::<TetrisState>(&app), TetrisState::InMenu);
+
+    // About the second played, the sprint does not count as a score
+    let time = app.world().resource::<SprintResult>().0.unwrap();
+    assert!((0.9..1.1).contains(&time), "{time}");
+    assert_eq!(
+        app.world().resource::<BestScores>().get(TetrisPlugin::NAME),
+        None
+    );
+}
+
+#[test]
+fn escape_pauses_with_the_board_hidden() {
+    let mut app = play_tetris();
+    assert!(board_shown(&mut app));
+
+    tap(&mut app, KeyCode::Escape);
+    assert_eq!(state::<TetrisState>(&app), TetrisState::InMenu);
+    assert!(!board_shown(&mut app));
+    menu_item(&mut app, "Resume");
+
+    // Nothing falls, and the time stops
+    let piece = game(&app).piece;
+    let time = game(&app).time;
+    run_frames(&mut app, 60);
+    assert_eq!(game(&app).piece, piece);
+    assert_eq!(game(&app).time, time);
+
+    // "Resume"
+    tap(&mut app, KeyCode::Space);
+    assert_eq!(state::<TetrisState>(&app), TetrisState::InGame);
+    assert!(board_shown(&mut app));
+    run_frames(&mut app, 31);
+    assert_eq!(game(&app).piece.coords.y, piece.coords.y - 1);
+}
+
+#[test]
+fn restart_deals_the_same_pieces_again() {
+    let mut app = play_tetris();
+    let first = game(&app).piece;
+    let next = game(&app).next;
+
+    tap(&mut app, KeyCode::Space);
+    assert!(game(&app).score > 0);
+
+    // "Restart"
+    tap(&mut app, KeyCode::Escape);
+    tap(&mut app, KeyCode::ArrowDown);
+    tap(&mut app, KeyCode::Space);
+    run_frames(&mut app, 1);
+    assert_eq!(state::<TetrisState>(&app), TetrisState::InGame);
+    assert_eq!(game(&app).score, 0);
+    assert_eq!(game(&app).piece, first);
+    assert_eq!(game(&app).next, next);
+}
+
+#[test]
+fn high_scores_are_shown_from_the_main_menu() {
+    let mut app = launcher_app();
+    app.world_mut()
+        .resource_mut::<BestScores>()
+        .submit(TetrisPlugin::NAME, 4321);
+    enter_tetris(&mut app);
+
+    // "High Scores", below "Play" and "Sprint"
+    tap(&mut app, KeyCode::ArrowDown);
+    tap(&mut app, KeyCode::ArrowDown);
+    tap(&mut app, KeyCode::Space);
+    run_frames(&mut app, 1);
+    menu_item(&mut app, "Best score: 4321");
+
+    tap(&mut app, KeyCode::Escape);
+    run_frames(&mut app, 1);
+    menu_item(&mut app, "High Scores");
+}
+
+#[test]
+fn leaving_a_paused_game_cleans_everything_up() {
+    let mut app = launcher_app();
+    let in_launcher = snapshot(&app);
+    enter_tetris(&mut app);
+
+    // Back to the main menu, which then offers to continue the game
+    let leave_to_main_menu = |app: &mut App| {
+        run_frames(app, 10);
+
+        // "Main Menu", below "Resume", "Restart" and "Hint"
+        tap(app, KeyCode::Escape);
+        for _ in 0..3 {
+            tap(app, KeyCode::ArrowDown);
+        }
+        tap(app, KeyCode::Space);
+        run_frames(app, 1);
+        assert_eq!(state::<TetrisState>(app), TetrisState::InMenu);
+        assert!(!app.world().contains_resource::<TetrisGame>());
+        menu_item(app, "Continue");
+    };
+
+    tap(&mut app, KeyCode::Space);
+    leave_to_main_menu(&mut app);
+    let in_menu = snapshot(&app);
+
+    // "Continue"
+    tap(&mut app, KeyCode::Space);
+    leave_to_main_menu(&mut app);
+    assert_eq!(snapshot(&app), in_menu);
+
+    // "Exit" straight from the pause menu, at its bottom
+    tap(&mut app, KeyCode::Space);
+    run_frames(&mut app, 10);
+    tap(&mut app, KeyCode::Escape);
+    tap(&mut app, KeyCode::ArrowUp);
+    tap(&mut app, KeyCode::Space);
+    run_frames(&mut app, 1);
+    assert_eq!(state::<CurrentGame>(&app), CurrentGame::InMainMenu);
+    assert_eq!(state::<TetrisState>(&app), TetrisState::Exited);
+    assert_eq!(snapshot(&app), in_launcher);
+}
+
+#[test]
+fn a_paused_game_is_saved_and_continued() {
+    let mut app = play_tetris();
+    tap(&mut app, KeyCode::Space);
+    tap(&mut app, KeyCode::ArrowLeft);
+    let piece = game(&app).piece;
+    let score = game(&app).score;
+    let grid = app.world().resource::<GameGrid>().clone();
+
+    tap(&mut app, KeyCode::Escape);
+    assert!(app
+        .world()
+        .resource::<SavedGames>()
+        .contains(TetrisPlugin::NAME));
+
+    // "Main Menu", then "Continue" at the top of it
+    for _ in 0..3 {
+        tap(&mut app, KeyCode::ArrowDown);
+    }
+    tap(&mut app, KeyCode::Space);
+    run_frames(&mut app, 1);
+    assert!(!app.world().contains_resource::<TetrisGame>());
+    tap(&mut app, KeyCode::Space);
+    run_frames(&mut app, 1);
+
+    assert_eq!(state::<TetrisState>(&app), TetrisState::InGame);
+    assert_eq!(game(&app).piece, piece);
+    assert_eq!(game(&app).score, score);
+    assert_eq!(app.world().resource::<GameGrid>(), &grid);
+}
+
+#[test]
+fn a_finished_game_can_not_be_continued() {
+    let mut app = play_tetris();
+    tap(&mut app, KeyCode::Escape);
+    tap(&mut app, KeyCode::Space);
+    run_frames(&mut app, 1);
+
+    app.world_mut().resource_mut::<TetrisGame>().game_over = true;
+    run_frames(&mut app, 2);
+    assert_eq!(state::<TetrisState>(&app), TetrisState::InMenu);
+    assert!(!app
+        .world()
+        .resource::<SavedGames>()
+        .contains(TetrisPlugin::NAME));
+    menu_item(&mut app, "Play");
+}
@@ -1,13 +1,52 @@
-use bevy::{math::uvec2, prelude::*};
+use bevy::{color::palettes, prelude::*};
 
-#[derive(Clone, Copy)]
+use crate::rng::GameRng;
+
+use super::grid::{NB_COLS, NB_ROWS};
+
+/// A piece on the grid, `coords` being the bottom left corner of the box it
+/// rotates in
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub struct Tetromino {
-    pub coords: UVec2,
-    pub blocks: [Entity; 4],
+    pub coords: IVec2,
+    pub rotation: Rotation,
     pub shape: TetrominoShape,
 }
 
-#[derive(Clone, Copy)]
+impl Tetromino {
+    /// Centered at the top of the grid
+    pub fn spawn(shape: TetrominoShape) -> Self {
+        let size = shape.box_size();
+
+        Self {
+            coords: IVec2 {
+                x: (NB_COLS as i32 - size) / 2,
+                y: NB_ROWS as i32 - size,
+            },
+            rotation: Rotation::R0,
+            shape,
+        }
+    }
+
+    pub fn blocks(&self) -> [IVec2; 4] {
+        self.shape
+            .get_local_coords(self.rotation)
+            .map(|local_coords| self.coords + local_coords.as_ivec2())
+    }
+
+    pub fn moved(&self, offset: IVec2) -> Self {
+        Self {
+            coords: self.coords + offset,
+            ..*self
+        }
+    }
+
+    pub fn rotated(&self, rotation: Rotation) -> Self {
+        Self { rotation, ..*self }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub enum TetrominoShape {
     I,
     O,
@@ -19,6 +58,16 @@ pub enum TetrominoShape {
 }
 
 impl TetrominoShape {
+    pub const ALL: [TetrominoShape; 7] = [
+        TetrominoShape::I,
+        TetrominoShape::O,
+        TetrominoShape::T,
+        TetrominoShape::S,
+        TetrominoShape::Z,
+        TetrominoShape::J,
+        TetrominoShape::L,
+    ];
+
     pub fn get_local_coords(&self, rotation: Rotation) -> [UVec2; 4] {
         // (0, 3)  (1, 3)  (2, 3)  (3, 3)
         //
@@ -28,35 +77,51 @@ impl TetrominoShape {
         //
         // (0, 0)  (1, 0)  (2, 0)  (3, 0)
 
+        let spawn_coords = match self {
+            TetrominoShape::I => [(0, 2), (1, 2), (2, 2), (3, 2)],
+            TetrominoShape::O => [(0, 0), (1, 0), (0, 1), (1, 1)],
+            TetrominoShape::T => [(0, 1), (1, 1), (2, 1), (1, 2)],
+            TetrominoShape::S => [(0, 1), (1, 1), (1, 2), (2, 2)],
+            TetrominoShape::Z => [(0, 2), (1, 2), (1, 1), (2, 1)],
+            TetrominoShape::J => [(0, 2), (0, 1), (1, 1), (2, 1)],
+            TetrominoShape::L => [(0, 1), (1, 1), (2, 1), (2, 2)],
+        };
+
+        // Turned clockwise in its box, a quarter turn at a time
+        let size = self.box_size();
+        spawn_coords.map(|(mut x, mut y)| {
+            for _ in 0..rotation.quarter_turns() {
+                (x, y) = (y, size - 1 - x);
+            }
+
+            UVec2::new(x as u32, y as u32)
+        })
+    }
+
+    // Side of the box the shape rotates in
+    fn box_size(&self) -> i32 {
         match self {
-            TetrominoShape::I => match rotation {
-                Rotation::R0 | Rotation::R180 => [(2, 3), (2, 2), (2, 1), (2, 0)],
-                Rotation::R90 | Rotation::R270 => [(0, 1), (1, 1), (2, 1), (3, 1)],
-            },
-            TetrominoShape::O => todo!(),
-            TetrominoShape::T => todo!(),
-            TetrominoShape::S => todo!(),
-            TetrominoShape::Z => todo!(),
-            TetrominoShape::J => todo!(),
-            TetrominoShape::L => todo!(),
+            TetrominoShape::I => 4,
+            TetrominoShape::O => 2,
+            _ => 3,
         }
-        .map(|(x, y)| uvec2(x as u32, y as u32))
     }
 
-    pub fn center_offset(&self) -> Vec2 {
+    pub fn color(&self) -> Color {
         match self {
-            TetrominoShape::I => Vec2::new(16.0, 0.0),
-            TetrominoShape::O => todo!(),
-            TetrominoShape::T => todo!(),
-            TetrominoShape::S => todo!(),
-            TetrominoShape::Z => todo!(),
-            TetrominoShape::J => todo!(),
-            TetrominoShape::L => todo!(),
+            TetrominoShape::I => palettes::css::AQUA,
+            TetrominoShape::O => palettes::css::YELLOW,
+            TetrominoShape::T => palettes::css::PURPLE,
+            TetrominoShape::S => palettes::css::LIME,
+            TetrominoShape::Z => palettes::css::RED,
+            TetrominoShape::J => palettes::css::BLUE,
+            TetrominoShape::L => palettes::css::ORANGE,
         }
+        .into()
     }
 }
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub enum Rotation {
     R0,
     R90,
@@ -65,6 +130,8 @@ pub enum Rotation {
 }
 
 impl Rotation {
+    pub const ALL: [Rotation; 4] = [Rotation::R0, Rotation::R90, Rotation::R180, Rotation::R270];
+
     pub fn rotate_left(&self) -> Self {
         match self {
             Self::R0 => Self::R270,
@@ -82,4 +149,41 @@ impl Rotation {
             Self::R270 => Self::R0,
         }
     }
+
+    fn quarter_turns(&self) -> usize {
+        match self {
+            Self::R0 => 0,
+            Self::R90 => 1,
+            Self::R180 => 2,
+            Self::R270 => 3,
+        }
+    }
+}
+
+/// Deals every shape once per bag, in an order shuffled by the run's `GameRng`
+#[derive(Resource, Clone, PartialEq, Eq, Default, Debug)]
+pub struct TetrominoBag {
+    // Left in the current bag, dealt from the end. Only empty before the
+    // first shape is dealt, the next bag is filled as soon as one runs out.
+    pub shapes: Vec<TetrominoShape>,
+}
+
+impl TetrominoBag {
+    pub fn next(&mut self, game_rng: &mut GameRng) -> TetrominoShape {
+        if self.shapes.is_empty() {
+            self.refill(game_rng);
+        }
+
+        let shape = self.shapes.pop().unwrap();
+        if self.shapes.is_empty() {
+            self.refill(game_rng);
+        }
+
+        shape
+    }
+
+    fn refill(&mut self, game_rng: &mut GameRng) {
+        self.shapes = TetrominoShape::ALL.to_vec();
+        game_rng.shuffle(&mut self.shapes);
+    }
 }